@@ -0,0 +1,85 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use leptos::*;
+
+use super::{badge::Badge, Color};
+
+// A key is added when it has no old value and removed when it has no new one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    pub key: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[component]
+pub fn DiffTable(#[prop(into)] entries: MaybeSignal<Vec<DiffEntry>>) -> impl IntoView {
+    view! {
+        <div class="overflow-x-auto border border-gray-200 rounded-lg dark:border-gray-700">
+            <table class="min-w-full divide-y divide-gray-200 dark:divide-gray-700">
+                <thead class="bg-gray-50 dark:bg-slate-800">
+                    <tr>
+                        <th
+                            scope="col"
+                            class="px-3 py-2 text-start text-xs font-medium text-gray-500 uppercase"
+                        >
+                            "Change"
+                        </th>
+                        <th
+                            scope="col"
+                            class="px-3 py-2 text-start text-xs font-medium text-gray-500 uppercase"
+                        >
+                            "Key"
+                        </th>
+                        <th
+                            scope="col"
+                            class="px-3 py-2 text-start text-xs font-medium text-gray-500 uppercase"
+                        >
+                            "Old value"
+                        </th>
+                        <th
+                            scope="col"
+                            class="px-3 py-2 text-start text-xs font-medium text-gray-500 uppercase"
+                        >
+                            "New value"
+                        </th>
+                    </tr>
+                </thead>
+                <tbody class="divide-y divide-gray-200 dark:divide-gray-700">
+                    <For
+                        each=move || entries.get()
+                        key=|entry| entry.key.clone()
+                        children=move |entry| {
+                            let (color, label) = match (&entry.old, &entry.new) {
+                                (None, _) => (Color::Green, "Added"),
+                                (_, None) => (Color::Red, "Removed"),
+                                _ => (Color::Yellow, "Modified"),
+                            };
+                            view! {
+                                <tr>
+                                    <td class="px-3 py-2 whitespace-nowrap">
+                                        <Badge color=color>{label}</Badge>
+                                    </td>
+                                    <td class="px-3 py-2 text-xs font-mono text-gray-800 break-all dark:text-gray-200">
+                                        {entry.key}
+                                    </td>
+                                    <td class="px-3 py-2 text-xs font-mono text-red-700 break-all whitespace-pre-wrap dark:text-red-400">
+                                        {entry.old}
+                                    </td>
+                                    <td class="px-3 py-2 text-xs font-mono text-teal-700 break-all whitespace-pre-wrap dark:text-teal-400">
+                                        {entry.new}
+                                    </td>
+                                </tr>
+                            }
+                        }
+                    />
+
+                </tbody>
+            </table>
+        </div>
+    }
+}
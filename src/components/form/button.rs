@@ -14,6 +14,7 @@ pub fn Button(
     #[prop(into)] color: MaybeSignal<Color>,
     #[prop(into)] on_click: Callback<(), ()>,
     #[prop(optional, into)] disabled: MaybeSignal<bool>,
+    #[prop(optional, into)] loading: MaybeSignal<bool>,
     #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
     #[prop(optional)] children: Option<Children>,
 ) -> impl IntoView {
@@ -35,10 +36,21 @@ pub fn Button(
                 }
             }
 
-            disabled=move || disabled.get()
-            on:click=move |_| on_click.call(())
+            disabled=move || disabled.get() || loading.get()
+            on:click=move |_| {
+                if !loading.get_untracked() {
+                    on_click.call(())
+                }
+            }
+
             {..attrs}
         >
+            <span
+                class="animate-spin inline-block size-4 border-[3px] border-current border-t-transparent rounded-full"
+                role="status"
+                aria-label="loading"
+                class:hidden=move || !loading.get()
+            ></span>
             {children.map(|children| children())}
             {text.get()}
         </button>
@@ -9,7 +9,8 @@ use leptos::*;
 use crate::{
     components::icon::{IconArrowRightCircle, IconPlus, IconVariable, IconXMark},
     core::{
-        form::{ExpressionError, FormErrorType},
+        expr::FUNCTIONS,
+        form::{ExpressionError, FormData, FormErrorType},
         schema::Validator,
     },
 };
@@ -22,46 +23,41 @@ pub fn InputExpression(
     #[prop(optional, into)] placeholder: Option<MaybeSignal<String>>,
 ) -> impl IntoView {
     let if_thens = create_memo(move |_| {
-        let data = element.data.get();
-        let error = data.error(element.id);
-
-        data.expr_if_thens(element.id)
-            .enumerate()
-            .map(|(idx, expr)| {
-                (
-                    idx,
-                    expr.clone(),
-                    error.as_ref().and_then(|e| match e.id {
-                        FormErrorType::Expression(ExpressionError::If(pos)) if pos == idx => {
-                            Some(ExpressionError::If(e.error.clone()))
-                        }
-                        FormErrorType::Expression(ExpressionError::Then(pos)) if pos == idx => {
-                            Some(ExpressionError::Then(e.error.clone()))
-                        }
-                        _ => None,
-                    }),
-                )
-            })
-            .collect::<Vec<_>>()
+        element.data.with(|data| {
+            let error = data.error(element.id);
+
+            data.expr_if_thens(element.id)
+                .enumerate()
+                .map(|(idx, expr)| {
+                    (
+                        idx,
+                        expr.clone(),
+                        error.as_ref().and_then(|e| match e.id {
+                            FormErrorType::Expression(ExpressionError::If(pos)) if pos == idx => {
+                                Some(ExpressionError::If((e.error.clone(), e.span)))
+                            }
+                            FormErrorType::Expression(ExpressionError::Then(pos)) if pos == idx => {
+                                Some(ExpressionError::Then((e.error.clone(), e.span)))
+                            }
+                            _ => None,
+                        }),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
     });
     let else_value = create_memo(move |_| {
         element
             .data
-            .get()
-            .expr_else(element.id)
-            .unwrap_or_default()
-            .to_string()
+            .with(|data| data.expr_else(element.id).unwrap_or_default().to_string())
     });
     let else_err = create_memo(move |_| {
-        element
-            .data
-            .get()
-            .error(element.id)
-            .as_ref()
-            .and_then(|e| match e.id {
-                FormErrorType::Expression(ExpressionError::Else) => Some(e.error.clone()),
+        element.data.with(|data| {
+            data.error(element.id).as_ref().and_then(|e| match e.id {
+                FormErrorType::Expression(ExpressionError::Else) => Some((e.error.clone(), e.span)),
                 _ => None,
             })
+        })
     });
     let disable_add = element
         .data
@@ -74,6 +70,8 @@ pub fn InputExpression(
         .default
         .as_ref()
         .is_some_and(|checks| checks.validators.contains(&Validator::MaxItems(1)));
+    // Variables can depend on other fields when the check is conditional
+    let suggestions = create_memo(move |_| element.data.with(|data| suggestions(data, element.id)));
 
     view! {
         <div class="space-y-3">
@@ -86,8 +84,12 @@ pub fn InputExpression(
 
                 children=move |(idx, if_then, error)| {
                     let (is_if_err, is_then_err, error) = match error {
-                        Some(ExpressionError::If(err)) => (true, false, err.into()),
-                        Some(ExpressionError::Then(err)) => (false, true, err.into()),
+                        Some(ExpressionError::If((err, span))) => {
+                            (true, false, Some((if_then.if_.clone(), err, span)))
+                        }
+                        Some(ExpressionError::Then((err, span))) => {
+                            (false, true, Some((if_then.then_.clone(), err, span)))
+                        }
                         _ => (false, false, None),
                     };
                     let ok_class = "py-2 px-3 pe-11 block w-full border-gray-200 shadow-sm -mt-px -ms-px first:rounded-t-lg last:rounded-b-lg sm:first:rounded-s-lg sm:mt-0 sm:first:ms-0 sm:first:rounded-se-none sm:last:rounded-es-none sm:last:rounded-e-lg text-sm relative focus:z-10 focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600";
@@ -96,22 +98,19 @@ pub fn InputExpression(
                         <div class="space-y-3">
                             <div class="relative">
                                 <div class="sm:flex rounded-lg shadow-sm">
-                                    <input
-                                        type="text"
+                                    <ExpressionInput
+                                        value=if_then.if_
                                         placeholder="if"
-                                        prop:value=if_then.if_
-                                        class=move || {
-                                            if !is_if_err { ok_class } else { err_class }
-                                        }
-
-                                        on:change=move |ev| {
+                                        class=if !is_if_err { ok_class } else { err_class }
+                                        suggestions
+                                        on_change=move |value: String| {
                                             element
                                                 .data
                                                 .update(|data| {
-                                                    data.expr_update_if(
+                                                    data.expr_update_if(element.id, idx, value);
+                                                    data.expr_validate(
                                                         element.id,
-                                                        idx,
-                                                        event_target_value(&ev),
+                                                        ExpressionError::If(idx),
                                                     );
                                                 });
                                         }
@@ -120,23 +119,19 @@ pub fn InputExpression(
                                     <span class="py-2 px-3 inline-flex items-center min-w-fit w-full border border-gray-200 bg-gray-50 text-sm text-gray-500 -mt-px -ms-px first:rounded-t-lg last:rounded-b-lg sm:w-auto sm:first:rounded-s-lg sm:mt-0 sm:first:ms-0 sm:first:rounded-se-none sm:last:rounded-es-none sm:last:rounded-e-lg dark:bg-gray-700 dark:border-gray-700 dark:text-gray-400">
                                         <IconArrowRightCircle attr:class="mx-auto size-4 text-gray-400"/>
                                     </span>
-                                    <input
-                                        type="text"
+                                    <ExpressionInput
+                                        value=if_then.then_
                                         placeholder="then"
-                                        class=move || {
-                                            if !is_then_err { ok_class } else { err_class }
-                                        }
-
-                                        prop:value=if_then.then_
-
-                                        on:change=move |ev| {
+                                        class=if !is_then_err { ok_class } else { err_class }
+                                        suggestions
+                                        on_change=move |value: String| {
                                             element
                                                 .data
                                                 .update(|data| {
-                                                    data.expr_update_then(
+                                                    data.expr_update_then(element.id, idx, value);
+                                                    data.expr_validate(
                                                         element.id,
-                                                        idx,
-                                                        event_target_value(&ev),
+                                                        ExpressionError::Then(idx),
                                                     );
                                                 });
                                         }
@@ -160,8 +155,8 @@ pub fn InputExpression(
 
                             </div>
                             {error
-                                .map(|error| {
-                                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                                .map(|(expr, error, span)| {
+                                    view! { <ExpressionErrorMessage expr error span/> }
                                 })}
 
                         </div>
@@ -171,24 +166,23 @@ pub fn InputExpression(
 
             <div class="space-y-3">
                 <div class="relative">
-                    <input
-                        type="text"
-                        class=move || {
+                    <ExpressionInput
+                        value=else_value
+                        placeholder=placeholder.clone().unwrap_or_default()
+                        class=Signal::derive(move || {
                             if else_err.get().is_none() {
                                 "py-2 px-3 pe-11 block w-full border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
                             } else {
                                 "py-2 px-3 pe-11 block w-full border-red-500 shadow-sm text-sm rounded-lg focus:border-red-500 focus:ring-red-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
                             }
-                        }
-
-                        placeholder=placeholder.clone().map(|p| move || p.get())
-                        prop:value=else_value
-
-                        on:change=move |ev| {
+                        })
+                        suggestions
+                        on_change=move |value: String| {
                             element
                                 .data
                                 .update(|data| {
-                                    data.expr_update_else(element.id, event_target_value(&ev));
+                                    data.expr_update_else(element.id, value);
+                                    data.expr_validate(element.id, ExpressionError::Else);
                                 });
                         }
                     />
@@ -201,8 +195,14 @@ pub fn InputExpression(
                 {move || {
                     else_err
                         .get()
-                        .map(|error| {
-                            view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                        .map(|(error, span)| {
+                            view! {
+                                <ExpressionErrorMessage
+                                    expr=else_value.get_untracked()
+                                    error
+                                    span
+                                />
+                            }
                         })
                 }}
 
@@ -234,3 +234,277 @@ pub fn InputExpression(
         </p>
     }
 }
+
+#[component]
+fn ExpressionErrorMessage(
+    expr: String,
+    error: String,
+    span: Option<(usize, usize)>,
+) -> impl IntoView {
+    let highlight = span.and_then(|(start, end)| {
+        let (before, marked, after) = (
+            expr.get(..start)?.to_string(),
+            expr.get(start..end)?.to_string(),
+            expr.get(end..)?.to_string(),
+        );
+        (!expr.trim().is_empty()).then(|| {
+            view! {
+                <p class="mt-1 font-mono text-xs text-gray-600 whitespace-pre-wrap break-all dark:text-gray-400">
+                    {before}
+                    <mark class="rounded-sm bg-red-100 text-red-700 dark:bg-red-800/30 dark:text-red-400">
+                        {if marked.is_empty() { " ".to_string() } else { marked }}
+                    </mark>
+                    {after}
+                </p>
+            }
+        })
+    });
+
+    view! {
+        <p class="text-xs text-red-600 mt-2">{error}</p>
+        {highlight}
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Suggestion {
+    name: &'static str,
+    kind: SuggestionKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SuggestionKind {
+    Variable,
+    Constant,
+    Function(u32),
+}
+
+// Completes the name under the caret with the variables, constants and
+// functions accepted by the field
+#[component]
+fn ExpressionInput(
+    #[prop(into)] value: MaybeSignal<String>,
+    #[prop(into)] placeholder: MaybeSignal<String>,
+    #[prop(into)] class: MaybeSignal<&'static str>,
+    suggestions: Memo<Vec<Suggestion>>,
+    #[prop(into)] on_change: Callback<String>,
+) -> impl IntoView {
+    let input_ref = create_node_ref::<html::Input>();
+    let matches = create_rw_signal(Vec::<Suggestion>::new());
+    let highlighted = create_rw_signal(0usize);
+    let is_dirty = store_value(false);
+
+    let refresh = move || {
+        let Some(input) = input_ref.get_untracked() else {
+            return;
+        };
+        let value = input.value();
+        let (start, end) = name_at_caret(&value, caret(&input, &value));
+        let prefix = &value[start..end];
+        highlighted.set(0);
+        matches.set(if !prefix.is_empty() {
+            suggestions.with_untracked(|suggestions| {
+                suggestions
+                    .iter()
+                    .filter(|s| s.name.starts_with(prefix) && s.name != prefix)
+                    .take(MAX_SUGGESTIONS)
+                    .copied()
+                    .collect()
+            })
+        } else {
+            Vec::new()
+        });
+    };
+    let pick = move |suggestion: Suggestion| {
+        let Some(input) = input_ref.get_untracked() else {
+            return;
+        };
+        let value = input.value();
+        let (start, end) = name_at_caret(&value, caret(&input, &value));
+        let name = match suggestion.kind {
+            SuggestionKind::Function(_) => format!("{}(", suggestion.name),
+            _ => suggestion.name.to_string(),
+        };
+        let value = format!("{}{name}{}", &value[..start], &value[end..]);
+        let caret = (value[..start].encode_utf16().count() + name.len()) as u32;
+        input.set_value(&value);
+        let _ = input.set_selection_range(caret, caret);
+        is_dirty.set_value(true);
+        matches.set(Vec::new());
+    };
+
+    view! {
+        <input
+            node_ref=input_ref
+            type="text"
+            autocomplete="off"
+            role="combobox"
+            aria-autocomplete="list"
+            aria-expanded=move || (!matches.with(|m| m.is_empty())).to_string()
+            placeholder=move || placeholder.get()
+            class=move || class.get()
+            prop:value=move || value.get()
+            on:input=move |_| refresh()
+            on:change=move |ev| {
+                is_dirty.set_value(false);
+                on_change.call(event_target_value(&ev));
+            }
+
+            on:blur=move |_| {
+                matches.set(Vec::new());
+                if is_dirty.get_value() {
+                    is_dirty.set_value(false);
+                    if let Some(input) = input_ref.get_untracked() {
+                        on_change.call(input.value());
+                    }
+                }
+            }
+
+            on:keydown=move |ev| {
+                let total = matches.with_untracked(|m| m.len());
+                if total == 0 {
+                    return;
+                }
+                match ev.key().as_str() {
+                    "ArrowDown" => {
+                        ev.prevent_default();
+                        highlighted.set((highlighted.get_untracked() + 1).min(total - 1));
+                    }
+                    "ArrowUp" => {
+                        ev.prevent_default();
+                        highlighted.set(highlighted.get_untracked().saturating_sub(1));
+                    }
+                    "Enter" | "Tab" => {
+                        ev.prevent_default();
+                        if let Some(suggestion) = matches
+                            .with_untracked(|m| m.get(highlighted.get_untracked()).copied())
+                        {
+                            pick(suggestion);
+                        }
+                    }
+                    "Escape" => {
+                        matches.set(Vec::new());
+                    }
+                    _ => {}
+                }
+            }
+        />
+
+        <Show when=move || !matches.with(|m| m.is_empty())>
+            <div
+                role="listbox"
+                class="absolute inset-x-0 top-full z-30 mt-1 max-h-60 overflow-y-auto bg-white border border-gray-200 rounded-lg shadow-md dark:bg-slate-900 dark:border-gray-700"
+            >
+                {move || {
+                    let current = highlighted.get();
+                    matches
+                        .get()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(idx, suggestion)| {
+                            let kind = match suggestion.kind {
+                                SuggestionKind::Variable => "variable".to_string(),
+                                SuggestionKind::Constant => "constant".to_string(),
+                                SuggestionKind::Function(1) => "function, 1 argument".to_string(),
+                                SuggestionKind::Function(args) => {
+                                    format!("function, {args} arguments")
+                                }
+                            };
+                            view! {
+                                <div
+                                    role="option"
+                                    aria-selected=(idx == current).to_string()
+                                    class=if idx == current {
+                                        "px-3 py-1.5 flex items-center justify-between gap-x-3 text-sm cursor-pointer bg-gray-100 text-gray-800 dark:bg-slate-800 dark:text-gray-200"
+                                    } else {
+                                        "px-3 py-1.5 flex items-center justify-between gap-x-3 text-sm cursor-pointer text-gray-800 hover:bg-gray-100 dark:text-gray-400 dark:hover:bg-slate-800"
+                                    }
+
+                                    on:mousedown=move |ev| {
+                                        ev.prevent_default();
+                                        pick(suggestion);
+                                    }
+                                >
+
+                                    <span class="font-mono truncate">{suggestion.name}</span>
+                                    <span class="text-xs text-gray-500 dark:text-gray-400">
+                                        {kind}
+                                    </span>
+                                </div>
+                            }
+                        })
+                        .collect_view()
+                }}
+
+            </div>
+        </Show>
+    }
+}
+
+const MAX_SUGGESTIONS: usize = 8;
+
+fn suggestions(data: &FormData, id: &str) -> Vec<Suggestion> {
+    let mut suggestions = data
+        .schema
+        .fields
+        .get(id)
+        .and_then(|field| field.input_check(data))
+        .and_then(|check| {
+            check.validators.iter().find_map(|v| match v {
+                Validator::IsValidExpression(v) => Some(*v),
+                _ => None,
+            })
+        })
+        .map(|validator| {
+            validator
+                .variables
+                .iter()
+                .map(|name| Suggestion {
+                    name: *name,
+                    kind: SuggestionKind::Variable,
+                })
+                .chain(validator.constants.iter().map(|name| Suggestion {
+                    name: *name,
+                    kind: SuggestionKind::Constant,
+                }))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    suggestions.extend(FUNCTIONS.iter().map(|(name, args)| Suggestion {
+        name: *name,
+        kind: SuggestionKind::Function(*args),
+    }));
+    suggestions
+}
+
+// Caret position as a byte offset, the browser reports UTF-16 units
+fn caret(input: &web_sys::HtmlInputElement, value: &str) -> usize {
+    let units = input
+        .selection_start()
+        .ok()
+        .flatten()
+        .map_or(usize::MAX, |pos| pos as usize);
+    let mut count = 0;
+    value
+        .char_indices()
+        .find(|(_, ch)| {
+            count += ch.len_utf16();
+            count > units
+        })
+        .map_or(value.len(), |(idx, _)| idx)
+}
+
+fn name_at_caret(value: &str, caret: usize) -> (usize, usize) {
+    let is_name = |ch: char| ch.is_ascii_alphanumeric() || ch == '_' || ch == '.';
+    let start = value[..caret]
+        .char_indices()
+        .rev()
+        .take_while(|(_, ch)| is_name(*ch))
+        .last()
+        .map_or(caret, |(idx, _)| idx);
+    let end = value[caret..]
+        .char_indices()
+        .find(|(_, ch)| !is_name(*ch))
+        .map_or(value.len(), |(idx, _)| caret + idx);
+    (start, end)
+}
@@ -8,7 +8,10 @@ use std::str::FromStr;
 
 use leptos::*;
 
-use crate::components::icon::{IconClock, IconExclamationCircle, IconInfo};
+use crate::{
+    components::icon::{IconClock, IconExclamationCircle, IconInfo},
+    core::duration::{duration_parts, parse_millis, DURATION_UNITS},
+};
 
 use super::FormElement;
 
@@ -20,23 +23,29 @@ pub fn InputText(
     #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
 ) -> impl IntoView {
     let value = create_memo(move |_| {
-        element
-            .data
-            .get()
-            .value::<String>(element.id)
-            .unwrap_or_default()
+        element.data.with(|data| {
+            data.display_value(
+                element.id,
+                data.value::<String>(element.id).unwrap_or_default(),
+            )
+        })
     });
     let error = create_memo(move |_| {
         element
             .data
-            .get()
-            .error_string(element.id)
-            .map(|s| s.to_string())
+            .with(|data| data.error_string(element.id).map(|s| s.to_string()))
     });
 
+    let ids = element.ids();
+    let error_id = ids.error.clone();
+
     view! {
         <div class="relative">
             <input
+                id=ids.input.clone()
+                aria-labelledby=ids.label.clone()
+                aria-describedby=ids.describe(error)
+                aria-invalid=move || error.get().is_some().to_string()
                 {..attrs}
                 type="text"
                 class=move || {
@@ -71,7 +80,7 @@ pub fn InputText(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! { <p id=error_id.clone() class="text-xs text-red-600 mt-2">{error}</p> }
                 })
         }}
     }
@@ -82,27 +91,32 @@ pub fn InputPassword(
     element: FormElement,
     #[prop(optional, into)] placeholder: Option<MaybeSignal<String>>,
     #[prop(optional, into)] disabled: MaybeSignal<bool>,
+    #[prop(optional)] generate: bool,
+    #[prop(optional)] no_reveal: bool,
     #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
 ) -> impl IntoView {
     let value = create_memo(move |_| {
         element
             .data
-            .get()
-            .value::<String>(element.id)
-            .unwrap_or_default()
+            .with(|data| data.value::<String>(element.id).unwrap_or_default())
     });
     let error = create_memo(move |_| {
         element
             .data
-            .get()
-            .error_string(element.id)
-            .map(|s| s.to_string())
+            .with(|data| data.error_string(element.id).map(|s| s.to_string()))
     });
     let show_password = create_rw_signal(false);
 
+    let ids = element.ids();
+    let error_id = ids.error.clone();
+
     view! {
         <div class="relative">
             <input
+                id=ids.input.clone()
+                aria-labelledby=ids.label.clone()
+                aria-describedby=ids.describe(error)
+                aria-invalid=move || error.get().is_some().to_string()
                 {..attrs}
                 type=move || if show_password.get() { "text" } else { "password" }
                 class=move || {
@@ -128,11 +142,14 @@ pub fn InputPassword(
             <button
                 type="button"
                 class="absolute top-0 end-0 p-3.5 rounded-e-md dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                class:hidden=no_reveal
+                aria-pressed=move || show_password.get().to_string()
                 on:click=move |_| {
                     show_password.update(|v| *v = !*v);
                 }
             >
 
+                <span class="sr-only">Show password</span>
                 <svg
                     class=move || {
                         let color = if error.get().is_none() { "gray-400" } else { "red-500" };
@@ -187,11 +204,24 @@ pub fn InputPassword(
             </button>
         </div>
 
+        <Show when=move || generate && !disabled.get()>
+            <button
+                type="button"
+                class="mt-2 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:text-blue-500"
+                on:click=move |_| {
+                    element.data.update(|data| data.generate_secret(element.id));
+                    show_password.set(!no_reveal);
+                }
+            >
+                "Generate"
+            </button>
+        </Show>
+
         {move || {
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! { <p id=error_id.clone() class="text-xs text-red-600 mt-2">{error}</p> }
                 })
         }}
     }
@@ -209,9 +239,7 @@ pub fn InputSize(
     let value = create_memo(move |_| {
         element
             .data
-            .get()
-            .value::<u64>(element.id)
-            .unwrap_or_default()
+            .with(|data| data.value::<u64>(element.id).unwrap_or_default())
     });
 
     let multiplier = create_memo(move |_| {
@@ -237,14 +265,19 @@ pub fn InputSize(
     let error = create_memo(move |_| {
         element
             .data
-            .get()
-            .error_string(element.id)
-            .map(|s| s.to_string())
+            .with(|data| data.error_string(element.id).map(|s| s.to_string()))
     });
 
+    let ids = element.ids();
+    let error_id = ids.error.clone();
+
     view! {
         <div class="relative">
             <input
+                id=ids.input.clone()
+                aria-labelledby=ids.label.clone()
+                aria-describedby=ids.describe(error)
+                aria-invalid=move || error.get().is_some().to_string()
                 type="text"
                 class="py-2 px-3 block w-full border-gray-200 shadow-sm rounded-lg text-sm focus:z-10 focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
                 prop:value=move || {
@@ -278,6 +311,7 @@ pub fn InputSize(
 
             <div class="absolute inset-y-0 end-0 flex items-center text-gray-500 pe-px">
                 <select
+                    aria-label="Unit"
                     class="block text-xs w-full border-transparent rounded-lg focus:ring-blue-600 focus:border-blue-600 dark:bg-gray-800"
                     on:change=move |ev| {
                         element
@@ -319,7 +353,7 @@ pub fn InputSize(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! { <p id=error_id.clone() class="text-xs text-red-600 mt-2">{error}</p> }
                 })
         }}
     }
@@ -331,103 +365,192 @@ pub fn InputDuration(
     #[prop(optional, into)] disabled: MaybeSignal<bool>,
     #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
 ) -> impl IntoView {
-    let value = create_memo(move |_| {
+    let raw = create_memo(move |_| {
         element
             .data
-            .get()
-            .value::<Duration>(element.id)
-            .unwrap_or_default()
+            .with(|data| data.value::<String>(element.id).unwrap_or_default())
     });
+    let value = create_memo(move |_| raw.with(|raw| raw.parse::<Duration>().unwrap_or_default()));
     let error = create_memo(move |_| {
         element
             .data
-            .get()
-            .error_string(element.id)
-            .map(|s| s.to_string())
+            .with(|data| data.error_string(element.id).map(|s| s.to_string()))
+    });
+    // Values that do not fit an amount and a unit, such as "1h30m", can
+    // only be edited as text
+    let is_complex = create_memo(move |_| {
+        raw.with(|raw| !raw.is_empty() && raw != "false" && raw.parse::<Duration>().is_err())
     });
+    let text_mode = create_rw_signal(false);
+    let show_text = move || text_mode.get() || is_complex.get();
+
+    let ids = element.ids();
+    let error_id = ids.error.clone();
+    let text_ids = ids.clone();
+    let text_attrs = attrs.clone();
 
     view! {
-        <div class="relative">
+        <Show
+            when=show_text
+            fallback=move || {
+                view! {
+                    <div class="relative">
+                        <input
+                            id=ids.input.clone()
+                            aria-labelledby=ids.label.clone()
+                            aria-describedby=ids.describe(error)
+                            aria-invalid=move || error.get().is_some().to_string()
+                            type="text"
+                            inputmode="numeric"
+                            class="py-2 px-3 block w-full border-gray-200 shadow-sm rounded-lg text-sm focus:z-10 focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                            prop:value=move || { value.get().value }
+
+                            on:change=move |ev| {
+                                element
+                                    .data
+                                    .update(|data| {
+                                        let amount = event_target_value(&ev);
+                                        match amount.trim().parse::<u64>() {
+                                            Ok(new_value) if new_value > 0 => {
+                                                data.update(
+                                                    element.id,
+                                                    value.get().value(new_value).to_string(),
+                                                );
+                                            }
+                                            // A cleared amount leaves the field empty, so
+                                            // required fields report it on save
+                                            _ if amount.trim().is_empty() => {
+                                                data.remove(element.id);
+                                            }
+                                            _ => {
+                                                data.new_error(element.id, "Invalid duration".to_string());
+                                            }
+                                        }
+                                    });
+                            }
+
+                            {..attrs.clone()}
+                            disabled=move || { disabled.get() || value.get().unit.is_empty() }
+                        />
+
+                        <div class="absolute inset-y-0 end-0 flex items-center text-gray-500 pe-px">
+                            <select
+                                aria-label="Unit"
+                                class="block text-xs w-full border-transparent rounded-lg focus:ring-blue-600 focus:border-blue-600 dark:bg-gray-800"
+                                on:change=move |ev| {
+                                    element
+                                        .data
+                                        .update(|data| {
+                                            let unit = event_target_value(&ev);
+                                            if !unit.is_empty() {
+                                                let mut value = value.get();
+                                                if value.value.is_empty() {
+                                                    value.value = "1".to_string();
+                                                }
+                                                value.unit = unit;
+                                                data.update(element.id, value.to_string());
+                                            } else if data.allows_disabled(element.id) {
+                                                data.update(element.id, "false");
+                                            } else {
+                                                data.remove(element.id);
+                                            }
+                                        });
+                                }
+
+                                disabled=move || disabled.get()
+                            >
+
+                                <option selected=move || value.get().unit.is_empty() value="">
+                                    None
+                                </option>
+                                <option selected=move || value.get().unit == "ms" value="ms">
+                                    ms
+                                </option>
+                                <option selected=move || value.get().unit == "s" value="s">
+                                    seconds
+                                </option>
+                                <option selected=move || value.get().unit == "m" value="m">
+                                    minutes
+                                </option>
+                                <option selected=move || value.get().unit == "h" value="h">
+                                    hours
+                                </option>
+                                <option selected=move || value.get().unit == "d" value="d">
+                                    days
+                                </option>
+                            </select>
+                        </div>
+                    </div>
+                }
+            }
+        >
+
             <input
+                id=text_ids.input.clone()
+                aria-labelledby=text_ids.label.clone()
+                aria-describedby=text_ids.describe(error)
+                aria-invalid=move || error.get().is_some().to_string()
                 type="text"
+                autocapitalize="off"
+                placeholder="1h30m"
                 class="py-2 px-3 block w-full border-gray-200 shadow-sm rounded-lg text-sm focus:z-10 focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
-                prop:value=move || { value.get().value }
-
+                prop:value=move || raw.get()
                 on:change=move |ev| {
                     element
                         .data
                         .update(|data| {
-                            match event_target_value(&ev).trim().parse::<u64>() {
-                                Ok(new_value) if new_value > 0 => {
-                                    data.update(
-                                        element.id,
-                                        value.get().value(new_value).to_string(),
-                                    );
-                                }
-                                _ => {
-                                    data.new_error(element.id, "Invalid duration".to_string());
-                                }
+                            let value = event_target_value(&ev);
+                            let value = value.trim();
+                            if value.is_empty() {
+                                data.remove(element.id);
+                            } else {
+                                // Stored in the compact form when it can be
+                                // parsed, otherwise kept for the validator
+                                data.update(
+                                    element.id,
+                                    Duration::millis(value)
+                                        .map(|millis| Duration::from_millis(millis).to_string())
+                                        .unwrap_or_else(|| value.to_string()),
+                                );
                             }
                         });
                 }
 
-                {..attrs}
-                disabled=move || { disabled.get() || value.get().unit.is_empty() }
+                {..text_attrs.clone()}
+                disabled=move || disabled.get()
             />
-
-            <div class="absolute inset-y-0 end-0 flex items-center text-gray-500 pe-px">
-                <select
-                    class="block text-xs w-full border-transparent rounded-lg focus:ring-blue-600 focus:border-blue-600 dark:bg-gray-800"
-                    on:change=move |ev| {
+        </Show>
+
+        <button
+            type="button"
+            class="mt-1 text-xs text-blue-600 decoration-2 hover:underline disabled:opacity-50 disabled:pointer-events-none dark:text-blue-500"
+            disabled=move || disabled.get()
+            on:click=move |_| {
+                if !show_text() {
+                    text_mode.set(true);
+                } else {
+                    // Complex values are converted when they add up to a
+                    // whole amount of a single unit
+                    if let Some(millis) = raw.with_untracked(|raw| Duration::millis(raw)) {
                         element
                             .data
                             .update(|data| {
-                                let unit = event_target_value(&ev);
-                                if !unit.is_empty() {
-                                    let mut value = value.get();
-                                    if value.value.is_empty() {
-                                        value.value = "1".to_string();
-                                    }
-                                    value.unit = unit;
-                                    data.update(element.id, value.to_string());
-                                } else if data.is_required(element.id) {
-                                    data.remove(element.id);
-                                } else {
-                                    data.update(element.id, "false");
-                                }
+                                data.update(element.id, Duration::from_millis(millis).to_string());
                             });
                     }
+                    text_mode.set(false);
+                }
+            }
+        >
 
-                    disabled=move || disabled.get()
-                >
-
-                    <option selected=move || value.get().unit.is_empty() value="">
-                        None
-                    </option>
-                    <option selected=move || value.get().unit == "ms" value="ms">
-                        ms
-                    </option>
-                    <option selected=move || value.get().unit == "s" value="s">
-                        seconds
-                    </option>
-                    <option selected=move || value.get().unit == "m" value="m">
-                        minutes
-                    </option>
-                    <option selected=move || value.get().unit == "h" value="h">
-                        hours
-                    </option>
-                    <option selected=move || value.get().unit == "d" value="d">
-                        days
-                    </option>
-                </select>
-            </div>
-        </div>
+            {move || if show_text() { "Use amount and unit" } else { "Enter as text" }}
+        </button>
 
         {move || {
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! { <p id=error_id.clone() class="text-xs text-red-600 mt-2">{error}</p> }
                 })
         }}
     }
@@ -442,22 +565,25 @@ pub fn InputRate(
     let value = create_memo(move |_| {
         element
             .data
-            .get()
-            .value::<Rate>(element.id)
-            .unwrap_or_default()
+            .with(|data| data.value::<Rate>(element.id).unwrap_or_default())
     });
     let error = create_memo(move |_| {
         element
             .data
-            .get()
-            .error_string(element.id)
-            .map(|s| s.to_string())
+            .with(|data| data.error_string(element.id).map(|s| s.to_string()))
     });
 
+    let ids = element.ids();
+    let error_id = ids.error.clone();
+
     view! {
         <div class="relative">
             <div class="sm:flex rounded-lg shadow-sm">
                 <input
+                    id=ids.input.clone()
+                    aria-labelledby=ids.label.clone()
+                    aria-describedby=ids.describe(error)
+                    aria-invalid=move || error.get().is_some().to_string()
                     type="text"
                     class="py-2 px-3 block w-full border-gray-200 shadow-sm rounded-lg text-sm focus:z-10 focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
                     prop:value=move || { value.get().amount }
@@ -492,6 +618,7 @@ pub fn InputRate(
                     <IconClock attr:class="mx-auto size-4 text-gray-400"/>
                 </span>
                 <input
+                    aria-label="Period"
                     type="text"
                     class="py-2 px-3 block w-full border-gray-200 shadow-sm rounded-lg text-sm focus:z-10 focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
                     prop:value=move || { value.get().period.value }
@@ -520,6 +647,7 @@ pub fn InputRate(
 
                 <div class="absolute inset-y-0 end-0 flex items-center text-gray-500 pe-px">
                     <select
+                        aria-label="Unit"
                         class="block text-xs w-full border-transparent rounded-lg focus:ring-blue-600 focus:border-blue-600 dark:bg-gray-800"
                         on:change=move |ev| {
                             element
@@ -574,7 +702,7 @@ pub fn InputRate(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! { <p id=error_id.clone() class="text-xs text-red-600 mt-2">{error}</p> }
                 })
         }}
     }
@@ -584,22 +712,27 @@ pub fn InputRate(
 pub fn InputSwitch(
     element: FormElement,
     #[prop(optional, into)] label: Option<MaybeSignal<String>>,
-    #[prop(optional)] tooltip: Option<&'static str>,
+    #[prop(optional, into)] tooltip: MaybeSignal<String>,
     #[prop(optional, into)] disabled: MaybeSignal<bool>,
     #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
 ) -> impl IntoView {
     let value = create_memo(move |_| {
         element
             .data
-            .get()
-            .value::<bool>(element.id)
-            .unwrap_or_default()
+            .with(|data| data.value::<bool>(element.id).unwrap_or_default())
     });
 
+    let ids = element.ids();
+    let input_id = ids.input.clone();
+
     view! {
         <div class="flex items-center">
             <input
+                id=ids.input.clone()
                 type="checkbox"
+                role="switch"
+                aria-labelledby=ids.label.clone()
+                aria-checked=move || value.get().to_string()
                 {..attrs}
                 class="relative w-11 h-6 p-px bg-gray-100 border-transparent text-transparent rounded-full cursor-pointer transition-colors ease-in-out duration-200 focus:ring-blue-600 disabled:opacity-50 disabled:pointer-events-none checked:bg-none checked:text-blue-600 checked:border-blue-600 focus:checked:border-blue-600 dark:bg-gray-800 dark:border-gray-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-600 before:inline-block before:size-5 before:bg-white checked:before:bg-blue-200 before:translate-x-0 checked:before:translate-x-full before:rounded-full before:shadow before:transform before:ring-0 before:transition before:ease-in-out before:duration-200 dark:before:bg-gray-400 dark:checked:before:bg-blue-200"
                 prop:checked=move || value.get()
@@ -617,18 +750,24 @@ pub fn InputSwitch(
             {label
                 .map(|label| {
                     view! {
-                        <label class="text-sm text-gray-500 ms-3 dark:text-gray-400">
+                        <label
+                            for=input_id.clone()
+                            class="text-sm text-gray-500 ms-3 dark:text-gray-400"
+                        >
                             {label.get()}
                         </label>
                     }
                 })}
 
-            {tooltip
-                .filter(|s| !s.is_empty())
-                .map(|tooltip| {
+            {(!matches!(&tooltip, MaybeSignal::Static(s) if s.is_empty()))
+                .then(|| {
+                    let tooltip = create_memo(move |_| tooltip.get());
                     let is_mouse_over = create_rw_signal(false);
                     view! {
-                        <div class="hs-tooltip inline-block">
+                        <div
+                            class="hs-tooltip inline-block"
+                            class:hidden=move || tooltip.with(|tooltip| tooltip.is_empty())
+                        >
                             <button
                                 type="button"
                                 class="hs-tooltip-toggle ms-1"
@@ -639,8 +778,17 @@ pub fn InputSwitch(
                                 on:mouseleave=move |_| {
                                     is_mouse_over.set(false);
                                 }
+
+                                on:focus=move |_| {
+                                    is_mouse_over.set(true);
+                                }
+
+                                on:blur=move |_| {
+                                    is_mouse_over.set(false);
+                                }
                             >
 
+                                <span class="sr-only">Help</span>
                                 <IconInfo
                                     size=16
                                     attr:stroke-width="1"
@@ -653,7 +801,7 @@ pub fn InputSwitch(
                                 class:hidden=move || !is_mouse_over.get()
                                 class:show=move || is_mouse_over.get()
                             >
-                                {tooltip}
+                                {move || tooltip.get()}
                             </span>
 
                         </div>
@@ -674,21 +822,24 @@ pub fn TextArea(
     let value = create_memo(move |_| {
         element
             .data
-            .get()
-            .value::<String>(element.id)
-            .unwrap_or_default()
+            .with(|data| data.value::<String>(element.id).unwrap_or_default())
     });
     let error = create_memo(move |_| {
         element
             .data
-            .get()
-            .error_string(element.id)
-            .map(|s| s.to_string())
+            .with(|data| data.error_string(element.id).map(|s| s.to_string()))
     });
 
+    let ids = element.ids();
+    let error_id = ids.error.clone();
+
     view! {
         <div class="relative">
             <textarea
+                id=ids.input.clone()
+                aria-labelledby=ids.label.clone()
+                aria-describedby=ids.describe(error)
+                aria-invalid=move || error.get().is_some().to_string()
                 {..attrs}
                 class=move || {
                     if error.get().is_none() {
@@ -723,7 +874,7 @@ pub fn TextArea(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! { <p id=error_id.clone() class="text-xs text-red-600 mt-2">{error}</p> }
                 })
         }}
     }
@@ -739,26 +890,12 @@ impl FromStr for Duration {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut duration = Duration::default();
-
-        for c in s.chars() {
-            match c {
-                '0'..='9' => duration.value.push(c),
-                'a'..='z' | 'A'..='Z' => duration.unit.push(c.to_ascii_lowercase()),
-                c => {
-                    if !c.is_ascii_whitespace() {
-                        return Err(());
-                    }
-                }
-            }
-        }
-
-        if !duration.value.is_empty()
-            && ["ms", "s", "m", "h", "d"].contains(&duration.unit.as_str())
-        {
-            Ok(duration)
-        } else {
-            Err(())
+        match duration_parts(s).ok_or(())?.as_slice() {
+            [(amount, unit)] => Ok(Duration {
+                value: amount.to_string(),
+                unit: unit.to_string(),
+            }),
+            _ => Err(()),
         }
     }
 }
@@ -821,6 +958,22 @@ impl Duration {
         self
     }
 
+    pub fn millis(value: &str) -> Option<u64> {
+        parse_millis(value)
+    }
+
+    // Uses the largest unit the duration is a whole amount of
+    pub fn from_millis(millis: u64) -> Self {
+        DURATION_UNITS
+            .iter()
+            .find(|(_, unit)| millis > 0 && millis % unit == 0)
+            .map(|(id, unit)| Duration {
+                value: (millis / unit).to_string(),
+                unit: id.to_string(),
+            })
+            .unwrap_or_default()
+    }
+
     pub fn format(&self) -> Option<String> {
         if !self.value.is_empty() && !self.unit.is_empty() {
             Some(format!(
@@ -11,12 +11,20 @@ pub mod select;
 pub mod stacked_badge;
 pub mod stacked_duration;
 pub mod stacked_input;
+pub mod stacked_map;
 pub mod tab;
+pub mod unsaved;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use leptos::*;
+use web_sys::wasm_bindgen::JsCast;
 
 use crate::{
-    components::{icon::IconInfo, messages::alert::Alerts},
+    components::{
+        icon::{IconBookOpen, IconInfo},
+        messages::alert::Alerts,
+    },
     core::form::FormData,
 };
 
@@ -28,10 +36,137 @@ pub struct FormElement {
 
 pub type ValidateCb = Callback<Result<String, String>, ()>;
 
+// Element ids used to wire labels, help and error text to form controls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormItemIds {
+    pub input: String,
+    pub label: Option<String>,
+    pub help: Option<String>,
+    pub error: String,
+}
+
+static NEXT_ITEM_ID: AtomicUsize = AtomicUsize::new(0);
+
+impl FormItemIds {
+    fn new(has_help: bool) -> Self {
+        let id = NEXT_ITEM_ID.fetch_add(1, Ordering::Relaxed);
+        FormItemIds {
+            input: format!("form-item-{id}"),
+            label: Some(format!("form-item-{id}-label")),
+            help: has_help.then(|| format!("form-item-{id}-help")),
+            error: format!("form-item-{id}-error"),
+        }
+    }
+
+    pub fn describe(&self, error: Memo<Option<String>>) -> impl Fn() -> Option<String> + 'static {
+        let ids = self.clone();
+        move || ids.described_by(error.with(|error| error.is_some()))
+    }
+
+    pub fn item(&self, idx: usize) -> String {
+        format!("{}-item-{idx}", self.input)
+    }
+
+    pub fn item_error(&self, idx: usize) -> String {
+        format!("{}-item-{idx}-error", self.input)
+    }
+
+    pub fn described_by(&self, has_error: bool) -> Option<String> {
+        match (&self.help, has_error) {
+            (Some(help), true) => Some(format!("{help} {}", self.error)),
+            (Some(help), false) => Some(help.clone()),
+            (None, true) => Some(self.error.clone()),
+            (None, false) => None,
+        }
+    }
+}
+
+impl FormElement {
+    pub fn ids(&self) -> FormItemIds {
+        use_context::<FormItemIds>().unwrap_or_else(|| FormItemIds {
+            input: format!("field-{}", self.id),
+            label: None,
+            help: None,
+            error: format!("field-{}-error", self.id),
+        })
+    }
+}
+
+// Returns the new position of an array item moved with Alt+Arrow keys
+pub(crate) fn reorder_target(
+    ev: &ev::KeyboardEvent,
+    idx: usize,
+    len: usize,
+    back: &str,
+    forward: &str,
+) -> Option<usize> {
+    reorder_position(ev.alt_key(), &ev.key(), idx, len, back, forward)
+}
+
+fn reorder_position(
+    alt_key: bool,
+    key: &str,
+    idx: usize,
+    len: usize,
+    back: &str,
+    forward: &str,
+) -> Option<usize> {
+    if !alt_key {
+        None
+    } else if key == back {
+        idx.checked_sub(1)
+    } else if key == forward {
+        (idx + 1 < len).then_some(idx + 1)
+    } else {
+        None
+    }
+}
+
+// Focuses an element once the pending DOM updates have been rendered
+pub(crate) fn focus_by_id(id: String) {
+    request_animation_frame(move || {
+        if let Some(element) = document()
+            .get_element_by_id(&id)
+            .and_then(|element| element.dyn_into::<web_sys::HtmlElement>().ok())
+        {
+            let _ = element.focus();
+        }
+    });
+}
+
+// Scrolls to a form field and focuses its first input
+pub(crate) fn focus_field(field_id: &str) {
+    let id = format!("field-anchor-{field_id}");
+    request_animation_frame(move || {
+        if let Some(anchor) = document().get_element_by_id(&id) {
+            anchor.scroll_into_view();
+            if let Some(input) = anchor
+                .parent_element()
+                .and_then(|parent| parent.query_selector("input, textarea, select").ok())
+                .flatten()
+                .and_then(|element| element.dyn_into::<web_sys::HtmlElement>().ok())
+            {
+                let _ = input.focus();
+            }
+        }
+    });
+}
+
+// Scrolls an element into view once the pending DOM updates have been rendered
+pub(crate) fn scroll_to_id(id: String) {
+    request_animation_frame(move || {
+        if let Some(element) = document().get_element_by_id(&id) {
+            element.scroll_into_view();
+        }
+    });
+}
+
 #[component]
 pub fn Form(
     #[prop(optional, into)] title: MaybeSignal<String>,
     #[prop(optional, into)] subtitle: MaybeSignal<String>,
+    #[prop(optional, into)] on_submit: Option<Callback<(), ()>>,
+    #[prop(optional, into)] doc_url: MaybeSignal<Option<String>>,
     children: Children,
 ) -> impl IntoView {
     let title_ = title.clone();
@@ -42,13 +177,37 @@ pub fn Form(
                 <div class="mb-8" class:hidden=move || title_.get().is_empty()>
                     <h2 class="text-xl font-bold text-gray-800 dark:text-gray-200">
                         {move || title.get()}
+                        {move || {
+                            doc_url
+                                .get()
+                                .map(|doc_url| {
+                                    view! {
+                                        <a
+                                            href=doc_url
+                                            target="_blank"
+                                            rel="noopener noreferrer"
+                                            class="ms-2 inline-block align-middle text-gray-400 hover:text-blue-600 dark:text-gray-600 dark:hover:text-blue-500"
+                                            title="Documentation"
+                                        >
+                                            <span class="sr-only">Documentation</span>
+                                            <IconBookOpen attr:class="size-5"/>
+                                        </a>
+                                    }
+                                })
+                        }}
+
                     </h2>
                     <p class="text-sm text-gray-600 dark:text-gray-400">{move || subtitle.get()}</p>
                 </div>
 
                 <Alerts/>
 
-                <form>{children()}</form>
+                <form on:submit=move |ev| {
+                    ev.prevent_default();
+                    if let Some(on_submit) = on_submit {
+                        on_submit.call(());
+                    }
+                }>{children()}</form>
             </div>
         </div>
     }
@@ -95,21 +254,30 @@ pub fn FormSection(
 #[component]
 pub fn FormItem(
     #[prop(into)] label: MaybeSignal<String>,
-    #[prop(optional)] tooltip: Option<&'static str>,
+    #[prop(optional, into)] tooltip: MaybeSignal<String>,
+    #[prop(optional_no_strip)] doc_url: Option<String>,
     #[prop(optional, into)] hide: MaybeSignal<bool>,
     #[prop(optional, into)] is_optional: MaybeSignal<bool>,
     #[prop(optional)] stacked: bool,
     children: Children,
 ) -> impl IntoView {
-    let tooltip = tooltip
-    .filter(|s| !s.is_empty())
-    .map(|tooltip| {
+    // Help that depends on other fields is kept in place while empty
+    let has_tooltip = !matches!(&tooltip, MaybeSignal::Static(s) if s.is_empty());
+    let ids = FormItemIds::new(has_tooltip);
+    provide_context(ids.clone());
+    let help_id = ids.help.clone();
+    let tooltip = has_tooltip.then(|| {
+        let tooltip = create_memo(move |_| tooltip.get());
         let is_mouse_over = create_rw_signal(false);
         view! {
-            <div class="hs-tooltip inline-block">
+            <div
+                class="hs-tooltip inline-block"
+                class:hidden=move || tooltip.with(|tooltip| tooltip.is_empty())
+            >
                 <button
                     type="button"
                     class="hs-tooltip-toggle ms-1"
+                    aria-describedby=help_id.clone()
                     on:mouseover=move |_| {
                         is_mouse_over.set(true);
                     }
@@ -117,8 +285,17 @@ pub fn FormItem(
                     on:mouseleave=move |_| {
                         is_mouse_over.set(false);
                     }
+
+                    on:focus=move |_| {
+                        is_mouse_over.set(true);
+                    }
+
+                    on:blur=move |_| {
+                        is_mouse_over.set(false);
+                    }
                 >
 
+                    <span class="sr-only">Help</span>
                     <IconInfo
                         size=16
                         attr:stroke-width="1"
@@ -126,18 +303,34 @@ pub fn FormItem(
                     />
                 </button>
                 <span
+                    id=help_id
                     class="hs-tooltip-content hs-tooltip-shown:opacity-100 hs-tooltip-shown:visible opacity-70 transition-opacity inline-block absolute w-40 text-center z-10 py-1 px-2 bg-gray-900 text-xs font-medium text-white rounded shadow-sm dark:bg-slate-700"
                     role="tooltip"
                     class:hidden=move || !is_mouse_over.get()
                     class:show=move || is_mouse_over.get()
                 >
-                    {tooltip}
+                    {move || tooltip.get()}
                 </span>
 
             </div>
         }
     });
 
+    let doc_url = doc_url.map(|doc_url| {
+        view! {
+            <a
+                href=doc_url
+                target="_blank"
+                rel="noopener noreferrer"
+                class="ms-1 inline-block text-gray-400 hover:text-blue-600 dark:text-gray-600 dark:hover:text-blue-500"
+                title="Documentation"
+            >
+                <span class="sr-only">Documentation</span>
+                <IconBookOpen size=16 attr:stroke-width="1" attr:class="inline-block size-3"/>
+            </a>
+        }
+    });
+
     let is_optional = move || {
         if is_optional.get() {
             Some(
@@ -151,10 +344,15 @@ pub fn FormItem(
     if !stacked {
         view! {
             <div class="sm:col-span-3" class:hidden=move || hide.get()>
-                <label class="inline-block text-sm text-gray-800 mt-2.5 dark:text-gray-200">
+                <label
+                    id=ids.label.clone()
+                    for=ids.input.clone()
+                    class="inline-block text-sm text-gray-800 mt-2.5 dark:text-gray-200"
+                >
                     {label}
                 </label>
                 {tooltip}
+                {doc_url}
                 {is_optional}
 
             </div>
@@ -166,7 +364,13 @@ pub fn FormItem(
     } else {
         view! {
             <div class="mb-4 sm:mb-8" class:hidden=move || hide.get()>
-                <label class="block mb-2 text-sm font-medium dark:text-white">{label}</label>
+                <label
+                    id=ids.label.clone()
+                    for=ids.input.clone()
+                    class="block mb-2 text-sm font-medium dark:text-white"
+                >
+                    {label}
+                </label>
                 <div class="relative">{children()}</div>
             </div>
         }
@@ -179,3 +383,67 @@ impl FormElement {
         FormElement { id, data }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn described_by_lists_help_then_error() {
+        let ids = FormItemIds::new(true);
+        let help = ids.help.clone().unwrap();
+        assert_eq!(ids.described_by(false), Some(help.clone()));
+        assert_eq!(
+            ids.described_by(true),
+            Some(format!("{help} {}", ids.error))
+        );
+
+        let ids = FormItemIds::new(false);
+        assert_eq!(ids.help, None);
+        assert_eq!(ids.described_by(false), None);
+        assert_eq!(ids.described_by(true), Some(ids.error.clone()));
+    }
+
+    #[test]
+    fn item_ids_are_unique() {
+        let first = FormItemIds::new(true);
+        let second = FormItemIds::new(true);
+        assert_ne!(first.input, second.input);
+        assert_ne!(first.error, second.error);
+        assert_ne!(first.label, second.label);
+        assert_ne!(first.help, second.help);
+
+        assert_eq!(first.item(2), format!("{}-item-2", first.input));
+        assert_eq!(first.item_error(2), format!("{}-item-2-error", first.input));
+        assert_ne!(first.item(1), first.item(2));
+        assert_ne!(first.item_error(1), second.item_error(1));
+    }
+
+    #[test]
+    fn reorder_requires_alt_and_stays_in_bounds() {
+        assert_eq!(
+            reorder_position(false, "ArrowUp", 1, 3, "ArrowUp", "ArrowDown"),
+            None
+        );
+        assert_eq!(
+            reorder_position(true, "ArrowUp", 1, 3, "ArrowUp", "ArrowDown"),
+            Some(0)
+        );
+        assert_eq!(
+            reorder_position(true, "ArrowDown", 1, 3, "ArrowUp", "ArrowDown"),
+            Some(2)
+        );
+        assert_eq!(
+            reorder_position(true, "ArrowUp", 0, 3, "ArrowUp", "ArrowDown"),
+            None
+        );
+        assert_eq!(
+            reorder_position(true, "ArrowDown", 2, 3, "ArrowUp", "ArrowDown"),
+            None
+        );
+        assert_eq!(
+            reorder_position(true, "ArrowLeft", 1, 3, "ArrowUp", "ArrowDown"),
+            None
+        );
+    }
+}
@@ -5,16 +5,28 @@
  */
 
 use std::{
+    cell::RefCell,
     fmt::{Display, Formatter},
     str::FromStr,
+    sync::Arc,
 };
 
-use ahash::AHashSet;
+use ahash::{AHashMap, AHashSet};
+use chrono::Local;
 use leptos::*;
+use leptos_use::signal_debounced;
 
-use crate::core::{
-    form::FormData,
-    schema::{Field, Schema, Source, Type, Value},
+use crate::{
+    components::{badge::Badge, icon::IconXMark, Color},
+    core::{
+        cron,
+        form::FormData,
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        schema::{Field, Schema, Source, Type, Value},
+        AccessToken,
+    },
+    pages::List,
 };
 
 use super::FormElement;
@@ -26,25 +38,29 @@ pub fn Select(
     #[prop(optional)] options: Option<Memo<Vec<(String, String)>>>,
     #[prop(optional)] add_none: bool,
 ) -> impl IntoView {
-    let options = options
-        .unwrap_or_else(|| create_memo(move |_| element.data.get().select_sources(element.id)));
+    let options = options.unwrap_or_else(|| {
+        create_memo(move |_| element.data.with(|data| data.select_sources(element.id)))
+    });
     let value = create_memo(move |_| {
         element
             .data
-            .get()
-            .value::<String>(element.id)
-            .unwrap_or_default()
+            .with(|data| data.value::<String>(element.id).unwrap_or_default())
     });
     let error = create_memo(move |_| {
         element
             .data
-            .get()
-            .error_string(element.id)
-            .map(|s| s.to_string())
+            .with(|data| data.error_string(element.id).map(|s| s.to_string()))
     });
 
+    let ids = element.ids();
+    let error_id = ids.error.clone();
+
     view! {
         <select
+            id=ids.input.clone()
+            aria-labelledby=ids.label.clone()
+            aria-describedby=ids.describe(error)
+            aria-invalid=move || error.get().is_some().to_string()
             class=move || {
                 if error.get().is_none() {
                     "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -87,38 +103,457 @@ pub fn Select(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! { <p id=error_id.clone() class="text-xs text-red-600 mt-2">{error}</p> }
                 })
         }}
     }
 }
 
+const COMBOBOX_ROW_HEIGHT: usize = 36;
+const COMBOBOX_VISIBLE_ROWS: usize = 8;
+const COMBOBOX_OVERSCAN: usize = 4;
+
+// Remote sources are searched once typing pauses, returning this many options
+const REMOTE_SEARCH_DELAY_MS: f64 = 300.0;
+const REMOTE_SEARCH_LIMIT: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RemoteSource {
+    pub endpoint: &'static str,
+    pub id_field: &'static str,
+    pub label_field: &'static str,
+}
+
+thread_local! {
+    // Search results by endpoint and query, kept for the rest of the session
+    static REMOTE_OPTIONS: RefCell<AHashMap<(&'static str, String), Vec<(String, String)>>> =
+        RefCell::new(AHashMap::new());
+}
+
 #[component]
-pub fn CheckboxGroup(
+pub fn ComboBox(
     element: FormElement,
     #[prop(optional, into)] disabled: MaybeSignal<bool>,
     #[prop(optional)] options: Option<Memo<Vec<(String, String)>>>,
+    #[prop(optional)] multiple: bool,
+    #[prop(optional)] allow_custom: bool,
 ) -> impl IntoView {
-    let options = options
-        .unwrap_or_else(|| create_memo(move |_| element.data.get().select_sources(element.id)));
+    let query = create_rw_signal(String::new());
+    let remote = element
+        .data
+        .with_untracked(|data| data.remote_source(element.id));
+    let remote_options = remote.map(|remote| {
+        let auth = use_authorization();
+        let search = signal_debounced(query, REMOTE_SEARCH_DELAY_MS);
+        create_local_resource(
+            move || search.get().trim().to_string(),
+            move |search| {
+                let auth = auth.get_untracked();
+                async move { fetch_remote_options(&auth, remote, search).await }
+            },
+        )
+    });
+    let options = options.unwrap_or_else(|| {
+        create_memo(move |_| {
+            let found = remote_options
+                .and_then(|options| options.get())
+                .unwrap_or_default();
+            element.data.with(|data| {
+                let mut options = data.select_sources(element.id);
+                if let Some(remote) = remote {
+                    // Saved values are labelled from earlier searches
+                    for (id, label) in options.iter_mut() {
+                        if let Some(found) = remote_label(remote, id) {
+                            *label = found;
+                        }
+                    }
+                    options.retain(|(id, _)| !found.iter().any(|(found, _)| found == id));
+                    return found.into_iter().chain(options).collect();
+                }
+                options
+            })
+        })
+    });
     let values = create_memo(move |_| {
+        element.data.with(|data| {
+            data.array_value(element.id)
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+        })
+    });
+    let is_required = create_memo(move |_| element.data.with(|data| data.is_required(element.id)));
+    let error = create_memo(move |_| {
         element
             .data
+            .with(|data| data.error_string(element.id).map(|s| s.to_string()))
+    });
+    let is_open = create_rw_signal(false);
+    let highlighted = create_rw_signal(0usize);
+    let scroll_top = create_rw_signal(0usize);
+    let list_ref = create_node_ref::<html::Div>();
+
+    let filtered = create_memo(move |_| {
+        let query = query.get().to_lowercase();
+        let values = values.get();
+        options
             .get()
-            .array_value(element.id)
-            .map(|s| s.to_string())
-            .collect::<AHashSet<_>>()
+            .into_iter()
+            .filter(|(id, label)| {
+                !id.is_empty()
+                    && (!multiple || !values.contains(id))
+                    && (remote.is_some()
+                        || query.is_empty()
+                        || label.to_lowercase().contains(&query)
+                        || id.to_lowercase().contains(&query))
+            })
+            .collect::<Vec<_>>()
+    });
+    let window = create_memo(move |_| {
+        let total = filtered.with(|f| f.len());
+        let start = (scroll_top.get() / COMBOBOX_ROW_HEIGHT).saturating_sub(COMBOBOX_OVERSCAN);
+        let end = (start + COMBOBOX_VISIBLE_ROWS + 2 * COMBOBOX_OVERSCAN).min(total);
+        (start.min(end), end)
+    });
+    let selected_label = create_memo(move |_| {
+        values.with(|values| {
+            values
+                .first()
+                .filter(|_| !multiple)
+                .map(|value| {
+                    options.with(|options| {
+                        options
+                            .iter()
+                            .find(|(id, _)| id == value)
+                            .map(|(_, label)| label.clone())
+                            .unwrap_or_else(|| value.clone())
+                    })
+                })
+                .unwrap_or_default()
+        })
+    });
+
+    let open = move || {
+        if !disabled.get_untracked() {
+            query.set(String::new());
+            highlighted.set(0);
+            scroll_top.set(0);
+            if let Some(list) = list_ref.get_untracked() {
+                list.set_scroll_top(0);
+            }
+            is_open.set(true);
+        }
+    };
+    let select = move |id: String| {
+        element.data.update(|data| {
+            if multiple {
+                data.array_push(element.id, id, true);
+            } else {
+                data.update(element.id, id);
+            }
+        });
+        query.set(String::new());
+        highlighted.set(0);
+        if !multiple {
+            is_open.set(false);
+        }
+    };
+    let scroll_to = move |idx: usize| {
+        if let Some(list) = list_ref.get_untracked() {
+            let top = (idx * COMBOBOX_ROW_HEIGHT) as i32;
+            let height = list.client_height();
+            if top < list.scroll_top() {
+                list.set_scroll_top(top);
+            } else if top + COMBOBOX_ROW_HEIGHT as i32 > list.scroll_top() + height {
+                list.set_scroll_top(top + COMBOBOX_ROW_HEIGHT as i32 - height);
+            }
+        }
+    };
+
+    let ids = element.ids();
+    let error_id = ids.error.clone();
+
+    view! {
+        <div class="relative">
+            <Show when=move || multiple>
+                <div class="flex flex-wrap gap-2 pb-2" class:hidden=move || values.get().is_empty()>
+                    <For
+                        each=move || values.get().into_iter().enumerate().collect::<Vec<_>>()
+                        key=move |(idx, item)| format!("{idx}_{item}")
+                        children=move |(_, item)| {
+                            let label = options
+                                .with_untracked(|options| {
+                                    options
+                                        .iter()
+                                        .find(|(id, _)| id == &item)
+                                        .map(|(_, label)| label.clone())
+                                })
+                                .unwrap_or_else(|| item.clone());
+                            view! {
+                                <Badge color=Color::Blue large=true>
+                                    {label}
+                                    <button
+                                        type="button"
+                                        class="flex-shrink-0 size-4 inline-flex items-center justify-center rounded-full hover:bg-blue-200 focus:outline-none focus:bg-blue-200 focus:text-blue-500 dark:hover:bg-blue-900"
+                                        disabled=move || disabled.get()
+                                        on:click=move |_| {
+                                            element
+                                                .data
+                                                .update(|data| {
+                                                    data.array_delete_item(element.id, &item);
+                                                });
+                                        }
+                                    >
+
+                                        <span class="sr-only">Remove</span>
+                                        <IconXMark attr:class="flex-shrink-0 size-3"/>
+                                    </button>
+                                </Badge>
+                            }
+                        }
+                    />
+
+                </div>
+            </Show>
+
+            <div class="relative">
+                <input
+                    id=ids.input.clone()
+                    aria-labelledby=ids.label.clone()
+                    aria-describedby=ids.describe(error)
+                    aria-invalid=move || error.get().is_some().to_string()
+                    type="text"
+                    role="combobox"
+                    aria-autocomplete="list"
+                    aria-expanded=move || is_open.get().to_string()
+                    class=move || {
+                        if error.get().is_none() {
+                            "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                        } else {
+                            "py-2 px-3 pe-9 block w-full border-red-500 rounded-lg text-sm focus:border-red-500 focus:ring-red-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                        }
+                    }
+
+                    placeholder=move || {
+                        if multiple || selected_label.get().is_empty() {
+                            "Search..."
+                        } else {
+                            ""
+                        }
+                    }
+
+                    prop:value=move || {
+                        if is_open.get() { query.get() } else { selected_label.get() }
+                    }
+
+                    disabled=move || disabled.get()
+                    on:focus=move |_| open()
+                    on:click=move |_| {
+                        if !is_open.get_untracked() {
+                            open();
+                        }
+                    }
+
+                    on:blur=move |_| is_open.set(false)
+                    on:input=move |ev| {
+                        query.set(event_target_value(&ev));
+                        highlighted.set(0);
+                        scroll_to(0);
+                        is_open.set(true);
+                    }
+
+                    on:keydown=move |ev| {
+                        let total = filtered.with_untracked(|f| f.len());
+                        match ev.key().as_str() {
+                            "ArrowDown" => {
+                                ev.prevent_default();
+                                if !is_open.get_untracked() {
+                                    open();
+                                } else if total > 0 {
+                                    let idx = (highlighted.get_untracked() + 1).min(total - 1);
+                                    highlighted.set(idx);
+                                    scroll_to(idx);
+                                }
+                            }
+                            "ArrowUp" => {
+                                ev.prevent_default();
+                                let idx = highlighted.get_untracked().saturating_sub(1);
+                                highlighted.set(idx);
+                                scroll_to(idx);
+                            }
+                            "Enter" => {
+                                ev.prevent_default();
+                                if is_open.get_untracked() {
+                                    if let Some((id, _)) = filtered
+                                        .with_untracked(|f| f.get(highlighted.get_untracked()).cloned())
+                                    {
+                                        select(id);
+                                    } else if allow_custom {
+                                        let value = query.get_untracked().trim().to_string();
+                                        if !value.is_empty() {
+                                            select(value);
+                                        }
+                                    }
+                                }
+                            }
+                            "Escape" => {
+                                is_open.set(false);
+                            }
+                            _ => {}
+                        }
+                    }
+                />
+
+                <button
+                    type="button"
+                    class="absolute inset-y-0 end-0 flex items-center pe-3 text-gray-400 hover:text-gray-600 dark:text-gray-500"
+                    class:hidden=move || {
+                        multiple || is_required.get() || disabled.get() || values.get().is_empty()
+                    }
+
+                    on:mousedown=move |ev| ev.prevent_default()
+                    on:click=move |_| {
+                        element
+                            .data
+                            .update(|data| {
+                                data.update(element.id, String::new());
+                            });
+                    }
+                >
+
+                    <span class="sr-only">Clear</span>
+                    <IconXMark attr:class="flex-shrink-0 size-4"/>
+                </button>
+
+                <div
+                    node_ref=list_ref
+                    role="listbox"
+                    class="absolute z-20 mt-1 w-full overflow-y-auto bg-white border border-gray-200 rounded-lg shadow-md dark:bg-slate-900 dark:border-gray-700"
+                    class:hidden=move || !is_open.get()
+                    style=format!("max-height: {}px", COMBOBOX_ROW_HEIGHT * COMBOBOX_VISIBLE_ROWS)
+                    on:scroll=move |_| {
+                        if let Some(list) = list_ref.get_untracked() {
+                            scroll_top.set(list.scroll_top().max(0) as usize);
+                        }
+                    }
+                >
+
+                    <div
+                        class="relative"
+                        style=move || {
+                            format!(
+                                "height: {}px",
+                                filtered.with(|f| f.len()).max(1) * COMBOBOX_ROW_HEIGHT,
+                            )
+                        }
+                    >
+
+                        <p
+                            class="px-3 py-2 text-sm text-gray-500 dark:text-gray-400"
+                            class:hidden=move || !filtered.with(|f| f.is_empty())
+                        >
+                            {move || {
+                                let value = query.get();
+                                let value = value.trim();
+                                if remote_options.is_some_and(|options| options.loading().get()) {
+                                    "Searching...".to_string()
+                                } else if allow_custom && !value.is_empty() {
+                                    format!("Press Enter to use \"{value}\"")
+                                } else {
+                                    "No matching options".to_string()
+                                }
+                            }}
+
+                        </p>
+                        {move || {
+                            let (start, end) = window.get();
+                            let current = highlighted.get();
+                            filtered
+                                .with(|filtered| {
+                                    filtered[start..end]
+                                        .iter()
+                                        .enumerate()
+                                        .map(|(offset, (id, label))| {
+                                            let idx = start + offset;
+                                            let id = id.clone();
+                                            view! {
+                                                <div
+                                                    role="option"
+                                                    aria-selected=(idx == current).to_string()
+                                                    class=if idx == current {
+                                                        "absolute inset-x-0 px-3 flex items-center text-sm truncate cursor-pointer bg-gray-100 text-gray-800 dark:bg-slate-800 dark:text-gray-200"
+                                                    } else {
+                                                        "absolute inset-x-0 px-3 flex items-center text-sm truncate cursor-pointer text-gray-800 hover:bg-gray-100 dark:text-gray-400 dark:hover:bg-slate-800"
+                                                    }
+
+                                                    style=format!(
+                                                        "top: {}px; height: {}px",
+                                                        idx * COMBOBOX_ROW_HEIGHT,
+                                                        COMBOBOX_ROW_HEIGHT,
+                                                    )
+
+                                                    on:mousedown=move |ev| {
+                                                        ev.prevent_default();
+                                                        select(id.clone());
+                                                    }
+                                                >
+
+                                                    {label.clone()}
+                                                </div>
+                                            }
+                                        })
+                                        .collect_view()
+                                })
+                        }}
+
+                    </div>
+                </div>
+            </div>
+        </div>
+
+        {move || {
+            error
+                .get()
+                .map(|error| {
+                    view! { <p id=error_id.clone() class="text-xs text-red-600 mt-2">{error}</p> }
+                })
+        }}
+    }
+}
+
+#[component]
+pub fn CheckboxGroup(
+    element: FormElement,
+    #[prop(optional, into)] disabled: MaybeSignal<bool>,
+    #[prop(optional)] options: Option<Memo<Vec<(String, String)>>>,
+) -> impl IntoView {
+    let options = options.unwrap_or_else(|| {
+        create_memo(move |_| element.data.with(|data| data.select_sources(element.id)))
+    });
+    let values = create_memo(move |_| {
+        element.data.with(|data| {
+            data.array_value(element.id)
+                .map(|s| s.to_string())
+                .collect::<AHashSet<_>>()
+        })
     });
     let error = create_memo(move |_| {
         element
             .data
-            .get()
-            .error_string(element.id)
-            .map(|s| s.to_string())
+            .with(|data| data.error_string(element.id).map(|s| s.to_string()))
     });
 
+    let ids = element.ids();
+    let error_id = ids.error.clone();
+
     view! {
-        <div class="space-y-2">
+        <div
+            id=ids.input.clone()
+            role="group"
+            aria-labelledby=ids.label.clone()
+            aria-describedby=ids.describe(error)
+            class="space-y-2"
+        >
 
             <For
                 each=move || {
@@ -176,7 +611,7 @@ pub fn CheckboxGroup(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! { <p id=error_id.clone() class="text-xs text-red-600 mt-2">{error}</p> }
                 })
         }}
     }
@@ -190,23 +625,32 @@ pub fn SelectCron(
     let value = create_memo(move |_| {
         element
             .data
-            .get()
-            .value::<SimpleCron>(element.id)
-            .unwrap_or_default()
+            .with(|data| data.value::<SimpleCron>(element.id).unwrap_or_default())
     });
     let error = create_memo(move |_| {
         element
             .data
-            .get()
-            .error_string(element.id)
-            .map(|s| s.to_string())
+            .with(|data| data.error_string(element.id).map(|s| s.to_string()))
     });
 
+    let schedule = create_memo(move |_| {
+        element
+            .data
+            .with(|data| data.value::<String>(element.id).unwrap_or_default())
+    });
+
+    let ids = element.ids();
+    let error_id = ids.error.clone();
+
     view! {
         <div class="space-y-3">
             <div class="relative">
                 <div class="sm:flex rounded-lg shadow-sm">
                     <select
+                        id=ids.input.clone()
+                        aria-labelledby=ids.label.clone()
+                        aria-describedby=ids.describe(error)
+                        aria-invalid=move || error.get().is_some().to_string()
                         class=move || {
                             if error.get().is_none() {
                                 "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -260,6 +704,7 @@ pub fn SelectCron(
 
                     </select>
                     <select
+                        aria-label="Hour"
                         class=move || {
                             if error.get().is_none() {
                                 "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -311,6 +756,7 @@ pub fn SelectCron(
 
                     </select>
                     <select
+                        aria-label="Minute"
                         class=move || {
                             if error.get().is_none() {
                                 "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -362,9 +808,34 @@ pub fn SelectCron(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! { <p id=error_id.clone() class="text-xs text-red-600 mt-2">{error}</p> }
                 })
         }}
+
+        {move || {
+            let schedule = schedule.get();
+            match cron::SimpleCron::parse(&schedule) {
+                Some(cron) => {
+                    let runs = cron
+                        .next_runs(Local::now().naive_local(), 3)
+                        .into_iter()
+                        .map(|time| time.format("%a %H:%M").to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    Some(view! { <p class="text-xs text-gray-500 mt-2">{format!("Next runs: {runs}")}</p> })
+                }
+                None if error.get().is_none() && !schedule.is_empty() => {
+                    Some(
+                        view! {
+                            <p class="text-xs text-red-600 mt-2">
+                                {format!("'{schedule}' is not a valid schedule, select new values above")}
+                            </p>
+                        },
+                    )
+                }
+                None => None,
+            }
+        }}
     }
 }
 
@@ -373,34 +844,10 @@ impl FormData {
         self.schema
             .fields
             .get(id)
-            .map(|t| match &t.typ_ {
-                Type::Select {
-                    source: Source::Static(options),
-                    ..
-                } => options
-                    .iter()
-                    .map(|(value, label)| (value.to_string(), label.to_string()))
-                    .collect::<Vec<_>>(),
-                Type::Select {
-                    source: Source::StaticId(options),
-                    ..
-                } => options
-                    .iter()
-                    .map(|id| (id.to_string(), id.to_string()))
-                    .collect::<Vec<_>>(),
-                Type::Select {
-                    source:
-                        Source::Dynamic {
-                            schema,
-                            field,
-                            filter,
-                        },
-                    ..
-                } => self.build_sources(schema, field, filter),
-                Type::Select {
-                    source: Source::DynamicSelf { field, filter },
-                    ..
-                } => self.build_sources(&self.schema, field, filter),
+            .map(|t| match t.typ(self) {
+                Type::Select { source, .. } => {
+                    self.keep_saved_values(id, self.source_options(source))
+                }
                 _ => {
                     log::warn!("Invalid schema type for select");
                     Vec::new()
@@ -409,6 +856,82 @@ impl FormData {
             .unwrap_or_default()
     }
 
+    pub fn remote_source(&self, id: &str) -> Option<RemoteSource> {
+        match self.schema.fields.get(id)?.typ(self) {
+            Type::Select {
+                source:
+                    Source::DynamicRemote {
+                        endpoint,
+                        id_field,
+                        label_field,
+                    },
+                ..
+            } => Some(RemoteSource {
+                endpoint,
+                id_field,
+                label_field,
+            }),
+            _ => None,
+        }
+    }
+
+    fn source_options(&self, source: &Source<Arc<Schema>, Arc<Field>>) -> Vec<(String, String)> {
+        match source {
+            Source::Static(options) => options
+                .iter()
+                .map(|(value, label)| (value.to_string(), label.to_string()))
+                .collect::<Vec<_>>(),
+            Source::StaticId(options) => options
+                .iter()
+                .map(|id| (id.to_string(), id.to_string()))
+                .collect::<Vec<_>>(),
+            Source::Dynamic {
+                schema,
+                field,
+                filter,
+            } => self.build_sources(schema, field, filter),
+            Source::DynamicSelf { field, filter } => {
+                self.build_sources(&self.schema, field, filter)
+            }
+            // Searched by the combo box, saved values are added below
+            Source::DynamicRemote { .. } => Vec::new(),
+            Source::Merged(_) => {
+                let leaves = source.leaves();
+                let mut options: Vec<(String, String)> = Vec::new();
+                for source in leaves
+                    .iter()
+                    .filter(|source| source.is_static())
+                    .chain(leaves.iter().filter(|source| !source.is_static()))
+                {
+                    for (value, label) in self.source_options(source) {
+                        // Static options replace the empty entry of record lists
+                        let is_none =
+                            value.is_empty() && !source.is_static() && !options.is_empty();
+                        if !is_none && !options.iter().any(|(option, _)| *option == value) {
+                            options.push((value, label));
+                        }
+                    }
+                }
+                options
+            }
+        }
+    }
+
+    // Saved ids stay selectable when their source failed to load or does
+    // not list them, so they are shown as is and saving does not drop them
+    fn keep_saved_values(
+        &self,
+        id: &str,
+        mut sources: Vec<(String, String)>,
+    ) -> Vec<(String, String)> {
+        for value in self.array_value(id).filter(|value| !value.is_empty()) {
+            if !sources.iter().any(|(source_id, _)| source_id == value) {
+                sources.push((value.to_string(), value.to_string()));
+            }
+        }
+        sources
+    }
+
     fn build_sources(
         &self,
         schema: &Schema,
@@ -443,6 +966,86 @@ impl FormData {
     }
 }
 
+async fn fetch_remote_options(
+    auth: &AccessToken,
+    remote: RemoteSource,
+    search: String,
+) -> Vec<(String, String)> {
+    let cache_key = (remote.endpoint, search);
+    if let Some(options) = REMOTE_OPTIONS.with(|cache| cache.borrow().get(&cache_key).cloned()) {
+        return options;
+    }
+
+    let (path, parameters) = remote
+        .endpoint
+        .split_once('?')
+        .unwrap_or((remote.endpoint, ""));
+    let mut request = HttpRequest::get(path)
+        .with_authorization(auth)
+        .with_parameter("limit", REMOTE_SEARCH_LIMIT.to_string())
+        .with_optional_parameter(
+            "filter",
+            (!cache_key.1.is_empty()).then(|| cache_key.1.clone()),
+        );
+    for (key, value) in parameters
+        .split('&')
+        .filter_map(|parameter| parameter.split_once('='))
+    {
+        request = request.with_parameter(key, value);
+    }
+
+    match request.send::<List<serde_json::Value>>().await {
+        Ok(list) => {
+            let options = list
+                .items
+                .iter()
+                .filter_map(|item| {
+                    let id = json_text(item.get(remote.id_field)?)?;
+                    let label = item
+                        .get(remote.label_field)
+                        .and_then(json_text)
+                        .filter(|label| !label.is_empty() && *label != id)
+                        .map_or_else(|| id.clone(), |label| format!("{label} ({id})"));
+                    Some((id, label))
+                })
+                .collect::<Vec<_>>();
+            REMOTE_OPTIONS.with(|cache| {
+                cache.borrow_mut().insert(cache_key, options.clone());
+            });
+            options
+        }
+        // Failed searches are not cached, saved values stay selectable
+        Err(http::Error::Unauthorized) => Vec::new(),
+        Err(err) => {
+            log::debug!("Failed to search {}: {err:?}", remote.endpoint);
+            Vec::new()
+        }
+    }
+}
+
+fn remote_label(remote: RemoteSource, id: &str) -> Option<String> {
+    REMOTE_OPTIONS.with(|cache| {
+        cache
+            .borrow()
+            .iter()
+            .filter(|((endpoint, _), _)| *endpoint == remote.endpoint)
+            .find_map(|(_, options)| {
+                options
+                    .iter()
+                    .find(|(option, _)| option == id)
+                    .map(|(_, label)| label.clone())
+            })
+    })
+}
+
+fn json_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct SimpleCron {
     hour: String,
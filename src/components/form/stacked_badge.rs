@@ -7,9 +7,12 @@
 use leptos::*;
 use web_sys::wasm_bindgen::JsCast;
 
-use crate::components::{badge::Badge, icon::IconXMark, Color};
+use crate::{
+    components::{badge::Badge, icon::IconXMark, Color},
+    core::schema::Type,
+};
 
-use super::{FormElement, ValidateCb};
+use super::{focus_by_id, reorder_target, FormElement, ValidateCb};
 
 #[component]
 pub fn StackedBadge(
@@ -18,33 +21,96 @@ pub fn StackedBadge(
     element: FormElement,
     #[prop(optional)] options: Option<Memo<Vec<(String, String)>>>,
     #[prop(into, optional)] validate_item: Option<Callback<(String, ValidateCb), ()>>,
+    #[prop(into, optional)] suggestions: Option<MaybeSignal<Vec<String>>>,
 ) -> impl IntoView {
     let show_tooltip = create_rw_signal(false);
     let validation_error = create_rw_signal(None::<String>);
     let is_select = options.is_some();
+    // Tags are typed in, only select fields offer a list of options
     let select_options = options.unwrap_or_else(|| {
-        create_memo(move |_| element.data.get_untracked().select_sources(element.id))
+        create_memo(move |_| {
+            element.data.with_untracked(|data| {
+                if data
+                    .schema
+                    .fields
+                    .get(element.id)
+                    .is_some_and(|field| matches!(field.typ(data), Type::Tag))
+                {
+                    Vec::new()
+                } else {
+                    data.select_sources(element.id)
+                }
+            })
+        })
     });
     let add_value = create_rw_signal(String::new());
     let value = create_memo(move |_| {
-        element
-            .data
-            .get()
-            .array_value(element.id)
-            .enumerate()
-            .map(|(idx, s)| (idx, s.to_string()))
-            .collect::<Vec<_>>()
+        element.data.with(|data| {
+            data.array_value(element.id)
+                .enumerate()
+                .map(|(idx, s)| {
+                    (
+                        idx,
+                        data.display_value(element.id, s.to_string()),
+                        data.item_error(element.id, idx).map(|e| e.to_string()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+    // Values already added are not suggested again
+    let suggestions = suggestions.map(|suggestions| {
+        create_memo(move |_| {
+            let mut suggestions = suggestions.get();
+            element.data.with(|data| {
+                suggestions.retain(|suggestion| {
+                    !data
+                        .array_value(element.id)
+                        .any(|value| value == suggestion)
+                });
+            });
+            suggestions
+        })
     });
     let add_button_text = (!add_button_text.is_empty()).then_some(add_button_text);
+    let add_list = move |list: String| {
+        let mut result = (0, vec![]);
+        element.data.update(|data| {
+            result = data.array_push_list(element.id, &list);
+        });
+        let (added, invalid) = result;
+        if invalid.is_empty() {
+            show_tooltip.set(false);
+        } else {
+            validation_error.set(Some(format!(
+                "Added {added}, skipped {} invalid: {}",
+                invalid.len(),
+                invalid.join(", "),
+            )));
+        }
+    };
+    let ids = store_value(element.ids());
+    let add_input_id = store_value(ids.with_value(|ids| format!("{}-add", ids.input)));
+    let add_error_id = store_value(format!("{}-error", add_input_id.get_value()));
+    let suggestions_id = store_value(format!("{}-suggestions", add_input_id.get_value()));
 
     let validate_value = move || {
         let add_value = add_value.get().trim().to_string();
+        // Stored values are normalized, so is the new one before looking for duplicates
+        let add_value = match element
+            .data
+            .with_untracked(|data| data.check_item(element.id, add_value))
+        {
+            Ok(add_value) => add_value,
+            Err(error) => {
+                validation_error.set(Some(error.into_owned()));
+                return;
+            }
+        };
         if !add_value.is_empty()
             && !element
                 .data
-                .get()
-                .array_value(element.id)
-                .any(|v| v == add_value)
+                .with(|data| data.array_value(element.id).any(|v| v == add_value))
         {
             if let Some(cb) = validate_item.as_ref() {
                 cb.call((
@@ -75,38 +141,96 @@ pub fn StackedBadge(
 
     view! {
         <div class="relative">
-
-            <For
-                each=move || { value.get() }
-                key=move |(idx, item)| format!("{idx}_{item}")
-                children=move |(idx, item)| {
-                    let label = item.clone();
-                    view! {
-                        <div class="inline-flex flex-wrap gap-2 p-1">
-
-                            <Badge color=color large=true>
-                                {label}
-                                <button
-                                    type="button"
-                                    class="flex-shrink-0 size-4 inline-flex items-center justify-center rounded-full hover:bg-teal-200 focus:outline-none focus:bg-teal-200 focus:text-teal-500 dark:hover:bg-teal-900"
-                                    on:click=move |_| {
+            <div
+                id=ids.with_value(|ids| ids.input.clone())
+                role="list"
+                aria-labelledby=ids.with_value(|ids| ids.label.clone())
+                class="inline"
+            >
+                <For
+                    each=move || { value.get() }
+                    key=move |(idx, item, error)| format!("{idx}_{item}_{}", error.is_some())
+                    children=move |(idx, item, error)| {
+                        let label = item.clone();
+                        let color = if error.is_some() { Color::Red } else { color };
+                        view! {
+                            <div
+                                id=ids.with_value(|ids| ids.item(idx))
+                                class="inline-flex flex-wrap gap-2 p-1 rounded-full focus:outline-none focus:ring-2 focus:ring-blue-500"
+                                role="listitem"
+                                tabindex="0"
+                                aria-label=item.clone()
+                                aria-invalid=error.is_some().to_string()
+                                title=error
+                                aria-keyshortcuts="Delete Alt+ArrowLeft Alt+ArrowRight"
+                                on:keydown=move |ev| {
+                                    let len = value.with_untracked(|value| value.len());
+                                    if let Some(to) = reorder_target(
+                                        &ev,
+                                        idx,
+                                        len,
+                                        "ArrowLeft",
+                                        "ArrowRight",
+                                    ) {
+                                        ev.prevent_default();
                                         element
                                             .data
                                             .update(|data| {
-                                                data.array_delete(element.id, idx);
+                                                data.array_move(element.id, idx, to);
                                             });
+                                        focus_by_id(ids.with_value(|ids| ids.item(to)));
+                                        return;
                                     }
-                                >
+                                    match ev.key().as_str() {
+                                        "Delete" | "Backspace" => {
+                                            ev.prevent_default();
+                                            element
+                                                .data
+                                                .update(|data| {
+                                                    data.array_delete(element.id, idx);
+                                                });
+                                            if len > 1 {
+                                                focus_by_id(
+                                                    ids.with_value(|ids| ids.item(idx.min(len - 2))),
+                                                );
+                                            }
+                                        }
+                                        "ArrowLeft" if idx > 0 => {
+                                            focus_by_id(ids.with_value(|ids| ids.item(idx - 1)));
+                                        }
+                                        "ArrowRight" if idx + 1 < len => {
+                                            focus_by_id(ids.with_value(|ids| ids.item(idx + 1)));
+                                        }
+                                        _ => {}
+                                    }
+                                }
+                            >
 
-                                    <span class="sr-only">Remove</span>
-                                    <IconXMark attr:class="flex-shrink-0 size-3"/>
-                                </button>
-                            </Badge>
+                                <Badge color=color large=true>
+                                    {label}
+                                    <button
+                                        type="button"
+                                        tabindex="-1"
+                                        class="flex-shrink-0 size-4 inline-flex items-center justify-center rounded-full hover:bg-teal-200 focus:outline-none focus:bg-teal-200 focus:text-teal-500 dark:hover:bg-teal-900"
+                                        on:click=move |_| {
+                                            element
+                                                .data
+                                                .update(|data| {
+                                                    data.array_delete(element.id, idx);
+                                                });
+                                        }
+                                    >
 
-                        </div>
+                                        <span class="sr-only">Remove</span>
+                                        <IconXMark attr:class="flex-shrink-0 size-3"/>
+                                    </button>
+                                </Badge>
+
+                            </div>
+                        }
                     }
-                }
-            />
+                />
+            </div>
 
             {add_button_text
                 .map(|add_button_text| {
@@ -115,10 +239,12 @@ pub fn StackedBadge(
                             <button
                                 type="button"
                                 class="py-1.5 px-2 inline-flex items-center gap-x-1 text-xs font-medium rounded-full border border-dashed border-gray-200 bg-white text-gray-800 hover:bg-gray-50 disabled:opacity-50 disabled:pointer-events-none dark:bg-gray-800 dark:border-gray-700 dark:text-gray-300 dark:hover:bg-gray-700 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                                aria-expanded=move || show_tooltip.get().to_string()
                                 on:click=move |_| {
                                     add_value.set(String::new());
                                     validation_error.set(None);
                                     show_tooltip.set(true);
+                                    focus_by_id(add_input_id.get_value());
                                 }
                             >
 
@@ -148,7 +274,12 @@ pub fn StackedBadge(
                         if !is_select && select_options.get().is_empty() {
                             view! {
                                 <input
+                                    id=add_input_id.get_value()
                                     type="text"
+                                    aria-label="New item"
+                                    aria-invalid=move || validation_error.get().is_some().to_string()
+                                    aria-describedby=add_error_id.get_value()
+                                    list=suggestions.map(|_| suggestions_id.get_value())
                                     class=move || {
                                         if validation_error.get().is_none() {
                                             "py-2 px-3 block w-full border-gray-200 shadow-sm rounded-s-md text-sm focus:z-10 focus:border-blue-500 focus:ring-blue-500 dark:bg-gray-800 dark:border-gray-700 dark:text-gray-400"
@@ -162,20 +293,55 @@ pub fn StackedBadge(
                                         add_value.set(event_target_value(&ev));
                                     }
 
+                                    on:paste=move |ev| {
+                                        let text = ev
+                                            .clipboard_data()
+                                            .and_then(|data| data.get_data("text").ok())
+                                            .unwrap_or_default();
+                                        if text.contains(['\n', ',', ';']) {
+                                            ev.prevent_default();
+                                            add_list(text);
+                                        }
+                                    }
+
                                     on:keyup=move |ev| {
-                                        if ev.unchecked_ref::<web_sys::KeyboardEvent>().key_code()
-                                            == 13
-                                        {
-                                            add_value.set(event_target_value(&ev));
-                                            validate_value();
+                                        match ev.unchecked_ref::<web_sys::KeyboardEvent>().key_code() {
+                                            13 => {
+                                                add_value.set(event_target_value(&ev));
+                                                validate_value();
+                                            }
+                                            27 => {
+                                                show_tooltip.set(false);
+                                            }
+                                            _ => {}
                                         }
                                     }
                                 />
+
+                                {suggestions
+                                    .map(|suggestions| {
+                                        view! {
+                                            <datalist id=suggestions_id.get_value()>
+                                                <For
+                                                    each=move || suggestions.get()
+                                                    key=|suggestion| suggestion.clone()
+                                                    children=move |suggestion| {
+                                                        view! { <option value=suggestion></option> }
+                                                    }
+                                                />
+
+                                            </datalist>
+                                        }
+                                    })}
                             }
                                 .into_view()
                         } else {
                             view! {
                                 <select
+                                    id=add_input_id.get_value()
+                                    aria-label="New item"
+                                    aria-invalid=move || validation_error.get().is_some().to_string()
+                                    aria-describedby=add_error_id.get_value()
                                     class=move || {
                                         if validation_error.get().is_none() {
                                             "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -187,6 +353,12 @@ pub fn StackedBadge(
                                     on:change=move |ev| {
                                         add_value.set(event_target_value(&ev));
                                     }
+
+                                    on:keyup=move |ev| {
+                                        if ev.key() == "Escape" {
+                                            show_tooltip.set(false);
+                                        }
+                                    }
                                 >
 
                                     <option value="">-- Select an option --</option>
@@ -227,6 +399,7 @@ pub fn StackedBadge(
 
                 </div>
                 <p
+                    id=add_error_id.get_value()
                     class="text-xs text-red-600 mt-2"
                     class:hidden=move || validation_error.get().is_none()
                 >
@@ -4,7 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::FormElement;
+use super::{focus_by_id, reorder_target, FormElement};
 use crate::components::form::input::Duration;
 use crate::{
     components::icon::{IconPlus, IconXMark},
@@ -17,36 +17,33 @@ use std::str::FromStr;
 pub fn StackedDuration(add_button_text: String, element: FormElement) -> impl IntoView {
     // TODO: Abstract all stacked components
     let values = create_memo(move |_| {
-        let data = element.data.get();
-        let error = data.error(element.id);
-
-        data.array_value(element.id)
-            .enumerate()
-            .map(|(idx, value)| {
-                (
-                    idx,
-                    value.to_string(),
-                    error.as_ref().and_then(|e| {
-                        if e.id == FormErrorType::Array(idx) {
-                            Some(e.error.clone())
-                        } else {
-                            None
-                        }
-                    }),
-                )
-            })
-            .collect::<Vec<_>>()
+        element.data.with(|data| {
+            data.array_value(element.id)
+                .enumerate()
+                .map(|(idx, value)| {
+                    (
+                        idx,
+                        value.to_string(),
+                        data.item_error(element.id, idx).map(|e| e.to_string()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
     });
     let error = create_memo(move |_| {
-        element.data.get().error(element.id).and_then(|e| {
-            if e.id == FormErrorType::None {
-                Some(e.error.clone())
-            } else {
-                None
-            }
+        element.data.with(|data| {
+            data.error(element.id).and_then(|e| {
+                if e.id == FormErrorType::None {
+                    Some(e.error.clone())
+                } else {
+                    None
+                }
+            })
         })
     });
 
+    let ids = store_value(element.ids());
+
     view! {
         <div class="space-y-3">
 
@@ -63,6 +60,8 @@ pub fn StackedDuration(add_button_text: String, element: FormElement) -> impl In
                 children=move |(idx, item, error)| {
                     let is_err = error.is_some();
                     let error = error.unwrap_or_default();
+                    let item_ids = ids.get_value();
+                    let error_id = item_ids.item_error(idx);
                     let value_ = Duration::from_str(&item).unwrap_or_default();
                     let mut value = value_.clone();
                     let contents = value_.value.clone();
@@ -72,7 +71,12 @@ pub fn StackedDuration(add_button_text: String, element: FormElement) -> impl In
                             <div class="relative">
 
                                 <input
+                                    id=item_ids.item(idx)
                                     type="text"
+                                    aria-labelledby=item_ids.label.clone()
+                                    aria-describedby=is_err.then(|| error_id.clone())
+                                    aria-invalid=is_err.to_string()
+                                    aria-keyshortcuts="Alt+ArrowUp Alt+ArrowDown"
                                     class=move || {
                                         if !is_err {
                                             "py-2 px-3 block w-full border-gray-200 shadow-sm rounded-lg text-sm focus:z-10 focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -83,6 +87,25 @@ pub fn StackedDuration(add_button_text: String, element: FormElement) -> impl In
 
                                     prop:value=contents
 
+                                    on:keydown=move |ev| {
+                                        let len = values.with_untracked(|values| values.len());
+                                        if let Some(to) = reorder_target(
+                                            &ev,
+                                            idx,
+                                            len,
+                                            "ArrowUp",
+                                            "ArrowDown",
+                                        ) {
+                                            ev.prevent_default();
+                                            element
+                                                .data
+                                                .update(|data| {
+                                                    data.array_move(element.id, idx, to);
+                                                });
+                                            focus_by_id(ids.get_value().item(to));
+                                        }
+                                    }
+
                                     on:change=move |ev| {
                                         let value = value_.clone();
                                         element
@@ -106,6 +129,7 @@ pub fn StackedDuration(add_button_text: String, element: FormElement) -> impl In
 
                                 <div class="absolute inset-y-0 end-0 flex items-center text-gray-500 pe-px">
                                     <select
+                                        aria-label="Unit"
                                         class="block text-xs w-full border-transparent rounded-lg focus:ring-blue-600 focus:border-blue-600 dark:bg-gray-800"
                                         on:change=move |ev| {
                                             element
@@ -159,11 +183,12 @@ pub fn StackedDuration(add_button_text: String, element: FormElement) -> impl In
                                     }
                                 >
 
+                                    <span class="sr-only">Remove item</span>
                                     <IconXMark/>
 
                                 </button>
                             </div>
-                            <p class="text-xs text-red-600 mt-2" class:hidden=!is_err>
+                            <p id=error_id class="text-xs text-red-600 mt-2" class:hidden=!is_err>
                                 {error}
                             </p>
                         </div>
@@ -197,7 +222,11 @@ pub fn StackedDuration(add_button_text: String, element: FormElement) -> impl In
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=ids.with_value(|ids| ids.error.clone()) class="text-xs text-red-600 mt-2">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
@@ -7,11 +7,11 @@
 use leptos::*;
 
 use crate::{
-    components::icon::{IconPlus, IconXMark},
+    components::icon::{IconClipboard, IconPlus, IconXMark},
     core::form::FormErrorType,
 };
 
-use super::FormElement;
+use super::{focus_by_id, reorder_target, FormElement};
 
 #[component]
 pub fn StackedInput(
@@ -20,36 +20,43 @@ pub fn StackedInput(
     #[prop(optional, into)] placeholder: Option<MaybeSignal<String>>,
 ) -> impl IntoView {
     let values = create_memo(move |_| {
-        let data = element.data.get();
-        let error = data.error(element.id);
-
-        data.array_value(element.id)
-            .enumerate()
-            .map(|(idx, value)| {
-                (
-                    idx,
-                    value.to_string(),
-                    error.as_ref().and_then(|e| {
-                        if e.id == FormErrorType::Array(idx) {
-                            Some(e.error.clone())
-                        } else {
-                            None
-                        }
-                    }),
-                )
-            })
-            .collect::<Vec<_>>()
+        element.data.with(|data| {
+            data.array_value(element.id)
+                .enumerate()
+                .map(|(idx, value)| {
+                    (
+                        idx,
+                        data.display_value(element.id, value.to_string()),
+                        data.item_error(element.id, idx).map(|e| e.to_string()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
     });
+    let paste_list = create_rw_signal(None::<String>);
+    let paste_result = create_rw_signal(None::<(usize, Vec<String>)>);
+    let add_list = move |list: String| {
+        let mut result = (0, vec![]);
+        element.data.update(|data| {
+            result = data.array_push_list(element.id, &list);
+        });
+        paste_list.set((!result.1.is_empty()).then(|| result.1.join("\n")));
+        paste_result.set(Some(result));
+    };
     let error = create_memo(move |_| {
-        element.data.get().error(element.id).and_then(|e| {
-            if e.id == FormErrorType::None {
-                Some(e.error.clone())
-            } else {
-                None
-            }
+        element.data.with(|data| {
+            data.error(element.id).and_then(|e| {
+                if e.id == FormErrorType::None {
+                    Some(e.error.clone())
+                } else {
+                    None
+                }
+            })
         })
     });
 
+    let ids = store_value(element.ids());
+
     view! {
         <div class="space-y-3">
 
@@ -66,11 +73,18 @@ pub fn StackedInput(
                 children=move |(idx, item, error)| {
                     let is_err = error.is_some();
                     let error = error.unwrap_or_default();
+                    let item_ids = ids.get_value();
+                    let error_id = item_ids.item_error(idx);
                     view! {
                         <div class="space-y-3">
                             <div class="relative">
                                 <input
+                                    id=item_ids.item(idx)
                                     type="text"
+                                    aria-labelledby=item_ids.label.clone()
+                                    aria-describedby=is_err.then(|| error_id.clone())
+                                    aria-invalid=is_err.to_string()
+                                    aria-keyshortcuts="Alt+ArrowUp Alt+ArrowDown"
                                     class=move || {
                                         if !is_err {
                                             "py-2 px-3 pe-11 block w-full border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
@@ -81,6 +95,26 @@ pub fn StackedInput(
 
                                     prop:value=item
                                     placeholder=placeholder.clone().map(|p| move || p.get())
+                                    on:keydown=move |ev| {
+                                        let len = values.with_untracked(|values| values.len());
+                                        if let Some(to) = reorder_target(
+                                            &ev,
+                                            idx,
+                                            len,
+                                            "ArrowUp",
+                                            "ArrowDown",
+                                        ) {
+                                            ev.prevent_default();
+                                            element
+                                                .data
+                                                .update(|data| {
+                                                    data.array_update(element.id, idx, event_target_value(&ev));
+                                                    data.array_move(element.id, idx, to);
+                                                });
+                                            focus_by_id(ids.get_value().item(to));
+                                        }
+                                    }
+
                                     on:change=move |ev| {
                                         element
                                             .data
@@ -88,6 +122,17 @@ pub fn StackedInput(
                                                 data.array_update(element.id, idx, event_target_value(&ev));
                                             });
                                     }
+
+                                    on:paste=move |ev| {
+                                        let text = ev
+                                            .clipboard_data()
+                                            .and_then(|data| data.get_data("text").ok())
+                                            .unwrap_or_default();
+                                        if text.contains(['\n', ',', ';']) {
+                                            ev.prevent_default();
+                                            add_list(text);
+                                        }
+                                    }
                                 />
 
                                 <button
@@ -102,11 +147,12 @@ pub fn StackedInput(
                                     }
                                 >
 
+                                    <span class="sr-only">Remove item</span>
                                     <IconXMark/>
 
                                 </button>
                             </div>
-                            <p class="text-xs text-red-600 mt-2" class:hidden=!is_err>
+                            <p id=error_id class="text-xs text-red-600 mt-2" class:hidden=!is_err>
                                 {error}
                             </p>
                         </div>
@@ -116,7 +162,87 @@ pub fn StackedInput(
 
         </div>
 
-        <p class="mt-3 text-end">
+        {move || {
+            paste_list
+                .get()
+                .map(|list| {
+                    view! {
+                        <div class="mt-3 space-y-2">
+                            <textarea
+                                class="py-2 px-3 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                                autocapitalize="off"
+                                rows="5"
+                                placeholder="One item per line, or separated by commas or semicolons"
+                                prop:value=list
+                                on:change=move |ev| {
+                                    paste_list.set(Some(event_target_value(&ev)));
+                                }
+                            ></textarea>
+                            <div class="flex justify-end gap-x-2">
+                                <button
+                                    type="button"
+                                    class="py-1.5 px-2 inline-flex items-center gap-x-1 text-xs font-medium rounded-lg border border-gray-200 bg-white text-gray-800 hover:bg-gray-50 dark:bg-gray-800 dark:border-gray-700 dark:text-gray-300 dark:hover:bg-gray-700"
+                                    on:click=move |_| {
+                                        paste_list.set(None);
+                                        paste_result.set(None);
+                                    }
+                                >
+
+                                    Cancel
+                                </button>
+                                <button
+                                    type="button"
+                                    class="py-1.5 px-2 inline-flex items-center gap-x-1 text-xs font-semibold rounded-lg border border-transparent bg-blue-600 text-white hover:bg-blue-700"
+                                    on:click=move |_| {
+                                        add_list(paste_list.get().unwrap_or_default());
+                                    }
+                                >
+
+                                    Add items
+                                </button>
+                            </div>
+                        </div>
+                    }
+                })
+        }}
+
+        {move || {
+            paste_result
+                .get()
+                .map(|(added, invalid)| {
+                    let (class, message) = if invalid.is_empty() {
+                        ("text-xs text-gray-500 mt-2", format!("Added {added}."))
+                    } else {
+                        (
+                            "text-xs text-red-600 mt-2",
+                            format!(
+                                "Added {added}, skipped {} invalid: {}",
+                                invalid.len(),
+                                invalid.join(", "),
+                            ),
+                        )
+                    };
+                    view! { <p class=class>{message}</p> }
+                })
+        }}
+
+        <p class="mt-3 text-end space-x-1">
+            <button
+                type="button"
+                class="py-1.5 px-2 inline-flex items-center gap-x-1 text-xs font-medium rounded-full border border-dashed border-gray-200 bg-white text-gray-800 hover:bg-gray-50 disabled:opacity-50 disabled:pointer-events-none dark:bg-gray-800 dark:border-gray-700 dark:text-gray-300 dark:hover:bg-gray-700 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                on:click=move |_| {
+                    paste_result.set(None);
+                    paste_list.update(|list| {
+                        if list.is_none() {
+                            *list = Some(String::new());
+                        }
+                    });
+                }
+            >
+
+                <IconClipboard attr:class="flex-shrink-0 size-3.5"/>
+                Paste list
+            </button>
             <button
                 type="button"
                 class="py-1.5 px-2 inline-flex items-center gap-x-1 text-xs font-medium rounded-full border border-dashed border-gray-200 bg-white text-gray-800 hover:bg-gray-50 disabled:opacity-50 disabled:pointer-events-none dark:bg-gray-800 dark:border-gray-700 dark:text-gray-300 dark:hover:bg-gray-700 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
@@ -140,7 +266,11 @@ pub fn StackedInput(
             error
                 .get()
                 .map(|error| {
-                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                    view! {
+                        <p id=ids.with_value(|ids| ids.error.clone()) class="text-xs text-red-600 mt-2">
+                            {error}
+                        </p>
+                    }
                 })
         }}
     }
@@ -0,0 +1,167 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use leptos::*;
+
+use crate::{
+    components::icon::{IconPlus, IconXMark},
+    core::form::FormErrorType,
+};
+
+use super::FormElement;
+
+const INPUT_CLASS: &str = "py-2 px-3 block w-full border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600";
+const INPUT_ERROR_CLASS: &str = "py-2 px-3 block w-full border-red-500 shadow-sm text-sm rounded-lg focus:border-red-500 focus:ring-red-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600";
+
+#[component]
+pub fn StackedMap(
+    add_button_text: String,
+    element: FormElement,
+    #[prop(optional, into)] key_placeholder: Option<MaybeSignal<String>>,
+    #[prop(optional, into)] value_placeholder: Option<MaybeSignal<String>>,
+) -> impl IntoView {
+    let entries = create_memo(move |_| {
+        element.data.with(|data| {
+            data.map_value(element.id)
+                .iter()
+                .enumerate()
+                .map(|(idx, (key, value))| {
+                    (
+                        idx,
+                        key.clone(),
+                        value.clone(),
+                        data.item_error(element.id, idx).map(|e| e.to_string()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+    let error = create_memo(move |_| {
+        element.data.with(|data| {
+            data.error(element.id).and_then(|e| {
+                if e.id == FormErrorType::None {
+                    Some(e.error.clone())
+                } else {
+                    None
+                }
+            })
+        })
+    });
+
+    let ids = store_value(element.ids());
+
+    view! {
+        <div class="space-y-3">
+
+            <For
+                each=move || { entries.get().into_iter() }
+                key=|entry| entry.clone()
+                children=move |(idx, key, value, error)| {
+                    let is_err = error.is_some();
+                    let error = error.unwrap_or_default();
+                    let item_ids = ids.get_value();
+                    let error_id = item_ids.item_error(idx);
+                    let class = if is_err { INPUT_ERROR_CLASS } else { INPUT_CLASS };
+                    view! {
+                        <div class="space-y-3">
+                            <div class="flex gap-x-2">
+                                <input
+                                    id=item_ids.item(idx)
+                                    type="text"
+                                    aria-labelledby=item_ids.label.clone()
+                                    aria-describedby=is_err.then(|| error_id.clone())
+                                    aria-invalid=is_err.to_string()
+                                    class=class
+                                    prop:value=key
+                                    placeholder=key_placeholder.clone().map(|p| move || p.get())
+                                    on:change=move |ev| {
+                                        element
+                                            .data
+                                            .update(|data| {
+                                                data.map_update_key(element.id, idx, event_target_value(&ev));
+                                            });
+                                    }
+                                />
+
+                                <input
+                                    type="text"
+                                    aria-labelledby=item_ids.label.clone()
+                                    class=class
+                                    prop:value=value
+                                    placeholder=value_placeholder.clone().map(|p| move || p.get())
+                                    on:change=move |ev| {
+                                        element
+                                            .data
+                                            .update(|data| {
+                                                data.map_update_value(element.id, idx, event_target_value(&ev));
+                                            });
+                                    }
+                                />
+
+                                <button
+                                    type="button"
+                                    class="p-2.5 rounded-md dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                                    on:click=move |_| {
+                                        element
+                                            .data
+                                            .update(|data| {
+                                                data.map_delete(element.id, idx);
+                                            });
+                                    }
+                                >
+
+                                    <span class="sr-only">Remove entry</span>
+                                    <IconXMark/>
+
+                                </button>
+                            </div>
+                            <p id=error_id class="text-xs text-red-600 mt-2" class:hidden=!is_err>
+                                {error}
+                            </p>
+                        </div>
+                    }
+                }
+            />
+
+        </div>
+
+        <p class="mt-3 text-end">
+            <button
+                type="button"
+                class="py-1.5 px-2 inline-flex items-center gap-x-1 text-xs font-medium rounded-full border border-dashed border-gray-200 bg-white text-gray-800 hover:bg-gray-50 disabled:opacity-50 disabled:pointer-events-none dark:bg-gray-800 dark:border-gray-700 dark:text-gray-300 dark:hover:bg-gray-700 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                on:click=move |_| {
+                    if entries
+                        .get()
+                        .last()
+                        .is_none_or(|(_, key, value, _)| !key.is_empty() || !value.is_empty())
+                    {
+                        element
+                            .data
+                            .update(|data| {
+                                data.map_push(element.id);
+                            });
+                    }
+                }
+            >
+
+                <IconPlus attr:class="flex-shrink-0 size-3.5"/>
+                {add_button_text}
+            </button>
+        </p>
+
+        {move || {
+            error
+                .get()
+                .map(|error| {
+                    view! {
+                        <p id=ids.with_value(|ids| ids.error.clone()) class="text-xs text-red-600 mt-2">
+                            {error}
+                        </p>
+                    }
+                })
+        }}
+    }
+}
@@ -0,0 +1,86 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use leptos::*;
+use leptos_router::use_navigate;
+use leptos_use::{use_event_listener_with_options, UseEventListenerOptions};
+use web_sys::wasm_bindgen::JsCast;
+
+use crate::components::messages::modal::Modal;
+
+// Asks before leaving a form with unsaved changes, links are checked before
+// the router handles them and reloads are confirmed by the browser
+#[component]
+pub fn UnsavedChangesGuard(
+    #[prop(into)] is_dirty: Signal<bool>,
+    #[prop(into)] on_discard: Callback<()>,
+) -> impl IntoView {
+    let modal = expect_context::<RwSignal<Modal>>();
+    let navigate = use_navigate();
+
+    let before_unload = window_event_listener(ev::beforeunload, move |ev| {
+        if is_dirty.get_untracked() {
+            ev.prevent_default();
+            ev.set_return_value("");
+        }
+    });
+    on_cleanup(move || before_unload.remove());
+
+    let stop_links = use_event_listener_with_options(
+        document(),
+        ev::click,
+        move |ev| {
+            if !is_dirty.get_untracked()
+                || ev.default_prevented()
+                || ev.button() != 0
+                || ev.meta_key()
+                || ev.ctrl_key()
+                || ev.shift_key()
+                || ev.alt_key()
+            {
+                return;
+            }
+            let Some(anchor) = ev
+                .target()
+                .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                .and_then(|element| element.closest("a[href]").ok().flatten())
+            else {
+                return;
+            };
+            let Some(href) = anchor
+                .get_attribute("href")
+                .filter(|href| href.starts_with('/') && !href.starts_with("//"))
+            else {
+                return;
+            };
+            if anchor.has_attribute("download")
+                || anchor
+                    .get_attribute("target")
+                    .is_some_and(|target| target != "_self")
+            {
+                return;
+            }
+
+            ev.prevent_default();
+            let navigate = navigate.clone();
+            modal.set(
+                Modal::with_title("Unsaved changes")
+                    .with_message(concat!(
+                        "You have unsaved changes that will be lost if you leave ",
+                        "this page. Stay to keep editing or discard them."
+                    ))
+                    .with_cancel_button("Stay")
+                    .with_button("Discard changes")
+                    .with_dangerous_callback(move || {
+                        on_discard.call(());
+                        navigate(&href, Default::default());
+                    }),
+            );
+        },
+        UseEventListenerOptions::default().capture(true),
+    );
+    on_cleanup(stop_links);
+}
@@ -10,9 +10,13 @@ use leptos_router::use_navigate;
 
 use crate::{
     components::icon::{
-        IconAdjustmentsHorizontal, IconHeart, IconPower, IconServer, IconUserCircle,
+        IconAdjustmentsHorizontal, IconArrowUTurnLeft, IconBell, IconHeart, IconPower, IconServer,
+        IconUserCircle,
+    },
+    core::{
+        alerting::use_alert_monitor, oauth::use_authorization, undo::use_undo_stack,
+        url::UrlBuilder, AccessToken, Permission, Permissions,
     },
-    core::{oauth::use_authorization, url::UrlBuilder, AccessToken, Permission, Permissions},
     pages::config::edit::DEFAULT_SETTINGS_URL,
     STATE_STORAGE_KEY, VERSION_NAME,
 };
@@ -22,7 +26,26 @@ use web_sys::wasm_bindgen::JsCast;
 pub fn Header(permissions: Memo<Option<Permissions>>) -> impl IntoView {
     let show_action_dropdown = RwSignal::new(false);
     let show_account_dropdown = RwSignal::new(false);
+    let show_alerts_dropdown = RwSignal::new(false);
+    let monitor = use_alert_monitor();
+    let active_alerts = create_memo(move |_| monitor.active());
     let auth_token = use_context::<RwSignal<AccessToken>>().unwrap();
+    let undo = use_undo_stack();
+    let last_undo = create_memo(move |_| undo.last());
+
+    // Ctrl/Cmd+Z undoes the last list operation unless a text field has focus
+    let _ = window_event_listener(ev::keydown, move |ev| {
+        if ev.key().eq_ignore_ascii_case("z")
+            && (ev.ctrl_key() || ev.meta_key())
+            && !ev.shift_key()
+            && !ev.alt_key()
+            && !is_editable_target(&ev)
+            && !undo.is_empty()
+        {
+            ev.prevent_default();
+            undo.undo();
+        }
+    });
 
     view! {
         <header class="sticky top-0 inset-x-0 flex flex-wrap sm:justify-start sm:flex-nowrap z-[48] w-full bg-white border-b text-sm py-2.5 sm:py-4 lg:ps-64 dark:bg-gray-800 dark:border-gray-700">
@@ -110,6 +133,107 @@ pub fn Header(permissions: Memo<Option<Permissions>>) -> impl IntoView {
                     <div class="flex flex-row items-center justify-end gap-2 ms-auto">
 
                         <div class="flex flex-row items-center justify-end gap-1">
+                            <div
+                                class="hs-dropdown relative inline-flex"
+                                class:hidden=move || {
+                                    permissions.get().is_none_or(|p| !p.has_admin_access())
+                                }
+                            >
+
+                                <button
+                                    type="button"
+                                    class="size-[38px] relative inline-flex justify-center items-center gap-x-2 text-sm font-semibold rounded-full border border-transparent text-gray-800 hover:bg-gray-100 focus:outline-none focus:bg-gray-100 disabled:opacity-50 disabled:pointer-events-none dark:text-white dark:hover:bg-neutral-700 dark:focus:bg-neutral-700"
+                                    on:click=move |_| {
+                                        show_action_dropdown.set(false);
+                                        show_account_dropdown.set(false);
+                                        show_alerts_dropdown
+                                            .update(|v| {
+                                                *v = !*v;
+                                            });
+                                    }
+                                >
+
+                                    <IconBell attr:class="shrink-0 size-4"/>
+                                    <span
+                                        class="absolute top-0 end-0 inline-flex items-center py-0.5 px-1.5 rounded-full text-xs font-medium transform -translate-y-1/2 translate-x-1/2 bg-red-500 text-white"
+                                        class:hidden=move || active_alerts.get().is_empty()
+                                    >
+                                        {move || active_alerts.get().len()}
+                                    </span>
+                                    <span class="sr-only">Alerts</span>
+                                </button>
+
+                                <div
+                                    role="menu"
+                                    class=move || {
+                                        if show_alerts_dropdown.get() {
+                                            "hs-dropdown-menu transition-[opacity,margin] absolute top-full right-0 duration opacity-100 open block divide-y divide-gray-200 min-w-72 z-50 bg-white shadow-2xl rounded-lg p-2 mt-2 dark:divide-neutral-700 dark:bg-neutral-800 dark:border dark:border-neutral-700"
+                                        } else {
+                                            "hs-dropdown-menu transition-[opacity,margin] duration hs-dropdown-open:opacity-100 opacity-0 hidden divide-y divide-gray-200 min-w-72 z-20 bg-white shadow-2xl rounded-lg p-2 mt-2 dark:divide-neutral-700 dark:bg-neutral-800 dark:border dark:border-neutral-700"
+                                        }
+                                    }
+                                >
+
+                                    <div class="py-3 px-5 bg-gray-100 rounded-t-lg dark:bg-neutral-700">
+                                        <p class="text-sm text-gray-500 dark:text-neutral-500">
+                                            Alerts
+                                        </p>
+                                    </div>
+                                    <div class="p-1.5 space-y-0.5">
+                                        {move || {
+                                            let alerts = active_alerts.get();
+                                            if alerts.is_empty() {
+                                                view! {
+                                                    <p class="py-2 px-3 text-sm text-gray-500 dark:text-neutral-400">
+                                                        "No active alerts"
+                                                    </p>
+                                                }
+                                                    .into_view()
+                                            } else {
+                                                alerts
+                                                    .into_iter()
+                                                    .map(|(rule, alert)| {
+                                                        let rule_id = rule.id.clone();
+                                                        view! {
+                                                            <div class="flex items-start justify-between gap-x-3 py-2 px-3 rounded-lg hover:bg-gray-100 dark:hover:bg-neutral-700">
+                                                                <a
+                                                                    class="text-sm text-gray-800 dark:text-neutral-300"
+                                                                    href=rule.metric.url()
+                                                                    on:click=move |_| show_alerts_dropdown.set(false)
+                                                                >
+                                                                    <span class="block font-medium">{rule.title()}</span>
+                                                                    <span class="block text-xs text-gray-500 dark:text-neutral-500">
+                                                                        {rule.description(alert.value)}
+                                                                    </span>
+                                                                </a>
+                                                                <button
+                                                                    type="button"
+                                                                    class="text-xs text-blue-600 decoration-2 hover:underline font-medium whitespace-nowrap"
+                                                                    on:click=move |_| monitor.acknowledge(&rule_id)
+                                                                >
+                                                                    Dismiss
+                                                                </button>
+                                                            </div>
+                                                        }
+                                                    })
+                                                    .collect_view()
+                                            }
+                                        }}
+
+                                    </div>
+                                    <div class="p-1.5">
+                                        <a
+                                            class="flex items-center gap-x-3.5 py-2 px-3 rounded-lg text-sm text-gray-800 hover:bg-gray-100 focus:outline-none focus:bg-gray-100 dark:text-neutral-400 dark:hover:bg-neutral-700 dark:hover:text-neutral-300 dark:focus:bg-neutral-700 dark:focus:text-neutral-300"
+                                            href="/manage/alerts"
+                                            on:click=move |_| show_alerts_dropdown.set(false)
+                                        >
+                                            Manage alert rules
+                                        </a>
+                                    </div>
+
+                                </div>
+                            </div>
+
                             <div class="hs-dropdown relative inline-flex">
 
                                 <button
@@ -117,6 +241,7 @@ pub fn Header(permissions: Memo<Option<Permissions>>) -> impl IntoView {
                                     class="size-[38px] relative inline-flex justify-center items-center gap-x-2 text-sm font-semibold rounded-full border border-transparent text-gray-800 hover:bg-gray-100 focus:outline-none focus:bg-gray-100 disabled:opacity-50 disabled:pointer-events-none dark:text-white dark:hover:bg-neutral-700 dark:focus:bg-neutral-700"
                                     on:click=move |_| {
                                         show_account_dropdown.set(false);
+                                        show_alerts_dropdown.set(false);
                                         show_action_dropdown
                                             .update(|v| {
                                                 *v = !*v;
@@ -212,6 +337,7 @@ pub fn Header(permissions: Memo<Option<Permissions>>) -> impl IntoView {
                                     class="size-[38px] relative inline-flex justify-center items-center gap-x-2 text-sm font-semibold rounded-full border border-transparent text-gray-800 hover:bg-gray-100 focus:outline-none focus:bg-gray-100 disabled:opacity-50 disabled:pointer-events-none dark:text-white dark:hover:bg-neutral-700 dark:focus:bg-neutral-700"
                                     on:click=move |_| {
                                         show_action_dropdown.set(false);
+                                        show_alerts_dropdown.set(false);
                                         show_account_dropdown
                                             .update(|v| {
                                                 *v = !*v;
@@ -269,27 +395,34 @@ pub fn Header(permissions: Memo<Option<Permissions>>) -> impl IntoView {
                                                     .map(|p| {
                                                         if p.has_access(Permission::ManageEncryption) {
                                                             "/account/crypto"
-                                                        } else {
+                                                        } else if p.has_access(Permission::ManagePasswords) {
                                                             "/account/password"
+                                                        } else {
+                                                            "/account/preferences"
                                                         }
                                                     })
                                             }
 
-                                            class:hidden=move || {
-                                                permissions
-                                                    .get()
-                                                    .is_none_or(|p| {
-                                                        !p
-                                                            .has_access_any(
-                                                                &[Permission::ManageEncryption, Permission::ManagePasswords],
-                                                            )
-                                                    })
-                                            }
+                                            class:hidden=move || permissions.get().is_none()
                                         >
 
                                             <IconUserCircle/>
                                             Account
                                         </a>
+                                        <button
+                                            type="button"
+                                            class="w-full flex items-center gap-x-3.5 py-2 px-3 rounded-lg text-sm text-gray-800 hover:bg-gray-100 focus:outline-none focus:bg-gray-100 disabled:opacity-50 disabled:pointer-events-none dark:text-neutral-400 dark:hover:bg-neutral-700 dark:hover:text-neutral-300 dark:focus:bg-neutral-700 dark:focus:text-neutral-300"
+                                            title=move || last_undo.get()
+                                            disabled=move || last_undo.get().is_none() || undo.is_pending()
+                                            on:click=move |_| {
+                                                show_account_dropdown.set(false);
+                                                undo.undo();
+                                            }
+                                        >
+
+                                            <IconArrowUTurnLeft/>
+                                            Undo last change
+                                        </button>
                                         <a
                                             class="flex items-center gap-x-3.5 py-2 px-3 rounded-lg text-sm text-gray-800 hover:bg-gray-100 focus:outline-none focus:bg-gray-100 dark:text-neutral-400 dark:hover:bg-neutral-700 dark:hover:text-neutral-300 dark:focus:bg-neutral-700 dark:focus:text-neutral-300"
                                             on:click=move |_| {
@@ -313,3 +446,12 @@ pub fn Header(permissions: Memo<Option<Permissions>>) -> impl IntoView {
         </header>
     }
 }
+
+fn is_editable_target(ev: &ev::KeyboardEvent) -> bool {
+    ev.target()
+        .and_then(|target| target.dyn_into::<web_sys::HtmlElement>().ok())
+        .is_some_and(|element| {
+            element.is_content_editable()
+                || matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+        })
+}
@@ -20,8 +20,9 @@ use crate::{
         messages::modal::Modal,
     },
     core::{
+        oauth::use_authorization,
         schema::{Schema, SchemaType},
-        Permissions,
+        Permissions, TARGET_API_VERSION,
     },
 };
 
@@ -52,6 +53,7 @@ pub fn Layout(
 ) -> impl IntoView {
     let menu_items_toggle = menu_items.clone();
     let show_sidebar = create_rw_signal(false);
+    let auth = use_authorization();
 
     view! {
         <Body class="bg-gray-50 dark:bg-slate-900"/>
@@ -60,6 +62,22 @@ pub fn Layout(
         <ToggleNavigation menu_items show_sidebar/>
         <SideBar menu_items=menu_items_toggle show_sidebar/>
         <div class="w-full pt-10 px-4 sm:px-6 md:px-8 lg:ps-72">
+            <Show when=move || auth.with(|auth| auth.is_newer_server())>
+                <div
+                    class="mt-4 bg-yellow-50 border border-yellow-200 text-sm text-yellow-800 rounded-lg p-4 dark:bg-yellow-800/10 dark:border-yellow-900 dark:text-yellow-500"
+                    role="alert"
+                >
+                    {move || {
+                        format!(
+                            "This web admin was built for Stalwart Mail Server {}.{} but the server is running version {}. Some settings may be missing, please upgrade the web admin.",
+                            TARGET_API_VERSION.major(),
+                            TARGET_API_VERSION.minor(),
+                            auth.with(|auth| auth.server_version),
+                        )
+                    }}
+
+                </div>
+            </Show>
             <Outlet/>
         </div>
     }
@@ -78,7 +96,7 @@ impl LayoutBuilder {
     pub fn create_from_schema(mut self, schema: &Schema) -> Self {
         let route = if matches!(
             schema.typ,
-            SchemaType::Record { .. } | SchemaType::Entry { .. }
+            SchemaType::Record { .. } | SchemaType::Entry { .. } | SchemaType::Map { .. }
         ) {
             format!("/settings/{}", schema.id)
         } else {
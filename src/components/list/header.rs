@@ -6,14 +6,44 @@
 
 use leptos::*;
 
-use crate::components::list::ItemSelection;
+use crate::{components::list::ItemSelection, core::schema::SortOrder};
+
+// Sort key of each column, columns without a key cannot be sorted
+#[derive(Clone)]
+pub struct ColumnSort {
+    pub keys: Vec<Option<String>>,
+    pub current: Signal<Option<(String, SortOrder)>>,
+    pub on_sort: Callback<String, ()>,
+}
+
+// Filter control of each column, the callback receives the column key and
+// the new value, or None when the filter is cleared
+#[derive(Clone)]
+pub struct ColumnFilters {
+    pub columns: Vec<Option<(String, ColumnFilter)>>,
+    pub current: Signal<Vec<(String, String)>>,
+    pub on_filter: Callback<(String, Option<String>), ()>,
+}
+
+#[derive(Clone)]
+pub enum ColumnFilter {
+    Text,
+    Options(Vec<(String, String)>),
+    Boolean,
+}
+
+const FILTER_CLASS: &str = "mt-1 py-1 px-2 block w-full border-gray-200 rounded-md text-xs font-normal normal-case focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400";
 
 #[component]
 pub fn ColumnList(
     #[prop(into)] headers: MaybeSignal<Vec<String>>,
     #[prop(into, optional)] has_select_all: bool,
+    #[prop(optional)] sort: Option<ColumnSort>,
+    #[prop(optional)] filters: Option<ColumnFilters>,
     children: Children,
 ) -> impl IntoView {
+    let sort = store_value(sort);
+    let filters = store_value(filters);
     let headers_ = headers.clone();
     let total_columns = create_memo(move |_| headers_.get().len());
 
@@ -55,13 +85,63 @@ pub fn ColumnList(
                         } else {
                             "px-6 py-3 text-start"
                         };
-                        view! {
-                            <th scope="col" class=class>
-                                <div class="flex items-center gap-x-2">
+                        let sort_key = sort
+                            .with_value(|sort| {
+                                sort.as_ref().and_then(|sort| sort.keys.get(idx).cloned().flatten())
+                            });
+                        let header = match sort_key {
+                            Some(sort_key) => {
+                                let sort_key_ = sort_key.clone();
+                                let indicator = move || {
+                                    sort.with_value(|sort| {
+                                        sort.as_ref()
+                                            .and_then(|sort| sort.current.get())
+                                            .filter(|(key, _)| key == &sort_key_)
+                                            .map(|(_, order)| match order {
+                                                SortOrder::Ascending => "↑",
+                                                SortOrder::Descending => "↓",
+                                            })
+                                    })
+                                };
+                                view! {
+                                    <button
+                                        type="button"
+                                        class="inline-flex items-center gap-x-1 text-xs font-semibold uppercase tracking-wide text-gray-800 hover:text-blue-600 dark:text-gray-200 dark:hover:text-blue-500"
+                                        on:click=move |_| {
+                                            sort.with_value(|sort| {
+                                                if let Some(sort) = sort {
+                                                    sort.on_sort.call(sort_key.clone());
+                                                }
+                                            })
+                                        }
+                                    >
+
+                                        {header}
+                                        <span class="text-gray-500">{indicator}</span>
+                                    </button>
+                                }
+                                    .into_view()
+                            }
+                            None => {
+                                view! {
                                     <span class="text-xs font-semibold uppercase tracking-wide text-gray-800 dark:text-gray-200">
                                         {header}
                                     </span>
-                                </div>
+                                }
+                                    .into_view()
+                            }
+                        };
+                        let filter = filters
+                            .with_value(|filters| {
+                                filters
+                                    .as_ref()
+                                    .and_then(|filters| filters.columns.get(idx).cloned().flatten())
+                            })
+                            .map(|(key, filter)| column_filter(filters, key, filter));
+                        view! {
+                            <th scope="col" class=class>
+                                <div class="flex items-center gap-x-2">{header}</div>
+                                {filter}
                             </th>
                         }
                     }
@@ -75,3 +155,87 @@ pub fn ColumnList(
         </tbody>
     }
 }
+
+fn column_filter(
+    filters: StoredValue<Option<ColumnFilters>>,
+    key: String,
+    filter: ColumnFilter,
+) -> impl IntoView {
+    let key = store_value(key);
+    let current = move || {
+        filters.with_value(|filters| {
+            filters.as_ref().and_then(|filters| {
+                key.with_value(|key| {
+                    filters
+                        .current
+                        .get()
+                        .into_iter()
+                        .find_map(|(k, value)| (&k == key).then_some(value))
+                })
+            })
+        })
+    };
+    let on_change = move |value: String| {
+        filters.with_value(|filters| {
+            if let Some(filters) = filters {
+                let value = Some(value).filter(|value| !value.trim().is_empty());
+                filters.on_filter.call((key.get_value(), value));
+            }
+        })
+    };
+
+    match filter {
+        ColumnFilter::Text => view! {
+            <input
+                type="text"
+                class=FILTER_CLASS
+                placeholder="Contains"
+                prop:value=move || current().unwrap_or_default()
+                on:change=move |ev| on_change(event_target_value(&ev))
+            />
+        }
+        .into_view(),
+        ColumnFilter::Options(options) => view! {
+            <select
+                class=FILTER_CLASS
+                on:change=move |ev| on_change(event_target_value(&ev))
+            >
+                <option value="" selected=move || current().is_none()>
+                    "All"
+                </option>
+                {options
+                    .into_iter()
+                    .map(|(value, label)| {
+                        let value_ = value.clone();
+                        view! {
+                            <option
+                                value=value
+                                selected=move || current().is_some_and(|current| current == value_)
+                            >
+                                {label}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+        }
+        .into_view(),
+        ColumnFilter::Boolean => view! {
+            <select
+                class=FILTER_CLASS
+                on:change=move |ev| on_change(event_target_value(&ev))
+            >
+                <option value="" selected=move || current().is_none()>
+                    "Any"
+                </option>
+                <option value="true" selected=move || current().as_deref() == Some("true")>
+                    "Yes"
+                </option>
+                <option value="false" selected=move || current().as_deref() == Some("false")>
+                    "No"
+                </option>
+            </select>
+        }
+        .into_view(),
+    }
+}
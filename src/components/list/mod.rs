@@ -37,10 +37,47 @@ pub enum ItemSelection {
 pub fn ListTable(
     #[prop(optional, into)] title: MaybeSignal<String>,
     #[prop(optional, into)] subtitle: MaybeSignal<String>,
+    #[prop(optional)] scroll_top: Option<RwSignal<i32>>,
     children: Children,
     toolbar: Toolbar,
     footer: Footer,
 ) -> impl IntoView {
+    let table = match scroll_top {
+        Some(scroll_top) => {
+            let container = create_node_ref::<html::Div>();
+            create_effect(move |_| {
+                let top = scroll_top.get();
+                if let Some(container) = container.get() {
+                    if container.scroll_top() != top {
+                        container.set_scroll_top(top);
+                    }
+                }
+            });
+
+            view! {
+                <div
+                    node_ref=container
+                    class="max-h-[36rem] overflow-y-auto"
+                    on:scroll=move |ev| {
+                        scroll_top.set(event_target::<web_sys::Element>(&ev).scroll_top());
+                    }
+                >
+
+                    <table class="min-w-full divide-y divide-gray-200 dark:divide-gray-700">
+                        {children()}
+                    </table>
+                </div>
+            }
+            .into_view()
+        }
+        None => view! {
+            <table class="min-w-full divide-y divide-gray-200 dark:divide-gray-700">
+                {children()}
+            </table>
+        }
+        .into_view(),
+    };
+
     view! {
         <div class="flex flex-col">
             <div class="-m-1.5">
@@ -73,9 +110,7 @@ pub fn ListTable(
 
                         </div>
 
-                        <table class="min-w-full divide-y divide-gray-200 dark:divide-gray-700">
-                            {children()}
-                        </table>
+                        {table}
 
                         {(footer.children)()}
                     </div>
@@ -125,8 +160,20 @@ pub fn ZeroResults(
     #[prop(into)] subtitle: MaybeSignal<String>,
     #[prop(into, optional)] button_text: MaybeSignal<String>,
     #[prop(into, optional)] button_action: Option<Callback<(), ()>>,
+    #[prop(optional)] doc_url: Option<String>,
 ) -> impl IntoView {
     let has_button = button_action.is_some();
+    let doc_link = doc_url.map(|url| {
+        view! {
+            <a
+                class="mt-2 inline-flex items-center gap-x-1 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:text-blue-500"
+                href=url
+                target="_blank"
+            >
+                Read the documentation
+            </a>
+        }
+    });
 
     view! {
         <div class="max-w-sm w-full min-h-[400px] flex flex-col justify-center mx-auto px-6 py-4">
@@ -151,6 +198,7 @@ pub fn ZeroResults(
 
             <h2 class="mt-5 font-semibold text-gray-800 dark:text-white">{title}</h2>
             <p class="mt-2 text-sm text-gray-600 dark:text-gray-400">{subtitle}</p>
+            {doc_link}
 
             <Show when=move || { has_button }>
 
@@ -201,6 +249,18 @@ impl ItemSelection {
         }
     }
 
+    pub fn select_items(&mut self, items: impl IntoIterator<Item = String>) {
+        match self {
+            ItemSelection::All => {}
+            ItemSelection::None => {
+                *self = ItemSelection::Some(items.into_iter().collect());
+            }
+            ItemSelection::Some(set) => {
+                set.extend(items);
+            }
+        }
+    }
+
     pub fn has_selection(&self) -> bool {
         match self {
             ItemSelection::Some(set) => !set.is_empty(),
@@ -14,10 +14,60 @@ pub fn Pagination(
     #[prop(into)] total_results: MaybeSignal<Option<u32>>,
     #[prop(into)] page_size: MaybeSignal<u32>,
     #[prop(into)] on_page_change: Callback<u32, ()>,
+    #[prop(optional)] show_range: bool,
+    #[prop(optional, into)] page_sizes: MaybeSignal<&'static [u32]>,
+    #[prop(optional, into)] on_page_size_change: Option<Callback<u32, ()>>,
 ) -> impl IntoView {
+    let page_size_select = move || {
+        let page_sizes = page_sizes.get();
+        on_page_size_change
+            .filter(|_| !page_sizes.is_empty())
+            .map(|on_page_size_change| {
+                view! {
+                    <p class="text-sm text-gray-600 dark:text-gray-400">"Show"</p>
+                    <div class="max-w-sm space-y-3">
+                        <select
+                            class="py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400"
+                            on:change=move |ev| {
+                                if let Ok(size) = event_target_value(&ev).parse() {
+                                    on_page_size_change.call(size);
+                                }
+                            }
+                        >
+
+                            {page_sizes
+                                .iter()
+                                .map(|size| {
+                                    let size = *size;
+                                    view! {
+                                        <option value=size selected=move || page_size.get() == size>
+                                            {size}
+                                        </option>
+                                    }
+                                })
+                                .collect_view()}
+
+                        </select>
+                    </div>
+                    <p class="text-sm text-gray-600 dark:text-gray-400">"per page"</p>
+                }
+            })
+    };
+
     let total_pages = create_memo(move |_| {
         (total_results.get().unwrap_or(0) as f64 / page_size.get() as f64).ceil() as u32
     });
+    let range = move || {
+        let total = total_results.get().unwrap_or(0);
+        let first = (current_page.get().saturating_sub(1) * page_size.get()).min(total);
+        let last = (first + page_size.get()).min(total);
+        format!(
+            "Showing {}\u{2013}{} of {}",
+            format_count(first + 1),
+            format_count(last),
+            format_count(total)
+        )
+    };
 
     view! {
         <div class="px-6 py-4 grid gap-3 md:flex md:justify-between md:items-center border-t border-gray-200 dark:border-gray-700">
@@ -25,10 +75,23 @@ pub fn Pagination(
                 <div class="inline-flex items-center gap-x-2">
 
                     <p class="text-sm text-gray-600 dark:text-gray-400">
-                        <span class="font-semibold text-gray-800 dark:text-gray-200">
-                            {move || { total_results.get().map_or(0, |r| r) }}
-                        </span>
-                        " results. Page"
+                        <Show
+                            when=move || show_range
+                            fallback=move || {
+                                view! {
+                                    <span class="font-semibold text-gray-800 dark:text-gray-200">
+                                        {move || { total_results.get().map_or(0, |r| r) }}
+                                    </span>
+                                    " results"
+                                }
+                            }
+                        >
+
+                            <span class="font-semibold text-gray-800 dark:text-gray-200">
+                                {range}
+                            </span>
+                        </Show>
+                        ". Page"
 
                     </p>
                     <div class="max-w-sm space-y-3">
@@ -106,6 +169,7 @@ pub fn Pagination(
                     </div>
 
                     <p class="text-sm text-gray-600 dark:text-gray-400">"of " {total_pages}</p>
+                    {page_size_select}
                 </div>
 
             </Show>
@@ -204,3 +268,15 @@ pub fn ItemPagination(
         </Show>
     }
 }
+
+fn format_count(count: u32) -> String {
+    let digits = count.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (idx, digit) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(digit);
+    }
+    result
+}
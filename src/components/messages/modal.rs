@@ -7,18 +7,31 @@
 use leptos::{html::Div, *};
 use leptos_use::on_click_outside;
 use std::sync::Arc;
+use web_sys::wasm_bindgen::JsCast;
 
-use crate::components::icon::IconXMark;
+use crate::components::{
+    diff::{DiffEntry, DiffTable},
+    icon::IconXMark,
+};
 
 pub trait ModalCb: Fn() + 'static {}
 
+const FOCUSABLE: &str = "a[href], button:not([disabled]), input:not([disabled]), select:not([disabled]), textarea:not([disabled]), [tabindex]:not([tabindex='-1'])";
+
 #[derive(Clone)]
 pub struct Modal {
     pub is_open: bool,
     pub title: String,
     pub message: String,
     pub button_text: String,
+    pub cancel_text: String,
     pub danger: bool,
+    pub details: Vec<String>,
+    pub diff: Vec<DiffEntry>,
+    pub links: Vec<(String, String)>,
+    pub acknowledge: Option<String>,
+    pub confirm_text: Option<String>,
+    pub options: Vec<(String, RwSignal<bool>)>,
     pub on_confirm: Arc<dyn Fn()>,
 }
 
@@ -36,19 +49,75 @@ pub fn use_modals() -> RwSignal<Modal> {
 pub fn Modal() -> impl IntoView {
     let modal = expect_context::<RwSignal<Modal>>();
     let (processing, set_processing) = create_signal(false);
+    let acknowledged = create_rw_signal(false);
+    let typed = create_rw_signal(String::new());
+    let modal_target: NodeRef<Div> = create_node_ref::<Div>();
+    let previous_focus = store_value(None::<web_sys::HtmlElement>);
+    create_effect(move |was_open: Option<bool>| {
+        let is_open = modal.with(|modal| modal.is_open);
+        if is_open && was_open != Some(true) {
+            acknowledged.set(false);
+            typed.set(String::new());
 
-    // Dismiss modal when "Escape" (or 'q') key is pressed
-    let dismiss_modal_with_keyboard = window_event_listener(ev::keydown, move |ev| {
-        if ev.key() == "Escape" || ev.key() == "q" || ev.key() == "Q" {
-            modal.update(|modal| {
-                modal.is_open = false;
+            // Move focus into the dialog and remember where it came from
+            previous_focus.set_value(
+                document()
+                    .active_element()
+                    .and_then(|element| element.dyn_into::<web_sys::HtmlElement>().ok()),
+            );
+            request_animation_frame(move || {
+                if let Some(element) = modal_target
+                    .get_untracked()
+                    .and_then(|target| focusable_elements(&target).into_iter().next())
+                {
+                    let _ = element.focus();
+                }
             });
+        } else if !is_open && was_open == Some(true) {
+            if let Some(element) = previous_focus.get_value() {
+                let _ = element.focus();
+            }
+            previous_focus.set_value(None);
+        }
+        is_open
+    });
+
+    // Dismiss modal when "Escape" (or 'q') key is pressed, keep Tab inside the dialog
+    let dismiss_modal_with_keyboard = window_event_listener(ev::keydown, move |ev| {
+        if !modal.with_untracked(|modal| modal.is_open) {
+            return;
+        }
+
+        match ev.key().as_str() {
+            "Escape" | "q" | "Q" => {
+                modal.update(|modal| {
+                    modal.is_open = false;
+                });
+            }
+            "Tab" => {
+                if let Some(target) = modal_target.get_untracked() {
+                    let focusable = focusable_elements(&target);
+                    if let (Some(first), Some(last)) = (focusable.first(), focusable.last()) {
+                        let active = document().active_element();
+                        let is_inside = target.contains(active.as_ref().map(|active| &**active));
+                        if ev.shift_key() && (!is_inside || active.as_ref() == Some(&**first)) {
+                            ev.prevent_default();
+                            let _ = last.focus();
+                        } else if !ev.shift_key()
+                            && (!is_inside || active.as_ref() == Some(&**last))
+                        {
+                            ev.prevent_default();
+                            let _ = first.focus();
+                        }
+                    }
+                }
+            }
+            _ => {}
         }
     });
     on_cleanup(move || dismiss_modal_with_keyboard.remove());
 
     // Click outside modal to dismiss
-    let modal_target: NodeRef<Div> = create_node_ref::<Div>();
     on_cleanup(on_click_outside(modal_target, move |_| {
         modal.update(|modal| {
             modal.is_open = false;
@@ -69,9 +138,18 @@ pub fn Modal() -> impl IntoView {
                             class="hs-overlay-open:mt-7 hs-overlay-open:opacity-100 hs-overlay-open:duration-500 mt-0 opacity-0 ease-out transition-all sm:max-w-lg sm:w-full m-3 sm:mx-auto min-h-[calc(100%-3.5rem)] flex items-center"
                             node_ref=modal_target
                         >
-                            <div class="w-full flex flex-col bg-white border shadow-sm rounded-xl dark:bg-gray-800 dark:border-gray-700 dark:shadow-slate-700/[.7]">
+                            <div
+                                class="w-full flex flex-col bg-white border shadow-sm rounded-xl dark:bg-gray-800 dark:border-gray-700 dark:shadow-slate-700/[.7]"
+                                role=move || {
+                                    if modal.get().danger { "alertdialog" } else { "dialog" }
+                                }
+
+                                aria-modal="true"
+                                aria-labelledby="modal-title"
+                                aria-describedby="modal-message"
+                            >
                                 <div class="flex justify-between items-center py-3 px-4 border-b dark:border-gray-700">
-                                    <h3 class="font-bold text-gray-800 dark:text-white">
+                                    <h3 id="modal-title" class="font-bold text-gray-800 dark:text-white">
                                         {move || { modal.get().title }}
                                     </h3>
                                     <button
@@ -91,9 +169,134 @@ pub fn Modal() -> impl IntoView {
                                     </button>
                                 </div>
                                 <div class="p-4 overflow-y-auto">
-                                    <p class="text-gray-800 dark:text-gray-400">
+                                    <p id="modal-message" class="text-gray-800 dark:text-gray-400">
                                         {move || { modal.get().message }}
                                     </p>
+                                    <ul
+                                        class="list-disc space-y-1 ps-5 mt-3 text-sm text-gray-800 dark:text-gray-400"
+                                        class:hidden=move || modal.get().details.is_empty()
+                                    >
+                                        {move || {
+                                            modal
+                                                .get()
+                                                .details
+                                                .into_iter()
+                                                .map(|detail| view! { <li>{detail}</li> })
+                                                .collect_view()
+                                        }}
+
+                                    </ul>
+                                    <Show when=move || modal.with(|modal| !modal.diff.is_empty())>
+                                        <div class="mt-3">
+                                            <DiffTable entries=Signal::derive(move || {
+                                                modal.with(|modal| modal.diff.clone())
+                                            })/>
+                                        </div>
+                                    </Show>
+                                    <ul
+                                        class="list-disc space-y-1 ps-5 mt-3 text-sm"
+                                        class:hidden=move || modal.get().links.is_empty()
+                                    >
+                                        {move || {
+                                            modal
+                                                .get()
+                                                .links
+                                                .into_iter()
+                                                .map(|(text, url)| {
+                                                    view! {
+                                                        <li>
+                                                            <a
+                                                                class="text-blue-600 decoration-2 hover:underline dark:text-blue-500"
+                                                                href=url
+                                                                on:click=move |_| {
+                                                                    modal
+                                                                        .update(|modal| {
+                                                                            modal.is_open = false;
+                                                                        });
+                                                                }
+                                                            >
+
+                                                                {text}
+                                                            </a>
+                                                        </li>
+                                                    }
+                                                })
+                                                .collect_view()
+                                        }}
+
+                                    </ul>
+                                    {move || {
+                                        modal
+                                            .get()
+                                            .acknowledge
+                                            .map(|text| {
+                                                view! {
+                                                    <label class="flex mt-4 text-sm text-gray-800 dark:text-gray-400">
+                                                        <input
+                                                            type="checkbox"
+                                                            class="shrink-0 mt-0.5 border-gray-200 rounded text-blue-600 focus:ring-blue-500 dark:bg-gray-800 dark:border-gray-700"
+                                                            prop:checked=move || acknowledged.get()
+                                                            on:change=move |ev| {
+                                                                acknowledged.set(event_target_checked(&ev));
+                                                            }
+                                                        />
+
+                                                        <span class="ms-3">{text}</span>
+                                                    </label>
+                                                }
+                                            })
+                                    }}
+                                    {move || {
+                                        modal
+                                            .get()
+                                            .confirm_text
+                                            .map(|text| {
+                                                view! {
+                                                    <label
+                                                        for="modal-confirm-text"
+                                                        class="block mt-4 text-sm text-gray-800 dark:text-gray-400"
+                                                    >
+                                                        "Type "
+                                                        <code class="font-semibold">{text}</code>
+                                                        " to confirm"
+                                                    </label>
+                                                    <input
+                                                        id="modal-confirm-text"
+                                                        type="text"
+                                                        autocomplete="off"
+                                                        class="mt-2 py-2 px-3 block w-full border-gray-200 rounded-lg text-sm focus:border-red-500 focus:ring-red-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                                                        prop:value=move || typed.get()
+                                                        on:input=move |ev| {
+                                                            typed.set(event_target_value(&ev));
+                                                        }
+                                                    />
+                                                }
+                                            })
+                                    }}
+                                    {move || {
+                                        modal
+                                            .get()
+                                            .options
+                                            .into_iter()
+                                            .map(|(text, value)| {
+                                                view! {
+                                                    <label class="flex mt-4 text-sm text-gray-800 dark:text-gray-400">
+                                                        <input
+                                                            type="checkbox"
+                                                            class="shrink-0 mt-0.5 border-gray-200 rounded text-blue-600 focus:ring-blue-500 dark:bg-gray-800 dark:border-gray-700"
+                                                            prop:checked=move || value.get()
+                                                            on:change=move |ev| {
+                                                                value.set(event_target_checked(&ev));
+                                                            }
+                                                        />
+
+                                                        <span class="ms-3">{text}</span>
+                                                    </label>
+                                                }
+                                            })
+                                            .collect_view()
+                                    }}
+
                                 </div>
                                 <div class="flex justify-end items-center gap-x-2 py-3 px-4 border-t dark:border-gray-700">
                                     <button
@@ -107,7 +310,7 @@ pub fn Modal() -> impl IntoView {
                                         }
                                     >
 
-                                        Close
+                                        {move || { modal.get().cancel_text }}
                                     </button>
                                     <button
                                         type="button"
@@ -129,7 +332,15 @@ pub fn Modal() -> impl IntoView {
                                                 });
                                         }
 
-                                        disabled=move || processing.get()
+                                        disabled=move || {
+                                            processing.get()
+                                                || (modal.get().acknowledge.is_some()
+                                                    && !acknowledged.get())
+                                                || modal
+                                                    .get()
+                                                    .confirm_text
+                                                    .is_some_and(|text| typed.get() != text)
+                                        }
                                     >
 
                                         {move || { modal.get().button_text }}
@@ -144,6 +355,18 @@ pub fn Modal() -> impl IntoView {
     }
 }
 
+fn focusable_elements(target: &web_sys::Element) -> Vec<web_sys::HtmlElement> {
+    let Ok(nodes) = target.query_selector_all(FOCUSABLE) else {
+        return vec![];
+    };
+
+    (0..nodes.length())
+        .filter_map(|idx| nodes.item(idx))
+        .filter_map(|node| node.dyn_into::<web_sys::HtmlElement>().ok())
+        .filter(|element| element.offset_parent().is_some())
+        .collect()
+}
+
 impl Modal {
     pub fn with_title(title: impl Into<String>) -> Self {
         Self {
@@ -151,7 +374,14 @@ impl Modal {
             title: title.into(),
             message: String::new(),
             button_text: "Confirm".to_string(),
+            cancel_text: "Close".to_string(),
             danger: false,
+            details: vec![],
+            diff: vec![],
+            links: vec![],
+            acknowledge: None,
+            confirm_text: None,
+            options: vec![],
             on_confirm: Arc::new(|| {}),
         }
     }
@@ -160,11 +390,48 @@ impl Modal {
         self
     }
 
+    pub fn with_details(mut self, details: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.details = details.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_diff(mut self, diff: Vec<DiffEntry>) -> Self {
+        self.diff = diff;
+        self
+    }
+
+    pub fn with_links(mut self, links: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.links = links.into_iter().collect();
+        self
+    }
+
+    pub fn with_acknowledgement(mut self, text: impl Into<String>) -> Self {
+        self.acknowledge = Some(text.into());
+        self
+    }
+
+    // The confirm button stays disabled until the text is typed back
+    pub fn with_typed_confirmation(mut self, text: impl Into<String>) -> Self {
+        self.confirm_text = Some(text.into());
+        self
+    }
+
+    // Options are read back by the confirm callback through their signals
+    pub fn with_option(mut self, text: impl Into<String>, value: RwSignal<bool>) -> Self {
+        self.options.push((text.into(), value));
+        self
+    }
+
     pub fn with_button(mut self, button_text: impl Into<String>) -> Self {
         self.button_text = button_text.into();
         self
     }
 
+    pub fn with_cancel_button(mut self, cancel_text: impl Into<String>) -> Self {
+        self.cancel_text = cancel_text.into();
+        self
+    }
+
     pub fn with_dangerous_callback(mut self, on_confirm: impl Fn() + 'static) -> Self {
         self.danger = true;
         self.on_confirm = Arc::new(on_confirm);
@@ -185,7 +452,14 @@ impl Default for Modal {
             title: String::new(),
             message: String::new(),
             button_text: "Confirm".to_string(),
+            cancel_text: "Close".to_string(),
             danger: false,
+            details: vec![],
+            diff: vec![],
+            links: vec![],
+            acknowledge: None,
+            confirm_text: None,
+            options: vec![],
             on_confirm: Arc::new(|| {}),
         }
     }
@@ -6,6 +6,7 @@
 
 pub mod badge;
 pub mod card;
+pub mod diff;
 pub mod form;
 pub mod icon;
 pub mod layout;
@@ -0,0 +1,513 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{str::FromStr, time::Duration};
+
+use ahash::AHashMap;
+use chrono::{DateTime, Utc};
+use gloo_storage::{LocalStorage, Storage};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+use crate::{
+    core::{
+        http::{self, HttpRequest},
+        pem::parse_certificates,
+        AccessToken, Permission,
+    },
+    pages::{
+        config::{edit::fetch_active_connections, list::fetch_prefix, Settings},
+        List,
+    },
+    STATE_ALERT_RULES_KEY,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+const CERTIFICATE_EXPIRY_DAYS: i64 = 14;
+
+pub static ALERT_METRICS: &[(&str, &str)] = &[
+    ("queue-size", "Queued messages"),
+    ("quarantine-size", "Quarantined messages"),
+    ("smtp-connections", "Active SMTP connections"),
+    ("imap-connections", "Active IMAP connections"),
+    ("http-connections", "Active HTTP connections"),
+    ("server-unavailable", "Server not ready"),
+    ("certificate-expiry", "Certificates expiring within 14 days"),
+    ("auth-failures", "Authentication failures in the last hour"),
+];
+
+pub static ALERT_COMPARISONS: &[(&str, &str)] = &[
+    ("gt", "Greater than"),
+    ("ge", "Greater than or equal to"),
+    ("lt", "Less than"),
+    ("le", "Less than or equal to"),
+    ("eq", "Equal to"),
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum AlertMetric {
+    QueueSize,
+    QuarantineSize,
+    SmtpConnections,
+    ImapConnections,
+    HttpConnections,
+    ServerUnavailable,
+    CertificateExpiry,
+    AuthFailures,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Comparison {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AlertRule {
+    pub id: String,
+    pub metric: AlertMetric,
+    pub comparison: Comparison,
+    pub threshold: u64,
+    // Check interval in minutes
+    pub interval: u64,
+    #[serde(default)]
+    pub muted: bool,
+    #[serde(default)]
+    pub acknowledged: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct AlertConfig {
+    #[serde(default)]
+    pub rules: Vec<AlertRule>,
+    #[serde(default)]
+    pub notify: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggeredAlert {
+    pub rule_id: String,
+    pub value: u64,
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy)]
+pub struct AlertMonitor {
+    pub config: RwSignal<AlertConfig>,
+    pub triggered: RwSignal<Vec<TriggeredAlert>>,
+}
+
+pub fn init_alert_monitor(auth: RwSignal<AccessToken>) {
+    let monitor = AlertMonitor {
+        config: create_rw_signal(
+            LocalStorage::get::<AlertConfig>(STATE_ALERT_RULES_KEY).unwrap_or_default(),
+        ),
+        triggered: create_rw_signal(Vec::new()),
+    };
+    provide_context(monitor);
+
+    create_effect(move |_| {
+        monitor.config.with(|config| {
+            if let Err(err) = LocalStorage::set(STATE_ALERT_RULES_KEY, config) {
+                log::error!("Failed to save alert rules to local storage: {}", err);
+            }
+        });
+    });
+
+    let last_checked = store_value(AHashMap::<String, DateTime<Utc>>::new());
+    let evaluate = create_action(move |rules: &Vec<AlertRule>| {
+        let rules = rules.clone();
+        let auth = auth.get_untracked();
+
+        async move {
+            for rule in rules {
+                match rule.metric.fetch(&auth).await {
+                    Ok(Some(value)) => {
+                        monitor.update(&rule, value);
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        log::debug!("Failed to evaluate alert rule {}: {:?}", rule.id, err);
+                    }
+                }
+            }
+        }
+    });
+
+    let _ = set_interval_with_handle(
+        move || {
+            let auth = auth.get_untracked();
+            if !auth.is_logged_in() || evaluate.pending().get_untracked() {
+                return;
+            }
+
+            let now = Utc::now();
+            let due = monitor
+                .config
+                .get_untracked()
+                .rules
+                .into_iter()
+                .filter(|rule| {
+                    rule.metric
+                        .permission()
+                        .map_or(true, |p| auth.permissions().has_access(p))
+                        && last_checked.with_value(|checked| {
+                            checked.get(&rule.id).map_or(true, |last| {
+                                (now - *last).num_minutes() >= rule.interval as i64
+                            })
+                        })
+                })
+                .collect::<Vec<_>>();
+
+            if !due.is_empty() {
+                last_checked.update_value(|checked| {
+                    for rule in &due {
+                        checked.insert(rule.id.clone(), now);
+                    }
+                });
+                evaluate.dispatch(due);
+            }
+        },
+        POLL_INTERVAL,
+    );
+}
+
+pub fn use_alert_monitor() -> AlertMonitor {
+    expect_context::<AlertMonitor>()
+}
+
+impl AlertMonitor {
+    pub fn active(&self) -> Vec<(AlertRule, TriggeredAlert)> {
+        let triggered = self.triggered.get();
+        self.config.with(|config| {
+            config
+                .rules
+                .iter()
+                .filter(|rule| !rule.muted && !rule.acknowledged)
+                .filter_map(|rule| {
+                    triggered
+                        .iter()
+                        .find(|alert| alert.rule_id == rule.id)
+                        .map(|alert| (rule.clone(), alert.clone()))
+                })
+                .collect()
+        })
+    }
+
+    pub fn triggered(&self, rule_id: &str) -> Option<TriggeredAlert> {
+        self.triggered.with(|triggered| {
+            triggered
+                .iter()
+                .find(|alert| alert.rule_id == rule_id)
+                .cloned()
+        })
+    }
+
+    pub fn rule(&self, rule_id: &str) -> Option<AlertRule> {
+        self.config
+            .with(|config| config.rules.iter().find(|rule| rule.id == rule_id).cloned())
+    }
+
+    pub fn save_rule(&self, rule: AlertRule) {
+        self.config.update(|config| {
+            if let Some(existing) = config.rules.iter_mut().find(|r| r.id == rule.id) {
+                *existing = rule;
+            } else {
+                config.rules.push(rule);
+            }
+        });
+    }
+
+    pub fn delete_rule(&self, rule_id: &str) {
+        self.config
+            .update(|config| config.rules.retain(|rule| rule.id != rule_id));
+        self.triggered
+            .update(|triggered| triggered.retain(|alert| alert.rule_id != rule_id));
+    }
+
+    pub fn set_muted(&self, rule_id: &str, muted: bool) {
+        self.update_rule(rule_id, |rule| rule.muted = muted);
+    }
+
+    pub fn acknowledge(&self, rule_id: &str) {
+        self.update_rule(rule_id, |rule| rule.acknowledged = true);
+    }
+
+    pub fn set_notify(&self, notify: bool) {
+        if notify && Notification::permission() != NotificationPermission::Granted {
+            let _ = Notification::request_permission();
+        }
+        self.config.update(|config| config.notify = notify);
+    }
+
+    fn update_rule(&self, rule_id: &str, f: impl FnOnce(&mut AlertRule)) {
+        self.config.update(|config| {
+            if let Some(rule) = config.rules.iter_mut().find(|rule| rule.id == rule_id) {
+                f(rule);
+            }
+        });
+    }
+
+    fn update(&self, rule: &AlertRule, value: u64) {
+        if rule.comparison.matches(value, rule.threshold) {
+            let mut is_new = false;
+            self.triggered.update(|triggered| {
+                if let Some(alert) = triggered.iter_mut().find(|a| a.rule_id == rule.id) {
+                    alert.value = value;
+                } else {
+                    is_new = true;
+                    triggered.push(TriggeredAlert {
+                        rule_id: rule.id.clone(),
+                        value,
+                        since: Utc::now(),
+                    });
+                }
+            });
+
+            if is_new && !rule.muted && self.config.get_untracked().notify {
+                show_notification(&rule.title(), &rule.description(value));
+            }
+        } else if self.triggered(&rule.id).is_some() {
+            self.triggered
+                .update(|triggered| triggered.retain(|alert| alert.rule_id != rule.id));
+
+            // Acknowledgements only last until the condition clears
+            self.update_rule(&rule.id, |rule| rule.acknowledged = false);
+        }
+    }
+}
+
+impl AlertRule {
+    pub fn new_id() -> String {
+        format!("{:016x}", rand::random::<u64>())
+    }
+
+    pub fn title(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.metric.label(),
+            self.comparison.symbol(),
+            self.threshold
+        )
+    }
+
+    pub fn description(&self, value: u64) -> String {
+        format!(
+            "{} is currently {value} (threshold {} {}).",
+            self.metric.label(),
+            self.comparison.symbol(),
+            self.threshold
+        )
+    }
+}
+
+impl AlertMetric {
+    pub fn id(&self) -> &'static str {
+        match self {
+            AlertMetric::QueueSize => "queue-size",
+            AlertMetric::QuarantineSize => "quarantine-size",
+            AlertMetric::SmtpConnections => "smtp-connections",
+            AlertMetric::ImapConnections => "imap-connections",
+            AlertMetric::HttpConnections => "http-connections",
+            AlertMetric::ServerUnavailable => "server-unavailable",
+            AlertMetric::CertificateExpiry => "certificate-expiry",
+            AlertMetric::AuthFailures => "auth-failures",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        ALERT_METRICS
+            .iter()
+            .find(|(id, _)| *id == self.id())
+            .map(|(_, label)| *label)
+            .unwrap_or_default()
+    }
+
+    pub fn url(&self) -> &'static str {
+        match self {
+            AlertMetric::QueueSize => "/manage/queue/messages",
+            AlertMetric::QuarantineSize => "/manage/queue/quarantine",
+            AlertMetric::SmtpConnections
+            | AlertMetric::ImapConnections
+            | AlertMetric::HttpConnections => "/manage/dashboard/network",
+            AlertMetric::ServerUnavailable => "/manage/system",
+            AlertMetric::CertificateExpiry => "/settings/certificate",
+            AlertMetric::AuthFailures => "/manage/dashboard/security",
+        }
+    }
+
+    pub fn permission(&self) -> Option<Permission> {
+        match self {
+            AlertMetric::QueueSize => Some(Permission::MessageQueueList),
+            AlertMetric::QuarantineSize => Some(Permission::QuarantineList),
+            AlertMetric::SmtpConnections
+            | AlertMetric::ImapConnections
+            | AlertMetric::HttpConnections
+            | AlertMetric::AuthFailures => Some(Permission::MetricsList),
+            AlertMetric::CertificateExpiry => Some(Permission::SettingsList),
+            AlertMetric::ServerUnavailable => None,
+        }
+    }
+
+    async fn fetch(&self, auth: &AccessToken) -> http::Result<Option<u64>> {
+        match self {
+            AlertMetric::QueueSize => fetch_total(auth, "/api/queue/messages").await,
+            AlertMetric::QuarantineSize => fetch_total(auth, "/api/quarantine").await,
+            AlertMetric::SmtpConnections => {
+                Ok(fetch_active_connections(auth, "smtp.active-connections").await)
+            }
+            AlertMetric::ImapConnections => {
+                Ok(fetch_active_connections(auth, "imap.active-connections").await)
+            }
+            AlertMetric::HttpConnections => {
+                Ok(fetch_active_connections(auth, "http.active-connections").await)
+            }
+            AlertMetric::ServerUnavailable => Ok(Some(
+                HttpRequest::get("/healthz/ready")
+                    .with_base_url(auth)
+                    .send_raw()
+                    .await
+                    .is_err() as u64,
+            )),
+            AlertMetric::CertificateExpiry => fetch_prefix(auth, "certificate")
+                .await
+                .map(|settings| Some(expiring_certificates(&settings))),
+            AlertMetric::AuthFailures => Ok(fetch_recent_total(auth, "auth.failed").await),
+        }
+    }
+}
+
+impl Comparison {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Eq => "=",
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Comparison::Gt => "gt",
+            Comparison::Ge => "ge",
+            Comparison::Lt => "lt",
+            Comparison::Le => "le",
+            Comparison::Eq => "eq",
+        }
+    }
+
+    pub fn matches(&self, value: u64, threshold: u64) -> bool {
+        match self {
+            Comparison::Gt => value > threshold,
+            Comparison::Ge => value >= threshold,
+            Comparison::Lt => value < threshold,
+            Comparison::Le => value <= threshold,
+            Comparison::Eq => value == threshold,
+        }
+    }
+}
+
+impl FromStr for AlertMetric {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queue-size" => Ok(AlertMetric::QueueSize),
+            "quarantine-size" => Ok(AlertMetric::QuarantineSize),
+            "smtp-connections" => Ok(AlertMetric::SmtpConnections),
+            "imap-connections" => Ok(AlertMetric::ImapConnections),
+            "http-connections" => Ok(AlertMetric::HttpConnections),
+            "server-unavailable" => Ok(AlertMetric::ServerUnavailable),
+            "certificate-expiry" => Ok(AlertMetric::CertificateExpiry),
+            "auth-failures" => Ok(AlertMetric::AuthFailures),
+            _ => Err(()),
+        }
+    }
+}
+
+impl FromStr for Comparison {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gt" => Ok(Comparison::Gt),
+            "ge" => Ok(Comparison::Ge),
+            "lt" => Ok(Comparison::Lt),
+            "le" => Ok(Comparison::Le),
+            "eq" => Ok(Comparison::Eq),
+            _ => Err(()),
+        }
+    }
+}
+
+pub(crate) async fn fetch_total(auth: &AccessToken, url: &str) -> http::Result<Option<u64>> {
+    HttpRequest::get(url)
+        .with_authorization(auth)
+        .with_parameter("limit", "1")
+        .send::<List<serde_json::Value>>()
+        .await
+        .map(|list| Some(list.total))
+}
+
+// Certificates loaded from files or environment macros cannot be read here
+fn expiring_certificates(settings: &Settings) -> u64 {
+    settings
+        .iter()
+        .filter(|(key, _)| key.ends_with(".cert"))
+        .filter_map(|(_, pem)| parse_certificates(pem).ok()?.into_iter().next())
+        .filter(|leaf| leaf.days_left() < CERTIFICATE_EXPIRY_DAYS)
+        .count() as u64
+}
+
+// SPDX-SnippetBegin
+// SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+// SPDX-License-Identifier: LicenseRef-SEL
+#[cfg(feature = "enterprise")]
+async fn fetch_recent_total(auth: &AccessToken, metric: &str) -> Option<u64> {
+    use crate::pages::enterprise::dashboard::Metric;
+    use chrono::SecondsFormat;
+
+    HttpRequest::get("/api/telemetry/metrics")
+        .with_authorization(auth)
+        .with_parameter(
+            "after",
+            (Utc::now() - chrono::Duration::hours(1)).to_rfc3339_opts(SecondsFormat::Secs, true),
+        )
+        .send::<Vec<Metric>>()
+        .await
+        .ok()
+        .map(|metrics| {
+            metrics
+                .into_iter()
+                .filter_map(|metric_| match metric_ {
+                    Metric::Counter { id, value, .. } if id == metric => Some(value),
+                    _ => None,
+                })
+                .sum()
+        })
+}
+// SPDX-SnippetEnd
+
+#[cfg(not(feature = "enterprise"))]
+async fn fetch_recent_total(_: &AccessToken, _: &str) -> Option<u64> {
+    None
+}
+
+fn show_notification(title: &str, body: &str) {
+    if Notification::permission() == NotificationPermission::Granted {
+        let options = NotificationOptions::new();
+        options.set_body(body);
+        let _ = Notification::new_with_options(title, &options);
+    }
+}
@@ -0,0 +1,54 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Timelike};
+
+// Simplified cron schedule accepted by the server: "<minute> <hour> <day>",
+// where hour and day may be '*' and days are numbered from Monday (1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimpleCron {
+    pub minute: u32,
+    pub hour: Option<u32>,
+    pub day: Option<u32>,
+}
+
+impl SimpleCron {
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split_whitespace();
+        let minute = parts.next()?.parse::<u32>().ok().filter(|m| *m < 60)?;
+        let hour = match parts.next()? {
+            "*" => None,
+            hour => Some(hour.parse::<u32>().ok().filter(|h| *h < 24)?),
+        };
+        let day = match parts.next()? {
+            "*" => None,
+            day => Some(day.parse::<u32>().ok().filter(|d| (1..=7).contains(d))?),
+        };
+
+        if parts.next().is_none() {
+            Some(SimpleCron { minute, hour, day })
+        } else {
+            None
+        }
+    }
+
+    pub fn next_runs(&self, from: NaiveDateTime, count: usize) -> Vec<NaiveDateTime> {
+        let start = from.date().and_time(NaiveTime::MIN) + Duration::hours(from.hour() as i64);
+
+        // A weekly schedule fires at least once within eight days
+        (0..24 * 8)
+            .map(|hours| start + Duration::hours(hours) + Duration::minutes(self.minute as i64))
+            .filter(|time| {
+                *time > from
+                    && self.hour.map_or(true, |hour| time.hour() == hour)
+                    && self
+                        .day
+                        .map_or(true, |day| time.weekday().number_from_monday() == day)
+            })
+            .take(count)
+            .collect()
+    }
+}
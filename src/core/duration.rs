@@ -0,0 +1,93 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+// Milliseconds in each unit, from the largest
+pub const DURATION_UNITS: &[(&str, u64)] = &[
+    ("d", 24 * 60 * 60 * 1000),
+    ("h", 60 * 60 * 1000),
+    ("m", 60 * 1000),
+    ("s", 1000),
+    ("ms", 1),
+];
+
+// Amounts and their units, compound values such as "1h 30m" have several
+pub fn duration_parts(s: &str) -> Option<Vec<(u64, &'static str)>> {
+    let mut parts = Vec::new();
+    let mut chars = s.trim().chars().peekable();
+
+    while chars.peek().is_some() {
+        let mut amount = String::new();
+        let mut unit = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || c.is_ascii_whitespace()) {
+            if !c.is_ascii_whitespace() {
+                amount.push(c);
+            }
+        }
+        while let Some(c) = chars.next_if(|c| c.is_ascii_alphabetic() || c.is_ascii_whitespace()) {
+            if !c.is_ascii_whitespace() {
+                unit.push(c.to_ascii_lowercase());
+            }
+        }
+        let unit = match unit.as_str() {
+            "ms" | "millisecond" | "milliseconds" => "ms",
+            "s" | "sec" | "secs" | "second" | "seconds" => "s",
+            "m" | "min" | "mins" | "minute" | "minutes" => "m",
+            "h" | "hr" | "hrs" | "hour" | "hours" => "h",
+            "d" | "day" | "days" => "d",
+            _ => return None,
+        };
+        parts.push((amount.parse().ok()?, unit));
+    }
+
+    (!parts.is_empty()).then_some(parts)
+}
+
+// Total length of a simple or compound duration, zero is not a duration
+pub fn parse_millis(value: &str) -> Option<u64> {
+    duration_parts(value)?
+        .into_iter()
+        .try_fold(0u64, |total, (amount, unit)| {
+            let (_, millis) = DURATION_UNITS.iter().find(|(id, _)| *id == unit)?;
+            total.checked_add(amount.checked_mul(*millis)?)
+        })
+        .filter(|millis| *millis > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_simple_and_compound_durations() {
+        for (value, expected) in [
+            ("500ms", 500),
+            ("30s", 30_000),
+            ("5m", 300_000),
+            ("1h30m", 5_400_000),
+            ("1h 30m", 5_400_000),
+            ("1 hour 30 minutes", 5_400_000),
+            ("2 Days", 172_800_000),
+            ("1d 1ms", 86_400_001),
+        ] {
+            assert_eq!(parse_millis(value), Some(expected), "{value}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        for value in [
+            "",
+            "0s",
+            "5",
+            "abc",
+            "5 weeks",
+            "1h-30m",
+            "99999999999999999999d",
+        ] {
+            assert_eq!(parse_millis(value), None, "{value}");
+        }
+    }
+}
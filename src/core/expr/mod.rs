@@ -16,6 +16,13 @@ pub struct Expression {
     pub items: Vec<ExpressionItem>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub start: usize,
+    pub end: usize,
+}
+
 #[derive(Debug, Clone)]
 pub enum ExpressionItem {
     Variable(u32),
@@ -97,7 +104,7 @@ impl From<usize> for Constant {
 }
 
 #[allow(clippy::type_complexity)]
-pub(super) const FUNCTIONS: &[(&str, u32)] = &[
+pub(crate) const FUNCTIONS: &[(&str, u32)] = &[
     ("count", 1),
     ("sort", 2),
     ("dedup", 1),
@@ -324,6 +331,40 @@ impl PartialEq for Token {
 
 impl Eq for Token {}
 
+pub fn suggest_name<'x>(
+    token: &str,
+    candidates: impl IntoIterator<Item = &'x str>,
+) -> Option<&'x str> {
+    candidates
+        .into_iter()
+        .chain(FUNCTIONS.iter().map(|(name, _)| *name))
+        .map(|name| (edit_distance(token, name), name))
+        .filter(|(distance, name)| *distance <= 2 && *distance < name.len())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                prev.min(cur).min(row[j]) + 1
+            };
+            prev = cur;
+        }
+    }
+
+    row[b.len()]
+}
+
 pub trait ParseValue: Sized {
     fn parse_value(value: &str) -> Option<Self>;
 }
@@ -4,7 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::{tokenizer::Tokenizer, BinaryOperator, Expression, ExpressionItem, Token};
+use super::{tokenizer::Tokenizer, BinaryOperator, Expression, ExpressionItem, ParseError, Token};
 
 pub struct ExpressionParser<'x, F>
 where
@@ -13,7 +13,7 @@ where
     pub(crate) tokenizer: Tokenizer<'x, F>,
     pub(crate) output: Vec<ExpressionItem>,
     operator_stack: Vec<(Token, Option<usize>)>,
-    arg_count: Vec<i32>,
+    arg_count: Vec<(i32, (usize, usize))>,
 }
 
 pub(crate) const ID_ARRAY_ACCESS: u32 = u32::MAX;
@@ -32,7 +32,15 @@ where
         }
     }
 
-    pub fn parse(mut self) -> Result<Expression, String> {
+    pub fn parse(mut self) -> Result<Expression, ParseError> {
+        self.parse_items().map_err(|message| ParseError {
+            message,
+            start: self.tokenizer.span.0,
+            end: self.tokenizer.span.1,
+        })
+    }
+
+    fn parse_items(&mut self) -> Result<Expression, String> {
         let mut last_is_var_or_fnc = false;
 
         while let Some(token) = self.tokenizer.next()? {
@@ -83,12 +91,20 @@ where
 
                     match self.operator_stack.last() {
                         Some((Token::Function { id, num_args, name }, _)) => {
-                            let got_args = self.arg_count.pop().unwrap();
+                            let (got_args, span) = self.arg_count.pop().unwrap();
                             if got_args != *num_args as i32 {
+                                self.tokenizer.span = span;
                                 return Err(if *id != u32::MAX {
                                     format!(
-                                        "Expression function {:?} expected {} arguments, got {}",
-                                        name, num_args, got_args
+                                        "Function {:?} expects {} {}, got {}",
+                                        name,
+                                        num_args,
+                                        if *num_args == 1 {
+                                            "argument"
+                                        } else {
+                                            "arguments"
+                                        },
+                                        got_args
                                     )
                                 } else {
                                     "Missing array index".to_string()
@@ -108,9 +124,13 @@ where
                             self.output.push(expr);
                         }
                         Some((Token::Regex(regex), _)) => {
-                            if self.arg_count.pop().unwrap() != 1 {
-                                return Err("Expression function \"matches\" expected 2 arguments"
-                                    .to_string());
+                            let (got_args, span) = self.arg_count.pop().unwrap();
+                            if got_args != 1 {
+                                self.tokenizer.span = span;
+                                return Err(format!(
+                                    "Function \"matches\" expects 2 arguments, got {}",
+                                    got_args + 1
+                                ));
                             }
                             self.output.push(ExpressionItem::Regex(regex.clone()));
                             self.operator_stack.pop();
@@ -164,13 +184,13 @@ where
                 }
                 Token::Function { id, name, num_args } => {
                     self.inc_arg_count();
-                    self.arg_count.push(0);
+                    self.arg_count.push((0, self.tokenizer.span));
                     self.operator_stack
                         .push((Token::Function { id, name, num_args }, None))
                 }
                 Token::Regex(regex) => {
                     self.inc_arg_count();
-                    self.arg_count.push(0);
+                    self.arg_count.push((0, self.tokenizer.span));
                     self.operator_stack.push((Token::Regex(regex), None))
                 }
                 Token::OpenBracket => {
@@ -181,7 +201,7 @@ where
                         self.inc_arg_count();
                         (ID_ARRAY_BUILD, 0, 0)
                     };
-                    self.arg_count.push(arg_count);
+                    self.arg_count.push((arg_count, self.tokenizer.span));
                     self.operator_stack.push((
                         Token::Function {
                             id,
@@ -227,14 +247,16 @@ where
         }
 
         if self.operator_stack.is_empty() {
-            Ok(Expression { items: self.output })
+            Ok(Expression {
+                items: std::mem::take(&mut self.output),
+            })
         } else {
             Err("Invalid expression".to_string())
         }
     }
 
     fn inc_arg_count(&mut self) {
-        if let Some(x) = self.arg_count.last_mut() {
+        if let Some((x, _)) = self.arg_count.last_mut() {
             *x = x.saturating_add(1);
             let op_pos = self.operator_stack.len().saturating_sub(2);
             match self.operator_stack.get_mut(op_pos) {
@@ -247,7 +269,7 @@ where
     }
 
     fn dec_arg_count(&mut self) {
-        if let Some(x) = self.arg_count.last_mut() {
+        if let Some((x, _)) = self.arg_count.last_mut() {
             *x = x.saturating_sub(1);
         }
     }
@@ -278,3 +300,106 @@ impl BinaryOperator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VARIABLES: &[&str] = &["sender", "sender_domain", "rcpt", "local_port"];
+
+    // Unknown names are reported by the form validator, see core::form
+    fn parse_error(expr: &str) -> (String, usize, usize) {
+        let result = ExpressionParser::new(Tokenizer::new(expr, |token| {
+            if VARIABLES.contains(&token) {
+                Ok(Token::Variable(0))
+            } else {
+                Err(format!("Unknown name {token:?}"))
+            }
+        }))
+        .parse();
+
+        match result {
+            Ok(_) => panic!("expected {expr:?} to fail"),
+            Err(err) => (err.message, err.start, err.end),
+        }
+    }
+
+    #[test]
+    fn valid_expressions_parse() {
+        for expr in [
+            "sender == 'a@b.c'",
+            "count(rcpt) > 1 && local_port != 25",
+            "matches('^a', sender_domain)",
+            "[sender, rcpt][0]",
+        ] {
+            assert!(
+                ExpressionParser::new(Tokenizer::new(expr, |_| Ok(Token::Variable(0))))
+                    .parse()
+                    .is_ok(),
+                "{expr}"
+            );
+        }
+    }
+
+    #[test]
+    fn tokenizer_errors_keep_their_span() {
+        assert_eq!(
+            parse_error("sender == qqqqqqqq"),
+            ("Unknown name \"qqqqqqqq\"".to_string(), 10, 18)
+        );
+    }
+
+    #[test]
+    fn wrong_argument_count() {
+        assert_eq!(
+            parse_error("count(sender, sender)"),
+            (
+                "Function \"count\" expects 1 argument, got 2".to_string(),
+                0,
+                5
+            )
+        );
+        assert_eq!(
+            parse_error("matches('a', sender, rcpt)").0,
+            "Function \"matches\" expects 2 arguments, got 3"
+        );
+        assert_eq!(
+            parse_error("sender[]"),
+            ("Missing array index".to_string(), 6, 7)
+        );
+    }
+
+    #[test]
+    fn unbalanced_parentheses() {
+        assert_eq!(
+            parse_error("(sender"),
+            ("Unmatched open parenthesis".to_string(), 0, 1)
+        );
+        assert_eq!(
+            parse_error("sender)"),
+            ("Unmatched close parenthesis".to_string(), 6, 7)
+        );
+        assert_eq!(
+            parse_error("sender]"),
+            ("Mismatched parentheses".to_string(), 6, 7)
+        );
+    }
+
+    #[test]
+    fn invalid_characters_and_literals() {
+        assert_eq!(
+            parse_error("sender # 1"),
+            ("Invalid character '#'".to_string(), 7, 8)
+        );
+        assert_eq!(
+            parse_error("sender == 'abc"),
+            ("Unterminated string".to_string(), 10, 14)
+        );
+        assert!(parse_error("99999999999999999999")
+            .0
+            .starts_with("Invalid integer value"));
+        assert!(parse_error("matches('[', sender)")
+            .0
+            .starts_with("Invalid regular expression"));
+    }
+}
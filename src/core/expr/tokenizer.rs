@@ -15,10 +15,13 @@ where
     F: Fn(&str) -> Result<Token, String>,
 {
     pub(crate) iter: Peekable<Iter<'x, u8>>,
+    pub(crate) span: (usize, usize),
     token_map: F,
     buf: Vec<u8>,
-    depth: u32,
-    next_token: Vec<Token>,
+    buf_start: usize,
+    expr_len: usize,
+    open_parens: Vec<usize>,
+    next_token: Vec<(Token, (usize, usize))>,
     has_number: bool,
     has_dot: bool,
     has_alpha: bool,
@@ -34,8 +37,11 @@ where
     pub fn new(expr: &'x str, token_map: F) -> Self {
         Self {
             iter: expr.as_bytes().iter().peekable(),
+            span: (0, 0),
             buf: Vec::new(),
-            depth: 0,
+            buf_start: 0,
+            expr_len: expr.len(),
+            open_parens: Vec::new(),
             next_token: Vec::with_capacity(2),
             has_number: false,
             has_dot: false,
@@ -48,13 +54,19 @@ where
 
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<Option<Token>, String> {
-        if let Some(token) = self.next_token.pop() {
+        if let Some((token, span)) = self.next_token.pop() {
+            self.span = span;
             return Ok(Some(token));
         } else if self.is_eof {
             return Ok(None);
         }
 
         while let Some(&ch) = self.iter.next() {
+            let pos = self.offset() - 1;
+            if self.buf.is_empty() {
+                self.buf_start = pos;
+            }
+
             match ch {
                 b'A'..=b'Z' | b'a'..=b'z' | b'_' | b'$' => {
                     self.buf.push(ch);
@@ -85,8 +97,10 @@ where
                     self.buf.push(ch);
                 }
                 _ => {
+                    let buf_span = (self.buf_start, pos);
                     let (prev_token, ch) = if ch == b'(' && self.buf.eq(b"matches") {
                         // Parse regular expressions
+                        self.span = (self.buf_start, self.expr_len);
                         let stop_ch = self.find_char(b"\"'")?;
                         let regex_str = self.parse_string(stop_ch)?;
                         let regex = Regex::new(&regex_str).map_err(|e| {
@@ -98,10 +112,13 @@ where
                         (Token::Regex(regex).into(), b'(')
                     } else if !self.buf.is_empty() {
                         self.is_start = false;
+                        self.span = buf_span;
                         (self.parse_buf()?.into(), ch)
                     } else {
                         (None, ch)
                     };
+                    let prev_span = self.span;
+                    self.span = (pos, pos + 1);
                     let token = match ch {
                         b'&' => {
                             if matches!(self.iter.peek(), Some(b'&')) {
@@ -125,14 +142,13 @@ where
                         }
                         b'^' => Token::BinaryOperator(BinaryOperator::Xor),
                         b'(' => {
-                            self.depth += 1;
+                            self.open_parens.push(pos);
                             Token::OpenParen
                         }
                         b')' => {
-                            if self.depth == 0 {
+                            if self.open_parens.pop().is_none() {
                                 return Err("Unmatched close parenthesis".to_string());
                             }
-                            self.depth -= 1;
                             Token::CloseParen
                         }
                         b'+' => Token::BinaryOperator(BinaryOperator::Add),
@@ -170,12 +186,18 @@ where
                         b']' => Token::CloseBracket,
                         b' ' | b'\r' | b'\n' => {
                             if prev_token.is_some() {
+                                self.span = prev_span;
                                 return Ok(prev_token);
                             } else {
                                 continue;
                             }
                         }
-                        b'\"' | b'\'' => Token::Constant(Constant::String(self.parse_string(ch)?)),
+                        b'\"' | b'\'' => {
+                            self.span = (pos, self.expr_len);
+                            let token = Token::Constant(Constant::String(self.parse_string(ch)?));
+                            self.span = (pos, self.offset());
+                            token
+                        }
                         _ => {
                             return Err(format!("Invalid character {:?}", char::from(ch),));
                         }
@@ -186,7 +208,8 @@ where
                     );
 
                     return if prev_token.is_some() {
-                        self.next_token.push(token);
+                        self.next_token.push((token, self.span));
+                        self.span = prev_span;
                         Ok(prev_token)
                     } else {
                         Ok(Some(token))
@@ -195,15 +218,21 @@ where
             }
         }
 
-        if self.depth > 0 {
+        if let Some(pos) = self.open_parens.last() {
+            self.span = (*pos, *pos + 1);
             Err("Unmatched open parenthesis".to_string())
         } else if !self.buf.is_empty() {
+            self.span = (self.buf_start, self.offset());
             self.parse_buf().map(Some)
         } else {
             Ok(None)
         }
     }
 
+    pub(crate) fn offset(&self) -> usize {
+        self.expr_len - self.iter.len()
+    }
+
     fn find_char(&mut self, chars: &[u8]) -> Result<u8, String> {
         for &ch in self.iter.by_ref() {
             if !ch.is_ascii_whitespace() {
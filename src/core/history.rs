@@ -0,0 +1,96 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{
+        form::{FormData, FormValue},
+        schema::SchemaType,
+    },
+    STATE_FORM_HISTORY_KEY,
+};
+
+const MAX_HISTORY_ENTRIES: usize = 10;
+
+// Form values as they were deployed, secrets are never included
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub schema: String,
+    pub recorded_at: DateTime<Utc>,
+    pub values: BTreeMap<String, FormValue>,
+}
+
+// Snapshots are kept by the prefix the settings are stored under, oldest
+// first
+pub fn history_key(data: &FormData) -> Option<String> {
+    let schema = &data.schema;
+    if schema.id.is_empty() {
+        return None;
+    }
+    match schema.typ {
+        SchemaType::List => Some(schema.id.to_string()),
+        SchemaType::Entry { .. } => data.value_as_str("_id").map(|id| schema.record_key(id)),
+        SchemaType::Record { .. } | SchemaType::Map { .. } => data
+            .value_as_str("_id")
+            .filter(|id| !id.is_empty())
+            .map(|id| schema.record_prefix(id)),
+    }
+}
+
+pub fn load_history(data: &FormData) -> Vec<Snapshot> {
+    history_key(data)
+        .and_then(|key| {
+            LocalStorage::get::<BTreeMap<String, Vec<Snapshot>>>(STATE_FORM_HISTORY_KEY)
+                .ok()?
+                .remove(&key)
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|snapshot| snapshot.schema == data.schema.id)
+        .collect()
+}
+
+// Called once a save succeeds, the values the form was loaded with are
+// recorded too so the first change can be reverted
+pub fn record_save(before: &FormData, after: &FormData) {
+    let Some(key) = history_key(after) else {
+        return;
+    };
+    let mut history = LocalStorage::get::<BTreeMap<String, Vec<Snapshot>>>(STATE_FORM_HISTORY_KEY)
+        .unwrap_or_default();
+    let snapshots = history.entry(key).or_default();
+
+    let mut push = |data: &FormData| {
+        let values = data.draft_values();
+        if snapshots
+            .last()
+            .is_none_or(|snapshot| snapshot.values != values)
+        {
+            snapshots.push(Snapshot {
+                schema: data.schema.id.to_string(),
+                recorded_at: Utc::now(),
+                values,
+            });
+        }
+    };
+    if before.is_update && history_key(before) == history_key(after) {
+        push(before);
+    }
+    push(after);
+
+    if snapshots.len() > MAX_HISTORY_ENTRIES {
+        let excess = snapshots.len() - MAX_HISTORY_ENTRIES;
+        snapshots.drain(..excess);
+    }
+    if let Err(err) = LocalStorage::set(STATE_FORM_HISTORY_KEY, history) {
+        log::error!("Failed to save settings history to local storage: {}", err);
+    }
+}
@@ -4,9 +4,15 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use std::time::Duration;
+
 use ahash::AHashMap;
 use base64::{engine::general_purpose::STANDARD, Engine};
 use gloo_net::http::{Headers, Method, RequestBuilder};
+use leptos::{
+    create_action, create_effect, on_cleanup, set_interval_with_handle, Signal, SignalGet,
+    SignalGetUntracked,
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use super::{url::UrlBuilder, AccessToken};
@@ -234,6 +240,49 @@ impl HttpRequest {
     }
 }
 
+// Streams a resource by repeating a request while `active` is set, a tick is
+// skipped if the previous request has not completed yet. Polling stops when
+// the calling component is unmounted.
+pub fn poll<T>(
+    request: impl Fn() -> HttpRequest + 'static,
+    interval: Duration,
+    active: Signal<bool>,
+    on_response: impl Fn(Result<T>) + 'static,
+) where
+    T: DeserializeOwned + Clone + 'static,
+{
+    let fetch = create_action(move |_: &()| {
+        let request = request();
+        async move { request.send::<T>().await }
+    });
+    create_effect(move |_| {
+        if let Some(response) = fetch.value().get() {
+            on_response(response);
+        }
+    });
+
+    // Fetch straight away when polling starts or resumes
+    create_effect(move |_| {
+        if active.get() && !fetch.pending().get_untracked() {
+            fetch.dispatch(());
+        }
+    });
+    let handle = set_interval_with_handle(
+        move || {
+            if active.get_untracked() && !fetch.pending().get_untracked() {
+                fetch.dispatch(());
+            }
+        },
+        interval,
+    )
+    .ok();
+    on_cleanup(move || {
+        if let Some(handle) = handle {
+            handle.clear();
+        }
+    });
+}
+
 impl IntoUrlBuilder for String {
     fn into_url_builder(self) -> UrlBuilder {
         UrlBuilder::new(self)
@@ -9,14 +9,22 @@ use std::{fmt::Display, sync::Arc};
 use ahash::AHashSet;
 use serde::{Deserialize, Serialize};
 
+pub mod alerting;
+pub mod cron;
+pub mod duration;
 pub mod expr;
 pub mod form;
+pub mod history;
 pub mod http;
 pub mod oauth;
+pub mod pem;
 pub mod schema;
+pub mod toml;
+pub mod undo;
 pub mod url;
 
 pub const MINIMUM_API_VERSION: Semver = Semver::new(0, 13, 0);
+pub const TARGET_API_VERSION: Semver = Semver::new(0, 13, 0);
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AccessToken {
@@ -27,6 +35,10 @@ pub struct AccessToken {
     pub is_valid: bool,
     pub is_enterprise: bool,
     pub permissions: Permissions,
+    #[serde(default)]
+    pub server_version: Semver,
+    #[serde(default)]
+    pub capabilities: Arc<AHashSet<String>>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -109,6 +121,11 @@ pub enum Permission {
     MetricsList,
     MetricsLive,
     Troubleshoot,
+    SessionList,
+    SessionDelete,
+    QuarantineList,
+    QuarantineRelease,
+    QuarantineDelete,
 
     // Account Management
     ManageEncryption,
@@ -158,6 +175,31 @@ impl AccessToken {
     pub fn default_url(&self) -> &'static str {
         self.permissions.default_url(self.is_enterprise())
     }
+
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.contains(capability)
+    }
+
+    // Capabilities checked by gated form fields and sections
+    pub fn form_capabilities(&self) -> Arc<AHashSet<String>> {
+        if self.is_enterprise() && !self.has_capability("enterprise") {
+            let mut capabilities = self.capabilities.as_ref().clone();
+            capabilities.insert("enterprise".to_string());
+            Arc::new(capabilities)
+        } else {
+            self.capabilities.clone()
+        }
+    }
+
+    pub fn supports(&self, since: Option<Semver>) -> bool {
+        self.server_version.supports(since)
+    }
+
+    pub fn is_newer_server(&self) -> bool {
+        self.server_version.is_valid()
+            && (self.server_version.major(), self.server_version.minor())
+                > (TARGET_API_VERSION.major(), TARGET_API_VERSION.minor())
+    }
 }
 
 impl Permissions {
@@ -315,11 +357,18 @@ impl Permission {
                 | Permission::MetricsList
                 | Permission::MetricsLive
                 | Permission::Troubleshoot
+                | Permission::SessionList
+                | Permission::SessionDelete
+                | Permission::QuarantineList
+                | Permission::QuarantineRelease
+                | Permission::QuarantineDelete
         )
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord,
+)]
 #[repr(transparent)]
 #[serde(transparent)]
 pub struct Semver(u64);
@@ -356,6 +405,14 @@ impl Semver {
     pub fn is_valid(&self) -> bool {
         self.0 > 0
     }
+
+    // Unknown versions are assumed to support everything
+    pub fn supports(&self, since: Option<Semver>) -> bool {
+        match since {
+            Some(since) if self.is_valid() => *self >= since,
+            _ => true,
+        }
+    }
 }
 
 impl AsRef<u64> for Semver {
@@ -388,3 +445,21 @@ impl Display for Semver {
         write!(f, "{major}.{minor}.{patch}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn semver_supports() {
+        let server = Semver::try_from("0.13.2").unwrap();
+        assert!(server.supports(None));
+        assert!(server.supports(Some(Semver::new(0, 13, 2))));
+        assert!(server.supports(Some(Semver::new(0, 12, 9))));
+        assert!(!server.supports(Some(Semver::new(0, 13, 3))));
+        assert!(!server.supports(Some(Semver::new(1, 0, 0))));
+
+        // Servers that did not report a version are not restricted
+        assert!(Semver::default().supports(Some(Semver::new(9, 0, 0))));
+    }
+}
@@ -47,6 +47,9 @@ pub struct OAuthCodeResponse {
     #[serde(default)]
     pub version: Option<String>,
 
+    #[serde(default)]
+    pub capabilities: AHashSet<String>,
+
     // TODO - Deprecated - remove in future
     #[serde(default)]
     pub is_admin: bool,
@@ -103,6 +106,8 @@ pub struct AuthenticationResponse {
     pub grant: OAuthGrant,
     pub permissions: AHashSet<Permission>,
     pub is_enterprise: bool,
+    pub server_version: Semver,
+    pub capabilities: AHashSet<String>,
 }
 
 const REDIRECT_URI: &str = "stalwart://auth";
@@ -135,6 +140,8 @@ pub async fn oauth_authenticate(
     };
     let permissions = response.permissions;
     let is_enterprise = response.is_enterprise;
+    let server_version = response.server_version();
+    let capabilities = response.capabilities;
     match HttpRequest::post(format!("{base_url}/auth/token"))
         .with_raw_body(
             serde_urlencoded::to_string([
@@ -155,6 +162,8 @@ pub async fn oauth_authenticate(
                 grant,
                 permissions,
                 is_enterprise,
+                server_version,
+                capabilities,
             })
         }
         Ok(OAuthResponse::Error { error }) => AuthenticationResult::Error(
@@ -179,12 +188,7 @@ pub async fn oauth_user_authentication(
         .await
     {
         Ok(response) => {
-            let server_version: Semver = response
-                .version
-                .as_deref()
-                .unwrap_or_default()
-                .try_into()
-                .unwrap_or_default();
+            let server_version = response.server_version();
 
             if server_version >= MINIMUM_API_VERSION {
                 AuthenticationResult::Success(response.legacy_admin())
@@ -289,6 +293,14 @@ pub fn use_authorization() -> RwSignal<AccessToken> {
 }
 
 impl OAuthCodeResponse {
+    pub fn server_version(&self) -> Semver {
+        self.version
+            .as_deref()
+            .unwrap_or_default()
+            .try_into()
+            .unwrap_or_default()
+    }
+
     pub fn legacy_admin(mut self) -> Self {
         if self.is_admin && self.permissions.is_empty() {
             for permission in [
@@ -367,6 +379,11 @@ impl OAuthCodeResponse {
                 Permission::ManageEncryption,
                 Permission::ManagePasswords,
                 Permission::Troubleshoot,
+                Permission::SessionList,
+                Permission::SessionDelete,
+                Permission::QuarantineList,
+                Permission::QuarantineRelease,
+                Permission::QuarantineDelete,
             ] {
                 self.permissions.insert(permission);
             }
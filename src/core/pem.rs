@@ -0,0 +1,336 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::fmt::Display;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+// Reader for pasted PEM certificates and keys, only the parts of X.509
+// shown in the form are decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    pub subjects: Vec<String>,
+    pub issuer: String,
+    pub not_after: DateTime<Utc>,
+    public_key: PublicKey,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PemError(pub &'static str);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PublicKey {
+    Rsa { modulus: Vec<u8>, exponent: Vec<u8> },
+    Ec(Vec<u8>),
+    Other,
+}
+
+#[derive(Clone, Copy)]
+struct Der<'x> {
+    data: &'x [u8],
+}
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+const TAG_UTC_TIME: u8 = 0x17;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_VERSION: u8 = 0xa0;
+const TAG_EXTENSIONS: u8 = 0xa3;
+const TAG_EC_PUBLIC_KEY: u8 = 0xa1;
+const TAG_DNS_NAME: u8 = 0x82;
+
+const OID_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+const OID_EC: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+const OID_ORGANIZATION: &[u8] = &[0x55, 0x04, 0x0a];
+const OID_SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+
+const INVALID_CERTIFICATE: PemError = PemError("Not a valid PEM certificate");
+
+// Certificates in the order they appear, the leaf comes first
+pub fn parse_certificates(pem: &str) -> Result<Vec<Certificate>, PemError> {
+    let certificates = pem_blocks(pem)?
+        .into_iter()
+        .filter(|(label, _)| *label == "CERTIFICATE")
+        .map(|(_, der)| parse_certificate(&der).ok_or(INVALID_CERTIFICATE))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !certificates.is_empty() {
+        Ok(certificates)
+    } else {
+        Err(INVALID_CERTIFICATE)
+    }
+}
+
+// None when the key type is not supported or the key does not include
+// its public part
+pub fn key_matches(certificate: &Certificate, pem: &str) -> Option<bool> {
+    let key = pem_blocks(pem)
+        .ok()?
+        .into_iter()
+        .find_map(|(label, der)| match label {
+            "PRIVATE KEY" => parse_pkcs8_key(&der),
+            "RSA PRIVATE KEY" => parse_rsa_key(&der),
+            "EC PRIVATE KEY" => parse_ec_key(&der),
+            _ => None,
+        })?;
+
+    match (&key, &certificate.public_key) {
+        (PublicKey::Other, _) | (_, PublicKey::Other) => None,
+        _ => Some(key == certificate.public_key),
+    }
+}
+
+fn pem_blocks(pem: &str) -> Result<Vec<(&str, Vec<u8>)>, PemError> {
+    let mut blocks = Vec::new();
+    let mut lines = pem.lines().map(str::trim);
+
+    while let Some(line) = lines.next() {
+        let Some(label) = line
+            .strip_prefix("-----BEGIN ")
+            .and_then(|line| line.strip_suffix("-----"))
+        else {
+            continue;
+        };
+        let end = format!("-----END {label}-----");
+        let mut contents = String::new();
+        loop {
+            match lines.next() {
+                Some(line) if line == end => break,
+                // Headers of encrypted keys are skipped
+                Some(line) if line.contains(':') => (),
+                Some(line) => contents.push_str(line),
+                None => {
+                    return Err(PemError(
+                        "Not a valid PEM certificate, the END line is missing",
+                    ))
+                }
+            }
+        }
+        let der = STANDARD
+            .decode(contents)
+            .map_err(|_| PemError("Not a valid PEM certificate, the base64 data is invalid"))?;
+        blocks.push((label, der));
+    }
+
+    Ok(blocks)
+}
+
+fn parse_certificate(der: &[u8]) -> Option<Certificate> {
+    let mut certificate = Der::new(der).expect(TAG_SEQUENCE)?;
+    let mut tbs = certificate.expect(TAG_SEQUENCE)?;
+
+    let (mut tag, _) = tbs.read()?;
+    if tag == TAG_VERSION {
+        (tag, _) = tbs.read()?;
+    }
+    if tag != TAG_INTEGER {
+        return None;
+    }
+    tbs.expect(TAG_SEQUENCE)?;
+    let issuer = tbs.expect(TAG_SEQUENCE)?;
+    let mut validity = tbs.expect(TAG_SEQUENCE)?;
+    let subject = tbs.expect(TAG_SEQUENCE)?;
+    let public_key = parse_public_key_info(tbs.expect(TAG_SEQUENCE)?)?;
+
+    validity.read()?;
+    let not_after = match validity.read()? {
+        (TAG_UTC_TIME, time) => {
+            let time = std::str::from_utf8(time).ok()?;
+            let century = if time.get(..2)?.parse::<u32>().ok()? >= 50 {
+                "19"
+            } else {
+                "20"
+            };
+            parse_time(&format!("{century}{time}"))?
+        }
+        (TAG_GENERALIZED_TIME, time) => parse_time(std::str::from_utf8(time).ok()?)?,
+        _ => return None,
+    };
+
+    let mut subjects = Vec::new();
+    while let Some((tag, contents)) = tbs.read() {
+        if tag == TAG_EXTENSIONS {
+            let mut extensions = Der::new(contents).expect(TAG_SEQUENCE)?;
+            while let Some(mut extension) = extensions.expect(TAG_SEQUENCE) {
+                if extension.expect_raw(TAG_OID)? != OID_SUBJECT_ALT_NAME {
+                    continue;
+                }
+                let value = loop {
+                    match extension.read()? {
+                        (TAG_OCTET_STRING, value) => break value,
+                        _ => continue,
+                    }
+                };
+                let mut names = Der::new(value).expect(TAG_SEQUENCE)?;
+                while let Some((tag, name)) = names.read() {
+                    if tag == TAG_DNS_NAME {
+                        subjects.push(std::str::from_utf8(name).ok()?.to_lowercase());
+                    }
+                }
+            }
+        }
+    }
+    if subjects.is_empty() {
+        subjects.extend(name_attribute(subject, OID_COMMON_NAME));
+    }
+
+    let issuer = match (
+        name_attribute(issuer, OID_COMMON_NAME),
+        name_attribute(issuer, OID_ORGANIZATION),
+    ) {
+        (Some(name), Some(organization)) if name != organization => {
+            format!("{name}, {organization}")
+        }
+        (Some(name), _) | (None, Some(name)) => name,
+        (None, None) => "Unknown issuer".to_string(),
+    };
+
+    Some(Certificate {
+        subjects,
+        issuer,
+        not_after,
+        public_key,
+    })
+}
+
+fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(time, "%Y%m%d%H%M%SZ")
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+fn name_attribute(mut name: Der<'_>, oid: &[u8]) -> Option<String> {
+    while let Some(mut set) = name.expect(TAG_SET) {
+        while let Some(mut attribute) = set.expect(TAG_SEQUENCE) {
+            if attribute.expect_raw(TAG_OID)? == oid {
+                let (_, value) = attribute.read()?;
+                return String::from_utf8(value.to_vec()).ok();
+            }
+        }
+    }
+    None
+}
+
+fn parse_public_key_info(mut info: Der<'_>) -> Option<PublicKey> {
+    let mut algorithm = info.expect(TAG_SEQUENCE)?;
+    let oid = algorithm.expect_raw(TAG_OID)?;
+    let key = info.expect_raw(TAG_BIT_STRING)?.get(1..)?;
+
+    Some(if oid == OID_RSA {
+        let mut key = Der::new(key).expect(TAG_SEQUENCE)?;
+        PublicKey::Rsa {
+            modulus: key.expect_integer()?,
+            exponent: key.expect_integer()?,
+        }
+    } else if oid == OID_EC {
+        PublicKey::Ec(key.to_vec())
+    } else {
+        PublicKey::Other
+    })
+}
+
+fn parse_pkcs8_key(der: &[u8]) -> Option<PublicKey> {
+    let mut key = Der::new(der).expect(TAG_SEQUENCE)?;
+    key.expect(TAG_INTEGER)?;
+    let oid = key.expect(TAG_SEQUENCE)?.expect_raw(TAG_OID)?;
+    let private_key = key.expect_raw(TAG_OCTET_STRING)?;
+
+    if oid == OID_RSA {
+        parse_rsa_key(private_key)
+    } else if oid == OID_EC {
+        parse_ec_key(private_key)
+    } else {
+        Some(PublicKey::Other)
+    }
+}
+
+fn parse_rsa_key(der: &[u8]) -> Option<PublicKey> {
+    let mut key = Der::new(der).expect(TAG_SEQUENCE)?;
+    key.expect(TAG_INTEGER)?;
+    Some(PublicKey::Rsa {
+        modulus: key.expect_integer()?,
+        exponent: key.expect_integer()?,
+    })
+}
+
+// The public point is optional in SEC1 keys and cannot be derived here
+fn parse_ec_key(der: &[u8]) -> Option<PublicKey> {
+    let mut key = Der::new(der).expect(TAG_SEQUENCE)?;
+    key.expect(TAG_INTEGER)?;
+    key.expect(TAG_OCTET_STRING)?;
+    while let Some((tag, contents)) = key.read() {
+        if tag == TAG_EC_PUBLIC_KEY {
+            let point = Der::new(contents).expect_raw(TAG_BIT_STRING)?.get(1..)?;
+            return Some(PublicKey::Ec(point.to_vec()));
+        }
+    }
+    Some(PublicKey::Other)
+}
+
+impl<'x> Der<'x> {
+    fn new(data: &'x [u8]) -> Self {
+        Self { data }
+    }
+
+    fn read(&mut self) -> Option<(u8, &'x [u8])> {
+        let (&tag, rest) = self.data.split_first()?;
+        let (&len, mut rest) = rest.split_first()?;
+        let len = if len & 0x80 == 0 {
+            len as usize
+        } else {
+            let bytes = (len & 0x7f) as usize;
+            if bytes == 0 || bytes > 4 || rest.len() < bytes {
+                return None;
+            }
+            let (len, remaining) = rest.split_at(bytes);
+            rest = remaining;
+            len.iter().fold(0, |acc, byte| (acc << 8) | *byte as usize)
+        };
+        if rest.len() < len {
+            return None;
+        }
+        let (contents, rest) = rest.split_at(len);
+        self.data = rest;
+        Some((tag, contents))
+    }
+
+    fn expect_raw(&mut self, tag: u8) -> Option<&'x [u8]> {
+        self.read()
+            .and_then(|(read_tag, contents)| (read_tag == tag).then_some(contents))
+    }
+
+    fn expect(&mut self, tag: u8) -> Option<Der<'x>> {
+        self.expect_raw(tag).map(Der::new)
+    }
+
+    // Integers are compared without their sign padding
+    fn expect_integer(&mut self) -> Option<Vec<u8>> {
+        let value = self.expect_raw(TAG_INTEGER)?;
+        let start = value
+            .iter()
+            .position(|byte| *byte != 0)
+            .unwrap_or(value.len());
+        Some(value[start..].to_vec())
+    }
+}
+
+impl Certificate {
+    pub fn days_left(&self) -> i64 {
+        (self.not_after - Utc::now()).num_days()
+    }
+}
+
+impl Display for PemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
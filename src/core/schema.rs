@@ -10,10 +10,19 @@ use std::{
     sync::Arc,
 };
 
-use ahash::AHashMap;
+use ahash::{AHashMap, AHashSet};
 use serde_json::json;
 
-use super::form::{FormData, FormValue};
+use crate::pages::config::Settings;
+
+use super::{
+    form::{FormData, FormValue},
+    url::{docs_url, UrlBuilder},
+    Semver,
+};
+
+const ENTRY_PAGE_SIZE: u32 = 100;
+pub const KEY_SEPARATOR: char = '.';
 
 #[derive(Default)]
 pub struct Schemas {
@@ -29,6 +38,8 @@ pub struct Builder<P, I> {
 pub enum Type<S, F> {
     Input,
     Array(ArrayType),
+    // Text values edited as removable chips, stored like an array
+    Tag,
     Secret,
     Text,
     #[default]
@@ -42,6 +53,7 @@ pub enum Type<S, F> {
     Rate,
     Size,
     Cron,
+    Map,
 }
 
 #[derive(Clone, Copy, Default, Debug)]
@@ -51,6 +63,68 @@ pub enum ArrayType {
     Duration,
 }
 
+// Ordered from weakest to strongest requirement
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Requires {
+    #[default]
+    Reload,
+    NewConnections,
+    Restart,
+}
+
+impl Requires {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Requires::Reload => "Applied on reload",
+            Requires::NewConnections => "Applies to new connections",
+            Requires::Restart => "Requires restart",
+        }
+    }
+
+    pub fn summary(&self) -> &'static str {
+        match self {
+            Requires::Reload => "These changes take effect once the configuration is reloaded.",
+            Requires::NewConnections => {
+                "These changes only apply to new connections, existing connections keep the previous settings."
+            }
+            Requires::Restart => "These changes need a server restart to take effect.",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SecretCharset {
+    Alphanumeric,
+    Hex,
+    Base64Url,
+}
+
+impl SecretCharset {
+    pub fn chars(&self) -> &'static [u8] {
+        match self {
+            SecretCharset::Alphanumeric => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"
+            }
+            SecretCharset::Hex => b"0123456789abcdef",
+            SecretCharset::Base64Url => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GenerateSecret {
+    pub length: usize,
+    pub charset: SecretCharset,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationTest {
+    Email,
+    Webhook,
+}
+
 #[derive(Clone, Copy, Default, Debug)]
 pub enum SelectType {
     #[default]
@@ -64,14 +138,56 @@ pub struct Field {
     pub id: &'static str,
     pub label_form: &'static str,
     pub label_column: &'static str,
-    pub help: Option<&'static str>,
+    pub help: Value<&'static str>,
     pub checks: Value<InputCheck>,
     pub typ_: Type<Arc<Schema>, Arc<Field>>,
+    pub typ_if: Value<Type<Arc<Schema>, Arc<Field>>>,
     pub default: Value<FormValue>,
     pub placeholder: Value<&'static str>,
     pub display: Vec<Eval>,
+    // Readonly once the record exists, always readonly for server managed values
     pub readonly: bool,
+    pub readonly_always: bool,
     pub enterprise: bool,
+    pub capability: Option<&'static str>,
+    pub allow_disabled: bool,
+    pub disruptive: bool,
+    pub advanced: bool,
+    pub sortable: bool,
+    pub filterable: bool,
+    pub skip_clone: bool,
+    pub generate_secret: Option<GenerateSecret>,
+    pub doc: Option<&'static str>,
+    pub auto_id: Option<&'static str>,
+    pub since: Option<Semver>,
+    pub notification_test: Option<NotificationTest>,
+    pub requires: Option<Requires>,
+    pub aliases: Vec<&'static str>,
+    pub preset_for: Option<Preset>,
+    pub certificate_names: Option<&'static str>,
+    pub downloadable: Option<&'static str>,
+    pub list_toggle: Option<ListToggle>,
+    // Select values outside the source, for options newer than the webadmin
+    pub allow_custom: bool,
+    // Checks for the keys of a map, its values use the regular checks
+    pub map_key_checks: Option<InputCheck>,
+    // Values offered while typing a new tag
+    pub suggestions: &'static [&'static str],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListToggle {
+    Independent,
+    // Setting the flag on one record clears it on all the others
+    Exclusive,
+}
+
+// A form-only select whose options set the default of another field, the
+// option is inferred from that field when a record is loaded
+#[derive(Clone, Copy, Debug)]
+pub struct Preset {
+    pub field: &'static str,
+    pub fallback: &'static str,
 }
 
 #[derive(Clone, Default, Debug)]
@@ -82,8 +198,61 @@ pub struct Schema {
     pub fields: AHashMap<&'static str, Arc<Field>>,
     pub typ: SchemaType,
     pub reload_prefix: Option<&'static str>,
+    pub default_provider: Option<DefaultProvider>,
+    pub warnings: Option<SchemaWarningsFn>,
+    pub validators: Vec<FormValidatorFn>,
+    pub doc: Option<&'static str>,
+    pub since: Option<Semver>,
     pub list: List,
     pub form: Form,
+    pub templates: Vec<Template>,
+    pub dependents: AHashMap<&'static str, Vec<&'static str>>,
+    pub groups: Vec<Arc<FieldGroup>>,
+}
+
+// Repeatable block of fields stored under a numeric index, as in
+// "<group>.<index>.<child>". Child fields are kept in a schema of their
+// own, so each entry is validated and displayed against its siblings
+#[derive(Clone, Default, Debug)]
+pub struct FieldGroup {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub help: Option<&'static str>,
+    pub item_name: &'static str,
+    pub fields: Vec<Arc<Field>>,
+    pub schema: Arc<Schema>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Template {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub values: &'static [(&'static str, &'static str)],
+}
+
+// Suggests defaults for a new record from the values prefilled in the form
+// and the existing records
+pub type DefaultProviderFn = fn(&FormData, &DefaultRecords) -> Vec<(&'static str, FormValue)>;
+
+// Existing records of the schema and of its related schema, if any
+#[derive(Clone, Debug, Default)]
+pub struct DefaultRecords {
+    pub records: Vec<Settings>,
+    pub related: Vec<Settings>,
+}
+
+// Returns warnings about insecure combinations that must be acknowledged before saving
+pub type SchemaWarningsFn = fn(&FormData) -> Vec<&'static str>;
+
+// Checks spanning several fields, returns the id of the field the error is
+// shown on along with the message
+pub type FormValidatorFn = fn(&FormData) -> Option<(&'static str, String)>;
+
+#[derive(Clone, Copy, Debug)]
+pub struct DefaultProvider {
+    pub related: Option<&'static str>,
+    pub provider: DefaultProviderFn,
 }
 
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
@@ -95,6 +264,9 @@ pub enum SchemaType {
     Entry {
         prefix: &'static str,
     },
+    Map {
+        prefix: &'static str,
+    },
     #[default]
     List,
 }
@@ -119,7 +291,54 @@ pub struct List {
     pub subtitle: &'static str,
     pub fields: Vec<Arc<Field>>,
     pub actions: Vec<Action>,
+    // Actions offered for the selected rows
+    pub bulk_actions: Vec<Action>,
     pub page_size: u32,
+    pub fixed_page_size: bool,
+    // Fetches every record and pages, searches and sorts them locally
+    pub load_all: bool,
+    pub confirm_delete: bool,
+    pub empty: Option<EmptyState>,
+    pub default_sort: Option<(&'static str, SortOrder)>,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "asc" => Some(SortOrder::Ascending),
+            "desc" => Some(SortOrder::Descending),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortOrder::Ascending => "asc",
+            SortOrder::Descending => "desc",
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        match self {
+            SortOrder::Ascending => SortOrder::Descending,
+            SortOrder::Descending => SortOrder::Ascending,
+        }
+    }
+}
+
+// Shown instead of the list when no records have been created yet
+#[derive(Clone, Default, Debug)]
+pub struct EmptyState {
+    pub description: &'static str,
+    pub template: Option<&'static str>,
+    pub optional: bool,
 }
 
 #[derive(Clone, Default, Debug)]
@@ -128,6 +347,8 @@ pub struct Form {
     pub subtitle: &'static str,
     pub sections: Vec<Section>,
     pub actions: Vec<Action>,
+    // Show the changed keys for review before saving
+    pub confirm_changes: bool,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -139,6 +360,12 @@ pub enum Action {
     Delete,
     Search,
     Reload,
+    Export,
+    Import,
+    Clone,
+    ResetToDefaults,
+    // Switches the "enable" field of the selected records
+    Enable,
 }
 
 #[derive(Clone, Default, Debug)]
@@ -146,6 +373,8 @@ pub struct Section {
     pub title: Option<&'static str>,
     pub display: Vec<Eval>,
     pub fields: Vec<Arc<Field>>,
+    pub groups: Vec<Arc<FieldGroup>>,
+    pub capability: Option<&'static str>,
 }
 
 #[derive(Clone, Debug)]
@@ -161,6 +390,16 @@ pub enum Source<S, F> {
         field: F,
         filter: Value<&'static [&'static str]>,
     },
+    // Options searched on a management API endpoint as the operator types,
+    // fixed parameters may be given in the endpoint as "/api/path?key=value"
+    DynamicRemote {
+        endpoint: &'static str,
+        id_field: &'static str,
+        label_field: &'static str,
+    },
+    // Options of several sources, static entries are listed first and
+    // repeated ids keep their first label
+    Merged(Vec<Source<S, F>>),
 }
 
 #[derive(Clone, Default, Debug)]
@@ -186,6 +425,10 @@ pub struct Eval {
 pub enum Condition {
     MatchAny,
     MatchNone,
+    StartsWithAny,
+    ContainsAny,
+    IsEmpty,
+    IsNotEmpty,
 }
 
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
@@ -201,17 +444,23 @@ pub enum Transformer {
     Lowercase,
     Uppercase,
     HashSecret,
+    IdnaToAscii,
+    NormalizeUrl,
+    NormalizeIp,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Validator {
     Required,
     IsEmail,
-    IsId,
+    IsId(IdKind),
     IsHost,
     IsDomain,
+    IsEmailOrWildcard,
+    IsDomainOrWildcard,
     IsPort,
-    IsIpOrMask,
+    IsIpAddr,
+    IsIpMask,
     IsUrl,
     IsRegex,
     IsSocketAddr,
@@ -219,9 +468,66 @@ pub enum Validator {
     MaxLength(usize),
     MinValue(NumberType),
     MaxValue(NumberType),
+    MinDuration(&'static str),
+    MaxDuration(&'static str),
+    MinSize(u64),
+    MaxSize(u64),
     MinItems(usize),
     MaxItems(usize),
     IsValidExpression(ExpressionValidator),
+    IsTemplate(&'static [Placeholder]),
+    IsDnsblZone,
+    IsCron,
+    IsCertificate,
+    MatchesRegex {
+        pattern: &'static str,
+        message: &'static str,
+    },
+    GreaterThanField(&'static str),
+    LessThanField(&'static str),
+    // Private key field holding the key of the certificate in another field
+    MatchesCertificate(&'static str),
+    // Wildcard items in an array require another field to hold a value
+    WildcardsRequire {
+        field: &'static str,
+        value: &'static str,
+    },
+}
+
+// Characters allowed in record ids besides letters and digits
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum IdKind {
+    #[default]
+    Default,
+    // Ids nested in other keys, such as queue names, cannot contain dots
+    Strict,
+    // Ids derived from e-mail addresses
+    Address,
+}
+
+impl IdKind {
+    pub fn chars(&self) -> &'static [char] {
+        match self {
+            IdKind::Default => &['_', '-', '.'],
+            IdKind::Strict => &['_', '-'],
+            IdKind::Address => &['_', '-', '.', '@'],
+        }
+    }
+
+    pub fn max_len(&self) -> usize {
+        match self {
+            IdKind::Default => 255,
+            IdKind::Strict => 64,
+            IdKind::Address => 320,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Placeholder {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub sample: &'static str,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -239,14 +545,141 @@ pub enum NumberType {
 impl Eq for NumberType {}
 
 impl Schemas {
-    pub fn get(&self, id: &str) -> Arc<Schema> {
-        self.schemas.get(id).cloned().unwrap_or_else(|| {
+    pub fn get(&self, id: &str) -> Option<Arc<Schema>> {
+        self.schemas.get(id).cloned()
+    }
+
+    // Only for ids that are hardcoded in the application
+    pub fn get_or_panic(&self, id: &str) -> Arc<Schema> {
+        self.get(id).unwrap_or_else(|| {
             panic!("Schema {id:?} not found.");
         })
     }
 
     pub fn build_form(&self, id: &str) -> FormData {
-        self.get(id).into()
+        self.get_or_panic(id).into()
+    }
+
+    // Cross references are captured when each schema is built, so a field
+    // or schema that was renamed afterwards leaves them dangling
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let mut ids = self.schemas.keys().copied().collect::<Vec<_>>();
+        ids.sort_unstable();
+
+        for schema in ids.iter().map(|id| &self.schemas[id]) {
+            let check_field = |errors: &mut Vec<String>, context: &str, field: &str| {
+                if !schema.fields.contains_key(field) {
+                    errors.push(format!(
+                        "{}: {context} references unknown field {field:?}",
+                        schema.id
+                    ));
+                }
+            };
+
+            for field in schema.list.fields.iter() {
+                check_field(&mut errors, "list", field.id);
+            }
+            for section in schema.form.sections.iter() {
+                let context = format!("section {:?}", section.title.unwrap_or_default());
+                for field in section.fields.iter() {
+                    check_field(&mut errors, &context, field.id);
+                }
+                for eval in section.display.iter() {
+                    check_field(&mut errors, &context, eval.field.id);
+                }
+            }
+            // Group fields can only depend on fields of the same entry
+            for group in schema.groups.iter() {
+                for field in group.schema.fields.values() {
+                    for eval in field.display.iter() {
+                        if !group.schema.fields.contains_key(eval.field.id) {
+                            errors.push(format!(
+                                "{}: field {:?} of group {:?} references unknown field {:?}",
+                                schema.id, field.id, group.id, eval.field.id
+                            ));
+                        }
+                    }
+                }
+            }
+
+            let mut fields = schema.fields.values().collect::<Vec<_>>();
+            fields.sort_unstable_by_key(|field| field.id);
+            for field in fields {
+                let context = format!("field {:?}", field.id);
+                for id in field
+                    .display
+                    .iter()
+                    .map(|eval| eval.field.id)
+                    .chain(field.default.dependencies())
+                    .chain(field.placeholder.dependencies())
+                    .chain(field.help.dependencies())
+                    .chain(field.checks.dependencies())
+                    .chain(field.typ_if.dependencies())
+                {
+                    check_field(&mut errors, &context, id);
+                }
+                for typ in field.all_types() {
+                    let Type::Select { source, .. } = typ else {
+                        continue;
+                    };
+                    for source in source.leaves() {
+                        match source {
+                            Source::Dynamic {
+                                schema: target,
+                                field: target_field,
+                                ..
+                            } => match self.schemas.get(target.id) {
+                                Some(target) if target.fields.contains_key(target_field.id) => {}
+                                Some(target) => errors.push(format!(
+                                    "{}: {context} references unknown field {:?} in schema {:?}",
+                                    schema.id, target_field.id, target.id
+                                )),
+                                None => errors.push(format!(
+                                    "{}: {context} references unknown schema {:?}",
+                                    schema.id, target.id
+                                )),
+                            },
+                            Source::DynamicSelf {
+                                field: target_field,
+                                ..
+                            } => check_field(&mut errors, &context, target_field.id),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    // Finds the schema field a setting is stored under, record schemas
+    // take precedence over list schemas sharing the same key space
+    pub fn find_field<'x>(&self, key: &'x str) -> Option<(&Arc<Schema>, &Arc<Field>, &'x str)> {
+        self.schemas
+            .values()
+            .filter_map(|schema| {
+                let (id, field_key) = schema.split_key(key)?;
+                let field = match schema.typ {
+                    SchemaType::Entry { .. } => schema.fields.get("_value")?,
+                    _ => schema
+                        .fields
+                        .values()
+                        .find(|field| field.matches_key(field_key))?,
+                };
+                Some((schema, field, id))
+            })
+            .max_by_key(|(schema, _, _)| match schema.typ {
+                SchemaType::Record { prefix, .. }
+                | SchemaType::Entry { prefix }
+                | SchemaType::Map { prefix } => prefix.len() + 1,
+                SchemaType::List => 0,
+            })
     }
 }
 
@@ -258,23 +691,120 @@ impl From<Arc<Schema>> for FormData {
             external_sources: Default::default(),
             schema,
             is_update: false,
+            unknown: Default::default(),
+            legacy: Default::default(),
+            server_version: Default::default(),
+            capabilities: Default::default(),
+            derived: Default::default(),
+            baseline: Default::default(),
+            groups: Default::default(),
+            defaulted: Default::default(),
+            stored: Default::default(),
         }
     }
 }
 
 impl Schema {
+    pub fn doc_url(&self) -> Option<String> {
+        self.doc.map(docs_url)
+    }
+
+    pub fn has_advanced_fields(&self) -> bool {
+        self.form
+            .sections
+            .iter()
+            .any(|section| section.fields.iter().any(|field| field.advanced))
+    }
+
+    // Fields whose default value or visibility is controlled by the given field
+    pub fn dependents(&self, id: &str) -> &[&'static str] {
+        self.dependents
+            .get(id)
+            .map(|ids| ids.as_slice())
+            .unwrap_or_default()
+    }
+
+    // A field inherits the capability required by its section
+    pub fn required_capability(&self, field: &Field) -> Option<&'static str> {
+        field.capability.or_else(|| {
+            self.form
+                .sections
+                .iter()
+                .find(|section| section.fields.iter().any(|f| f.id == field.id))
+                .and_then(|section| section.capability)
+        })
+    }
+
+    pub fn template(&self, id: &str) -> Option<&Template> {
+        self.templates.iter().find(|template| template.id == id)
+    }
+
     pub fn can_edit(&self) -> bool {
         self.has_list_action(Action::Modify)
     }
 
+    pub fn is_known_key(&self, key: &str) -> bool {
+        self.fields.values().any(|field| {
+            key == field.id
+                || (field.is_multivalue()
+                    && key
+                        .strip_prefix(field.id)
+                        .is_some_and(|rest| rest.starts_with('.')))
+        }) || self
+            .groups
+            .iter()
+            .any(|group| group.split_key(key).is_some())
+    }
+
+    pub fn group(&self, id: &str) -> Option<&Arc<FieldGroup>> {
+        self.groups.iter().find(|group| group.id == id)
+    }
+
     pub fn can_delete(&self) -> bool {
         self.has_list_action(Action::Delete)
     }
 
+    // Bulk actions also need the matching list action, editing and
+    // enabling write a single key per record
+    pub fn has_bulk_action(&self, action: Action) -> bool {
+        self.list.bulk_actions.contains(&action)
+            && match action {
+                Action::Delete => self.can_delete(),
+                Action::Modify => self.can_edit() && matches!(self.typ, SchemaType::Record { .. }),
+                Action::Enable => {
+                    self.can_edit()
+                        && self.enable_field().is_some()
+                        && matches!(self.typ, SchemaType::Record { .. } | SchemaType::Map { .. })
+                }
+                _ => false,
+            }
+    }
+
+    pub fn enable_field(&self) -> Option<&Arc<Field>> {
+        self.fields
+            .get("enable")
+            .filter(|field| matches!(field.typ_, Type::Boolean) && !field.is_readonly(true))
+    }
+
+    // Toggles are only shown to those who can modify records and write a
+    // single key per record
+    pub fn toggle_mode(&self, field: &Field) -> Option<ListToggle> {
+        field.list_toggle.filter(|_| {
+            self.can_edit()
+                && !field.is_readonly(true)
+                && matches!(field.typ_, Type::Boolean)
+                && matches!(self.typ, SchemaType::Record { .. } | SchemaType::Map { .. })
+        })
+    }
+
     pub fn can_create(&self) -> bool {
         self.has_list_action(Action::Create)
     }
 
+    pub fn can_clone(&self) -> bool {
+        self.has_list_action(Action::Clone) && matches!(self.typ, SchemaType::Record { .. })
+    }
+
     pub fn has_list_action(&self, action: Action) -> bool {
         self.list.actions.contains(&action)
     }
@@ -285,36 +815,171 @@ impl Schema {
 
     pub fn unwrap_prefix(&self) -> &str {
         match self.typ {
-            SchemaType::Record { prefix, .. } | SchemaType::Entry { prefix } => prefix,
-            SchemaType::List => panic!("Schema type is not Record or Entry for {:?}.", self.id),
+            SchemaType::Record { prefix, .. }
+            | SchemaType::Entry { prefix }
+            | SchemaType::Map { prefix } => prefix,
+            SchemaType::List => {
+                panic!("Schema type is not Record, Entry or Map for {:?}.", self.id)
+            }
         }
     }
 
     pub fn try_unwrap_suffix(&self) -> Option<&str> {
         match self.typ {
             SchemaType::Record { suffix, .. } => Some(suffix),
-            SchemaType::Entry { .. } | SchemaType::List => None,
+            SchemaType::Entry { .. } | SchemaType::Map { .. } | SchemaType::List => None,
+        }
+    }
+
+    pub fn record_key(&self, id: &str) -> String {
+        format!("{}{KEY_SEPARATOR}{id}", self.unwrap_prefix())
+    }
+
+    pub fn record_prefix(&self, id: &str) -> String {
+        format!("{}{KEY_SEPARATOR}{id}{KEY_SEPARATOR}", self.unwrap_prefix())
+    }
+
+    // Keys removed when a record is deleted, multi-value fields are stored
+    // under numbered keys so only their prefix is listed
+    pub fn record_keys(&self, id: &str) -> Vec<String> {
+        let mut keys = match self.typ {
+            SchemaType::Record { .. } | SchemaType::Map { .. } => self
+                .fields
+                .values()
+                .filter(|field| !field.id.starts_with('_'))
+                .map(|field| {
+                    let key = self.field_key(id, field.id);
+                    if field.is_multivalue() {
+                        format!("{key}{KEY_SEPARATOR}*")
+                    } else {
+                        key
+                    }
+                })
+                .collect::<Vec<_>>(),
+            SchemaType::Entry { .. } => vec![self.record_key(id)],
+            SchemaType::List => vec![],
+        };
+        keys.sort();
+        keys
+    }
+
+    pub fn field_key(&self, id: &str, field: &str) -> String {
+        match self.typ {
+            SchemaType::Record { .. } | SchemaType::Map { .. } => {
+                format!("{}{field}", self.record_prefix(id))
+            }
+            SchemaType::Entry { .. } => self.record_key(id),
+            SchemaType::List => field.to_string(),
         }
     }
 
+    // Splits a key into its record id and field name, the id of records
+    // ends at the first separator so dotted ids cannot be recovered. Keys
+    // with an empty id or field, such as the bare entry prefix followed by
+    // a separator, belong to no record and are not split.
+    pub fn split_key<'x>(&self, key: &'x str) -> Option<(&'x str, &'x str)> {
+        match self.typ {
+            SchemaType::Record { prefix, .. } | SchemaType::Map { prefix } => key
+                .strip_prefix(prefix)?
+                .strip_prefix(KEY_SEPARATOR)?
+                .split_once(KEY_SEPARATOR)
+                .filter(|(id, field)| !id.is_empty() && !field.is_empty()),
+            SchemaType::Entry { prefix } => key
+                .strip_prefix(prefix)?
+                .strip_prefix(KEY_SEPARATOR)
+                .filter(|id| !id.is_empty())
+                .map(|id| (id, "")),
+            SchemaType::List => Some(("", key)),
+        }
+    }
+
+    // Groups the settings listed under a map prefix into records, the id of
+    // each ends at the first separator of the key
+    pub fn map_records(&self, settings: Settings) -> Vec<Settings> {
+        let mut records = BTreeMap::<String, Settings>::new();
+        for (key, value) in settings {
+            let Some((id, field)) = key
+                .split_once(KEY_SEPARATOR)
+                .filter(|(id, field)| !id.is_empty() && !field.is_empty())
+            else {
+                continue;
+            };
+            records
+                .entry(id.to_string())
+                .or_insert_with(|| Settings::from_iter([("_id".to_string(), id.to_string())]))
+                .insert(field.to_string(), value);
+        }
+        records.into_values().collect()
+    }
+
+    pub fn is_ambiguous_id(&self, id: &str) -> bool {
+        matches!(self.typ, SchemaType::Record { .. } | SchemaType::Map { .. })
+            && id.contains(KEY_SEPARATOR)
+    }
+
     pub fn external_sources(&self) -> impl Iterator<Item = (Option<Arc<Schema>>, Arc<Field>)> + '_ {
         self.fields
             .values()
-            .filter_map(|field_| match &field_.typ_ {
-                Type::Select {
-                    source: Source::Dynamic { schema, field, .. },
-                    ..
-                } => Some((schema.clone().into(), field.clone())),
-                Type::Select {
-                    source: Source::DynamicSelf { field, .. },
-                    ..
-                } => Some((None, field.clone())),
+            .flat_map(|field_| field_.all_types())
+            .flat_map(|typ_| match typ_ {
+                Type::Select { source, .. } => source.leaves(),
+                _ => Vec::new(),
+            })
+            .filter_map(|source| match source {
+                Source::Dynamic { schema, field, .. } => {
+                    Some((schema.clone().into(), field.clone()))
+                }
+                Source::DynamicSelf { field, .. } => Some((None, field.clone())),
                 _ => None,
             })
     }
 }
 
 impl Field {
+    // Option of this preset field whose default matches the value of the
+    // target field, trailing slashes are ignored
+    pub fn infer_preset(&self, settings: &FormData) -> Option<&'static str> {
+        let preset = self.preset_for?;
+        let value = settings
+            .value_as_str(preset.field)?
+            .trim()
+            .trim_end_matches('/');
+        let target = settings.schema.fields.get(preset.field)?;
+
+        Some(
+            target
+                .default
+                .if_thens
+                .iter()
+                .find(|if_then| {
+                    if_then.eval.field.id == self.id
+                        && if_then.eval.condition == Condition::MatchAny
+                        && matches!(&if_then.value, FormValue::Value(default)
+                            if default.trim_end_matches('/') == value)
+                })
+                .and_then(|if_then| if_then.eval.values.first().copied())
+                .unwrap_or(preset.fallback),
+        )
+    }
+
+    pub fn doc_url(&self) -> Option<String> {
+        match self.doc {
+            Some(doc) => docs_url(doc).into(),
+            None if !self.id.starts_with('_') => UrlBuilder::new(docs_url("search"))
+                .with_parameter("q", self.id)
+                .finish()
+                .into(),
+            None => None,
+        }
+    }
+
+    pub fn generate_id(&self, existing: &[String]) -> Option<String> {
+        let pattern = self.auto_id?;
+        (1..)
+            .map(|n: u32| pattern.replace("{n}", &n.to_string()))
+            .find(|id| !existing.contains(id))
+    }
+
     pub fn value(&self, settings: &FormData) -> String {
         settings
             .get(self.id)
@@ -326,18 +991,113 @@ impl Field {
         self.display.is_empty() || self.display.iter().any(|eval| eval.eval(settings))
     }
 
-    pub fn placeholder(&self, settings: &FormData) -> Option<&str> {
-        self.placeholder.eval(settings).copied()
+    pub fn placeholder(&self, settings: &FormData) -> Option<String> {
+        self.placeholder
+            .eval(settings)
+            .map(|placeholder| interpolate(placeholder, self.id, settings).0)
+    }
+
+    pub fn help(&self, settings: &FormData) -> Option<String> {
+        self.help
+            .eval(settings)
+            .map(|help| interpolate(help, self.id, settings).0)
     }
 
-    pub fn default(&self, settings: &FormData) -> Option<&FormValue> {
-        self.default.eval(settings)
+    // Records without an id yet are named after the field
+    pub fn download_name(&self, settings: &FormData) -> Option<String> {
+        let template = self.downloadable?;
+        Some(match interpolate(template, self.id, settings) {
+            (filename, true) => filename,
+            (_, false) => match template.rsplit_once('.') {
+                Some((_, extension)) => format!("{}.{extension}", self.id),
+                None => self.id.to_string(),
+            },
+        })
+    }
+
+    // Defaults referencing a field that is still empty are not applied
+    pub fn default(&self, settings: &FormData) -> Option<FormValue> {
+        match self.default.eval(settings)? {
+            FormValue::Value(default) => match interpolate(default, self.id, settings) {
+                (default, true) => Some(FormValue::Value(default)),
+                (_, false) => None,
+            },
+            default => Some(default.clone()),
+        }
+    }
+
+    pub fn has_default_template(&self) -> bool {
+        self.default_references().next().is_some()
+    }
+
+    fn default_references(&self) -> impl Iterator<Item = &str> {
+        self.default
+            .if_thens
+            .iter()
+            .map(|if_then| &if_then.value)
+            .chain(self.default.default.as_ref())
+            .filter_map(|default| match default {
+                FormValue::Value(default) => Some(default.as_str()),
+                _ => None,
+            })
+            .flat_map(template_references)
+            .filter(|id| *id != self.id)
     }
 
     pub fn input_check(&self, settings: &FormData) -> Option<&InputCheck> {
         self.checks.eval(settings)
     }
 
+    pub fn placeholders(&self) -> &'static [Placeholder] {
+        self.checks
+            .default
+            .as_ref()
+            .and_then(|checks| {
+                checks
+                    .validators
+                    .iter()
+                    .find_map(|validator| match validator {
+                        Validator::IsTemplate(placeholders) => Some(*placeholders),
+                        _ => None,
+                    })
+            })
+            .unwrap_or_default()
+    }
+
+    // Array and map fields store their items under the field id
+    pub fn matches_key(&self, key: &str) -> bool {
+        key.strip_prefix(self.id)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(KEY_SEPARATOR))
+    }
+
+    pub fn is_dnsbl_zone(&self) -> bool {
+        self.checks
+            .default
+            .as_ref()
+            .is_some_and(|checks| checks.validators.contains(&Validator::IsDnsblZone))
+    }
+
+    pub fn is_certificate(&self) -> bool {
+        self.checks
+            .default
+            .as_ref()
+            .is_some_and(|checks| checks.validators.contains(&Validator::IsCertificate))
+    }
+
+    pub fn requires(&self) -> Requires {
+        self.requires.unwrap_or_default()
+    }
+
+    // Size limits are entered with binary multiples, as the server reads them
+    pub fn help_text(&self) -> Option<String> {
+        match (self.help.default, &self.typ_) {
+            (Some(help), Type::Size) => Some(format!(
+                "{help} Suffixes use binary multiples, e.g. 1 KB is 1024 bytes."
+            )),
+            (help, _) => help.map(|help| help.to_string()),
+        }
+    }
+
     pub fn is_required(&self, settings: &FormData) -> bool {
         matches!(self.typ_, Type::Boolean | Type::Select { .. })
             || self
@@ -346,11 +1106,77 @@ impl Field {
                 .unwrap_or_default()
     }
 
+    // Type of the field for the current values, the first matching
+    // conditional type wins over the base type
+    pub fn typ(&self, settings: &FormData) -> &Type<Arc<Schema>, Arc<Field>> {
+        self.typ_at(self.typ_arm(settings))
+    }
+
+    pub fn typ_arm(&self, settings: &FormData) -> Option<usize> {
+        self.typ_if
+            .if_thens
+            .iter()
+            .position(|if_then| if_then.eval.eval(settings))
+    }
+
+    pub fn typ_at(&self, arm: Option<usize>) -> &Type<Arc<Schema>, Arc<Field>> {
+        arm.and_then(|arm| self.typ_if.if_thens.get(arm))
+            .map_or(&self.typ_, |if_then| &if_then.value)
+    }
+
+    fn text_references(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.help
+            .if_thens
+            .iter()
+            .map(|if_then| if_then.value)
+            .chain(self.help.default)
+            .chain(
+                self.placeholder
+                    .if_thens
+                    .iter()
+                    .map(|if_then| if_then.value),
+            )
+            .chain(self.placeholder.default)
+            .flat_map(template_references)
+    }
+
+    pub fn all_types(&self) -> impl Iterator<Item = &Type<Arc<Schema>, Arc<Field>>> {
+        std::iter::once(&self.typ_).chain(self.typ_if.if_thens.iter().map(|if_then| &if_then.value))
+    }
+
+    // Fields read when evaluating the display, default, placeholder, help, checks
+    // and type of this field
+    pub fn dependencies(&self) -> Vec<&'static str> {
+        let mut ids = Vec::new();
+        for id in self
+            .display
+            .iter()
+            .map(|eval| eval.field.id)
+            .chain(self.default.dependencies())
+            .chain(self.placeholder.dependencies())
+            .chain(self.help.dependencies())
+            .chain(self.text_references())
+            .chain(self.checks.dependencies())
+            .chain(self.typ_if.dependencies())
+        {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        ids
+    }
+
+    pub fn is_readonly(&self, is_update: bool) -> bool {
+        self.readonly_always || (self.readonly && is_update)
+    }
+
     pub fn is_multivalue(&self) -> bool {
         matches!(
             self.typ_,
             Type::Array(_)
+                | Type::Tag
                 | Type::Expression
+                | Type::Map
                 | Type::Select {
                     typ: SelectType::Many | SelectType::ManyWithSearch,
                     ..
@@ -359,6 +1185,69 @@ impl Field {
     }
 }
 
+// Replaces "{field-id}" references with the value of that field after its
+// transformers. This is a single pass, so substituted values are never
+// expanded again. Unknown fields, the field itself and "{{" are left as
+// written. The flag is false when a referenced field is still empty
+fn interpolate(template: &str, own_id: &str, settings: &FormData) -> (String, bool) {
+    let mut result = String::with_capacity(template.len());
+    let mut resolved = true;
+    let mut rest = template;
+
+    while let Some((before, after)) = rest.split_once('{') {
+        result.push_str(before);
+        if let Some(after) = after.strip_prefix('{') {
+            result.push_str("{{");
+            rest = after;
+            continue;
+        }
+        match after.split_once('}').and_then(|(id, after)| {
+            settings
+                .schema
+                .fields
+                .get(id)
+                .filter(|field| field.id != own_id)
+                .map(|field| (field, after))
+        }) {
+            Some((field, after)) => {
+                match settings
+                    .value_as_str(field.id)
+                    .filter(|value| !value.is_empty())
+                {
+                    Some(value) => result.push_str(
+                        &field
+                            .input_check(settings)
+                            .and_then(|check| check.check_value(value.to_string()).ok())
+                            .unwrap_or_else(|| value.to_string()),
+                    ),
+                    None => {
+                        resolved = false;
+                        result.push('{');
+                        result.push_str(field.id);
+                        result.push('}');
+                    }
+                }
+                rest = after;
+            }
+            None => {
+                result.push('{');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+
+    (result, resolved)
+}
+
+fn template_references(template: &str) -> impl Iterator<Item = &str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(id, _)| id))
+        .filter(|id| !id.is_empty())
+}
+
 impl<T> Value<T> {
     pub fn eval(&self, settings: &FormData) -> Option<&T> {
         for if_then in &self.if_thens {
@@ -369,22 +1258,85 @@ impl<T> Value<T> {
 
         self.default.as_ref()
     }
+
+    pub fn dependencies(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.if_thens.iter().map(|if_then| if_then.eval.field.id)
+    }
 }
 
 impl Eval {
+    // A missing key is matched as an empty value by the new conditions
     pub fn eval(&self, settings: &FormData) -> bool {
         let value = settings.get(self.field.id);
         match self.condition {
             Condition::MatchAny => self.values.iter().any(|v| value == Some(v)),
             Condition::MatchNone => self.values.iter().all(|v| value != Some(v)),
+            Condition::StartsWithAny => {
+                let value = value.unwrap_or_default();
+                self.values.iter().any(|v| value.starts_with(v))
+            }
+            Condition::ContainsAny => {
+                let value = value.unwrap_or_default();
+                self.values.iter().any(|v| value.contains(v))
+            }
+            Condition::IsEmpty => settings.value_is_empty(self.field.id),
+            Condition::IsNotEmpty => !settings.value_is_empty(self.field.id),
         }
     }
 }
 
+impl Condition {
+    pub fn uses_values(&self) -> bool {
+        !matches!(self, Condition::IsEmpty | Condition::IsNotEmpty)
+    }
+}
+
+impl FieldGroup {
+    // Splits "<group>.<index>.<child>" into the index and the key of the
+    // child field, only keys of known children are accepted
+    pub fn split_key(&self, key: &str) -> Option<(u64, String)> {
+        let (index, rest) = key
+            .strip_prefix(self.id)?
+            .strip_prefix('.')?
+            .split_once('.')?;
+        let index = index.parse().ok()?;
+        let key = format!("{}.{rest}", self.id);
+        self.schema.is_known_key(&key).then_some((index, key))
+    }
+
+    // Key of a child field for the entry at the given index
+    pub fn item_key(&self, index: impl std::fmt::Display, key: &str) -> String {
+        format!(
+            "{}.{index}{}",
+            self.id,
+            key.strip_prefix(self.id).unwrap_or(key)
+        )
+    }
+}
+
 impl Section {
     pub fn display(&self, settings: &FormData) -> bool {
         self.display.is_empty() || self.display.iter().any(|eval| eval.eval(settings))
     }
+
+    pub fn has_capability(&self, capabilities: &AHashSet<String>) -> bool {
+        self.capability
+            .map_or(true, |capability| capabilities.contains(capability))
+    }
+
+    pub fn is_advanced(&self) -> bool {
+        !self.fields.is_empty() && self.fields.iter().all(|field| field.advanced)
+    }
+
+    pub fn dependencies(&self) -> Vec<&'static str> {
+        let mut ids = Vec::new();
+        for eval in &self.display {
+            if !ids.contains(&eval.field.id) {
+                ids.push(eval.field.id);
+            }
+        }
+        ids
+    }
 }
 
 impl Schemas {
@@ -409,7 +1361,10 @@ impl Builder<Schemas, ()> {
                         Action::Delete,
                         Action::Modify,
                         Action::Reload,
+                        Action::Export,
+                        Action::Import,
                     ],
+                    bulk_actions: vec![Action::Delete, Action::Modify],
                     page_size: 10,
                     ..Default::default()
                 },
@@ -451,7 +1406,7 @@ impl Builder<Schemas, Schema> {
         .typ(Type::Input)
         .input_check(
             [Transformer::Trim, Transformer::Lowercase],
-            [Validator::Required, Validator::IsId],
+            [Validator::Required, Validator::IsId(IdKind::Default)],
         )
         .readonly()
     }
@@ -469,6 +1424,17 @@ impl Builder<Schemas, Schema> {
         .input_check([Transformer::Trim], [])
     }
 
+    pub fn new_field_group(self, id: &'static str) -> Builder<(Schemas, Schema), FieldGroup> {
+        Builder {
+            parent: (self.parent, self.item),
+            item: FieldGroup {
+                id,
+                item_name: "Entry",
+                ..Default::default()
+            },
+        }
+    }
+
     pub fn new_form_section(self) -> Builder<(Schemas, Schema), Section> {
         Builder {
             parent: (self.parent, self.item),
@@ -495,6 +1461,16 @@ impl Builder<Schemas, Schema> {
         self
     }
 
+    pub fn map(mut self) -> Self {
+        match self.item.typ {
+            SchemaType::Entry { prefix } => {
+                self.item.typ = SchemaType::Map { prefix };
+            }
+            _ => panic!("Schema type is not Entry."),
+        }
+        self
+    }
+
     pub fn list_title(mut self, title: &'static str) -> Self {
         self.item.list.title = title;
         self
@@ -526,13 +1502,54 @@ impl Builder<Schemas, Schema> {
         builder
     }
 
+    pub fn list_default_sort(mut self, field: &'static str, order: SortOrder) -> Self {
+        if !self.item.fields.contains_key(field) {
+            panic!("Field {field:?} not found in schema {:?}.", self.item.id);
+        }
+        self.item.list.default_sort = Some((field, order));
+        self
+    }
+
+    // Hides the page size selector, for lists where large pages are expensive
+    pub fn list_fixed_page_size(mut self) -> Self {
+        self.item.list.fixed_page_size = true;
+        self
+    }
+
+    // For small lists, where fetching every record is cheaper than a
+    // request per page
+    pub fn list_load_all(mut self) -> Self {
+        self.item.list.load_all = true;
+        self
+    }
+
     pub fn no_list_action(mut self, action: Action) -> Self {
         self.item.list.actions.retain(|a| *a != action);
         self
     }
 
+    pub fn bulk_action(mut self, action: Action) -> Self {
+        if !self.item.list.bulk_actions.contains(&action) {
+            self.item.list.bulk_actions.push(action);
+        }
+        self
+    }
+
+    pub fn no_bulk_action(mut self, action: Action) -> Self {
+        self.item.list.bulk_actions.retain(|a| *a != action);
+        self
+    }
+
+    // Deletions must be confirmed by typing the record id
+    pub fn confirm_delete(mut self) -> Self {
+        self.item.list.confirm_delete = true;
+        self
+    }
+
     pub fn list_action(mut self, action: Action) -> Self {
-        self.item.list.actions.push(action);
+        if !self.item.list.actions.contains(&action) {
+            self.item.list.actions.push(action);
+        }
         self
     }
 
@@ -551,18 +1568,130 @@ impl Builder<Schemas, Schema> {
         self
     }
 
+    pub fn form_confirm_changes(mut self) -> Self {
+        self.item.form.confirm_changes = true;
+        self
+    }
+
     pub fn reload_prefix(mut self, prefix: &'static str) -> Self {
         self.item.reload_prefix = Some(prefix);
         self
     }
 
+    pub fn doc(mut self, doc: &'static str) -> Self {
+        self.item.doc = Some(doc);
+        self
+    }
+
+    pub fn since(mut self, version: &'static str) -> Self {
+        self.item.since = Some(parse_since(version));
+        self
+    }
+
     pub fn names(mut self, singular: &'static str, plural: &'static str) -> Self {
         self.item.name_singular = singular;
         self.item.name_plural = plural;
         self
     }
 
+    pub fn empty_state(mut self, description: &'static str) -> Self {
+        self.item
+            .list
+            .empty
+            .get_or_insert_with(Default::default)
+            .description = description;
+        self
+    }
+
+    // Template used to prefill the form opened from the empty state
+    pub fn empty_state_template(mut self, template: &'static str) -> Self {
+        self.item
+            .list
+            .empty
+            .get_or_insert_with(Default::default)
+            .template = Some(template);
+        self
+    }
+
+    // Records are not required for the server to work
+    pub fn optional_records(mut self) -> Self {
+        self.item
+            .list
+            .empty
+            .get_or_insert_with(Default::default)
+            .optional = true;
+        self
+    }
+
+    pub fn template(
+        mut self,
+        id: &'static str,
+        name: &'static str,
+        description: &'static str,
+        values: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        self.item.templates.push(Template {
+            id,
+            name,
+            description,
+            values,
+        });
+        self
+    }
+
+    pub fn default_provider(mut self, provider: DefaultProviderFn) -> Self {
+        self.item.default_provider = Some(DefaultProvider {
+            related: None,
+            provider,
+        });
+        self
+    }
+
+    pub fn default_provider_with(
+        mut self,
+        related: &'static str,
+        provider: DefaultProviderFn,
+    ) -> Self {
+        self.item.default_provider = Some(DefaultProvider {
+            related: Some(related),
+            provider,
+        });
+        self
+    }
+
+    pub fn warnings(mut self, warnings: SchemaWarningsFn) -> Self {
+        self.item.warnings = Some(warnings);
+        self
+    }
+
+    pub fn form_validator(mut self, validator: FormValidatorFn) -> Self {
+        self.item.validators.push(validator);
+        self
+    }
+
     pub fn build(mut self) -> Builder<Schemas, ()> {
+        // Entry schemas are backed by lookup prefixes that may hold many keys
+        if matches!(self.item.typ, SchemaType::Entry { .. }) {
+            self.item.list.page_size = ENTRY_PAGE_SIZE;
+        }
+        for field in self.item.fields.values() {
+            for id in field
+                .display
+                .iter()
+                .map(|eval| eval.field.id)
+                .chain(field.default.dependencies())
+                .chain(
+                    field
+                        .default_references()
+                        .filter_map(|id| self.item.fields.get_key_value(id).map(|(id, _)| *id)),
+                )
+            {
+                let dependents = self.item.dependents.entry(id).or_default();
+                if !dependents.contains(&field.id) {
+                    dependents.push(field.id);
+                }
+            }
+        }
         self.parent
             .schemas
             .insert(self.item.id, Arc::new(self.item));
@@ -573,21 +1702,61 @@ impl Builder<Schemas, Schema> {
     }
 }
 
+fn parse_since(version: &'static str) -> Semver {
+    Semver::try_from(version).unwrap_or_else(|_| panic!("Invalid version {version:?}."))
+}
+
 impl<T, I> Type<T, I> {
     pub fn label<'x>(&'x self, id: &'x str) -> &'x str {
         match self {
-            Type::Select {
-                source: Source::Static(items),
-                ..
-            } => items
-                .iter()
-                .find_map(|(k, v)| if *k == id { Some(*v) } else { None })
+            Type::Select { source, .. } => source
+                .leaves()
+                .into_iter()
+                .find_map(|source| match source {
+                    Source::Static(items) => {
+                        items
+                            .iter()
+                            .find_map(|(k, v)| if *k == id { Some(*v) } else { None })
+                    }
+                    _ => None,
+                })
                 .unwrap_or(id),
             _ => id,
         }
     }
 }
 
+impl<S, F> Source<S, F> {
+    pub fn leaves(&self) -> Vec<&Source<S, F>> {
+        match self {
+            Source::Merged(sources) => sources.iter().flat_map(Source::leaves).collect(),
+            source => vec![source],
+        }
+    }
+
+    pub fn is_static(&self) -> bool {
+        matches!(self, Source::Static(_) | Source::StaticId(_))
+    }
+}
+
+impl Source<&'static str, &'static str> {
+    // Fixed options followed by the ids of the records in other schemas
+    pub fn static_and_records(
+        items: &'static [(&'static str, &'static str)],
+        schemas: impl IntoIterator<Item = &'static str>,
+    ) -> Self {
+        Source::Merged(
+            std::iter::once(Source::Static(items))
+                .chain(schemas.into_iter().map(|schema| Source::Dynamic {
+                    schema,
+                    field: "_id",
+                    filter: Default::default(),
+                }))
+                .collect(),
+        )
+    }
+}
+
 impl Builder<(Schemas, Schema), Field> {
     fn field(&self, id: &'static str) -> Arc<Field> {
         self.parent
@@ -624,7 +1793,19 @@ impl Builder<(Schemas, Schema), Field> {
     }
 
     pub fn help(mut self, help: &'static str) -> Self {
-        self.item.help = Some(help);
+        self.item.help.push_else(help);
+        self
+    }
+
+    pub fn help_if_eq(
+        mut self,
+        field: &'static str,
+        conditions: impl IntoIterator<Item = &'static str>,
+        help: &'static str,
+    ) -> Self {
+        self.item
+            .help
+            .push_if_matches_eq(self.field(field), conditions, help);
         self
     }
 
@@ -633,52 +1814,218 @@ impl Builder<(Schemas, Schema), Field> {
         self
     }
 
+    pub fn readonly_always(mut self) -> Self {
+        self.item.readonly_always = true;
+        self
+    }
+
+    pub fn allow_custom_values(mut self) -> Self {
+        self.item.allow_custom = true;
+        self
+    }
+
+    pub fn map_key_check(
+        mut self,
+        transformers: impl IntoIterator<Item = Transformer>,
+        validators: impl IntoIterator<Item = Validator>,
+    ) -> Self {
+        self.item.map_key_checks = Some(InputCheck::new(transformers, validators));
+        self
+    }
+
+    pub fn suggestions(mut self, suggestions: &'static [&'static str]) -> Self {
+        self.item.suggestions = suggestions;
+        self
+    }
+
     pub fn enterprise_feature(mut self) -> Self {
         self.item.enterprise = true;
         self
     }
 
+    pub fn requires_capability(mut self, capability: &'static str) -> Self {
+        self.item.capability = Some(capability);
+        self
+    }
+
+    // Duration fields accepting "false" to turn the feature off
+    pub fn allow_disabled(mut self) -> Self {
+        self.item.allow_disabled = true;
+        self
+    }
+
+    pub fn disruptive(mut self) -> Self {
+        self.item.disruptive = true;
+        self
+    }
+
+    pub fn advanced(mut self) -> Self {
+        self.item.advanced = true;
+        self
+    }
+
+    pub fn skip_clone(mut self) -> Self {
+        self.item.skip_clone = true;
+        self
+    }
+
+    // Fallback is selected when the loaded value matches none of the presets
+    pub fn preset_for(mut self, field: &'static str, fallback: &'static str) -> Self {
+        self.item.preset_for = Some(Preset { field, fallback });
+        self
+    }
+
+    // Array field filled with the names found in a pasted certificate
+    pub fn certificate_names(mut self, field: &'static str) -> Self {
+        self.item.certificate_names = Some(field);
+        self
+    }
+
+    // Boolean columns can be switched from the list
+    pub fn list_toggle(mut self) -> Self {
+        self.item.list_toggle = Some(ListToggle::Independent);
+        self
+    }
+
+    pub fn list_toggle_exclusive(mut self) -> Self {
+        self.item.list_toggle = Some(ListToggle::Exclusive);
+        self
+    }
+
+    // File name template, the value can then be downloaded and copied
+    pub fn downloadable(mut self, filename: &'static str) -> Self {
+        self.item.downloadable = Some(filename);
+        self
+    }
+
+    pub fn generate_secret(mut self, length: usize, charset: SecretCharset) -> Self {
+        self.item.generate_secret = Some(GenerateSecret { length, charset });
+        self
+    }
+
+    pub fn sortable(mut self) -> Self {
+        self.item.sortable = true;
+        self
+    }
+
+    pub fn filterable(mut self) -> Self {
+        self.item.filterable = true;
+        self
+    }
+
+    pub fn requires(mut self, requires: Requires) -> Self {
+        self.item.requires = Some(requires);
+        self
+    }
+
+    pub fn notification_test(mut self, test: NotificationTest) -> Self {
+        self.item.notification_test = Some(test);
+        self
+    }
+
+    // Key used by older server versions for this field, aliases are
+    // looked up in the order they are added
+    pub fn alias(mut self, key: &'static str) -> Self {
+        self.item.aliases.push(key);
+        self
+    }
+
+    pub fn doc(mut self, doc: &'static str) -> Self {
+        self.item.doc = Some(doc);
+        self
+    }
+
+    pub fn since(mut self, version: &'static str) -> Self {
+        self.item.since = Some(parse_since(version));
+        self
+    }
+
+    pub fn auto_generate(mut self, pattern: &'static str) -> Self {
+        debug_assert!(
+            pattern.contains("{n}"),
+            "Missing {{n}} in id pattern {pattern}"
+        );
+        self.item.auto_id = Some(pattern);
+        self.placeholder("Leave blank to generate automatically")
+    }
+
     pub fn typ(mut self, typ_: Type<&'static str, &'static str>) -> Self {
-        self.item.typ_ = match typ_ {
-            Type::Select {
-                source:
-                    Source::Dynamic {
-                        schema,
-                        field,
-                        filter,
-                    },
+        self.item.typ_ = self.resolve_type(typ_);
+        self
+    }
+
+    pub fn typ_if_eq(
+        mut self,
+        field: &'static str,
+        conditions: impl IntoIterator<Item = &'static str>,
+        typ_: Type<&'static str, &'static str>,
+    ) -> Self {
+        let typ_ = self.resolve_type(typ_);
+        self.item
+            .typ_if
+            .push_if_matches_eq(self.field(field), conditions, typ_);
+        self
+    }
+
+    fn resolve_type(
+        &self,
+        typ_: Type<&'static str, &'static str>,
+    ) -> Type<Arc<Schema>, Arc<Field>> {
+        match typ_ {
+            Type::Select { source, typ } => Type::Select {
+                source: self.resolve_source(source),
                 typ,
+            },
+            typ_ => typ_.into(),
+        }
+    }
+
+    fn resolve_source(
+        &self,
+        source: Source<&'static str, &'static str>,
+    ) -> Source<Arc<Schema>, Arc<Field>> {
+        match source {
+            Source::Static(items) => Source::Static(items),
+            Source::StaticId(items) => Source::StaticId(items),
+            Source::Dynamic {
+                schema,
+                field,
+                filter,
             } => {
                 let schema = self.schema(schema);
 
-                Type::Select {
-                    source: Source::Dynamic {
-                        field: schema
-                            .fields
-                            .get(field)
-                            .unwrap_or_else(|| {
-                                panic!("Field {field:?} not found in schema {}.", schema.id)
-                            })
-                            .clone(),
-                        schema,
-                        filter,
-                    },
-                    typ,
+                Source::Dynamic {
+                    field: schema
+                        .fields
+                        .get(field)
+                        .unwrap_or_else(|| {
+                            panic!("Field {field:?} not found in schema {}.", schema.id)
+                        })
+                        .clone(),
+                    schema,
+                    filter,
                 }
             }
-            Type::Select {
-                source: Source::DynamicSelf { field, filter },
-                typ,
-            } => Type::Select {
-                source: Source::DynamicSelf {
-                    field: self.field(field),
-                    filter,
-                },
-                typ,
+            Source::DynamicSelf { field, filter } => Source::DynamicSelf {
+                field: self.field(field),
+                filter,
             },
-            typ_ => typ_.into(),
-        };
-        self
+            Source::DynamicRemote {
+                endpoint,
+                id_field,
+                label_field,
+            } => Source::DynamicRemote {
+                endpoint,
+                id_field,
+                label_field,
+            },
+            Source::Merged(sources) => Source::Merged(
+                sources
+                    .into_iter()
+                    .map(|source| self.resolve_source(source))
+                    .collect(),
+            ),
+        }
     }
 
     pub fn source_filter_if_eq(
@@ -713,21 +2060,89 @@ impl Builder<(Schemas, Schema), Field> {
         self
     }
 
-    pub fn input_check_if_eq(
+    pub fn input_check_if(
         mut self,
         field: &'static str,
-        conditions: impl IntoIterator<Item = &'static str>,
+        condition: Condition,
+        values: impl IntoIterator<Item = &'static str>,
         transformers: impl IntoIterator<Item = Transformer>,
         validators: impl IntoIterator<Item = Validator>,
     ) -> Self {
-        self.item.checks.push_if_matches_eq(
+        self.item.checks.push_if(
             self.field(field),
-            conditions,
+            condition,
+            values,
             InputCheck::new(transformers, validators),
         );
         self
     }
 
+    pub fn input_check_if_eq(
+        self,
+        field: &'static str,
+        conditions: impl IntoIterator<Item = &'static str>,
+        transformers: impl IntoIterator<Item = Transformer>,
+        validators: impl IntoIterator<Item = Validator>,
+    ) -> Self {
+        self.input_check_if(
+            field,
+            Condition::MatchAny,
+            conditions,
+            transformers,
+            validators,
+        )
+    }
+
+    pub fn input_check_if_starts_with(
+        self,
+        field: &'static str,
+        prefixes: impl IntoIterator<Item = &'static str>,
+        transformers: impl IntoIterator<Item = Transformer>,
+        validators: impl IntoIterator<Item = Validator>,
+    ) -> Self {
+        self.input_check_if(
+            field,
+            Condition::StartsWithAny,
+            prefixes,
+            transformers,
+            validators,
+        )
+    }
+
+    pub fn input_check_if_contains(
+        self,
+        field: &'static str,
+        values: impl IntoIterator<Item = &'static str>,
+        transformers: impl IntoIterator<Item = Transformer>,
+        validators: impl IntoIterator<Item = Validator>,
+    ) -> Self {
+        self.input_check_if(
+            field,
+            Condition::ContainsAny,
+            values,
+            transformers,
+            validators,
+        )
+    }
+
+    pub fn input_check_if_empty(
+        self,
+        field: &'static str,
+        transformers: impl IntoIterator<Item = Transformer>,
+        validators: impl IntoIterator<Item = Validator>,
+    ) -> Self {
+        self.input_check_if(field, Condition::IsEmpty, [], transformers, validators)
+    }
+
+    pub fn input_check_if_not_empty(
+        self,
+        field: &'static str,
+        transformers: impl IntoIterator<Item = Transformer>,
+        validators: impl IntoIterator<Item = Validator>,
+    ) -> Self {
+        self.input_check_if(field, Condition::IsNotEmpty, [], transformers, validators)
+    }
+
     pub fn input_check(
         mut self,
         transformers: impl IntoIterator<Item = Transformer>,
@@ -761,18 +2176,54 @@ impl Builder<(Schemas, Schema), Field> {
         self
     }
 
-    pub fn default_if_eq(
+    pub fn default_if(
         mut self,
         field: &'static str,
-        conditions: impl IntoIterator<Item = &'static str>,
+        condition: Condition,
+        values: impl IntoIterator<Item = &'static str>,
         value: impl Into<FormValue>,
     ) -> Self {
         self.item
             .default
-            .push_if_matches_eq(self.field(field), conditions, value.into());
+            .push_if(self.field(field), condition, values, value.into());
         self
     }
 
+    pub fn default_if_eq(
+        self,
+        field: &'static str,
+        conditions: impl IntoIterator<Item = &'static str>,
+        value: impl Into<FormValue>,
+    ) -> Self {
+        self.default_if(field, Condition::MatchAny, conditions, value)
+    }
+
+    pub fn default_if_starts_with(
+        self,
+        field: &'static str,
+        prefixes: impl IntoIterator<Item = &'static str>,
+        value: impl Into<FormValue>,
+    ) -> Self {
+        self.default_if(field, Condition::StartsWithAny, prefixes, value)
+    }
+
+    pub fn default_if_contains(
+        self,
+        field: &'static str,
+        values: impl IntoIterator<Item = &'static str>,
+        value: impl Into<FormValue>,
+    ) -> Self {
+        self.default_if(field, Condition::ContainsAny, values, value)
+    }
+
+    pub fn default_if_empty(self, field: &'static str, value: impl Into<FormValue>) -> Self {
+        self.default_if(field, Condition::IsEmpty, [], value)
+    }
+
+    pub fn default_if_not_empty(self, field: &'static str, value: impl Into<FormValue>) -> Self {
+        self.default_if(field, Condition::IsNotEmpty, [], value)
+    }
+
     pub fn display_if(
         mut self,
         field: &'static str,
@@ -780,7 +2231,7 @@ impl Builder<(Schemas, Schema), Field> {
         condition: Condition,
     ) -> Self {
         let values = values.into_iter().collect::<Vec<_>>();
-        if !values.is_empty() {
+        if !values.is_empty() || !condition.uses_values() {
             self.item.display.push(Eval {
                 field: self.field(field),
                 values,
@@ -806,6 +2257,30 @@ impl Builder<(Schemas, Schema), Field> {
         self.display_if(field, values, Condition::MatchNone)
     }
 
+    pub fn display_if_starts_with(
+        self,
+        field: &'static str,
+        prefixes: impl IntoIterator<Item = &'static str>,
+    ) -> Self {
+        self.display_if(field, prefixes, Condition::StartsWithAny)
+    }
+
+    pub fn display_if_contains(
+        self,
+        field: &'static str,
+        values: impl IntoIterator<Item = &'static str>,
+    ) -> Self {
+        self.display_if(field, values, Condition::ContainsAny)
+    }
+
+    pub fn display_if_empty(self, field: &'static str) -> Self {
+        self.display_if(field, [], Condition::IsEmpty)
+    }
+
+    pub fn display_if_not_empty(self, field: &'static str) -> Self {
+        self.display_if(field, [], Condition::IsNotEmpty)
+    }
+
     pub fn build(mut self) -> Builder<Schemas, Schema> {
         self.parent
             .1
@@ -821,6 +2296,7 @@ impl Builder<(Schemas, Schema), Field> {
         let cloned_field = Field {
             id,
             typ_: self.item.typ_.clone(),
+            typ_if: self.item.typ_if.clone(),
             display: self.item.display.clone(),
             checks: self.item.checks.clone(),
             ..Default::default()
@@ -836,6 +2312,58 @@ impl Builder<(Schemas, Schema), Field> {
     }
 }
 
+impl Builder<(Schemas, Schema), FieldGroup> {
+    pub fn label(mut self, label: &'static str) -> Self {
+        self.item.label = label;
+        self
+    }
+
+    pub fn help(mut self, help: &'static str) -> Self {
+        self.item.help = Some(help);
+        self
+    }
+
+    pub fn item_name(mut self, name: &'static str) -> Self {
+        self.item.item_name = name;
+        self
+    }
+
+    // Moves fields declared as "<group>.<child>" into the group, display
+    // conditions between them are evaluated within each entry
+    pub fn fields(mut self, fields: impl IntoIterator<Item = &'static str>) -> Self {
+        let mut schema = Schema {
+            id: self.item.id,
+            ..Default::default()
+        };
+        for id in fields {
+            if !id
+                .strip_prefix(self.item.id)
+                .is_some_and(|rest| rest.starts_with('.'))
+            {
+                panic!("Field {id:?} is not a child of group {:?}.", self.item.id);
+            }
+            let field = self.parent.1.fields.remove(id).unwrap_or_else(|| {
+                panic!("Field {id:?} not found in schema {:?}.", self.parent.1.id)
+            });
+            self.item.fields.push(field.clone());
+            schema.fields.insert(id, field);
+        }
+        self.item.schema = Arc::new(schema);
+        self
+    }
+
+    pub fn build(mut self) -> Builder<Schemas, Schema> {
+        if self.item.schema.fields.is_empty() {
+            panic!("Group {:?} has no fields.", self.item.id);
+        }
+        self.parent.1.groups.push(Arc::new(self.item));
+        Builder {
+            parent: self.parent.0,
+            item: self.parent.1,
+        }
+    }
+}
+
 impl Builder<(Schemas, Schema), Section> {
     pub fn title(mut self, title: &'static str) -> Self {
         self.item.title = Some(title);
@@ -867,6 +2395,22 @@ impl Builder<(Schemas, Schema), Section> {
         builder
     }
 
+    pub fn field_group(mut self, group: &'static str) -> Self {
+        self.item.groups.push(
+            self.parent
+                .1
+                .group(group)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Group {group:?} not found in schema {:?}.",
+                        self.parent.1.id
+                    )
+                })
+                .clone(),
+        );
+        self
+    }
+
     fn display_if(
         mut self,
         field: &'static str,
@@ -908,6 +2452,35 @@ impl Builder<(Schemas, Schema), Section> {
         self.display_if(field, values, Condition::MatchNone)
     }
 
+    pub fn display_if_starts_with(
+        self,
+        field: &'static str,
+        prefixes: impl IntoIterator<Item = &'static str>,
+    ) -> Self {
+        self.display_if(field, prefixes, Condition::StartsWithAny)
+    }
+
+    pub fn display_if_contains(
+        self,
+        field: &'static str,
+        values: impl IntoIterator<Item = &'static str>,
+    ) -> Self {
+        self.display_if(field, values, Condition::ContainsAny)
+    }
+
+    pub fn display_if_empty(self, field: &'static str) -> Self {
+        self.display_if(field, [], Condition::IsEmpty)
+    }
+
+    pub fn display_if_not_empty(self, field: &'static str) -> Self {
+        self.display_if(field, [], Condition::IsNotEmpty)
+    }
+
+    pub fn requires_capability(mut self, capability: &'static str) -> Self {
+        self.item.capability = Some(capability);
+        self
+    }
+
     pub fn build(mut self) -> Builder<Schemas, Schema> {
         self.parent.1.form.sections.push(self.item);
         Builder {
@@ -918,36 +2491,65 @@ impl Builder<(Schemas, Schema), Section> {
 }
 
 impl<T> Value<T> {
-    pub fn push_if_matches_eq(
+    pub fn push_if(
         &mut self,
         field: Arc<Field>,
-        contains: impl IntoIterator<Item = &'static str>,
+        condition: Condition,
+        values: impl IntoIterator<Item = &'static str>,
         then: T,
     ) {
         self.if_thens.push(IfThen {
             eval: Eval {
                 field,
-                values: contains.into_iter().collect(),
-                condition: Condition::MatchAny,
+                values: values.into_iter().collect(),
+                condition,
             },
             value: then,
         });
     }
 
+    pub fn push_if_matches_eq(
+        &mut self,
+        field: Arc<Field>,
+        contains: impl IntoIterator<Item = &'static str>,
+        then: T,
+    ) {
+        self.push_if(field, Condition::MatchAny, contains, then);
+    }
+
     pub fn push_if_matches_ne(
         &mut self,
         field: Arc<Field>,
         contains: impl IntoIterator<Item = &'static str>,
         then: T,
     ) {
-        self.if_thens.push(IfThen {
-            eval: Eval {
-                field,
-                values: contains.into_iter().collect(),
-                condition: Condition::MatchNone,
-            },
-            value: then,
-        });
+        self.push_if(field, Condition::MatchNone, contains, then);
+    }
+
+    pub fn push_if_starts_with(
+        &mut self,
+        field: Arc<Field>,
+        prefixes: impl IntoIterator<Item = &'static str>,
+        then: T,
+    ) {
+        self.push_if(field, Condition::StartsWithAny, prefixes, then);
+    }
+
+    pub fn push_if_contains(
+        &mut self,
+        field: Arc<Field>,
+        values: impl IntoIterator<Item = &'static str>,
+        then: T,
+    ) {
+        self.push_if(field, Condition::ContainsAny, values, then);
+    }
+
+    pub fn push_if_empty(&mut self, field: Arc<Field>, then: T) {
+        self.push_if(field, Condition::IsEmpty, [], then);
+    }
+
+    pub fn push_if_not_empty(&mut self, field: Arc<Field>, then: T) {
+        self.push_if(field, Condition::IsNotEmpty, [], then);
     }
 
     pub fn push_else(&mut self, value: T) {
@@ -960,9 +2562,19 @@ impl InputCheck {
         transformers: impl IntoIterator<Item = Transformer>,
         validators: impl IntoIterator<Item = Validator>,
     ) -> Self {
+        let validators = validators.into_iter().collect::<Vec<_>>();
+        for validator in &validators {
+            if let Validator::MatchesRegex { pattern, .. } = validator {
+                debug_assert!(
+                    regex::Regex::new(pattern).is_ok(),
+                    "Invalid regular expression {pattern:?} in schema validator"
+                );
+            }
+        }
+
         InputCheck {
             transformers: transformers.into_iter().collect(),
-            validators: validators.into_iter().collect(),
+            validators,
         }
     }
 }
@@ -994,10 +2606,12 @@ impl From<Type<&'static str, &'static str>> for Type<Arc<Schema>, Arc<Field>> {
             Type::Expression => Type::Expression,
             Type::Input => Type::Input,
             Type::Array(t) => Type::Array(t),
+            Type::Tag => Type::Tag,
             Type::Secret => Type::Secret,
             Type::Text => Type::Text,
             Type::Size => Type::Size,
             Type::Cron => Type::Cron,
+            Type::Map => Type::Map,
             Type::Rate => Type::Rate,
             Type::Select {
                 source: Source::Static(items),
@@ -1075,6 +2689,7 @@ struct XList {
     subtitle: String,
     fields: Vec<XLabel>,
     actions: Vec<String>,
+    bulk_actions: Vec<String>,
 }
 
 pub fn print_schemas(schemas: &Schemas) {
@@ -1097,41 +2712,52 @@ pub fn print_schemas(schemas: &Schemas) {
                         ArrayType::Text => "String".to_string(),
                         ArrayType::Duration => "Duration".to_string(),
                     },
+                    Type::Tag => "String".to_string(),
                     Type::Secret => "Secret".to_string(),
                     Type::Text => "Text".to_string(),
                     Type::Expression => "Expression".to_string(),
-                    Type::Select { source, .. } => match source {
-                        Source::Static(items) => {
-                            if let Some(pos) = enums.iter().position(|enums| enums == items) {
-                                format!("Enum{pos}")
-                            } else {
-                                let pos = enums.len();
-                                enums.push(*items);
-                                format!("Enum{pos}")
+                    Type::Select { source, .. } => source
+                        .leaves()
+                        .into_iter()
+                        .map(|source| match source {
+                            Source::Static(items) => {
+                                if let Some(pos) = enums.iter().position(|enums| enums == items) {
+                                    format!("Enum{pos}")
+                                } else {
+                                    let pos = enums.len();
+                                    enums.push(*items);
+                                    format!("Enum{pos}")
+                                }
                             }
-                        }
-                        Source::StaticId(items) => {
-                            if let Some(pos) = enums_single.iter().position(|enums| enums == items)
-                            {
-                                format!("EnumShort{pos}")
-                            } else {
-                                let pos = enums.len();
-                                enums_single.push(*items);
-                                format!("EnumShort{pos}")
+                            Source::StaticId(items) => {
+                                if let Some(pos) =
+                                    enums_single.iter().position(|enums| enums == items)
+                                {
+                                    format!("EnumShort{pos}")
+                                } else {
+                                    let pos = enums.len();
+                                    enums_single.push(*items);
+                                    format!("EnumShort{pos}")
+                                }
                             }
-                        }
-                        Source::Dynamic { schema, .. } => format!("@{}", schema.id),
-                        Source::DynamicSelf { .. } => format!("@{this_id}"),
-                    },
+                            Source::Dynamic { schema, .. } => format!("@{}", schema.id),
+                            Source::DynamicSelf { .. } => format!("@{this_id}"),
+                            Source::DynamicRemote { endpoint, .. } => format!("@{endpoint}"),
+                            Source::Merged(_) => unreachable!(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join("|"),
                     Type::Boolean => "Boolean".to_string(),
                     Type::Duration => "Duration".to_string(),
                     Type::Rate => "Rate".to_string(),
                     Type::Size => "Size".to_string(),
                     Type::Cron => "Cron".to_string(),
+                    Type::Map => "Map".to_string(),
                 },
                 array: matches!(
                     field.typ_,
                     Type::Array(_)
+                        | Type::Tag
                         | Type::Select {
                             typ: SelectType::Many | SelectType::ManyWithSearch,
                             ..
@@ -1139,19 +2765,25 @@ pub fn print_schemas(schemas: &Schemas) {
                 ),
                 required: false,
                 flags: BTreeSet::new(),
-                description: field.help.unwrap_or_default().to_string(),
+                description: field.help_text().unwrap_or_default(),
             };
 
             xfield.required = matches!(field.typ_, Type::Boolean | Type::Select { .. });
 
-            if field.readonly {
+            if field.readonly_always {
                 xfield.flags.insert("readonly".to_string());
+            } else if field.readonly {
+                xfield.flags.insert("readonly_on_update".to_string());
             }
 
             if field.enterprise {
                 xfield.flags.insert("enterprise".to_string());
             }
 
+            if field.allow_disabled {
+                xfield.flags.insert("allow-disabled".to_string());
+            }
+
             if let Some(checks) = field.checks.default.as_ref() {
                 for validator in &checks.validators {
                     match validator {
@@ -1161,8 +2793,15 @@ pub fn print_schemas(schemas: &Schemas) {
                         Validator::IsEmail => {
                             xfield.typ = "Email".to_string();
                         }
-                        Validator::IsId => {
+                        Validator::IsId(kind) => {
                             xfield.typ = "Id".to_string();
+                            xfield.flags.insert(format!(
+                                "id-chars:{}",
+                                kind.chars().iter().collect::<String>()
+                            ));
+                            xfield
+                                .flags
+                                .insert(format!("id-max-length:{}", kind.max_len()));
                         }
                         Validator::IsHost => {
                             xfield.typ = "Host".to_string();
@@ -1170,12 +2809,21 @@ pub fn print_schemas(schemas: &Schemas) {
                         Validator::IsDomain => {
                             xfield.typ = "Domain".to_string();
                         }
+                        Validator::IsEmailOrWildcard => {
+                            xfield.typ = "EmailOrWildcard".to_string();
+                        }
+                        Validator::IsDomainOrWildcard => {
+                            xfield.typ = "DomainOrWildcard".to_string();
+                        }
                         Validator::IsPort => {
                             xfield.typ = "Integer".to_string();
                             xfield.flags.insert("min-value:1".to_string());
                             xfield.flags.insert("max-value:65535".to_string());
                         }
-                        Validator::IsIpOrMask => {
+                        Validator::IsIpAddr => {
+                            xfield.typ = "IpAddr".to_string();
+                        }
+                        Validator::IsIpMask => {
                             xfield.typ = "IpMask".to_string();
                         }
                         Validator::IsUrl => {
@@ -1187,6 +2835,34 @@ pub fn print_schemas(schemas: &Schemas) {
                         Validator::IsSocketAddr => {
                             xfield.typ = "SocketAddr".to_string();
                         }
+                        Validator::IsDnsblZone => {
+                            xfield.typ = "DnsblZone".to_string();
+                        }
+                        Validator::IsCron => {
+                            xfield.typ = "Cron".to_string();
+                        }
+                        Validator::IsCertificate => {
+                            xfield.typ = "Certificate".to_string();
+                        }
+                        Validator::MatchesRegex { pattern, .. } => {
+                            xfield.flags.insert(format!("pattern:{}", pattern));
+                        }
+                        Validator::GreaterThanField(other) => {
+                            xfield.flags.insert(format!("greater-than:{}", other));
+                        }
+                        Validator::LessThanField(other) => {
+                            xfield.flags.insert(format!("less-than:{}", other));
+                        }
+                        Validator::MatchesCertificate(other) => {
+                            xfield
+                                .flags
+                                .insert(format!("matches-certificate:{}", other));
+                        }
+                        Validator::WildcardsRequire { field, value } => {
+                            xfield
+                                .flags
+                                .insert(format!("wildcards-require:{}={}", field, value));
+                        }
                         Validator::MinLength(v) => {
                             xfield.flags.insert(format!("min-length:{}", v));
                         }
@@ -1213,12 +2889,34 @@ pub fn print_schemas(schemas: &Schemas) {
                                 xfield.flags.insert(format!("max-value:{}", v));
                             }
                         },
+                        Validator::MinDuration(v) => {
+                            xfield.flags.insert(format!("min-duration:{}", v));
+                        }
+                        Validator::MaxDuration(v) => {
+                            xfield.flags.insert(format!("max-duration:{}", v));
+                        }
+                        Validator::MinSize(v) => {
+                            xfield.flags.insert(format!("min-size:{}", v));
+                        }
+                        Validator::MaxSize(v) => {
+                            xfield.flags.insert(format!("max-size:{}", v));
+                        }
                         Validator::MinItems(v) => {
                             xfield.flags.insert(format!("min-items:{}", v));
                         }
                         Validator::MaxItems(v) => {
                             xfield.flags.insert(format!("max-items:{}", v));
                         }
+                        Validator::IsTemplate(placeholders) => {
+                            xfield.flags.insert(format!(
+                                "placeholders:{}",
+                                placeholders
+                                    .iter()
+                                    .map(|placeholder| placeholder.name)
+                                    .collect::<Vec<_>>()
+                                    .join(",")
+                            ));
+                        }
                         Validator::IsValidExpression(expression_validator) => {
                             let vars = expression_validator.variables.join(",");
                             let consts = expression_validator.constants.join(",");
@@ -1249,6 +2947,15 @@ pub fn print_schemas(schemas: &Schemas) {
                         Transformer::HashSecret => {
                             xfield.flags.insert("hash".to_string());
                         }
+                        Transformer::IdnaToAscii => {
+                            xfield.flags.insert("idna".to_string());
+                        }
+                        Transformer::NormalizeUrl => {
+                            xfield.flags.insert("normalize-url".to_string());
+                        }
+                        Transformer::NormalizeIp => {
+                            xfield.flags.insert("normalize-ip".to_string());
+                        }
                     }
                 }
             }
@@ -1267,6 +2974,9 @@ pub fn print_schemas(schemas: &Schemas) {
                         }
                     }
                     FormValue::Array(items) => json!(items).to_string(),
+                    FormValue::Map(entries) => {
+                        json!(entries.iter().cloned().collect::<BTreeMap<_, _>>()).to_string()
+                    }
                     FormValue::Expression(expression) => {
                         json!(
                             {
@@ -1330,6 +3040,12 @@ pub fn print_schemas(schemas: &Schemas) {
                 .iter()
                 .map(|a| format!("{:?}", a))
                 .collect(),
+            bulk_actions: schema
+                .list
+                .bulk_actions
+                .iter()
+                .map(|a| format!("{:?}", a))
+                .collect(),
             fields: schema
                 .list
                 .fields
@@ -1431,3 +3147,135 @@ pub fn print_schemas(schemas: &Schemas) {
         println!();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schemas() -> Schemas {
+        Schemas::builder()
+            .new_schema("store")
+            .prefix("store")
+            .suffix("type")
+            .build()
+            .new_schema("lookup")
+            .prefix("lookup")
+            .map()
+            .build()
+            .new_schema("hosts")
+            .prefix("hosts")
+            .build()
+            .new_schema("server")
+            .build()
+            .build()
+    }
+
+    #[test]
+    fn record_keys_round_trip() {
+        for id in ["store", "lookup"] {
+            let schema = schemas().get_or_panic(id);
+            assert_eq!(schema.record_key("pg"), format!("{id}.pg"));
+            assert_eq!(schema.record_prefix("pg"), format!("{id}.pg."));
+
+            let key = schema.field_key("pg", "auth.username");
+            assert_eq!(key, format!("{id}.pg.auth.username"));
+            assert_eq!(schema.split_key(&key), Some(("pg", "auth.username")));
+        }
+    }
+
+    #[test]
+    fn entry_keys_round_trip() {
+        let schema = schemas().get_or_panic("hosts");
+        assert_eq!(schema.record_key("mx1"), "hosts.mx1");
+        assert_eq!(schema.field_key("mx1", "_value"), "hosts.mx1");
+        assert_eq!(schema.split_key("hosts.mx1"), Some(("mx1", "")));
+
+        // Entries hold a single value, so their ids may contain dots
+        let key = schema.field_key("mx1.example.org", "_value");
+        assert_eq!(key, "hosts.mx1.example.org");
+        assert_eq!(schema.split_key(&key), Some(("mx1.example.org", "")));
+        assert!(!schema.is_ambiguous_id("mx1.example.org"));
+    }
+
+    #[test]
+    fn list_keys_round_trip() {
+        let schema = schemas().get_or_panic("server");
+        let key = schema.field_key("", "server.hostname");
+        assert_eq!(key, "server.hostname");
+        assert_eq!(schema.split_key(&key), Some(("", "server.hostname")));
+        assert!(!schema.is_ambiguous_id("a.b"));
+    }
+
+    #[test]
+    fn empty_ids_and_fields_are_not_split() {
+        let schemas = schemas();
+        for id in ["store", "lookup"] {
+            let schema = schemas.get_or_panic(id);
+            assert_eq!(schema.split_key(id), None);
+            assert_eq!(schema.split_key(&format!("{id}.")), None);
+            assert_eq!(schema.split_key(&format!("{id}.pg")), None);
+            assert_eq!(schema.split_key(&format!("{id}.pg.")), None);
+            assert_eq!(schema.split_key(&format!("{id}..host")), None);
+            assert_eq!(schema.split_key(&format!("{id}x.pg.host")), None);
+        }
+
+        let schema = schemas.get_or_panic("hosts");
+        assert_eq!(schema.split_key("hosts"), None);
+        assert_eq!(schema.split_key("hosts."), None);
+        assert_eq!(schema.split_key("hostsx.mx1"), None);
+    }
+
+    #[test]
+    fn dotted_record_ids_are_ambiguous() {
+        let schemas = schemas();
+        for id in ["store", "lookup"] {
+            let schema = schemas.get_or_panic(id);
+            assert!(schema.is_ambiguous_id("a.b"));
+            assert!(!schema.is_ambiguous_id("a-b"));
+
+            // The id would be read back as "a" with field "b.host"
+            let key = schema.field_key("a.b", "host");
+            assert_eq!(schema.split_key(&key), Some(("a", "b.host")));
+        }
+    }
+
+    #[test]
+    fn map_records_are_grouped_by_id() {
+        let schema = schemas().get_or_panic("lookup");
+        let settings = Settings::from_iter(
+            [
+                ("pg.host", "localhost"),
+                ("pg.auth.username", "admin"),
+                ("mysql.port", "3306"),
+                ("pg", "orphan"),
+                (".host", "no-id"),
+                ("pg.", "no-field"),
+            ]
+            .map(|(key, value)| (key.to_string(), value.to_string())),
+        );
+
+        let records = schema.map_records(settings);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].len(), 2);
+        assert_eq!(records[0].get("_id").map(String::as_str), Some("mysql"));
+        assert_eq!(records[0].get("port").map(String::as_str), Some("3306"));
+        assert_eq!(records[1].len(), 3);
+        assert_eq!(records[1].get("_id").map(String::as_str), Some("pg"));
+        assert_eq!(
+            records[1].get("host").map(String::as_str),
+            Some("localhost")
+        );
+        assert_eq!(
+            records[1].get("auth.username").map(String::as_str),
+            Some("admin")
+        );
+    }
+
+    #[test]
+    fn schemas_have_no_dangling_references() {
+        let schemas = crate::build_schemas();
+        if let Err(errors) = schemas.validate() {
+            panic!("Invalid schema references:\n{}", errors.join("\n"));
+        }
+    }
+}
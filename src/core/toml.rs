@@ -0,0 +1,156 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::fmt::Display;
+
+use toml::{Table, Value};
+
+// Reader for pasted configuration fragments, tables and dotted keys are
+// flattened and array items are numbered like the server stores them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TomlError {
+    pub line: usize,
+    pub message: String,
+}
+
+pub fn parse_flat(input: &str) -> Result<Vec<(String, String)>, TomlError> {
+    let table = input.parse::<Table>().map_err(|err| TomlError {
+        line: err.span().map_or(1, |span| {
+            input[..span.start.min(input.len())].matches('\n').count() + 1
+        }),
+        message: err.message().trim().to_string(),
+    })?;
+    let mut result = Vec::new();
+    for (key, value) in table {
+        flatten(key, value, &mut result);
+    }
+
+    Ok(result)
+}
+
+fn flatten(key: String, value: Value, result: &mut Vec<(String, String)>) {
+    match value {
+        Value::String(value) => result.push((key, value)),
+        Value::Array(items) => {
+            let pad_len = items.len().saturating_sub(1).to_string().len();
+            for (idx, item) in items.into_iter().enumerate() {
+                flatten(format!("{key}.{idx:0>pad_len$}"), item, result);
+            }
+        }
+        Value::Table(entries) => {
+            for (entry_key, value) in entries {
+                flatten(format!("{key}.{entry_key}"), value, result);
+            }
+        }
+        // Numbers, booleans and dates are kept as written
+        Value::Datetime(value) => result.push((key, value.to_string())),
+        value => result.push((key, value.to_string())),
+    }
+}
+
+impl Display for TomlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat(input: &str) -> Vec<(String, String)> {
+        parse_flat(input).unwrap()
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(
+            flat(concat!(
+                "a = \"tab\\there \\u00e9\"\n",
+                "b = 'C:\\path'\n",
+                "c = \"\"\"\nline one\nline two\"\"\"\n",
+                "d = '''\nraw \\n'''\n",
+            )),
+            pairs(&[
+                ("a", "tab\there é"),
+                ("b", "C:\\path"),
+                ("c", "line one\nline two"),
+                ("d", "raw \\n"),
+            ])
+        );
+    }
+
+    #[test]
+    fn scalars_are_kept_as_written() {
+        assert_eq!(
+            flat("port = 25\nratio = 1.0\nenable = true\nsince = 2024-01-01"),
+            pairs(&[
+                ("enable", "true"),
+                ("port", "25"),
+                ("ratio", "1.0"),
+                ("since", "2024-01-01"),
+            ])
+        );
+    }
+
+    #[test]
+    fn arrays_are_numbered() {
+        assert_eq!(
+            flat("hosts = [\"a\", \"b\"]"),
+            pairs(&[("hosts.0", "a"), ("hosts.1", "b")])
+        );
+        let items = (0..11).map(|idx| format!("\"{idx}\"")).collect::<Vec<_>>();
+        let result = flat(&format!("list = [{}]", items.join(", ")));
+        assert_eq!(result.len(), 11);
+        assert_eq!(result[0], ("list.00".to_string(), "0".to_string()));
+        assert_eq!(result[10], ("list.10".to_string(), "10".to_string()));
+    }
+
+    #[test]
+    fn tables_and_dotted_keys_are_flattened() {
+        assert_eq!(
+            flat(concat!(
+                "[server.listener.smtp]\n",
+                "bind = [\"[::]:25\"]\n",
+                "protocol = \"smtp\"\n",
+                "tls.implicit = false\n",
+                "\n",
+                "[\"store\".'rocksdb']\n",
+                "settings = { type = \"rocksdb\", path = \"/data\" }\n",
+            )),
+            pairs(&[
+                ("server.listener.smtp.bind.0", "[::]:25"),
+                ("server.listener.smtp.protocol", "smtp"),
+                ("server.listener.smtp.tls.implicit", "false"),
+                ("store.rocksdb.settings.path", "/data"),
+                ("store.rocksdb.settings.type", "rocksdb"),
+            ])
+        );
+    }
+
+    #[test]
+    fn errors_report_their_line() {
+        for (input, line) in [
+            ("a = \"unterminated", 1),
+            ("a = 1\nb = \nc = 2", 2),
+            ("a = 1\n\n[table\nb = 2", 3),
+            ("a = 1\na = 2", 2),
+            ("a = [1, 2\nb = 3", 2),
+        ] {
+            let err = parse_flat(input).unwrap_err();
+            assert_eq!(err.line, line, "{input}: {err}");
+            assert!(!err.message.is_empty());
+            assert!(err.to_string().starts_with(&format!("Line {line}: ")));
+        }
+    }
+}
@@ -0,0 +1,128 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use leptos::*;
+use leptos_router::use_navigate;
+
+use crate::{
+    components::messages::alert::{use_alerts, Alert},
+    core::{
+        http::{self, HttpRequest},
+        AccessToken,
+    },
+    pages::config::UpdateSettings,
+};
+
+const MAX_UNDO_ENTRIES: usize = 25;
+
+// Settings changes that restore the state prior to an operation.
+// Only operations that can be reverted by rewriting settings belong here.
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub description: String,
+    pub restore: Vec<UpdateSettings>,
+}
+
+#[derive(Clone, Copy)]
+pub struct UndoStack {
+    entries: RwSignal<Vec<UndoEntry>>,
+    pending: RwSignal<bool>,
+    auth: RwSignal<AccessToken>,
+    pub version: RwSignal<u32>,
+}
+
+pub fn init_undo_stack(auth: RwSignal<AccessToken>) {
+    let stack = UndoStack {
+        entries: create_rw_signal(Vec::new()),
+        pending: create_rw_signal(false),
+        auth,
+        version: create_rw_signal(0),
+    };
+    provide_context(stack);
+
+    create_effect(move |_| {
+        if !auth.with(|auth| auth.is_logged_in()) {
+            stack.clear();
+        }
+    });
+}
+
+pub fn use_undo_stack() -> UndoStack {
+    expect_context::<UndoStack>()
+}
+
+impl UndoStack {
+    pub fn push(&self, description: impl Into<String>, restore: Vec<UpdateSettings>) {
+        if restore.is_empty() {
+            return;
+        }
+
+        self.entries.update(|entries| {
+            entries.push(UndoEntry {
+                description: description.into(),
+                restore,
+            });
+            if entries.len() > MAX_UNDO_ENTRIES {
+                entries.remove(0);
+            }
+        });
+    }
+
+    pub fn clear(&self) {
+        self.entries.update(|entries| entries.clear());
+    }
+
+    pub fn last(&self) -> Option<String> {
+        self.entries
+            .with(|entries| entries.last().map(|entry| entry.description.clone()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.with(|entries| entries.is_empty())
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.get()
+    }
+
+    pub fn undo(&self) {
+        if self.pending.get_untracked() {
+            return;
+        }
+        let Some(entry) = self.entries.try_update(|entries| entries.pop()).flatten() else {
+            return;
+        };
+
+        let stack = *self;
+        let alert = use_alerts();
+        let navigate = use_navigate();
+        let auth = self.auth.get_untracked();
+        self.pending.set(true);
+
+        spawn_local(async move {
+            match HttpRequest::post("/api/settings")
+                .with_authorization(&auth)
+                .with_body(&entry.restore)
+                .unwrap()
+                .send::<serde_json::Value>()
+                .await
+            {
+                Ok(_) => {
+                    stack.version.update(|version| *version += 1);
+                    alert.set(Alert::success(format!("Undone: {}.", entry.description)));
+                }
+                Err(http::Error::Unauthorized) => {
+                    navigate("/login", Default::default());
+                }
+                Err(err) => {
+                    stack.entries.update(|entries| entries.push(entry));
+                    alert.set(Alert::from(err));
+                }
+            }
+            stack.pending.set(false);
+        });
+    }
+}
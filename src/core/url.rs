@@ -4,9 +4,14 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::BTreeMap};
 
 use ahash::AHashMap;
+use gloo_storage::{LocalStorage, SessionStorage, Storage};
+use leptos::*;
+use leptos_router::use_location;
+
+use crate::{DEFAULT_DOCS_URL, STATE_DOCS_URL_KEY, STATE_LIST_URLS_KEY};
 
 pub struct UrlBuilder {
     pub path: String,
@@ -81,3 +86,94 @@ impl UrlBuilder {
         }
     }
 }
+
+// Remembers the query string a list was last shown with (page, filter, sort)
+// so that forms opened from it can navigate back to the same view
+pub fn use_list_state() {
+    let location = use_location();
+    create_effect(move |_| {
+        let path = location.pathname.get();
+        let search = location.search.get();
+        let mut urls = SessionStorage::get::<BTreeMap<String, String>>(STATE_LIST_URLS_KEY)
+            .unwrap_or_default();
+        let search = search.trim_start_matches('?');
+        if search.is_empty() {
+            urls.remove(&path);
+        } else {
+            urls.insert(path, search.to_string());
+        }
+        let _ = SessionStorage::set(STATE_LIST_URLS_KEY, urls);
+    });
+}
+
+// Returns the list URL with its last known query string, or the plain
+// path when the list was not visited in this session (e.g. deep links)
+pub fn list_url(path: impl Into<String>) -> String {
+    let path = path.into();
+    match SessionStorage::get::<BTreeMap<String, String>>(STATE_LIST_URLS_KEY)
+        .ok()
+        .and_then(|mut urls| urls.remove(&path))
+    {
+        Some(search) => format!("{path}?{search}"),
+        None => path,
+    }
+}
+
+// Documentation base URL, read from local storage once at startup
+#[derive(Clone, Copy)]
+pub struct DocsUrl {
+    base_url: StoredValue<Option<String>>,
+}
+
+pub fn init_docs_url() {
+    provide_context(DocsUrl {
+        base_url: store_value(
+            LocalStorage::get::<String>(STATE_DOCS_URL_KEY)
+                .ok()
+                .filter(|url| !url.is_empty()),
+        ),
+    });
+}
+
+pub fn use_docs_url() -> DocsUrl {
+    expect_context::<DocsUrl>()
+}
+
+impl DocsUrl {
+    pub fn default_url() -> &'static str {
+        option_env!("DOCS_URL").unwrap_or(DEFAULT_DOCS_URL)
+    }
+
+    // Custom base URL, None when the default is used
+    pub fn custom_url(&self) -> Option<String> {
+        self.base_url.get_value()
+    }
+
+    // An empty URL restores the default
+    pub fn set(&self, url: &str) {
+        let url = url.trim();
+        if url.is_empty() {
+            LocalStorage::delete(STATE_DOCS_URL_KEY);
+            self.base_url.set_value(None);
+        } else {
+            let _ = LocalStorage::set(STATE_DOCS_URL_KEY, url);
+            self.base_url.set_value(Some(url.to_string()));
+        }
+    }
+}
+
+pub fn docs_url(path: &str) -> String {
+    if path.starts_with("https://") || path.starts_with("http://") {
+        return path.to_string();
+    }
+
+    let base_url = use_context::<DocsUrl>()
+        .and_then(|docs| docs.custom_url())
+        .unwrap_or_else(|| DocsUrl::default_url().to_string());
+
+    format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
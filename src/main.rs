@@ -10,9 +10,9 @@ use std::{sync::Arc, time::Duration};
 
 use components::{
     icon::{
-        IconAdjustmentsHorizontal, IconBeaker, IconChartBarSquare, IconClock, IconDocumentChartBar,
-        IconKey, IconLockClosed, IconQueueList, IconShieldCheck, IconSignal, IconSquare2x2,
-        IconUserGroup, IconWrench,
+        IconAdjustmentsHorizontal, IconBeaker, IconBell, IconChartBarSquare, IconClock,
+        IconComputerDesktop, IconDocumentChartBar, IconKey, IconLockClosed, IconQueueList,
+        IconShieldCheck, IconSignal, IconSquare2x2, IconUserGroup, IconWrench,
     },
     layout::MenuItem,
 };
@@ -25,12 +25,15 @@ use pages::{
     account::{
         app_password::{AppPasswordCreate, AppPasswords},
         mfa::ManageMfa,
+        preferences::Preferences,
     },
     config::edit::DEFAULT_SETTINGS_URL,
-    directory::{dns::DnsDisplay, edit::PrincipalEdit, list::PrincipalList},
+    directory::{
+        dns::DnsDisplay, edit::PrincipalEdit, import::PrincipalImport, list::PrincipalList,
+    },
     manage::{
         spam::{SpamTest, SpamTrain},
-        troubleshoot::{TroubleshootDelivery, TroubleshootDmarc},
+        troubleshoot::{TroubleshootDelivery, TroubleshootDmarc, TroubleshootSendTest},
     },
 };
 
@@ -52,16 +55,30 @@ use crate::{
         layout::{Layout, LayoutBuilder},
         messages::{alert::init_alerts, modal::init_modals},
     },
-    core::oauth::oauth_refresh_token,
+    core::{
+        alerting::init_alert_monitor, oauth::oauth_refresh_token, undo::init_undo_stack,
+        url::init_docs_url,
+    },
     pages::{
         account::{crypto::ManageCrypto, password::ChangePassword},
         authorize::Authorize,
-        config::{edit::SettingsEdit, list::SettingsList, search::SettingsSearch},
+        config::{
+            edit::SettingsEdit, explorer::SettingsExplorer, import::SettingsImport,
+            list::SettingsList, search::SettingsSearch, version::SchemaVersionGate,
+        },
         login::Login,
-        manage::{logs::Logs, maintenance::Maintenance},
+        manage::{
+            alerts::{AlertRuleEdit, AlertRuleList},
+            logs::{LiveLogs, Logs},
+            maintenance::Maintenance,
+            protocols::ProtocolList,
+            sessions::SessionList,
+            system::SystemInformation,
+        },
         notfound::NotFound,
         queue::{
-            messages::{list::QueueList, manage::QueueManage},
+            messages::{list::QueueList, manage::QueueManage, overview::QueueOverview},
+            quarantine::{list::QuarantineList, manage::QuarantineManage},
             reports::{display::ReportDisplay, list::ReportList},
         },
         reports::{display::IncomingReportDisplay, list::IncomingReportList},
@@ -74,6 +91,14 @@ pub mod pages;
 
 pub const STATE_STORAGE_KEY: &str = "webadmin_state";
 pub const STATE_LOGIN_NAME_KEY: &str = "webadmin_login_name";
+pub const STATE_DOCS_URL_KEY: &str = "webadmin_docs_url";
+pub const STATE_ALERT_RULES_KEY: &str = "webadmin_alert_rules";
+pub const STATE_LIST_URLS_KEY: &str = "webadmin_list_urls";
+pub const STATE_SHOW_ADVANCED_KEY: &str = "webadmin_show_advanced";
+pub const STATE_PAGE_SIZES_KEY: &str = "webadmin_page_sizes";
+pub const STATE_FORM_DRAFTS_KEY: &str = "webadmin_form_drafts";
+pub const STATE_FORM_HISTORY_KEY: &str = "webadmin_form_history";
+pub const DEFAULT_DOCS_URL: &str = "https://stalw.art/docs";
 
 fn main() {
     core::schema::print_schemas(&build_schemas());
@@ -97,9 +122,19 @@ pub fn App() -> impl IntoView {
     );
     provide_meta_context();
     provide_context(auth_token);
-    provide_context(build_schemas());
+    let schemas = build_schemas();
+    #[cfg(debug_assertions)]
+    if let Err(errors) = schemas.validate() {
+        for error in errors {
+            log::error!("Invalid schema reference: {error}");
+        }
+    }
+    provide_context(schemas);
     init_alerts();
     init_modals();
+    init_alert_monitor(auth_token);
+    init_undo_stack(auth_token);
+    init_docs_url();
 
     // Create a resource to refresh the OAuth token
     let _refresh_token_resource = create_resource(
@@ -238,6 +273,21 @@ pub fn App() -> impl IntoView {
                         }
                     />
 
+                    <ProtectedRoute
+                        path="/directory/accounts/import"
+                        view=PrincipalImport
+                        redirect_path="/login"
+                        condition=move || {
+                            permissions
+                                .get()
+                                .is_some_and(|p| {
+                                    p.has_access_all(
+                                        &[Permission::IndividualCreate, Permission::SettingsList],
+                                    )
+                                })
+                        }
+                    />
+
                     <ProtectedRoute
                         path="/dns/:id/view"
                         view=DnsDisplay
@@ -264,6 +314,17 @@ pub fn App() -> impl IntoView {
                         }
                     />
 
+                    <ProtectedRoute
+                        path="/queue/overview"
+                        view=QueueOverview
+                        redirect_path="/login"
+                        condition=move || {
+                            permissions
+                                .get()
+                                .is_some_and(|p| { p.has_access(Permission::MessageQueueList) })
+                        }
+                    />
+
                     <ProtectedRoute
                         path="/queue/message/:id"
                         view=QueueManage
@@ -275,6 +336,28 @@ pub fn App() -> impl IntoView {
                         }
                     />
 
+                    <ProtectedRoute
+                        path="/queue/quarantine"
+                        view=QuarantineList
+                        redirect_path="/login"
+                        condition=move || {
+                            permissions
+                                .get()
+                                .is_some_and(|p| { p.has_access(Permission::QuarantineList) })
+                        }
+                    />
+
+                    <ProtectedRoute
+                        path="/queue/quarantine/:id"
+                        view=QuarantineManage
+                        redirect_path="/login"
+                        condition=move || {
+                            permissions
+                                .get()
+                                .is_some_and(|p| { p.has_access(Permission::QuarantineList) })
+                        }
+                    />
+
                     <ProtectedRoute
                         path="/queue/reports"
                         view=ReportList
@@ -330,6 +413,28 @@ pub fn App() -> impl IntoView {
                         }
                     />
 
+                    <ProtectedRoute
+                        path="/logs/live"
+                        view=LiveLogs
+                        redirect_path="/login"
+                        condition=move || {
+                            permissions
+                                .get()
+                                .is_some_and(|p| { p.has_access(Permission::LogsView) })
+                        }
+                    />
+
+                    <ProtectedRoute
+                        path="/sessions"
+                        view=SessionList
+                        redirect_path="/login"
+                        condition=move || {
+                            permissions
+                                .get()
+                                .is_some_and(|p| { p.has_access(Permission::SessionList) })
+                        }
+                    />
+
                     <ProtectedRoute
                         path="/spam/upload"
                         view=SpamTrain
@@ -352,6 +457,41 @@ pub fn App() -> impl IntoView {
                         }
                     />
 
+                    <ProtectedRoute
+                        path="/alerts"
+                        view=AlertRuleList
+                        redirect_path="/login"
+                        condition=move || permissions.get().is_some_and(|p| p.has_admin_access())
+                    />
+                    <ProtectedRoute
+                        path="/alerts/edit/:id?"
+                        view=AlertRuleEdit
+                        redirect_path="/login"
+                        condition=move || permissions.get().is_some_and(|p| p.has_admin_access())
+                    />
+
+                    <ProtectedRoute
+                        path="/protocols"
+                        view=ProtocolList
+                        redirect_path="/login"
+                        condition=move || {
+                            permissions
+                                .get()
+                                .is_some_and(|p| { p.has_access(Permission::SettingsList) })
+                        }
+                    />
+
+                    <ProtectedRoute
+                        path="/system"
+                        view=SystemInformation
+                        redirect_path="/login"
+                        condition=move || {
+                            permissions
+                                .get()
+                                .is_some_and(|p| { p.has_access(Permission::SettingsList) })
+                        }
+                    />
+
                     <ProtectedRoute
                         path="/maintenance"
                         view=Maintenance
@@ -438,6 +578,17 @@ pub fn App() -> impl IntoView {
                         }
                     />
 
+                    <ProtectedRoute
+                        path="/troubleshoot/send"
+                        view=TroubleshootSendTest
+                        redirect_path="/login"
+                        condition=move || {
+                            permissions
+                                .get()
+                                .is_some_and(|p| { p.has_access(Permission::Troubleshoot) })
+                        }
+                    />
+
                 </ProtectedRoute>
                 <ProtectedRoute
                     path="/settings"
@@ -451,7 +602,14 @@ pub fn App() -> impl IntoView {
                 >
                     <ProtectedRoute
                         path="/:object"
-                        view=SettingsList
+                        view=|| {
+                            view! {
+                                <SchemaVersionGate>
+                                    <SettingsList/>
+                                </SchemaVersionGate>
+                            }
+                        }
+
                         redirect_path="/login"
                         condition=move || {
                             permissions
@@ -460,9 +618,34 @@ pub fn App() -> impl IntoView {
                         }
                     />
 
+                    <ProtectedRoute
+                        path="/:object/import"
+                        view=|| {
+                            view! {
+                                <SchemaVersionGate>
+                                    <SettingsImport/>
+                                </SchemaVersionGate>
+                            }
+                        }
+
+                        redirect_path="/login"
+                        condition=move || {
+                            permissions
+                                .get()
+                                .is_some_and(|p| { p.has_access(Permission::SettingsUpdate) })
+                        }
+                    />
+
                     <ProtectedRoute
                         path="/:object/:id?/edit"
-                        view=SettingsEdit
+                        view=|| {
+                            view! {
+                                <SchemaVersionGate>
+                                    <SettingsEdit/>
+                                </SchemaVersionGate>
+                            }
+                        }
+
                         redirect_path="/login"
                         condition=move || {
                             permissions
@@ -482,6 +665,17 @@ pub fn App() -> impl IntoView {
                         }
                     />
 
+                    <ProtectedRoute
+                        path="/explorer"
+                        view=SettingsExplorer
+                        redirect_path="/login"
+                        condition=move || {
+                            permissions
+                                .get()
+                                .is_some_and(|p| { p.has_access(Permission::SettingsList) })
+                        }
+                    />
+
                 </ProtectedRoute>
                 <ProtectedRoute
                     path="/account"
@@ -550,6 +744,13 @@ pub fn App() -> impl IntoView {
                         }
                     />
 
+                    <ProtectedRoute
+                        path="/preferences"
+                        view=Preferences
+                        redirect_path="/login"
+                        condition=move || permissions.get().is_some()
+                    />
+
                 </ProtectedRoute>
                 <Route path="/" view=Login/>
                 <Route path="/login" view=Login/>
@@ -624,14 +825,21 @@ impl LayoutBuilder {
                 ]))
                 .create("Queues")
                 .icon(view! { <IconQueueList/> })
+                .create("Overview")
+                .route("/queue/overview")
+                .insert(permissions.has_access(Permission::MessageQueueList))
                 .create("Messages")
                 .route("/queue/messages")
                 .insert(permissions.has_access(Permission::MessageQueueList))
+                .create("Quarantine")
+                .route("/queue/quarantine")
+                .insert(permissions.has_access(Permission::QuarantineList))
                 .create("Reports")
                 .route("/queue/reports")
                 .insert(permissions.has_access(Permission::OutgoingReportList))
                 .insert(permissions.has_access_any(&[
                     Permission::MessageQueueList,
+                    Permission::QuarantineList,
                     Permission::OutgoingReportList,
                 ]))
                 .create("Reports")
@@ -660,12 +868,20 @@ impl LayoutBuilder {
                 .create("Logs")
                 .route("/logs")
                 .insert(permissions.has_access(Permission::LogsView))
+                .create("Live logs")
+                .route("/logs/live")
+                .insert(permissions.has_access(Permission::LogsView))
+                .create("Sessions")
+                .route("/sessions")
+                .insert(permissions.has_access(Permission::SessionList))
                 .create("Live tracing")
                 .route("/tracing/live")
                 .insert(permissions.has_access(Permission::TracingLive))
-                .insert(
-                    permissions.has_access_any(&[Permission::LogsView, Permission::TracingLive]),
-                )
+                .insert(permissions.has_access_any(&[
+                    Permission::LogsView,
+                    Permission::SessionList,
+                    Permission::TracingLive,
+                ]))
                 .create("Spam filter")
                 .icon(view! { <IconShieldCheck/> })
                 .create("Upload samples")
@@ -683,11 +899,26 @@ impl LayoutBuilder {
                 .create("DMARC")
                 .route("/troubleshoot/dmarc")
                 .insert(true)
+                .create("Send test e-mail")
+                .route("/troubleshoot/send")
+                .insert(true)
                 .insert(permissions.has_access(Permission::Troubleshoot))
                 .create("Settings")
                 .icon(view! { <IconAdjustmentsHorizontal/> })
                 .raw_route(DEFAULT_SETTINGS_URL)
                 .insert(permissions.has_access(Permission::SettingsList))
+                .create("Alerts")
+                .icon(view! { <IconBell/> })
+                .route("/alerts")
+                .insert(true)
+                .create("Protocols")
+                .icon(view! { <IconSignal/> })
+                .route("/protocols")
+                .insert(permissions.has_access(Permission::SettingsList))
+                .create("System")
+                .icon(view! { <IconComputerDesktop/> })
+                .route("/system")
+                .insert(permissions.has_access(Permission::SettingsList))
                 .create("Maintenance")
                 .icon(view! { <IconWrench/> })
                 .route("/maintenance")
@@ -738,14 +969,21 @@ impl LayoutBuilder {
                 ]))
                 .create("Queues")
                 .icon(view! { <IconQueueList/> })
+                .create("Overview")
+                .route("/queue/overview")
+                .insert(permissions.has_access(Permission::MessageQueueList))
                 .create("Messages")
                 .route("/queue/messages")
                 .insert(permissions.has_access(Permission::MessageQueueList))
+                .create("Quarantine")
+                .route("/queue/quarantine")
+                .insert(permissions.has_access(Permission::QuarantineList))
                 .create("Reports")
                 .route("/queue/reports")
                 .insert(permissions.has_access(Permission::OutgoingReportList))
                 .insert(permissions.has_access_any(&[
                     Permission::MessageQueueList,
+                    Permission::QuarantineList,
                     Permission::OutgoingReportList,
                 ]))
                 .create("Reports")
@@ -765,9 +1003,17 @@ impl LayoutBuilder {
                 .create("Logs")
                 .route("/logs")
                 .insert(permissions.has_access(Permission::LogsView))
-                .insert(
-                    permissions.has_access_any(&[Permission::LogsView, Permission::TracingLive]),
-                )
+                .create("Live logs")
+                .route("/logs/live")
+                .insert(permissions.has_access(Permission::LogsView))
+                .create("Sessions")
+                .route("/sessions")
+                .insert(permissions.has_access(Permission::SessionList))
+                .insert(permissions.has_access_any(&[
+                    Permission::LogsView,
+                    Permission::SessionList,
+                    Permission::TracingLive,
+                ]))
                 .create("Spam filter")
                 .icon(view! { <IconShieldCheck/> })
                 .create("Upload samples")
@@ -785,11 +1031,26 @@ impl LayoutBuilder {
                 .create("DMARC")
                 .route("/troubleshoot/dmarc")
                 .insert(true)
+                .create("Send test e-mail")
+                .route("/troubleshoot/send")
+                .insert(true)
                 .insert(permissions.has_access(Permission::Troubleshoot))
                 .create("Settings")
                 .icon(view! { <IconAdjustmentsHorizontal/> })
                 .raw_route(DEFAULT_SETTINGS_URL)
                 .insert(permissions.has_access(Permission::SettingsList))
+                .create("Alerts")
+                .icon(view! { <IconBell/> })
+                .route("/alerts")
+                .insert(true)
+                .create("Protocols")
+                .icon(view! { <IconSignal/> })
+                .route("/protocols")
+                .insert(permissions.has_access(Permission::SettingsList))
+                .create("System")
+                .icon(view! { <IconComputerDesktop/> })
+                .route("/system")
+                .insert(permissions.has_access(Permission::SettingsList))
                 .create("Maintenance")
                 .icon(view! { <IconWrench/> })
                 .route("/maintenance")
@@ -825,6 +1086,10 @@ impl LayoutBuilder {
             .icon(view! { <IconSquare2x2/> })
             .route("/app-passwords")
             .insert(permissions.has_access(Permission::ManagePasswords))
+            .create("Preferences")
+            .icon(view! { <IconAdjustmentsHorizontal/> })
+            .route("/preferences")
+            .insert(true)
             .menu_items
     }
 }
@@ -833,6 +1098,8 @@ pub fn build_schemas() -> Arc<Schemas> {
     Schemas::builder()
         .build_login()
         .build_principals()
+        .build_principal_import()
+        .build_vacation()
         .build_store()
         .build_directory()
         .build_authentication()
@@ -858,6 +1125,8 @@ pub fn build_schemas() -> Arc<Schemas> {
         .build_app_passwords()
         .build_live_tracing()
         .build_troubleshoot()
+        .build_alert_rules()
+        .build_preferences()
         .build()
         .into()
 }
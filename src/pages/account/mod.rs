@@ -10,6 +10,7 @@ pub mod app_password;
 pub mod crypto;
 pub mod mfa;
 pub mod password;
+pub mod preferences;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type")]
@@ -0,0 +1,87 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use leptos::*;
+
+use crate::{
+    components::{
+        form::{
+            button::Button, input::InputText, Form, FormButtonBar, FormElement, FormItem,
+            FormSection,
+        },
+        messages::alert::{use_alerts, Alert},
+        Color,
+    },
+    core::{
+        schema::{Builder, Schemas, Transformer, Type, Validator},
+        url::{use_docs_url, DocsUrl},
+    },
+};
+
+#[component]
+pub fn Preferences() -> impl IntoView {
+    let docs = use_docs_url();
+    let alert = use_alerts();
+
+    let mut data = expect_context::<Arc<Schemas>>().build_form("preferences");
+    if let Some(url) = docs.custom_url() {
+        data.set("docs-url", url);
+    }
+    let data = data.into_signal();
+
+    view! {
+        <Form title="Preferences" subtitle="Settings stored in this browser.">
+            <FormSection>
+                <FormItem
+                    label="Documentation URL"
+                    tooltip="Base URL of the documentation linked from settings pages. Leave empty to use the default."
+                >
+                    <InputText element=FormElement::new("docs-url", data)/>
+                </FormItem>
+            </FormSection>
+
+            <FormButtonBar>
+                <Button
+                    text="Reset"
+                    color=Color::Gray
+                    on_click=Callback::new(move |_| {
+                        docs.set("");
+                        data.update(|data| data.set("docs-url", ""));
+                        alert.set(Alert::success("Preferences reset"));
+                    })
+                />
+
+                <Button
+                    text="Save changes"
+                    color=Color::Blue
+                    on_click=Callback::new(move |_| {
+                        data.update(|data| {
+                            if data.validate_form() {
+                                docs.set(&data.value::<String>("docs-url").unwrap_or_default());
+                                alert.set(Alert::success("Preferences saved"));
+                            }
+                        });
+                    })
+                />
+
+            </FormButtonBar>
+        </Form>
+    }
+}
+
+impl Builder<Schemas, ()> {
+    pub fn build_preferences(self) -> Self {
+        self.new_schema("preferences")
+            .new_field("docs-url")
+            .typ(Type::Input)
+            .input_check([Transformer::Trim], [Validator::IsUrl])
+            .placeholder(DocsUrl::default_url())
+            .build()
+            .build()
+    }
+}
@@ -0,0 +1,310 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use ahash::AHashSet;
+use leptos::*;
+use leptos_router::*;
+
+use crate::{
+    components::{
+        form::{
+            button::Button,
+            input::{InputDuration, InputSwitch, InputText},
+            select::Select,
+            FormElement, FormItem,
+        },
+        list::ItemSelection,
+        messages::alert::{use_alerts, Alert},
+        Color,
+    },
+    core::{
+        form::FormData,
+        http,
+        oauth::use_authorization,
+        schema::{Field, Schema, SelectType, Source, Type},
+        undo::use_undo_stack,
+    },
+    pages::{
+        config::{
+            list::{fetch_ids, fetch_prefix, post_batch, restore_field, BULK_BATCH_SIZE},
+            UpdateSettings,
+        },
+        maybe_plural,
+    },
+};
+
+const SELECT_CLASS: &str = "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600";
+
+// Value written to each record, None when the key was removed
+pub type BulkChanges = Vec<(String, Option<String>)>;
+
+#[component]
+pub fn BulkEdit(
+    schema: Arc<Schema>,
+    selection: ItemSelection,
+    filter: Option<String>,
+    #[prop(into)] on_close: Callback<()>,
+    #[prop(into)] on_applied: Callback<(&'static str, BulkChanges)>,
+) -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let undo = use_undo_stack();
+    let fields = bulk_fields(&schema, &auth.get_untracked().form_capabilities());
+    let field = create_rw_signal(fields.first().cloned());
+    let schema_ = schema.clone();
+    let data = FormData::from(schema.clone()).into_signal();
+    let total = match &selection {
+        ItemSelection::Some(ids) => Some(ids.len()),
+        _ => None,
+    };
+    let title = match total {
+        Some(total) => format!(
+            "Edit {}",
+            maybe_plural(total, schema.name_singular, schema.name_plural)
+        ),
+        None => format!("Edit all {}", schema.name_plural),
+    };
+
+    let apply_action = create_action(move |field: &Arc<Field>| {
+        let field = field.clone();
+        let value = data
+            .with_untracked(|data| data.values.get(field.id).cloned())
+            .or_else(|| matches!(field.typ_, Type::Boolean).then(|| "false".into()));
+        let selection = selection.clone();
+        let filter = filter.clone();
+        let auth = auth.get();
+        let schema = schema_.clone();
+
+        async move {
+            let ids = match selection {
+                ItemSelection::Some(ids) => Ok(ids),
+                _ => fetch_ids(&auth, &schema, filter).await,
+            };
+            let mut ids = match ids {
+                Ok(ids) => ids.into_iter().collect::<Vec<_>>(),
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                    return;
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                    return;
+                }
+            };
+            ids.sort();
+
+            // Each record is validated on its own, as checks and display
+            // conditions may depend on its other fields
+            let mut updates = Vec::with_capacity(ids.len());
+            let mut changes = Vec::with_capacity(ids.len());
+            let mut previous = Vec::with_capacity(ids.len());
+            let mut failures = Vec::new();
+            for id in ids {
+                let mut settings = match fetch_prefix(&auth, &schema.record_key(&id)).await {
+                    Ok(settings) if !settings.is_empty() => settings,
+                    Ok(_) => {
+                        failures.push(format!("{id}: the record no longer exists"));
+                        continue;
+                    }
+                    Err(http::Error::Unauthorized) => {
+                        use_navigate()("/login", Default::default());
+                        return;
+                    }
+                    Err(_) => {
+                        failures.push(format!("{id}: the record could not be fetched"));
+                        continue;
+                    }
+                };
+                let prior = settings.get(field.id).cloned();
+                settings.insert("_id".to_string(), id.clone());
+                let mut record = FormData::from_settings(schema.clone(), Some(settings))
+                    .with_capabilities(auth.form_capabilities());
+                match &value {
+                    Some(value) => record.update(field.id, value.clone()),
+                    None => {
+                        record.values.remove(field.id);
+                    }
+                }
+                if !field.display(&record) {
+                    failures.push(format!(
+                        "{id}: {} does not apply to this record",
+                        field.label_form
+                    ));
+                    continue;
+                }
+                record.validate_form();
+                if let Some(error) = record.errors.get(field.id) {
+                    failures.push(format!("{id}: {}", error.error));
+                    continue;
+                }
+
+                let value = record
+                    .value_as_str(field.id)
+                    .filter(|value| !value.is_empty())
+                    .map(|value| value.to_string());
+                let update = match &value {
+                    Some(value) => UpdateSettings::Insert {
+                        prefix: schema.record_key(&id).into(),
+                        values: vec![(field.id.to_string(), value.clone())],
+                        assert_empty: false,
+                    },
+                    None => UpdateSettings::Delete {
+                        keys: vec![schema.field_key(&id, field.id)],
+                    },
+                };
+                updates.push((id.clone(), update));
+                previous.push((id.clone(), prior));
+                changes.push((id, value));
+            }
+
+            // Records rejected by the server are reported with the others
+            // that could not be changed
+            for batch in updates.chunks(BULK_BATCH_SIZE) {
+                match post_batch(&auth, batch).await {
+                    Ok(failed) => {
+                        for (id, reason) in failed {
+                            changes.retain(|(changed, _)| *changed != id);
+                            previous.retain(|(changed, _)| *changed != id);
+                            failures.push(format!("{id}: {reason}"));
+                        }
+                    }
+                    Err(_) => {
+                        use_navigate()("/login", Default::default());
+                        return;
+                    }
+                }
+            }
+
+            let updated = format!(
+                "Updated {} in {}",
+                field.label_form,
+                maybe_plural(changes.len(), schema.name_singular, schema.name_plural)
+            );
+            if !changes.is_empty() {
+                undo.push(updated.clone(), restore_field(&schema, field.id, previous));
+                on_applied.call((field.id, changes));
+            }
+            if failures.is_empty() {
+                alert.set(Alert::success(format!("{updated}.")));
+                on_close.call(());
+            } else {
+                alert.set(
+                    Alert::warning(format!(
+                        "{updated}, {} could not be changed.",
+                        maybe_plural(failures.len(), "record", "records")
+                    ))
+                    .with_details_list(failures),
+                );
+            }
+        }
+    });
+
+    let options = fields
+        .iter()
+        .map(|item| {
+            let id = item.id;
+            view! {
+                <option
+                    value=id
+                    selected=move || field.with(|field| field.as_ref().is_some_and(|f| f.id == id))
+                >
+                    {item.label_form}
+                </option>
+            }
+        })
+        .collect_view();
+    let input = move || {
+        field.get().map(|field| {
+            let element = FormElement::new(field.id, data);
+            match &field.typ_ {
+                Type::Boolean => view! { <InputSwitch element/> }.into_view(),
+                Type::Duration => view! { <InputDuration element/> }.into_view(),
+                Type::Select { .. } => view! { <Select element/> }.into_view(),
+                _ => view! { <InputText element/> }.into_view(),
+            }
+        })
+    };
+
+    view! {
+        <div class="mb-6 p-4 sm:p-7 bg-white border border-gray-200 rounded-xl shadow-sm dark:bg-slate-900 dark:border-gray-700">
+            <h3 class="text-lg font-semibold text-gray-800 dark:text-gray-200">{title}</h3>
+            <p class="text-sm text-gray-600 dark:text-gray-400">
+                "The value is checked against each record before it is saved."
+            </p>
+            <div class="mt-6 grid sm:grid-cols-12 gap-2 sm:gap-6">
+                <FormItem label="Field">
+                    <select
+                        class=SELECT_CLASS
+                        disabled=fields.is_empty()
+                        on:change=move |ev| {
+                            let id = event_target_value(&ev);
+                            field.set(schema.fields.get(id.as_str()).cloned());
+                            data.set(FormData::from(schema.clone()));
+                        }
+                    >
+
+                        {options}
+                    </select>
+                </FormItem>
+                <FormItem label="Value">{input}</FormItem>
+            </div>
+            <div class="mt-6 flex justify-end gap-x-2">
+                <Button
+                    text="Cancel"
+                    color=Color::Gray
+                    on_click=move |_| {
+                        on_close.call(());
+                    }
+                />
+
+                <Button
+                    text="Apply"
+                    color=Color::Blue
+                    disabled=Signal::derive(move || field.with(Option::is_none))
+                    loading=apply_action.pending()
+                    on_click=move |_| {
+                        if let Some(field) = field.get() {
+                            apply_action.dispatch(field);
+                        }
+                    }
+                />
+
+            </div>
+        </div>
+    }
+}
+
+// Only single value fields that do not depend on external sources can be
+// changed in bulk
+fn bulk_fields(schema: &Schema, capabilities: &AHashSet<String>) -> Vec<Arc<Field>> {
+    let mut fields = schema
+        .fields
+        .values()
+        .filter(|field| {
+            !field.id.starts_with('_')
+                && !field.is_readonly(true)
+                && field.typ_if.if_thens.is_empty()
+                && schema
+                    .required_capability(field)
+                    .map_or(true, |capability| capabilities.contains(capability))
+                && matches!(
+                    field.typ_,
+                    Type::Input
+                        | Type::Boolean
+                        | Type::Duration
+                        | Type::Select {
+                            typ: SelectType::Single,
+                            source: Source::Static(_) | Source::StaticId(_),
+                        }
+                )
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    fields.sort_by_key(|field| field.label_form);
+    fields
+}
@@ -0,0 +1,126 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use leptos::*;
+
+use crate::{
+    components::{badge::Badge, form::button::Button, Color},
+    core::{form::FormData, pem::parse_certificates, schema::Field},
+    pages::{maybe_plural, FormatDateTime},
+};
+
+const EXPIRY_WARNING_DAYS: i64 = 30;
+
+#[component]
+pub fn CertificateDetails(field: Arc<Field>, data: RwSignal<FormData>) -> impl IntoView {
+    let field_id = field.id;
+    let names_field = field.certificate_names;
+    let pem =
+        create_memo(move |_| data.with(|data| data.get(field_id).unwrap_or_default().to_string()));
+    // Only the leaf certificate is described, the rest of the chain is
+    // checked by the field validator
+    let leaf = create_memo(move |_| {
+        pem.with(|pem| {
+            (!pem.starts_with("%{"))
+                .then(|| parse_certificates(pem).ok())
+                .flatten()
+                .and_then(|certificates| certificates.into_iter().next())
+        })
+    });
+    let names = create_memo(move |_| {
+        leaf.with(|leaf| {
+            leaf.as_ref()
+                .map(|leaf| leaf.subjects.clone())
+                .unwrap_or_default()
+        })
+    });
+    let current_names = create_memo(move |_| {
+        names_field
+            .map(|names_field| {
+                data.with(|data| {
+                    data.array_value(names_field)
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                })
+            })
+            .unwrap_or_default()
+    });
+
+    // Names are filled in when a certificate is pasted, existing ones are
+    // only replaced on request
+    create_effect(move |prev: Option<()>| {
+        let names = names.get();
+        if let (Some(_), Some(names_field)) = (prev, names_field) {
+            if !names.is_empty() && data.with_untracked(|data| data.value_is_empty(names_field)) {
+                data.update(|data| data.array_set(names_field, names));
+            }
+        }
+    });
+    let can_replace = create_memo(move |_| {
+        names_field.is_some()
+            && names.with(|names| {
+                current_names.with(|current| {
+                    let mut current = current.clone();
+                    let mut names = names.clone();
+                    current.sort();
+                    names.sort();
+                    !names.is_empty() && !current.is_empty() && current != names
+                })
+            })
+    });
+
+    move || {
+        leaf.get().map(|leaf| {
+            let days_left = leaf.days_left();
+            let badge = if days_left < 0 {
+                Some(view! { <Badge color=Color::Red>"Expired"</Badge> }.into_view())
+            } else if days_left < EXPIRY_WARNING_DAYS {
+                Some(
+                    view! {
+                        <Badge color=Color::Yellow>
+                            {format!(
+                                "Expires in {}",
+                                maybe_plural(days_left as usize, "day", "days"),
+                            )}
+                        </Badge>
+                    }
+                    .into_view(),
+                )
+            } else {
+                None
+            };
+
+            view! {
+                <div class="mt-2 flex flex-wrap items-center gap-2 text-sm text-gray-600 dark:text-gray-400">
+                    <span>
+                        {format!(
+                            "Issued by {}, valid until {}",
+                            leaf.issuer,
+                            leaf.not_after.format_date(),
+                        )}
+                    </span>
+                    {badge}
+                    <Show when=move || can_replace.get()>
+                        <Button
+                            text="Replace subject names"
+                            color=Color::Gray
+                            on_click=move |_| {
+                                if let Some(names_field) = names_field {
+                                    data.update(|data| {
+                                        data.array_set(names_field, names.get_untracked())
+                                    });
+                                }
+                            }
+                        />
+
+                    </Show>
+                </div>
+            }
+        })
+    }
+}
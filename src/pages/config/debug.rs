@@ -0,0 +1,216 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use leptos::*;
+
+use crate::core::{
+    form::FormData,
+    schema::{Condition, Eval, Field, Value},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvalTrace {
+    pub field: &'static str,
+    pub condition: Condition,
+    pub values: Vec<&'static str>,
+    pub current: Option<String>,
+    pub passed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HiddenField {
+    pub field: &'static str,
+    pub section: Option<&'static str>,
+    pub reasons: Vec<EvalTrace>,
+}
+
+impl Eval {
+    pub fn trace(&self, settings: &FormData) -> EvalTrace {
+        EvalTrace {
+            field: self.field.id,
+            condition: self.condition,
+            values: self.values.clone(),
+            current: settings.get(self.field.id).map(|v| v.to_string()),
+            passed: self.eval(settings),
+        }
+    }
+}
+
+impl<T> Value<T> {
+    pub fn trace(&self, settings: &FormData) -> Vec<EvalTrace> {
+        self.if_thens
+            .iter()
+            .map(|if_then| if_then.eval.trace(settings))
+            .collect()
+    }
+}
+
+impl Field {
+    pub fn display_trace(&self, settings: &FormData) -> Vec<EvalTrace> {
+        self.display
+            .iter()
+            .map(|eval| eval.trace(settings))
+            .collect()
+    }
+}
+
+impl FormData {
+    pub fn settings_key(&self, field: &Field) -> String {
+        self.schema
+            .field_key(self.value_as_str("_id").unwrap_or("<id>"), field.id)
+    }
+
+    pub fn hidden_fields(&self) -> Vec<HiddenField> {
+        let mut hidden = Vec::new();
+
+        for section in &self.schema.form.sections {
+            let section_traces = section
+                .display
+                .iter()
+                .map(|eval| eval.trace(self))
+                .collect::<Vec<_>>();
+            let section_hidden = !section.display(self);
+
+            for field in &section.fields {
+                let reasons = if section_hidden {
+                    section_traces.clone()
+                } else if !field.display(self) {
+                    field.display_trace(self)
+                } else {
+                    continue;
+                };
+
+                hidden.push(HiddenField {
+                    field: field.id,
+                    section: section.title,
+                    reasons,
+                });
+            }
+        }
+
+        hidden
+    }
+}
+
+impl EvalTrace {
+    pub fn describe(&self) -> String {
+        let op = match self.condition {
+            Condition::MatchAny => "in",
+            Condition::MatchNone => "not in",
+            Condition::StartsWithAny => "starts with any of",
+            Condition::ContainsAny => "contains any of",
+            Condition::IsEmpty => "is empty",
+            Condition::IsNotEmpty => "is not empty",
+        };
+        if !self.condition.uses_values() {
+            return format!(
+                "{} {op} (current: {})",
+                self.field,
+                self.current.as_deref().unwrap_or("<empty>")
+            );
+        }
+        format!(
+            "{} {op} [{}] (current: {})",
+            self.field,
+            self.values.join(", "),
+            self.current.as_deref().unwrap_or("<empty>")
+        )
+    }
+}
+
+#[component]
+pub fn FieldDebug(field: Arc<Field>, data: RwSignal<FormData>) -> impl IntoView {
+    let field = store_value(field);
+
+    view! {
+        <div class="mt-2 p-2 rounded-lg border border-dashed border-purple-300 bg-purple-50 text-xs font-mono text-purple-900 dark:bg-purple-900/20 dark:border-purple-800 dark:text-purple-200">
+            {move || {
+                let field = field.get_value();
+                data.with(|data| {
+                    let key = data.settings_key(&field);
+                    let display = field.display(data);
+                    let display_traces = field.display_trace(data);
+                    let default_traces = field.default.trace(data);
+                    view! {
+                        <div>{format!("key: {key}")}</div>
+                        <div>
+                            {format!(
+                                "display: {display}{}",
+                                if display_traces.is_empty() { " (unconditional)" } else { "" },
+                            )}
+
+                        </div>
+                        {trace_list("display_if", display_traces)}
+                        {trace_list("default_if", default_traces)}
+                    }
+                })
+            }}
+
+        </div>
+    }
+}
+
+#[component]
+pub fn FormDebugPanel(data: RwSignal<FormData>) -> impl IntoView {
+    view! {
+        <div class="mt-5 p-4 rounded-xl border border-dashed border-purple-300 bg-purple-50 text-xs font-mono text-purple-900 dark:bg-purple-900/20 dark:border-purple-800 dark:text-purple-200">
+            <h3 class="mb-2 text-sm font-semibold">"Hidden fields"</h3>
+            {move || {
+                let hidden = data.with(|data| data.hidden_fields());
+                if hidden.is_empty() {
+                    view! { <p>"All fields are currently displayed."</p> }.into_view()
+                } else {
+                    hidden
+                        .into_iter()
+                        .map(|hidden| {
+                            let title = match hidden.section {
+                                Some(section) => format!("{} (section \"{section}\")", hidden.field),
+                                None => hidden.field.to_string(),
+                            };
+                            view! {
+                                <div class="mb-2">
+                                    <div class="font-semibold">{title}</div>
+                                    {trace_list("conditions", hidden.reasons)}
+                                </div>
+                            }
+                        })
+                        .collect_view()
+                }
+            }}
+
+        </div>
+    }
+}
+
+fn trace_list(label: &'static str, traces: Vec<EvalTrace>) -> Option<View> {
+    if traces.is_empty() {
+        return None;
+    }
+
+    let items = traces
+        .into_iter()
+        .map(|trace| {
+            let (mark, class) = if trace.passed {
+                ("✓", "text-green-700 dark:text-green-400")
+            } else {
+                ("✗", "text-red-700 dark:text-red-400")
+            };
+            view! {
+                <li class=class>{format!("{mark} {}", trace.describe())}</li>
+            }
+        })
+        .collect_view();
+
+    Some(
+        view! {
+            <div>{format!("{label}:")}</div>
+            <ul class="ps-4">{items}</ul>
+        }
+        .into_view(),
+    )
+}
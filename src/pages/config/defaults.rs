@@ -0,0 +1,67 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use leptos::*;
+
+use crate::{
+    components::icon::IconArrowUTurnLeft,
+    core::{
+        form::{FormData, FormValue},
+        schema::Field,
+    },
+};
+
+// Shown while the field overrides its default, the default follows the
+// conditions of the other fields
+#[component]
+pub fn DefaultMarker(field: Arc<Field>, data: RwSignal<FormData>) -> impl IntoView {
+    let field_id = field.id;
+    let default = create_memo(move |_| {
+        data.with(|data| {
+            data.differs_from_default(&field)
+                .then(|| field.default(data).map(describe_default))
+                .flatten()
+        })
+    });
+
+    move || {
+        default.get().map(|default| {
+            view! {
+                <div class="mt-2 flex flex-wrap items-center gap-x-2 text-xs text-gray-500 dark:text-gray-400">
+                    <span>{format!("Modified from default ({default})")}</span>
+                    <button
+                        type="button"
+                        class="inline-flex items-center gap-x-1 text-blue-600 decoration-2 hover:underline font-medium dark:text-blue-500"
+                        on:click=move |_| {
+                            data.update(|data| data.reset_field(field_id));
+                        }
+                    >
+
+                        <IconArrowUTurnLeft attr:class="flex-shrink-0 size-3"/>
+                        "Reset"
+                    </button>
+                </div>
+            }
+        })
+    }
+}
+
+fn describe_default(default: FormValue) -> String {
+    match default {
+        FormValue::Value(value) if value.is_empty() => "empty".to_string(),
+        FormValue::Value(value) => value,
+        FormValue::Array(values) if values.is_empty() => "none".to_string(),
+        FormValue::Array(values) => values.join(", "),
+        FormValue::Expression(expr) => expr.else_,
+        FormValue::Map(entries) => entries
+            .into_iter()
+            .map(|(key, value)| format!("{key} = {value}"))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
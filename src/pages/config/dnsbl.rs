@@ -0,0 +1,210 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::net::IpAddr;
+
+use leptos::*;
+use serde::Deserialize;
+
+use crate::{
+    components::{form::button::Button, Color},
+    core::{
+        form::{parse_dnsbl_zone, FormData},
+        http::HttpRequest,
+    },
+};
+
+// Lookups are sent from the browser, the server resolver is not involved
+const DOH_URL: &str = "https://cloudflare-dns.com/dns-query";
+const REJECT_SCORE: &str = "session.connect.dnsbl.reject-score";
+const NXDOMAIN: u32 = 3;
+
+#[derive(Debug, Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DohAnswer {
+    data: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ZoneResult {
+    zone: String,
+    weight: f64,
+    listed: Result<Vec<String>, String>,
+}
+
+#[component]
+pub fn DnsblTest(field_id: &'static str, data: RwSignal<FormData>) -> impl IntoView {
+    let address = create_rw_signal(String::new());
+    let error = create_rw_signal(None::<&'static str>);
+    let has_zones = create_memo(move |_| {
+        data.with(|data| {
+            data.array_value(field_id)
+                .any(|zone| parse_dnsbl_zone(zone).is_some())
+        })
+    });
+
+    let test = create_action(move |ip: &IpAddr| {
+        let ip = *ip;
+        let zones = data.with_untracked(|data| {
+            data.array_value(field_id)
+                .filter_map(parse_dnsbl_zone)
+                .map(|(zone, weight)| (zone.to_string(), weight))
+                .collect::<Vec<_>>()
+        });
+
+        async move {
+            let mut results = Vec::with_capacity(zones.len());
+            for (zone, weight) in zones {
+                let listed = lookup(&format!("{}.{zone}", reverse_ip(ip))).await;
+                results.push(ZoneResult {
+                    zone,
+                    weight,
+                    listed,
+                });
+            }
+            results
+        }
+    });
+    let results = test.value();
+
+    view! {
+        <div class="mt-3 p-3 border border-gray-200 rounded-lg dark:border-gray-700">
+            <p class="text-sm font-semibold text-gray-800 dark:text-gray-200">"Test IP"</p>
+            <div class="mt-2 flex flex-wrap items-center gap-2">
+                <input
+                    type="text"
+                    class="py-2 px-3 block w-64 border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                    placeholder="192.0.2.1 or 2001:db8::1"
+                    prop:value=move || address.get()
+                    on:input=move |ev| {
+                        address.set(event_target_value(&ev));
+                        error.set(None);
+                    }
+                />
+
+                <Button
+                    text="Look up"
+                    color=Color::Gray
+                    disabled=Signal::derive(move || !has_zones.get())
+                    loading=test.pending()
+                    on_click=move |_| {
+                        match address.get_untracked().trim().parse::<IpAddr>() {
+                            Ok(ip) => test.dispatch(ip),
+                            Err(_) => error.set(Some("Enter a valid IPv4 or IPv6 address.")),
+                        }
+                    }
+                />
+
+            </div>
+            {move || error.get().map(|error| view! { <p class="mt-2 text-xs text-red-600">{error}</p> })}
+            {move || {
+                results
+                    .get()
+                    .map(|results| {
+                        let score = results
+                            .iter()
+                            .filter(|result| result.listed.as_ref().is_ok_and(|codes| !codes.is_empty()))
+                            .map(|result| result.weight)
+                            .sum::<f64>();
+                        let reject_score = data
+                            .with_untracked(|data| data.value::<String>(REJECT_SCORE))
+                            .and_then(|score| score.parse::<f64>().ok());
+                        let summary = match reject_score {
+                            Some(reject_score) if score >= reject_score => {
+                                format!("Score {score}, the connection would be rejected.")
+                            }
+                            _ => format!("Score {score}, the connection would be accepted."),
+                        };
+                        view! {
+                            <ul class="mt-3 space-y-1 text-sm">
+                                {results
+                                    .into_iter()
+                                    .map(|result| {
+                                        let (class, status) = match &result.listed {
+                                            Ok(codes) if !codes.is_empty() => {
+                                                (
+                                                    "text-red-600 dark:text-red-500",
+                                                    format!("listed ({}), +{}", codes.join(", "), result.weight),
+                                                )
+                                            }
+                                            Ok(_) => {
+                                                (
+                                                    "text-green-600 dark:text-green-500",
+                                                    "not listed".to_string(),
+                                                )
+                                            }
+                                            Err(err) => {
+                                                ("text-yellow-600 dark:text-yellow-500", err.clone())
+                                            }
+                                        };
+                                        view! {
+                                            <li>
+                                                <span class="font-mono text-gray-800 dark:text-gray-200">
+                                                    {result.zone}
+                                                </span>
+                                                <span class=format!("ms-2 {class}")>{status}</span>
+                                            </li>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </ul>
+                            <p class="mt-2 text-sm font-semibold text-gray-800 dark:text-gray-200">
+                                {summary}
+                            </p>
+                        }
+                    })
+            }}
+
+        </div>
+    }
+}
+
+async fn lookup(name: &str) -> Result<Vec<String>, String> {
+    let response = HttpRequest::get(DOH_URL)
+        .with_parameter("name", name)
+        .with_parameter("type", "A")
+        .with_header("Accept", "application/dns-json")
+        .send_raw()
+        .await
+        .map_err(|_| "lookup failed".to_string())?;
+
+    match serde_json::from_slice::<DohResponse>(&response) {
+        Ok(response) if response.status == 0 => Ok(response
+            .answer
+            .into_iter()
+            .map(|answer| answer.data)
+            .collect()),
+        Ok(response) if response.status == NXDOMAIN => Ok(vec![]),
+        Ok(response) => Err(format!("lookup failed (DNS status {})", response.status)),
+        Err(_) => Err("invalid DNS response".to_string()),
+    }
+}
+
+// Builds the reversed query label, e.g. 2.0.0.127 for IPv4 or
+// one nibble per label for IPv6
+fn reverse_ip(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, d] = ip.octets();
+            format!("{d}.{c}.{b}.{a}")
+        }
+        IpAddr::V6(ip) => ip
+            .octets()
+            .iter()
+            .rev()
+            .flat_map(|byte| [byte & 0x0f, byte >> 4])
+            .map(|nibble| format!("{nibble:x}"))
+            .collect::<Vec<_>>()
+            .join("."),
+    }
+}
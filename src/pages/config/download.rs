@@ -0,0 +1,68 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use leptos::*;
+
+use crate::{
+    components::{
+        form::button::Button,
+        messages::alert::{use_alerts, Alert},
+        Color,
+    },
+    core::{form::FormData, schema::Field},
+    pages::manage::support::download,
+};
+
+// Saves or copies the value as currently shown in the form, including
+// unsaved edits
+#[component]
+pub fn FieldDownload(field: Arc<Field>, data: RwSignal<FormData>) -> impl IntoView {
+    let alert = use_alerts();
+    let field_id = field.id;
+    let value =
+        create_memo(move |_| data.with(|data| data.get(field_id).unwrap_or_default().to_string()));
+    let filename =
+        create_memo(move |_| data.with(|data| field.download_name(data).unwrap_or_default()));
+
+    view! {
+        <Show when=move || value.with(|value| !value.trim().is_empty())>
+            <div class="mt-2 flex flex-wrap items-center gap-2">
+                <Button
+                    text="Download"
+                    color=Color::Gray
+                    on_click=move |_| {
+                        let filename = filename.get_untracked();
+                        if download(&filename, content_type(&filename), &value.get_untracked())
+                            .is_err()
+                        {
+                            alert.set(Alert::error(format!("Failed to download {filename}")));
+                        }
+                    }
+                />
+
+                <Button
+                    text="Copy"
+                    color=Color::Gray
+                    on_click=move |_| {
+                        let _ = window().navigator().clipboard().write_text(&value.get_untracked());
+                        alert.set(Alert::success("Copied to clipboard"));
+                    }
+                />
+
+            </div>
+        </Show>
+    }
+}
+
+fn content_type(filename: &str) -> &'static str {
+    match filename.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("pem" | "crt" | "key") => "application/x-pem-file",
+        Some("json") => "application/json",
+        _ => "text/plain",
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{collections::BTreeMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use gloo_storage::{LocalStorage, Storage};
+use leptos::{leptos_dom::helpers::TimeoutHandle, *};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{form::button::Button, Color},
+    core::form::{FormData, FormValue},
+    pages::FormatDateTime,
+    STATE_FORM_DRAFTS_KEY,
+};
+
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(2);
+
+// Unsaved values of a form, kept until the form is saved or the draft
+// is discarded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Draft {
+    pub schema: String,
+    pub id: Option<String>,
+    pub saved_at: DateTime<Utc>,
+    pub values: BTreeMap<String, FormValue>,
+}
+
+#[component]
+pub fn DraftRestore(data: RwSignal<FormData>) -> impl IntoView {
+    let key = create_memo(move |_| data.with(draft_key));
+    let pending = create_rw_signal(None::<Draft>);
+    let timer = store_value(None::<TimeoutHandle>);
+
+    // Drafts matching the loaded values are not offered
+    create_effect(move |_| {
+        pending.set(key.get().and_then(|key| {
+            load_draft(&key)
+                .filter(|draft| data.with_untracked(|data| draft.values != data.draft_values()))
+        }));
+    });
+
+    // Changes are written once the form has been left alone for a moment
+    create_effect(move |_| {
+        if let Some(handle) = timer.get_value() {
+            handle.clear();
+        }
+        if !data.with(FormData::is_dirty) {
+            return;
+        }
+        timer.set_value(
+            set_timeout_with_handle(
+                move || {
+                    if let Some((key, draft)) = data
+                        .try_with_untracked(|data| {
+                            data.is_dirty()
+                                .then(|| Some((draft_key(data)?, Draft::new(data))))
+                                .flatten()
+                        })
+                        .flatten()
+                    {
+                        save_draft(key, draft);
+                    }
+                },
+                AUTOSAVE_DELAY,
+            )
+            .ok(),
+        );
+    });
+    on_cleanup(move || {
+        if let Some(handle) = timer.get_value() {
+            handle.clear();
+        }
+    });
+
+    view! {
+        {move || {
+            pending
+                .get()
+                .map(|draft| {
+                    let saved_at = draft.saved_at.format_date_time();
+                    let draft = store_value(draft);
+                    view! {
+                        <div class="mb-5 flex flex-wrap items-center justify-between gap-2 bg-blue-50 border border-blue-200 text-sm text-blue-800 rounded-lg p-4 dark:bg-blue-800/10 dark:border-blue-900 dark:text-blue-500">
+                            <span>{format!("Restore draft from {saved_at}?")}</span>
+                            <div class="flex gap-x-2">
+                                <Button
+                                    text="Discard"
+                                    color=Color::Gray
+                                    on_click=move |_| {
+                                        data.with_untracked(delete_draft);
+                                        pending.set(None);
+                                    }
+                                />
+
+                                <Button
+                                    text="Restore"
+                                    color=Color::Blue
+                                    on_click=move |_| {
+                                        let values = draft.with_value(|draft| draft.values.clone());
+                                        data.update(|data| data.restore_draft(values));
+                                        pending.set(None);
+                                    }
+                                />
+
+                            </div>
+                        </div>
+                    }
+                })
+        }}
+    }
+}
+
+// Records are identified by their id, new records share one draft per
+// schema
+fn draft_key(data: &FormData) -> Option<String> {
+    let id = data
+        .is_update
+        .then(|| data.value_as_str("_id"))
+        .flatten()
+        .unwrap_or_default();
+    (!data.schema.id.is_empty()).then(|| format!("{}/{id}", data.schema.id))
+}
+
+fn load_draft(key: &str) -> Option<Draft> {
+    LocalStorage::get::<BTreeMap<String, Draft>>(STATE_FORM_DRAFTS_KEY)
+        .ok()?
+        .remove(key)
+}
+
+fn save_draft(key: String, draft: Draft) {
+    let mut drafts =
+        LocalStorage::get::<BTreeMap<String, Draft>>(STATE_FORM_DRAFTS_KEY).unwrap_or_default();
+    drafts.insert(key, draft);
+    let _ = LocalStorage::set(STATE_FORM_DRAFTS_KEY, drafts);
+}
+
+pub fn delete_draft(data: &FormData) {
+    if let Some(key) = draft_key(data) {
+        let mut drafts =
+            LocalStorage::get::<BTreeMap<String, Draft>>(STATE_FORM_DRAFTS_KEY).unwrap_or_default();
+        if drafts.remove(&key).is_some() {
+            let _ = LocalStorage::set(STATE_FORM_DRAFTS_KEY, drafts);
+        }
+    }
+}
+
+impl Draft {
+    fn new(data: &FormData) -> Self {
+        Draft {
+            schema: data.schema.id.to_string(),
+            id: data
+                .is_update
+                .then(|| data.value_as_str("_id").map(str::to_string))
+                .flatten(),
+            saved_at: Utc::now(),
+            values: data.draft_values(),
+        }
+    }
+}
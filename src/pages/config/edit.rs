@@ -5,25 +5,31 @@
  */
 
 use ahash::AHashMap;
+use gloo_storage::{LocalStorage, Storage};
 use leptos::*;
-use leptos_router::{use_navigate, use_params_map};
+use leptos_router::{use_navigate, use_params_map, use_query_map};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::{
     components::{
+        badge::Badge,
         form::{
             button::Button,
             expression::InputExpression,
+            focus_field,
             input::{
                 InputDuration, InputPassword, InputRate, InputSize, InputSwitch, InputText,
                 TextArea,
             },
-            select::{CheckboxGroup, Select, SelectCron},
+            scroll_to_id,
+            select::{CheckboxGroup, ComboBox, Select, SelectCron},
             stacked_badge::StackedBadge,
             stacked_duration::StackedDuration,
             stacked_input::StackedInput,
-            Form, FormButtonBar, FormElement, FormItem, FormSection,
+            stacked_map::StackedMap,
+            unsaved::UnsavedChangesGuard,
+            Form, FormButtonBar, FormElement, FormItem, FormSection, ValidateCb,
         },
         icon::IconRefresh,
         messages::{
@@ -34,19 +40,41 @@ use crate::{
         Color,
     },
     core::{
-        form::{ExternalSources, FormData},
+        form::{watch_values, ExternalSources, FormData, LegacyStatus},
+        history::record_save,
         http::{self, HttpRequest},
         oauth::use_authorization,
-        schema::{ArrayType, SelectType},
+        schema::{Action, ArrayType, DefaultRecords, Field, Requires, SelectType, Source},
+        url::{list_url, UrlBuilder},
+        AccessToken, Permission,
     },
     pages::{
-        config::{ReloadSettings, Schema, SchemaType, Schemas, Settings, Type, UpdateSettings},
-        List,
+        config::{
+            certificate::CertificateDetails,
+            defaults::DefaultMarker,
+            dnsbl::DnsblTest,
+            download::FieldDownload,
+            draft::{delete_draft, DraftRestore},
+            errors::ErrorSummary,
+            group::FieldGroupEdit,
+            history::SnapshotHistory,
+            list::fetch_group,
+            notify::NotificationTestButton,
+            placeholders::PlaceholderPreview,
+            preview::{key_changes, ConfigPreview, KeyChanges},
+            references::ReferencesPanel,
+            ReloadSettings, Schema, SchemaType, Schemas, Settings, Type, UpdateSettings,
+        },
+        maybe_plural,
     },
+    STATE_SHOW_ADVANCED_KEY,
 };
 
+#[cfg(feature = "form-debug")]
+use crate::pages::config::debug::{FieldDebug, FormDebugPanel};
+
 #[derive(Clone, Serialize, Deserialize, Default)]
-struct FetchSettings {
+pub(crate) struct FetchSettings {
     pub items: Settings,
     pub total: u64,
 }
@@ -59,6 +87,8 @@ enum FetchResult {
     },
     Create {
         external_sources: ExternalSources,
+        records: Option<(Vec<Settings>, Vec<Settings>)>,
+        source: Option<Settings>,
     },
     NotFound,
 }
@@ -70,28 +100,52 @@ pub fn SettingsEdit() -> impl IntoView {
     let auth = use_authorization();
     let alert = use_alerts();
     let params = use_params_map();
+    let query = use_query_map();
     let modal = use_modals();
+    let show_advanced =
+        create_rw_signal(LocalStorage::get::<bool>(STATE_SHOW_ADVANCED_KEY).unwrap_or_default());
+    #[cfg(feature = "form-debug")]
+    let is_debug = leptos_router::use_query_map()
+        .get_untracked()
+        .get("debug")
+        .is_some();
 
     let schemas = expect_context::<Arc<Schemas>>();
+    let schemas_ = schemas.clone();
+    // Unknown sections are handled by SchemaVersionGate
     let current_schema = create_memo(move |_| {
-        if let Some(schema) = params
+        params
             .get()
             .get("object")
-            .and_then(|id| schemas.schemas.get(id.as_str()))
-        {
-            schema.clone()
-        } else {
-            use_navigate()("/404", Default::default());
-            Arc::new(Schema::default())
-        }
+            .and_then(|id| schemas.get(id.as_str()))
+            .unwrap_or_default()
+    });
+
+    let choose_template = create_memo(move |_| {
+        let is_create = params.with(|params| params.get("id").map_or(true, |id| id.is_empty()));
+        is_create
+            && current_schema.with(|schema| {
+                !schema.templates.is_empty()
+                    && query.with(|query| {
+                        query.get("template").is_none()
+                            && query.get("clone").is_none()
+                            && !schema.fields.keys().any(|id| query.get(id).is_some())
+                    })
+            })
     });
 
     let fetch_settings = create_resource(
-        move || params.get().get("id").cloned().unwrap_or_default(),
-        move |name| {
+        move || {
+            (
+                params.get().get("id").cloned().unwrap_or_default(),
+                query.with(|query| query.get("clone").cloned()),
+            )
+        },
+        move |(name, clone_id)| {
             let auth = auth.get_untracked();
             let current_schema = current_schema.get();
             let is_create = name.is_empty();
+            let schemas = schemas_.clone();
 
             async move {
                 // Fetch external sources
@@ -100,17 +154,19 @@ pub fn SettingsEdit() -> impl IntoView {
                     let schema = schema.unwrap_or_else(|| current_schema.clone());
                     let source_key = format!("{}_{}", schema.id, field.id);
                     if !external_sources.contains_key(&source_key) {
-                        let items = HttpRequest::get("/api/settings/group")
-                            .with_authorization(&auth)
-                            .with_parameter("prefix", schema.unwrap_prefix())
-                            .with_parameter(
-                                "suffix",
-                                schema.try_unwrap_suffix().unwrap_or_default(),
-                            )
-                            .with_parameter("field", field.id)
-                            .send::<List<Settings>>()
-                            .await?
-                            .items;
+                        let items = match fetch_group(&auth, &schema, None).await {
+                            Ok(list) => list.items,
+                            Err(http::Error::Unauthorized) => {
+                                return Err(http::Error::Unauthorized);
+                            }
+                            Err(_) => {
+                                alert.set(Alert::warning(format!(
+                                    "Could not load the {}, saved selections are kept.",
+                                    schema.name_plural
+                                )));
+                                continue;
+                            }
+                        };
 
                         external_sources.insert(
                             source_key,
@@ -128,13 +184,54 @@ pub fn SettingsEdit() -> impl IntoView {
                     }
                 }
 
+                // Fetch existing records for the default provider
+                let mut records = None;
+                if let (true, false, Some(provider)) = (
+                    is_create,
+                    matches!(current_schema.typ, SchemaType::List),
+                    current_schema.default_provider,
+                ) {
+                    let mut lists = Vec::with_capacity(2);
+                    let mut is_complete = true;
+                    for schema in [
+                        Some(current_schema.clone()),
+                        provider
+                            .related
+                            .and_then(|id| schemas.schemas.get(id).cloned()),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    {
+                        match fetch_group(&auth, &schema, None).await {
+                            Ok(list) => lists.push(list.items),
+                            Err(http::Error::Unauthorized) => {
+                                return Err(http::Error::Unauthorized);
+                            }
+                            // The schema defaults are kept when the records
+                            // cannot be read
+                            Err(_) => {
+                                is_complete = false;
+                                break;
+                            }
+                        }
+                    }
+                    if is_complete {
+                        let related = if lists.len() > 1 {
+                            lists.pop().unwrap_or_default()
+                        } else {
+                            vec![]
+                        };
+                        records = Some((lists.pop().unwrap_or_default(), related));
+                    }
+                }
+
                 // Fetch settings
                 match current_schema.typ {
-                    SchemaType::Record { prefix, .. } => {
+                    SchemaType::Record { .. } | SchemaType::Map { .. } => {
                         if !is_create {
                             HttpRequest::get("/api/settings/list")
                                 .with_authorization(&auth)
-                                .with_parameter("prefix", format!("{prefix}.{name}"))
+                                .with_parameter("prefix", current_schema.record_key(&name))
                                 .send::<FetchSettings>()
                                 .await
                                 .map(|mut list| {
@@ -149,14 +246,34 @@ pub fn SettingsEdit() -> impl IntoView {
                                     }
                                 })
                         } else {
-                            Ok(FetchResult::Create { external_sources })
+                            // Duplicated records start from the settings of the source record
+                            let source = match clone_id.filter(|_| current_schema.can_clone()) {
+                                Some(clone_id) => Some(
+                                    HttpRequest::get("/api/settings/list")
+                                        .with_authorization(&auth)
+                                        .with_parameter(
+                                            "prefix",
+                                            current_schema.record_key(&clone_id),
+                                        )
+                                        .send::<FetchSettings>()
+                                        .await?
+                                        .items,
+                                )
+                                .filter(|settings| !settings.is_empty()),
+                                None => None,
+                            };
+                            Ok(FetchResult::Create {
+                                external_sources,
+                                records,
+                                source,
+                            })
                         }
                     }
-                    SchemaType::Entry { prefix } => {
+                    SchemaType::Entry { .. } => {
                         if !is_create {
                             HttpRequest::get("/api/settings/keys")
                                 .with_authorization(&auth)
-                                .with_parameter("keys", format!("{prefix}.{name}"))
+                                .with_parameter("keys", current_schema.record_key(&name))
                                 .send::<AHashMap<String, Option<String>>>()
                                 .await
                                 .map(|list| {
@@ -173,7 +290,11 @@ pub fn SettingsEdit() -> impl IntoView {
                                     }
                                 })
                         } else {
-                            Ok(FetchResult::Create { external_sources })
+                            Ok(FetchResult::Create {
+                                external_sources,
+                                records,
+                                source: None,
+                            })
                         }
                     }
                     SchemaType::List => {
@@ -181,11 +302,13 @@ pub fn SettingsEdit() -> impl IntoView {
                         let mut prefixes = Vec::new();
 
                         for field in current_schema.fields.values() {
-                            if field.is_multivalue() {
-                                prefixes.push(field.id);
-                                keys.push(field.id);
-                            } else {
-                                keys.push(field.id);
+                            for key in
+                                std::iter::once(field.id).chain(field.aliases.iter().copied())
+                            {
+                                if field.is_multivalue() {
+                                    prefixes.push(key);
+                                }
+                                keys.push(key);
                             }
                         }
 
@@ -207,7 +330,11 @@ pub fn SettingsEdit() -> impl IntoView {
                                         external_sources,
                                     }
                                 } else {
-                                    FetchResult::Create { external_sources }
+                                    FetchResult::Create {
+                                        external_sources,
+                                        records,
+                                        source: None,
+                                    }
                                 }
                             })
                     }
@@ -217,6 +344,12 @@ pub fn SettingsEdit() -> impl IntoView {
     );
     let (pending, set_pending) = create_signal(false);
     let data = FormData::default().into_signal();
+    let original = store_value(FormData::default());
+    let fetched = store_value(Settings::new());
+    let is_dirty = create_memo(move |_| data.with(FormData::is_dirty));
+    let requirement = store_value(Requires::default());
+    let review_requested = store_value(false);
+    let show_history = create_rw_signal(false);
 
     let save_changes = create_action(
         move |(changes, reload): &(Arc<Vec<UpdateSettings>>, bool)| {
@@ -224,6 +357,8 @@ pub fn SettingsEdit() -> impl IntoView {
             let reload = *reload;
             let auth = auth.get();
             let schema = current_schema.get();
+            let can_restart = auth.permissions().has_access(Permission::Restart);
+            let requirement_modal = requirement_modal(requirement.get_value(), can_restart);
 
             async move {
                 set_pending.set(true);
@@ -236,6 +371,12 @@ pub fn SettingsEdit() -> impl IntoView {
                     .map(|_| ())
                 {
                     Ok(_) => {
+                        data.update_untracked(|data| {
+                            original.with_value(|original| record_save(original, data));
+                            delete_draft(data);
+                            data.reset_baseline();
+                            original.set_value(data.clone());
+                        });
                         if reload {
                             match HttpRequest::get(format!(
                                 "/api/reload/{}",
@@ -248,9 +389,17 @@ pub fn SettingsEdit() -> impl IntoView {
                                 Ok(result) => {
                                     set_pending.set(false);
                                     if result.errors.is_empty() {
-                                        match schema.list_path() {
-                                            Some(url) => use_navigate()(&url, Default::default()),
-                                            None => {
+                                        match (schema.list_path(), requirement_modal) {
+                                            (Some(url), requirement_modal) => {
+                                                use_navigate()(&url, Default::default());
+                                                if let Some(requirement_modal) = requirement_modal {
+                                                    modal.set(requirement_modal);
+                                                }
+                                            }
+                                            (None, Some(requirement_modal)) => {
+                                                modal.set(requirement_modal);
+                                            }
+                                            (None, None) => {
                                                 modal.set(
                                                     Modal::with_title("Settings reloaded")
                                                         .with_message(concat!(
@@ -268,6 +417,7 @@ pub fn SettingsEdit() -> impl IntoView {
                                     }
                                 }
                                 Err(http::Error::Unauthorized) => {
+                                    set_pending.set(false);
                                     use_navigate()("/login", Default::default());
                                 }
                                 Err(err) => {
@@ -277,9 +427,17 @@ pub fn SettingsEdit() -> impl IntoView {
                             }
                         } else {
                             set_pending.set(false);
-                            match schema.list_path() {
-                                Some(url) => use_navigate()(&url, Default::default()),
-                                None => {
+                            match (schema.list_path(), requirement_modal) {
+                                (Some(url), requirement_modal) => {
+                                    use_navigate()(&url, Default::default());
+                                    if let Some(requirement_modal) = requirement_modal {
+                                        modal.set(requirement_modal);
+                                    }
+                                }
+                                (None, Some(requirement_modal)) => {
+                                    modal.set(requirement_modal);
+                                }
+                                (None, None) => {
                                     modal.set(
                                         Modal::with_title("Settings saved")
                                             .with_message(concat!(
@@ -309,12 +467,345 @@ pub fn SettingsEdit() -> impl IntoView {
         },
     );
 
+    let confirm_disruptive = create_action(
+        move |(changes, reload, fields, metric): &(
+            Arc<Vec<UpdateSettings>>,
+            bool,
+            Vec<Arc<Field>>,
+            Option<String>,
+        )| {
+            let changes = changes.clone();
+            let reload = *reload;
+            let fields = fields.clone();
+            let metric = metric.clone();
+            let auth = auth.get();
+
+            async move {
+                let mut details = fields
+                    .iter()
+                    .map(|field| format!("{} ({})", field.label_form, field.id))
+                    .collect::<Vec<_>>();
+                if let Some(metric) = metric {
+                    if let Some(connections) = fetch_active_connections(&auth, &metric).await {
+                        details.push(format!(
+                            concat!(
+                                "There are currently {} active connections across all ",
+                                "listeners of this protocol, some of them may be interrupted."
+                            ),
+                            connections
+                        ));
+                    }
+                }
+
+                modal.set(
+                    Modal::with_title("Disruptive changes")
+                        .with_message(concat!(
+                            "The following changes affect live traffic and may ",
+                            "interrupt active connections once applied:"
+                        ))
+                        .with_details(details)
+                        .with_acknowledgement(
+                            "I understand that active connections may be interrupted",
+                        )
+                        .with_button("Save changes")
+                        .with_dangerous_callback(move || {
+                            save_changes.dispatch((changes.clone(), reload));
+                        }),
+                );
+            }
+        },
+    );
+    let warnings = create_memo(move |_| {
+        current_schema
+            .with(|schema| schema.warnings)
+            .map(|warnings| data.with(|data| warnings(data)))
+            .unwrap_or_default()
+    });
+    let acknowledged = create_rw_signal(false);
+    let show_errors = create_rw_signal(false);
+    let ambiguous_id = create_memo(move |_| {
+        let id = params.with(|params| params.get("id").cloned().unwrap_or_default());
+        current_schema.with(|schema| schema.is_ambiguous_id(&id))
+    });
+    create_effect(move |_| {
+        warnings.track();
+        acknowledged.set(false);
+    });
+    let save_or_confirm = move |changes: Arc<Vec<UpdateSettings>>,
+                                reload: bool,
+                                disruptive: Vec<Arc<Field>>,
+                                metric: Option<String>| {
+        if disruptive.is_empty() {
+            save_changes.dispatch((changes, reload));
+        } else {
+            confirm_disruptive.dispatch((changes, reload, disruptive, metric));
+        }
+    };
+    let review_changes = create_action(
+        move |(changes, reload, disruptive, metric, review): &(
+            Arc<Vec<UpdateSettings>>,
+            bool,
+            Vec<Arc<Field>>,
+            Option<String>,
+            KeyChanges,
+        )| {
+            let changes = changes.clone();
+            let reload = *reload;
+            let disruptive = disruptive.clone();
+            let metric = metric.clone();
+            let review = review.clone();
+
+            async move {
+                let message = if review.is_empty() {
+                    "Saving does not change any setting keys.".to_string()
+                } else {
+                    format!(
+                        "Saving writes the following setting keys: {} added, {} modified and {} removed.",
+                        review.added.len(),
+                        review.modified.len(),
+                        review.removed.len()
+                    )
+                };
+                modal.set(
+                    Modal::with_title("Review changes")
+                        .with_message(message)
+                        .with_diff(review.entries())
+                        .with_button("Save changes")
+                        .with_callback(move || {
+                            save_or_confirm(
+                                changes.clone(),
+                                reload,
+                                disruptive.clone(),
+                                metric.clone(),
+                            );
+                        }),
+                );
+            }
+        },
+    );
+    let proceed = move |changes: Arc<Vec<UpdateSettings>>,
+                        reload: bool,
+                        disruptive: Vec<Arc<Field>>,
+                        metric: Option<String>,
+                        review: Option<KeyChanges>| match review {
+        Some(review) => review_changes.dispatch((changes, reload, disruptive, metric, review)),
+        None => save_or_confirm(changes, reload, disruptive, metric),
+    };
+    let submit = move |reload: bool| {
+        if !acknowledged.get_untracked() && warnings.with_untracked(|warnings| !warnings.is_empty())
+        {
+            alert.set(Alert::warning(
+                "Review and acknowledge the configuration warnings before saving.",
+            ));
+            return;
+        }
+
+        data.update(|data| {
+            // Hidden values are pruned from a copy, so the form keeps them if
+            // validation fails or the save is cancelled
+            let mut payload = data.clone();
+            payload.prune_hidden();
+            let is_valid = payload.validate_form();
+            data.values.extend(
+                payload
+                    .values
+                    .iter()
+                    .map(|(id, value)| (id.clone(), value.clone())),
+            );
+            data.groups = payload.groups.clone();
+            data.errors = payload.errors.clone();
+            if is_valid {
+                let changes = Arc::new(payload.build_update());
+                let review = (payload.schema.form.confirm_changes || review_requested.get_value())
+                    .then(|| {
+                        fetched.with_value(|original| key_changes(&payload, original, &changes))
+                    });
+                let disruptive =
+                    original.with_value(|original| payload.disruptive_changes(original));
+                let metric = connection_metric(&payload);
+                let renames = payload.legacy_changes();
+                requirement.set_value(
+                    original.with_value(|original| payload.change_requirement(original)),
+                );
+                if renames.is_empty() {
+                    proceed(changes, reload, disruptive, metric, review);
+                } else {
+                    modal.set(
+                        Modal::with_title("Renamed settings")
+                            .with_message(concat!(
+                                "Some settings use keys from an older server version ",
+                                "and will be saved under their current names:"
+                            ))
+                            .with_details(renames)
+                            .with_button("Save changes")
+                            .with_callback(move || {
+                                proceed(
+                                    changes.clone(),
+                                    reload,
+                                    disruptive.clone(),
+                                    metric.clone(),
+                                    review.clone(),
+                                );
+                            }),
+                    );
+                }
+            } else {
+                show_errors.set(true);
+                if let Some((_, field, _)) = data.ordered_errors().first() {
+                    // Advanced fields are validated while collapsed, reveal them so
+                    // the error is not hidden from view
+                    if field.advanced {
+                        show_advanced.set(true);
+                    }
+                    focus_field(field.id);
+                }
+            }
+        });
+    };
+    let assign_id = create_action(move |(field, reload): &(Arc<Field>, bool)| {
+        let field = field.clone();
+        let reload = *reload;
+        let auth = auth.get();
+        let schema = current_schema.get();
+
+        async move {
+            // Fetch the current ids right before submitting to avoid collisions
+            match fetch_group(&auth, &schema, None).await {
+                Ok(list) => {
+                    let existing = list
+                        .items
+                        .into_iter()
+                        .filter_map(|mut item| item.remove("_id"))
+                        .collect::<Vec<_>>();
+                    if let Some(id) = field.generate_id(&existing) {
+                        data.update(|data| {
+                            data.set(field.id, id);
+                        });
+                    }
+                    submit(reload);
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+    let check_id = create_action(move |(id, reload): &(String, bool)| {
+        let id = id.clone();
+        let reload = *reload;
+        let auth = auth.get();
+        let schema = current_schema.get();
+
+        async move {
+            // Creating a record must never overwrite one with the same id
+            let exists = match schema.typ {
+                SchemaType::Entry { .. } => HttpRequest::get("/api/settings/keys")
+                    .with_authorization(&auth)
+                    .with_parameter("keys", schema.record_key(&id))
+                    .send::<AHashMap<String, Option<String>>>()
+                    .await
+                    .map(|list| list.into_values().any(|value| value.is_some())),
+                _ => fetch_group(&auth, &schema, None).await.map(|list| {
+                    data.with_untracked(|data| {
+                        list.items.into_iter().any(|mut item| {
+                            item.remove("_id")
+                                .and_then(|existing| data.normalize_id(&existing))
+                                .is_some_and(|existing| existing == id)
+                        })
+                    })
+                }),
+            };
+
+            match exists {
+                Ok(false) => submit(reload),
+                Ok(true) => {
+                    data.update(|data| data.duplicate_id_error());
+                    scroll_to_id("field-anchor-_id".to_string());
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(_) => {
+                    alert.set(Alert::error("Could not verify the record id").with_details(
+                        concat!(
+                            "Existing records could not be fetched to check for a duplicate id. ",
+                            "Nothing was saved, please try again."
+                        ),
+                    ));
+                }
+            }
+        }
+    });
+    let save = move |reload: bool, review: bool| {
+        if choose_template.get_untracked()
+            || ambiguous_id.get_untracked()
+            || pending.get_untracked()
+            || save_changes.pending().get_untracked()
+            || confirm_disruptive.pending().get_untracked()
+            || assign_id.pending().get_untracked()
+            || check_id.pending().get_untracked()
+        {
+            return;
+        }
+        review_requested.set_value(review);
+
+        match data.with_untracked(|data| (data.pending_auto_id(), data.new_record_id())) {
+            (Some(field), _) => assign_id.dispatch((field, reload)),
+            (None, Some(id)) => check_id.dispatch((id, reload)),
+            (None, None) => submit(reload),
+        }
+    };
+
+    #[cfg(feature = "form-debug")]
+    let debug_panel = is_debug.then(|| {
+        view! { <FormDebugPanel data/> }
+    });
+    #[cfg(not(feature = "form-debug"))]
+    let debug_panel = ();
+
     view! {
         <Form
             title=Signal::derive(move || current_schema.get().form.title.to_string())
             subtitle=Signal::derive(move || current_schema.get().form.subtitle.to_string())
+            on_submit=Callback::new(move |_| {
+                save(false, false);
+            })
+            doc_url=Signal::derive(move || current_schema.get().doc_url())
         >
 
+            <UnsavedChangesGuard
+                is_dirty=is_dirty
+                on_discard=move |_| {
+                    data.update_untracked(|data| {
+                        delete_draft(data);
+                        data.reset_baseline();
+                    })
+                }
+            />
+            <ErrorSummary data is_open=show_errors show_advanced/>
+            <DraftRestore data/>
+
+            <Show when=move || current_schema.with(|schema| schema.has_advanced_fields())>
+                <div class="mb-5 flex justify-end">
+                    <label class="inline-flex items-center gap-x-2 text-sm text-gray-600 dark:text-gray-400">
+                        <input
+                            type="checkbox"
+                            class="shrink-0 border-gray-200 rounded text-blue-600 focus:ring-blue-500 dark:bg-gray-800 dark:border-gray-700"
+                            prop:checked=move || show_advanced.get()
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                show_advanced.set(checked);
+                                let _ = LocalStorage::set(STATE_SHOW_ADVANCED_KEY, checked);
+                            }
+                        />
+                        "Show advanced options"
+                    </label>
+                </div>
+            </Show>
+
             <Transition fallback=Skeleton set_pending>
 
                 {move || match fetch_settings.get() {
@@ -333,29 +824,81 @@ pub fn SettingsEdit() -> impl IntoView {
                         Some(view! { <div></div> }.into_view())
                     }
                     Some(Ok(result)) => {
-                        let (is_create, settings, external_sources) = match result {
+                        let (is_create, settings, external_sources, records) = match result {
                             FetchResult::Update { settings, external_sources } => {
-                                (false, Some(settings), external_sources)
+                                (false, Some(settings), external_sources, None)
                             }
-                            FetchResult::Create { external_sources } => {
-                                (true, None, external_sources)
+                            FetchResult::Create { external_sources, records, source } => {
+                                (true, source, external_sources, records)
                             }
                             FetchResult::NotFound => unreachable!(),
                         };
                         let schema = current_schema.get();
+                        if is_create && choose_template.get() {
+                            return Some(view! { <TemplatePicker schema/> }.into_view());
+                        }
                         let sections = schema.form.sections.iter().cloned();
                         let is_enterprise = auth.get().is_enterprise();
-                        data.set(
-                            FormData::from_settings(schema.clone(), settings)
-                                .with_external_sources(external_sources),
+                        let is_clone = is_create && settings.is_some();
+                        fetched.set_value(
+                            settings.clone().filter(|_| !is_create).unwrap_or_default(),
                         );
+                        let mut form = FormData::from_settings(schema.clone(), settings)
+                            .with_external_sources(external_sources)
+                            .with_server_version(auth.get_untracked().server_version)
+                            .with_capabilities(auth.get_untracked().form_capabilities());
+                        if is_clone {
+                            form = form.into_clone();
+                        }
+                        if is_create {
+                            query
+                                .with_untracked(|query| {
+                                    if let Some(template) = query
+                                        .get("template")
+                                        .and_then(|id| schema.template(id))
+                                    {
+                                        form.apply_prefill(template.values.iter().copied());
+                                    }
+                                    form.apply_prefill(
+                                        schema
+                                            .form
+                                            .sections
+                                            .iter()
+                                            .flat_map(|section| section.fields.iter())
+                                            .filter_map(|field| {
+                                                query
+                                                    .get(field.id)
+                                                    .map(|value| (field.id, value.as_str()))
+                                            }),
+                                    );
+                                });
+                        }
+                        // Suggested defaults depend on the prefilled values
+                        if let (Some(provider), Some((records, related))) = (
+                            schema.default_provider,
+                            records,
+                        ) {
+                            let overrides =
+                                (provider.provider)(&form, &DefaultRecords { records, related });
+                            form.apply_default_overrides(overrides);
+                        }
+                        form.reset_baseline();
+                        original.set_value(form.clone());
+                        data.set(form);
                         Some(
                             sections
                                 .map(|section| {
                                     let title = section.title.map(|s| s.to_string());
                                     let section_ = section.clone();
+                                    let section_dependencies = watch_values(
+                                        data,
+                                        section.dependencies(),
+                                    );
+                                    let is_advanced = section.is_advanced();
                                     let hide_section = create_memo(move |_| {
-                                        !section_.display(&data.get())
+                                        section_dependencies.track();
+                                        (is_advanced && !show_advanced.get())
+                                            || !data.with_untracked(|data| section_.display(data))
                                     });
                                     #[cfg(feature = "enterprise")]
                                     let fields = section.fields.iter().cloned();
@@ -365,233 +908,552 @@ pub fn SettingsEdit() -> impl IntoView {
                                         .iter()
                                         .filter(|field| !field.enterprise)
                                         .cloned();
+                                    let unsupported = data
+                                        .with_untracked(|data| {
+                                            section
+                                                .fields
+                                                .iter()
+                                                .filter(|field| !data.is_supported(field))
+                                                .map(|field| field.label_form)
+                                                .collect::<Vec<_>>()
+                                        });
+                                    let section_capability = section
+                                        .capability
+                                        .filter(|_| {
+                                            data.with_untracked(|data| {
+                                                !section.has_capability(&data.capabilities)
+                                            })
+                                        });
                                     let components = fields
+                                        .filter(|field| {
+                                            data.with_untracked(|data| data.is_supported(field))
+                                        })
                                         .map(|field| {
-                                            let is_disabled = (field.readonly && !is_create)
-                                                || (!is_enterprise && field.enterprise);
+                                            let missing_capability = data
+                                                .with_untracked(|data| data.missing_capability(&field));
+                                            let is_disabled = field.is_readonly(!is_create)
+                                                || (!is_enterprise && field.enterprise)
+                                                || missing_capability.is_some()
+                                                || ambiguous_id.get_untracked();
                                             let field_label = field.label_form;
-                                            let help = field.help;
+                                            let doc_url = field.doc_url();
+                                            let dependencies = watch_values(
+                                                data,
+                                                field.dependencies(),
+                                            );
                                             let field_ = field.clone();
+                                            let help = create_memo(move |_| {
+                                                dependencies.track();
+                                                data.with_untracked(|data| {
+                                                    field_.help(data).unwrap_or_default()
+                                                })
+                                            });
+                                            let field_ = field.clone();
+                                            let is_advanced = field.advanced;
                                             let hide_label = create_memo(move |_| {
-                                                !field_.display(&data.get())
+                                                dependencies.track();
+                                                (is_advanced && !show_advanced.get())
+                                                    || !data.with_untracked(|data| field_.display(data))
                                             });
+                                            let anchor_id = format!("field-anchor-{}", field.id);
                                             let field_ = field.clone();
                                             let is_optional = create_memo(move |_| {
-                                                !field_.is_required(&data.get())
+                                                dependencies.track();
+                                                !data.with_untracked(|data| field_.is_required(data))
                                             });
                                             let is_switch = matches!(field.typ_, Type::Boolean);
-                                            let component = match field.typ_ {
-                                                Type::Input => {
+                                            let requires_badge = field
+                                                .requires
+                                                .map(|requires| {
+                                                    let color = match requires {
+                                                        Requires::Reload => Color::Gray,
+                                                        Requires::NewConnections => Color::Blue,
+                                                        Requires::Restart => Color::Yellow,
+                                                    };
                                                     view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-                                                        <InputText
-                                                            element=FormElement::new(field.id, data)
-                                                            placeholder=create_memo(move |_| {
-                                                                field
-                                                                    .placeholder(&data.get())
-                                                                    .unwrap_or_default()
-                                                                    .to_string()
-                                                            })
-
-                                                            disabled=is_disabled
-                                                        />
+                                                        <div class="mt-2">
+                                                            <Badge color>{requires.label()}</Badge>
+                                                        </div>
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Array(ArrayType::Text) => {
+                                                });
+                                            let capability_badge = missing_capability
+                                                .filter(|capability| section_capability != Some(*capability))
+                                                .map(|capability| {
                                                     view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-
-                                                        <StackedInput
-                                                            add_button_text="Add".to_string()
-                                                            element=FormElement::new(field.id, data)
-                                                            placeholder=create_memo(move |_| {
-                                                                field
-                                                                    .placeholder(&data.get())
-                                                                    .unwrap_or_default()
-                                                                    .to_string()
-                                                            })
-                                                        />
+                                                        <div class="mt-2">
+                                                            <Badge color=Color::Gray>
+                                                                {format!("Requires {capability}")}
+                                                            </Badge>
+                                                        </div>
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Array(ArrayType::Duration) => {
+                                                });
+                                            let field_id = field.id;
+                                            let legacy_badge = move || {
+                                                data.with(|data| {
+                                                    data.legacy
+                                                        .values()
+                                                        .filter(|legacy| legacy.field == field_id)
+                                                        .cloned()
+                                                        .collect::<Vec<_>>()
+                                                })
+                                                    .into_iter()
+                                                    .map(|legacy| {
+                                                        let legacy_key = legacy.key;
+                                                        let message = match legacy.status {
+                                                            LegacyStatus::Renamed => {
+                                                                format!("Migrated from {}", legacy.key)
+                                                            }
+                                                            LegacyStatus::Duplicate => {
+                                                                format!("Also set in {}", legacy.key)
+                                                            }
+                                                            LegacyStatus::Conflict => {
+                                                                format!("{} has a conflicting value", legacy.key)
+                                                            }
+                                                        };
+                                                        let toggle = if legacy.keep {
+                                                            "Remove legacy key on save"
+                                                        } else {
+                                                            "Keep legacy key"
+                                                        };
+                                                        view! {
+                                                            <div class="mt-2 flex flex-wrap items-center gap-x-2 gap-y-1 text-sm text-gray-500">
+                                                                <Badge color=Color::Yellow>"Legacy key"</Badge>
+                                                                <span>{message}</span>
+                                                                <button
+                                                                    type="button"
+                                                                    class="text-blue-600 decoration-2 hover:underline font-medium dark:text-blue-500"
+                                                                    on:click=move |_| {
+                                                                        data.update(|data| data.toggle_keep_legacy(legacy_key));
+                                                                    }
+                                                                >
+                                                                    {toggle}
+                                                                </button>
+                                                            </div>
+                                                        }
+                                                    })
+                                                    .collect_view()
+                                            };
+                                            let notification_test = field
+                                                .notification_test
+                                                .map(|test| {
                                                     view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-
-                                                        <StackedDuration
-                                                            add_button_text="Add".to_string()
-                                                            element=FormElement::new(field.id, data)
+                                                        <NotificationTestButton
+                                                            field_id=field.id
+                                                            test
+                                                            data
                                                         />
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Secret => {
+                                                });
+                                            let placeholder_preview = (!field
+                                                .placeholders()
+                                                .is_empty())
+                                                .then(|| {
                                                     view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-
-                                                        <InputPassword
-                                                            element=FormElement::new(field.id, data)
-                                                            disabled=is_disabled
+                                                        <PlaceholderPreview
+                                                            field=field.clone()
+                                                            data
                                                         />
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Select { typ: SelectType::Single, .. } => {
+                                                });
+                                            let dnsbl_test = field
+                                                .is_dnsbl_zone()
+                                                .then(|| {
+                                                    view! { <DnsblTest field_id=field.id data/> }
+                                                });
+                                            let download = field
+                                                .downloadable
+                                                .is_some()
+                                                .then(|| {
                                                     view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-
-                                                        <Select
-                                                            element=FormElement::new(field.id, data)
-                                                            disabled=is_disabled
+                                                        <FieldDownload
+                                                            field=field.clone()
+                                                            data
                                                         />
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Select { typ: SelectType::Many, .. } => {
+                                                });
+                                            let default_marker = (field.default.default.is_some()
+                                                || !field.default.if_thens.is_empty())
+                                                .then(|| {
                                                     view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-
-                                                        <CheckboxGroup
-                                                            element=FormElement::new(field.id, data)
-                                                            disabled=is_disabled
+                                                        <DefaultMarker
+                                                            field=field.clone()
+                                                            data
                                                         />
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Select { typ: SelectType::ManyWithSearch, .. } => {
+                                                });
+                                            let certificate_details = field
+                                                .is_certificate()
+                                                .then(|| {
                                                     view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-
-                                                        <StackedBadge
-                                                            element=FormElement::new(field.id, data)
-                                                            add_button_text="Add Item"
-                                                            color=Color::Green
+                                                        <CertificateDetails
+                                                            field=field.clone()
+                                                            data
                                                         />
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Size => {
+                                                });
+                                            #[cfg(feature = "form-debug")]
+                                            let debug_view = is_debug
+                                                .then(|| {
                                                     view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-
-                                                        <InputSize
-                                                            element=FormElement::new(field.id, data)
-                                                            disabled=is_disabled
+                                                        <FieldDebug
+                                                            field=field.clone()
+                                                            data
                                                         />
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Boolean => {
-                                                    view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-
-                                                        <InputSwitch
-                                                            label=field_label
-                                                            tooltip=help.unwrap_or_default()
-                                                            element=FormElement::new(field.id, data)
-                                                            disabled=is_disabled
-                                                        />
+                                                });
+                                            #[cfg(not(feature = "form-debug"))]
+                                            let debug_view = ();
+                                            let field_ = field.clone();
+                                            let active_type = create_memo(move |_| {
+                                                dependencies.track();
+                                                data.with_untracked(|data| field_.typ_arm(data))
+                                            });
+                                            // Built lazily so inputs are created inside their FormItem,
+                                            // and rebuilt when a conditional type applies
+                                            let component = move || {
+                                                let field = field.clone();
+                                                match field.typ_at(active_type.get()).clone() {
+                                                    Type::Input => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+                                                            <InputText
+                                                                element=FormElement::new(field.id, data)
+                                                                placeholder=create_memo(move |_| {
+                                                                    dependencies.track();
+                                                                    data.with_untracked(|data| {
+                                                                        field.placeholder(data).unwrap_or_default()
+                                                                    })
+                                                                })
+
+                                                                disabled=is_disabled
+                                                            />
+                                                        }
+                                                            .into_view()
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Duration => {
-                                                    view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-
-                                                        <InputDuration
-                                                            element=FormElement::new(field.id, data)
-                                                            disabled=is_disabled
-                                                        />
+                                                    Type::Array(ArrayType::Text) => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <StackedInput
+                                                                add_button_text="Add".to_string()
+                                                                element=FormElement::new(field.id, data)
+                                                                placeholder=create_memo(move |_| {
+                                                                    dependencies.track();
+                                                                    data.with_untracked(|data| {
+                                                                        field.placeholder(data).unwrap_or_default()
+                                                                    })
+                                                                })
+                                                            />
+                                                        }
+                                                            .into_view()
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Rate => {
-                                                    view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-
-                                                        <InputRate
-                                                            element=FormElement::new(field.id, data)
-                                                            disabled=is_disabled
-                                                        />
+                                                    Type::Array(ArrayType::Duration) => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <StackedDuration
+                                                                add_button_text="Add".to_string()
+                                                                element=FormElement::new(field.id, data)
+                                                            />
+                                                        }
+                                                            .into_view()
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Expression => {
-                                                    view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
+                                                    Type::Tag => {
+                                                        let suggestions = field
+                                                            .suggestions
+                                                            .iter()
+                                                            .map(|suggestion| suggestion.to_string())
+                                                            .collect::<Vec<_>>();
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
 
-                                                        <InputExpression element=FormElement::new(field.id, data)/>
+                                                            <StackedBadge
+                                                                element=FormElement::new(field.id, data)
+                                                                add_button_text="Add"
+                                                                color=Color::Gray
+                                                                suggestions=suggestions
+                                                                validate_item=Callback::new(move |
+                                                                    (value, cb): (String, ValidateCb)|
+                                                                {
+                                                                    let result = data
+                                                                        .with_untracked(|data| match field.input_check(data) {
+                                                                            Some(check) => {
+                                                                                check.check_value(value).map_err(|err| err.to_string())
+                                                                            }
+                                                                            None => Ok(value),
+                                                                        });
+                                                                    cb.call(result);
+                                                                })
+                                                            />
+                                                        }
+                                                            .into_view()
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Cron => {
-                                                    view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
+                                                    Type::Map => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
 
-                                                        <SelectCron element=FormElement::new(field.id, data)/>
+                                                            <StackedMap
+                                                                add_button_text="Add".to_string()
+                                                                element=FormElement::new(field.id, data)
+                                                                key_placeholder="Key".to_string()
+                                                                value_placeholder=create_memo(move |_| {
+                                                                    dependencies.track();
+                                                                    data.with_untracked(|data| {
+                                                                        field.placeholder(data).unwrap_or_else(|| "Value".to_string())
+                                                                    })
+                                                                })
+                                                            />
+                                                        }
+                                                            .into_view()
                                                     }
-                                                        .into_view()
-                                                }
-                                                Type::Text => {
-                                                    view! {
-                                                        // SPDX-SnippetBegin
-                                                        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
-                                                        // SPDX-License-Identifier: LicenseRef-SEL
-                                                        // SPDX-SnippetEnd
-
-                                                        <TextArea
-                                                            element=FormElement::new(field.id, data)
-                                                            placeholder=create_memo(move |_| {
-                                                                field
-                                                                    .placeholder(&data.get())
-                                                                    .unwrap_or_default()
-                                                                    .to_string()
-                                                            })
-
-                                                            disabled=is_disabled
-                                                        />
+                                                    Type::Secret => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <InputPassword
+                                                                element=FormElement::new(field.id, data)
+                                                                disabled=is_disabled
+                                                                generate=field.generate_secret.is_some()
+                                                                no_reveal=field.is_readonly(!is_create)
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Select {
+                                                        typ,
+                                                        source: Source::DynamicRemote { .. },
+                                                    } => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <ComboBox
+                                                                element=FormElement::new(field.id, data)
+                                                                disabled=is_disabled
+                                                                multiple=!matches!(typ, SelectType::Single)
+                                                                allow_custom=field.allow_custom
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Select {
+                                                        typ: SelectType::Single,
+                                                        source: Source::Dynamic { .. } | Source::DynamicSelf { .. } | Source::Merged(_),
+                                                    } => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <ComboBox
+                                                                element=FormElement::new(field.id, data)
+                                                                disabled=is_disabled
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Select {
+                                                        typ: SelectType::Many,
+                                                        source: Source::Dynamic { .. } | Source::DynamicSelf { .. } | Source::Merged(_),
+                                                    } => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <ComboBox
+                                                                element=FormElement::new(field.id, data)
+                                                                disabled=is_disabled
+                                                                multiple=true
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Select {
+                                                        typ: typ @ (SelectType::Single | SelectType::Many),
+                                                        ..
+                                                    } if field.allow_custom => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <ComboBox
+                                                                element=FormElement::new(field.id, data)
+                                                                disabled=is_disabled
+                                                                multiple=matches!(typ, SelectType::Many)
+                                                                allow_custom=true
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Select { typ: SelectType::Single, .. } => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <Select
+                                                                element=FormElement::new(field.id, data)
+                                                                disabled=is_disabled
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Select { typ: SelectType::Many, .. } => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <CheckboxGroup
+                                                                element=FormElement::new(field.id, data)
+                                                                disabled=is_disabled
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Select { typ: SelectType::ManyWithSearch, .. } => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <StackedBadge
+                                                                element=FormElement::new(field.id, data)
+                                                                add_button_text="Add Item"
+                                                                color=Color::Green
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Size => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <InputSize
+                                                                element=FormElement::new(field.id, data)
+                                                                disabled=is_disabled
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Boolean => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <InputSwitch
+                                                                label=field_label
+                                                                tooltip=help
+                                                                element=FormElement::new(field.id, data)
+                                                                disabled=is_disabled
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Duration => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <InputDuration
+                                                                element=FormElement::new(field.id, data)
+                                                                disabled=is_disabled
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Rate => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <InputRate
+                                                                element=FormElement::new(field.id, data)
+                                                                disabled=is_disabled
+                                                            />
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Expression => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <InputExpression element=FormElement::new(field.id, data)/>
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Cron => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <SelectCron element=FormElement::new(field.id, data)/>
+                                                        }
+                                                            .into_view()
+                                                    }
+                                                    Type::Text => {
+                                                        view! {
+                                                            // SPDX-SnippetBegin
+                                                            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                                                            // SPDX-License-Identifier: LicenseRef-SEL
+                                                            // SPDX-SnippetEnd
+
+                                                            <TextArea
+                                                                element=FormElement::new(field.id, data)
+                                                                placeholder=create_memo(move |_| {
+                                                                    dependencies.track();
+                                                                    data.with_untracked(|data| {
+                                                                        field.placeholder(data).unwrap_or_default()
+                                                                    })
+                                                                })
+
+                                                                disabled=is_disabled
+                                                            />
+                                                        }
+                                                            .into_view()
                                                     }
-                                                        .into_view()
                                                 }
                                             };
                                             if !is_switch {
@@ -605,9 +1467,21 @@ pub fn SettingsEdit() -> impl IntoView {
                                                         label=field_label
                                                         hide=hide_label
                                                         is_optional=is_optional
-                                                        tooltip=help.unwrap_or_default()
+                                                        tooltip=help
+                                                        doc_url=doc_url
                                                     >
+                                                        <span id=anchor_id></span>
                                                         {component}
+                                                        {default_marker}
+                                                        {capability_badge}
+                                                        {legacy_badge}
+                                                        {requires_badge}
+                                                        {notification_test}
+                                                        {placeholder_preview}
+                                                        {dnsbl_test}
+                                                        {download}
+                                                        {certificate_details}
+                                                        {debug_view}
                                                     </FormItem>
                                                 }
                                             } else {
@@ -618,12 +1492,21 @@ pub fn SettingsEdit() -> impl IntoView {
                                                     // SPDX-SnippetEnd
 
                                                     <FormItem label="" hide=hide_label is_optional=is_optional>
+                                                        <span id=anchor_id></span>
                                                         {component}
+                                                        {default_marker}
+                                                        {debug_view}
                                                     </FormItem>
                                                 }
                                             }
                                         })
                                         .collect_view();
+                                    let groups = section
+                                        .groups
+                                        .iter()
+                                        .cloned()
+                                        .map(|group| view! { <FieldGroupEdit group data/> })
+                                        .collect_view();
                                     view! {
                                         // SPDX-SnippetBegin
                                         // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
@@ -634,7 +1517,33 @@ pub fn SettingsEdit() -> impl IntoView {
                                             title=title.unwrap_or_default()
                                             hide=hide_section
                                         >
+                                            {section_capability
+                                                .map(|capability| {
+                                                    view! {
+                                                        <div class="sm:col-span-12 flex items-center gap-x-2 text-xs text-gray-500 dark:text-gray-400">
+                                                            <Badge color=Color::Gray>
+                                                                {format!("Requires {capability}")}
+                                                            </Badge>
+                                                            <span>
+                                                                "These settings are not available on this server and will not be saved."
+                                                            </span>
+                                                        </div>
+                                                    }
+                                                })}
                                             {components}
+                                            {groups}
+                                            {(!unsupported.is_empty())
+                                                .then(|| {
+                                                    view! {
+                                                        <p class="sm:col-span-12 text-xs text-gray-500 dark:text-gray-400">
+                                                            {format!(
+                                                                "Hidden because they require a newer server version: {}.",
+                                                                unsupported.join(", "),
+                                                            )}
+
+                                                        </p>
+                                                    }
+                                                })}
                                         </FormSection>
                                     }
                                         .into_view()
@@ -646,58 +1555,285 @@ pub fn SettingsEdit() -> impl IntoView {
 
             </Transition>
 
-            <FormButtonBar>
-                <Button
-                    text="Cancel"
-                    color=Color::Gray
-                    on_click=move |_| {
-                        use_navigate()(
-                            &current_schema.get().list_path_or_default(),
-                            Default::default(),
-                        );
-                    }
-                />
+            <Show when=move || data.with(|data| !data.unknown.is_empty())>
+                <PreservedSettings data/>
+            </Show>
 
-                <Button
-                    text="Save & Reload"
-                    color=Color::Gray
-                    on_click=Callback::new(move |_| {
-                        data.update(|data| {
-                            if data.validate_form() {
-                                save_changes.dispatch((Arc::new(data.build_update()), true));
-                            }
-                        });
-                    })
+            {debug_panel}
+
+            {move || {
+                let id = params.get().get("id").cloned().unwrap_or_default();
+                let schema = current_schema.get();
+                (!id.is_empty()
+                    && matches!(schema.typ, SchemaType::Record { .. } | SchemaType::Map { .. }))
+                    .then(|| view! { <ReferencesPanel schema=schema id=id/> })
+            }}
 
-                    disabled=pending
+            <Show when=move || ambiguous_id.get()>
+                <div
+                    class="mt-5 bg-yellow-50 border border-yellow-200 text-sm text-yellow-800 rounded-lg p-4 dark:bg-yellow-800/10 dark:border-yellow-900 dark:text-yellow-500"
+                    role="alert"
                 >
+                    <p class="font-semibold">This record is read-only</p>
+                    <p class="mt-2">
+                        "Its id contains a dot, which cannot be told apart from the field names in its settings keys. "
+                        "Saving it would corrupt the record, recreate it under an id without dots instead."
+                    </p>
+                </div>
+            </Show>
 
-                    <IconRefresh/>
-                </Button>
+            <Show when=move || warnings.with(|warnings| !warnings.is_empty())>
+                <div
+                    class="mt-5 bg-yellow-50 border border-yellow-200 text-sm text-yellow-800 rounded-lg p-4 dark:bg-yellow-800/10 dark:border-yellow-900 dark:text-yellow-500"
+                    role="alert"
+                >
+                    <p class="font-semibold">This configuration may be insecure</p>
+                    <ul class="mt-2 list-disc space-y-1 ps-5">
+                        {move || {
+                            warnings
+                                .get()
+                                .into_iter()
+                                .map(|warning| view! { <li>{warning}</li> })
+                                .collect_view()
+                        }}
 
-                <Button
-                    text="Save changes"
-                    color=Color::Blue
-                    on_click=Callback::new(move |_| {
-                        data.update(|data| {
-                            if data.validate_form() {
-                                save_changes.dispatch((Arc::new(data.build_update()), false));
+                    </ul>
+                    <label class="flex mt-3">
+                        <input
+                            type="checkbox"
+                            class="shrink-0 mt-0.5 border-yellow-300 rounded text-yellow-600 focus:ring-yellow-500 dark:bg-gray-800 dark:border-yellow-900"
+                            prop:checked=move || acknowledged.get()
+                            on:change=move |ev| {
+                                acknowledged.set(event_target_checked(&ev));
                             }
-                        });
-                    })
+                        />
+
+                        <span class="ms-3">I understand the risks and want to save these settings</span>
+                    </label>
+                </div>
+            </Show>
+
+            <div class:hidden=move || choose_template.get()>
+                <ConfigPreview data/>
+                <SnapshotHistory data is_open=show_history/>
+                <FormButtonBar>
+                    <Show when=move || {
+                        current_schema.with(|schema| schema.has_form_action(Action::ResetToDefaults))
+                    }>
+                        <Button
+                            text="Restore defaults"
+                            color=Color::Gray
+                            on_click=move |_| {
+                                modal
+                                    .set(
+                                        Modal::with_title("Restore defaults")
+                                            .with_message(concat!(
+                                                "Every setting on this page that has a default will be reset to it. ",
+                                                "Settings without a default are left unchanged. ",
+                                                "Nothing is written until the form is saved."
+                                            ))
+                                            .with_button("Restore defaults")
+                                            .with_dangerous_callback(move || {
+                                                let mut total = 0;
+                                                data.update(|data| {
+                                                    total = data.reset_all_to_defaults();
+                                                });
+                                                alert
+                                                    .set(
+                                                        if total > 0 {
+                                                            Alert::success(
+                                                                format!(
+                                                                    "Restored {} to their defaults.",
+                                                                    maybe_plural(total, "setting", "settings"),
+                                                                ),
+                                                            )
+                                                        } else {
+                                                            Alert::success("All settings already use their defaults.")
+                                                        },
+                                                    );
+                                            }),
+                                    );
+                            }
+                        />
+
+                    </Show>
+                    <Button
+                        text="Cancel"
+                        color=Color::Gray
+                        on_click=move |_| {
+                            data.update_untracked(|data| {
+                                delete_draft(data);
+                                data.reset_baseline();
+                            });
+                            use_navigate()(
+                                &current_schema.get().list_path_or_default(),
+                                Default::default(),
+                            );
+                        }
+                    />
+
+                    <Button
+                        text="Revert"
+                        color=Color::Gray
+                        on_click=move |_| {
+                            show_history.update(|show| *show = !*show);
+                        }
+                    />
+
+                    <Button
+                        text="Review changes"
+                        color=Color::Gray
+                        on_click=Callback::new(move |_| {
+                            save(false, true);
+                        })
+
+                        disabled=Signal::derive(move || pending.get() || ambiguous_id.get())
+                    />
 
-                    disabled=pending
-                />
-            </FormButtonBar>
+                    <Button
+                        text="Save & Reload"
+                        color=Color::Gray
+                        on_click=Callback::new(move |_| {
+                            save(true, false);
+                        })
+
+                        disabled=Signal::derive(move || pending.get() || ambiguous_id.get())
+                        loading=Signal::derive(move || {
+                            save_changes
+                                .input()
+                                .with(|input| input.as_ref().is_some_and(|(_, reload)| *reload))
+                                || assign_id
+                                    .input()
+                                    .with(|input| input.as_ref().is_some_and(|(_, reload)| *reload))
+                        })
+                    >
+
+                        <IconRefresh/>
+                    </Button>
+
+                    <Button
+                        text="Save changes"
+                        color=Color::Blue
+                        on_click=Callback::new(move |_| {
+                            save(false, false);
+                        })
+
+                        disabled=Signal::derive(move || pending.get() || ambiguous_id.get())
+                        loading=Signal::derive(move || {
+                            save_changes
+                                .input()
+                                .with(|input| input.as_ref().is_some_and(|(_, reload)| !*reload))
+                                || assign_id
+                                    .input()
+                                    .with(|input| input.as_ref().is_some_and(|(_, reload)| !*reload))
+                        })
+                    />
+                </FormButtonBar>
+            </div>
 
         </Form>
     }
 }
 
+#[component]
+fn TemplatePicker(schema: Arc<Schema>) -> impl IntoView {
+    let card_class = "group flex flex-col bg-white border shadow-sm rounded-xl hover:shadow-md transition dark:bg-slate-900 dark:border-gray-800";
+    let url = move |template: &str| {
+        UrlBuilder::new(format!("/settings/{}/edit", schema.id))
+            .with_parameter("template", template)
+            .finish()
+    };
+    let templates = schema
+        .templates
+        .iter()
+        .map(|template| {
+            view! {
+                <a class=card_class href=url(template.id)>
+                    <div class="p-4 md:p-5">
+                        <h3 class="group-hover:text-blue-600 font-semibold text-gray-800 dark:group-hover:text-gray-400 dark:text-gray-200">
+                            {template.name}
+                        </h3>
+                        <p class="text-sm text-gray-500">{template.description}</p>
+                    </div>
+                </a>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <h3 class="mb-4 text-sm font-semibold text-gray-800 dark:text-gray-200">
+            "Start from a template"
+        </h3>
+        <div class="grid sm:grid-cols-2 gap-3 sm:gap-4">
+            {templates}
+            <a class=card_class href=url("")>
+                <div class="p-4 md:p-5">
+                    <h3 class="group-hover:text-blue-600 font-semibold text-gray-800 dark:group-hover:text-gray-400 dark:text-gray-200">
+                        "Blank form"
+                    </h3>
+                    <p class="text-sm text-gray-500">
+                        {format!("Create a {} from scratch", schema.name_singular)}
+                    </p>
+                </div>
+            </a>
+        </div>
+    }
+}
+
+#[component]
+fn PreservedSettings(data: RwSignal<FormData>) -> impl IntoView {
+    view! {
+        <details class="mt-5 bg-yellow-50 border border-yellow-200 text-sm text-yellow-800 rounded-lg p-4 dark:bg-yellow-800/10 dark:border-yellow-900 dark:text-yellow-500">
+            <summary class="cursor-pointer font-semibold">
+                {move || {
+                    let total = data.with(|data| data.unknown.len());
+                    format!(
+                        "{} not shown by this UI {} preserved",
+                        maybe_plural(total, "setting", "settings"),
+                        if total == 1 { "was" } else { "were" },
+                    )
+                }}
+
+            </summary>
+            <p class="mt-2">
+                These keys are not recognized by this version of the web admin and will be saved unchanged.
+            </p>
+            <ul class="mt-2 space-y-1 font-mono text-xs">
+                {move || {
+                    data.with(|form| {
+                        form.unknown
+                            .iter()
+                            .map(|(key, value)| {
+                                let key_ = key.clone();
+                                view! {
+                                    <li class="flex items-center gap-x-2">
+                                        <span class="break-all">{format!("{key} = {value}")}</span>
+                                        <button
+                                            type="button"
+                                            class="text-red-600 decoration-2 hover:underline font-medium"
+                                            on:click=move |_| {
+                                                data.update(|data| data.remove_unknown(&key_));
+                                            }
+                                        >
+
+                                            Delete
+                                        </button>
+                                    </li>
+                                }
+                            })
+                            .collect_view()
+                    })
+                }}
+
+            </ul>
+        </details>
+    }
+}
+
 impl Schema {
     fn list_path(&self) -> Option<String> {
         if !matches!(self.typ, SchemaType::List) {
-            format!("/settings/{}", self.id).into()
+            list_url(format!("/settings/{}", self.id)).into()
         } else {
             None
         }
@@ -708,3 +1844,73 @@ impl Schema {
             .unwrap_or_else(|| DEFAULT_SETTINGS_URL.to_string())
     }
 }
+
+fn requirement_modal(requires: Requires, can_restart: bool) -> Option<Modal> {
+    match requires {
+        Requires::Reload => None,
+        Requires::NewConnections => Some(
+            Modal::with_title("Settings saved")
+                .with_message(requires.summary())
+                .with_button("OK"),
+        ),
+        Requires::Restart if can_restart => Some(
+            Modal::with_title("Restart required")
+                .with_message(requires.summary())
+                .with_button("Go to restart")
+                .with_callback(|| {
+                    use_navigate()("/manage/maintenance", Default::default());
+                }),
+        ),
+        Requires::Restart => Some(
+            Modal::with_title("Restart required")
+                .with_message(requires.summary())
+                .with_button("OK"),
+        ),
+    }
+}
+
+// The server only reports active connections per protocol, not per listener
+fn connection_metric(data: &FormData) -> Option<String> {
+    match data.schema.id {
+        "listener" => data
+            .get("protocol")
+            .map(|protocol| format!("{protocol}.active-connections")),
+        _ => None,
+    }
+}
+
+// SPDX-SnippetBegin
+// SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+// SPDX-License-Identifier: LicenseRef-SEL
+#[cfg(feature = "enterprise")]
+pub(crate) async fn fetch_active_connections(auth: &AccessToken, metric: &str) -> Option<u64> {
+    use crate::pages::enterprise::dashboard::Metric;
+    use chrono::{Duration, SecondsFormat, Utc};
+
+    HttpRequest::get("/api/telemetry/metrics")
+        .with_authorization(auth)
+        .with_parameter(
+            "after",
+            (Utc::now() - Duration::minutes(5)).to_rfc3339_opts(SecondsFormat::Secs, true),
+        )
+        .send::<Vec<Metric>>()
+        .await
+        .ok()?
+        .into_iter()
+        .filter_map(|metric_| match metric_ {
+            Metric::Gauge {
+                id,
+                timestamp,
+                value,
+            } if id == metric => Some((timestamp, value)),
+            _ => None,
+        })
+        .max_by_key(|(timestamp, _)| *timestamp)
+        .map(|(_, value)| value)
+}
+// SPDX-SnippetEnd
+
+#[cfg(not(feature = "enterprise"))]
+pub(crate) async fn fetch_active_connections(_: &AccessToken, _: &str) -> Option<u64> {
+    None
+}
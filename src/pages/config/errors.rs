@@ -0,0 +1,93 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use leptos::*;
+
+use crate::{components::form::focus_field, core::form::FormData};
+
+// Lists the errors left after a failed save, entries are removed as the
+// fields are corrected
+#[component]
+pub fn ErrorSummary(
+    data: RwSignal<FormData>,
+    is_open: RwSignal<bool>,
+    show_advanced: RwSignal<bool>,
+) -> impl IntoView {
+    let errors = create_memo(move |_| {
+        data.with(|data| {
+            data.ordered_errors()
+                .into_iter()
+                .map(|(section, field, error)| {
+                    let label = match section {
+                        Some(section) => format!("{section} › {}", field.label_form),
+                        None => field.label_form.to_string(),
+                    };
+                    (
+                        format!("{label}: {}", error.error),
+                        field.id,
+                        field.advanced,
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+    create_effect(move |_| {
+        if errors.with(Vec::is_empty) {
+            is_open.set(false);
+        }
+    });
+
+    view! {
+        <Show when=move || is_open.get() && !errors.with(Vec::is_empty)>
+            <div
+                class="mb-5 bg-red-50 border border-red-200 text-sm text-red-800 rounded-lg p-4 dark:bg-red-800/10 dark:border-red-900 dark:text-red-500"
+                role="alert"
+            >
+                <h3 class="font-semibold">
+                    {move || {
+                        let total = errors.with(Vec::len);
+                        if total == 1 {
+                            "Fix the following error before saving".to_string()
+                        } else {
+                            format!("Fix the following {total} errors before saving")
+                        }
+                    }}
+
+                </h3>
+                <ul class="list-disc space-y-1 ps-5 mt-2">
+                    <For
+                        each=move || errors.get()
+                        key=|(message, field_id, _)| (*field_id, message.clone())
+                        let:error
+                    >
+
+                        {
+                            let (message, field_id, is_advanced) = error;
+                            view! {
+                                <li>
+                                    <button
+                                        type="button"
+                                        class="text-start decoration-2 hover:underline"
+                                        on:click=move |_| {
+                                            if is_advanced {
+                                                show_advanced.set(true);
+                                            }
+                                            focus_field(field_id);
+                                        }
+                                    >
+
+                                        {message}
+                                    </button>
+                                </li>
+                            }
+                        }
+
+                    </For>
+                </ul>
+            </div>
+        </Show>
+    }
+}
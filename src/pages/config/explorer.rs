@@ -0,0 +1,633 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use ahash::AHashSet;
+use leptos::*;
+use leptos_router::use_navigate;
+
+use crate::{
+    components::{
+        form::{button::Button, Form, FormButtonBar, FormItem, FormSection},
+        icon::IconArrowRight,
+        messages::{
+            alert::{use_alerts, Alert},
+            modal::{use_modals, Modal},
+        },
+        skeleton::Skeleton,
+        Color,
+    },
+    core::{
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        schema::{SchemaType, Schemas, KEY_SEPARATOR},
+        undo::use_undo_stack,
+        AccessToken, Permission,
+    },
+    pages::config::{edit::FetchSettings, UpdateSettings},
+};
+
+const PAGE_SIZE: u32 = 500;
+const MASKED: &str = "********";
+const SECRET_PATTERNS: &[&str] = &["secret", "key", "password"];
+const INPUT_CLASS: &str = "py-2 px-3 block w-full border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TreeRow {
+    Group {
+        prefix: String,
+        name: String,
+        depth: usize,
+        total: usize,
+    },
+    Key {
+        key: String,
+        name: String,
+        depth: usize,
+        value: String,
+        managed: Option<(String, String)>,
+    },
+}
+
+#[component]
+pub fn SettingsExplorer() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let modal = use_modals();
+    let undo = use_undo_stack();
+    let schemas = expect_context::<Arc<Schemas>>();
+    let permissions = auth.get_untracked().permissions();
+    let can_edit = permissions.has_access(Permission::SettingsUpdate);
+    let can_delete = permissions.has_access(Permission::SettingsDelete);
+
+    let filter = create_rw_signal(String::new());
+    let expanded = create_rw_signal(AHashSet::<String>::new());
+    let revealed = create_rw_signal(AHashSet::<String>::new());
+    let editing = create_rw_signal(None::<(String, String)>);
+    let new_key = create_rw_signal(String::new());
+    let new_value = create_rw_signal(String::new());
+    let new_key_error = create_rw_signal(None::<String>);
+
+    let settings = create_resource(
+        move || undo.version.get(),
+        move |_| {
+            let auth = auth.get_untracked();
+
+            async move { fetch_all_settings(&auth).await }
+        },
+    );
+
+    let schemas_ = schemas.clone();
+    let rows = create_memo(move |_| {
+        let filter = filter.get().trim().to_lowercase();
+        settings.with(|settings| match settings {
+            Some(Ok(settings)) => {
+                expanded.with(|expanded| tree_rows(&schemas_, settings, expanded, &filter))
+            }
+            _ => vec![],
+        })
+    });
+
+    let update_action = create_action(
+        move |(updates, restore, message): &(Vec<UpdateSettings>, Vec<UpdateSettings>, String)| {
+            let updates = updates.clone();
+            let restore = restore.clone();
+            let message = message.clone();
+            let auth = auth.get();
+
+            async move {
+                match HttpRequest::post("/api/settings")
+                    .with_authorization(&auth)
+                    .with_body(updates)
+                    .unwrap()
+                    .send::<serde_json::Value>()
+                    .await
+                {
+                    Ok(_) => {
+                        editing.set(None);
+                        settings.refetch();
+                        alert.set(Alert::success(format!("{message}.")));
+                        undo.push(message, restore);
+                    }
+                    Err(http::Error::Unauthorized) => {
+                        use_navigate()("/login", Default::default());
+                    }
+                    Err(err) => {
+                        alert.set(Alert::from(err));
+                    }
+                }
+            }
+        },
+    );
+
+    let schemas_ = store_value(schemas.clone());
+    let add_setting = move |_| {
+        let key = new_key.get_untracked().trim().to_string();
+        let value = new_value.get_untracked();
+        let error = if !is_valid_key(&key) {
+            Some("Keys must be non-empty, without spaces or empty segments".to_string())
+        } else if settings.with_untracked(|settings| {
+            settings
+                .as_ref()
+                .and_then(|settings| settings.as_ref().ok())
+                .is_some_and(|settings| settings.contains_key(&key))
+        }) {
+            Some("This key already exists, edit it in the tree below".to_string())
+        } else {
+            schemas_.with_value(|schemas| {
+                schemas.find_field(&key).map(|(schema, _, _)| {
+                    format!(
+                        "This key is managed by the {} form",
+                        schema.name_singular.to_lowercase()
+                    )
+                })
+            })
+        };
+        if error.is_some() {
+            new_key_error.set(error);
+            return;
+        }
+
+        expanded.update(|expanded| {
+            let mut prefix = String::new();
+            for segment in key.split(KEY_SEPARATOR) {
+                if !prefix.is_empty() {
+                    prefix.push(KEY_SEPARATOR);
+                }
+                prefix.push_str(segment);
+                expanded.insert(prefix.clone());
+            }
+        });
+        new_key.set(String::new());
+        new_value.set(String::new());
+        new_key_error.set(None);
+        update_action.dispatch((
+            vec![UpdateSettings::Insert {
+                prefix: None,
+                values: vec![(key.clone(), value)],
+                assert_empty: false,
+            }],
+            vec![UpdateSettings::Delete {
+                keys: vec![key.clone()],
+            }],
+            format!("Added {key}"),
+        ));
+    };
+
+    let save_edit = move |(key, previous): (String, String)| {
+        if let Some((_, value)) = editing.get_untracked() {
+            update_action.dispatch((
+                vec![UpdateSettings::Insert {
+                    prefix: None,
+                    values: vec![(key.clone(), value)],
+                    assert_empty: false,
+                }],
+                vec![UpdateSettings::Insert {
+                    prefix: None,
+                    values: vec![(key.clone(), previous)],
+                    assert_empty: false,
+                }],
+                format!("Updated {key}"),
+            ));
+        }
+    };
+
+    let confirm_delete = move |(key, previous): (String, String)| {
+        modal.set(
+            Modal::with_title("Confirm deletion")
+                .with_message(format!(
+                    concat!(
+                        "Are you sure you want to delete the setting {}? ",
+                        "The server may fall back to its built-in default."
+                    ),
+                    key
+                ))
+                .with_button("Delete setting")
+                .with_dangerous_callback(move || {
+                    update_action.dispatch((
+                        vec![UpdateSettings::Delete {
+                            keys: vec![key.clone()],
+                        }],
+                        vec![UpdateSettings::Insert {
+                            prefix: None,
+                            values: vec![(key.clone(), previous.clone())],
+                            assert_empty: false,
+                        }],
+                        format!("Deleted {key}"),
+                    ));
+                }),
+        );
+    };
+
+    view! {
+        <Form
+            title="All settings"
+            subtitle="Browse every key stored on the server, including those without a form"
+        >
+            <Show when=move || can_edit>
+                <FormSection title="Add setting".to_string()>
+                    <FormItem label="Key">
+                        <input
+                            type="text"
+                            class=INPUT_CLASS
+                            placeholder="section.name"
+                            prop:value=move || new_key.get()
+                            on:input=move |ev| {
+                                new_key.set(event_target_value(&ev));
+                                new_key_error.set(None);
+                            }
+                        />
+                        {move || {
+                            new_key_error
+                                .get()
+                                .map(|error| {
+                                    view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                                })
+                        }}
+
+                    </FormItem>
+                    <FormItem label="Value">
+                        <textarea
+                            class=INPUT_CLASS
+                            rows=2
+                            prop:value=move || new_value.get()
+                            on:input=move |ev| new_value.set(event_target_value(&ev))
+                        ></textarea>
+                    </FormItem>
+                </FormSection>
+                <FormButtonBar>
+                    <Button
+                        text="Add setting"
+                        color=Color::Blue
+                        loading=update_action.pending()
+                        on_click=add_setting
+                    />
+                </FormButtonBar>
+            </Show>
+
+            <div class="mt-8 flex flex-wrap items-center gap-2">
+                <input
+                    type="text"
+                    class="py-2 px-3 block w-72 border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                    placeholder="Filter keys"
+                    prop:value=move || filter.get()
+                    on:input=move |ev| filter.set(event_target_value(&ev))
+                />
+                <Button
+                    text="Collapse all"
+                    color=Color::Gray
+                    on_click=move |_| expanded.set(AHashSet::new())
+                />
+            </div>
+
+            <Transition fallback=Skeleton>
+                {move || match settings.get() {
+                    None => None,
+                    Some(Err(http::Error::Unauthorized)) => {
+                        use_navigate()("/login", Default::default());
+                        Some(view! { <div></div> }.into_view())
+                    }
+                    Some(Err(err)) => {
+                        alert.set(Alert::from(err));
+                        Some(view! { <Skeleton/> }.into_view())
+                    }
+                    Some(Ok(all_settings)) => {
+                        let unmanaged = all_settings
+                            .keys()
+                            .filter(|key| schemas.find_field(key).is_none())
+                            .count();
+                        let summary = format!(
+                            "{} settings, {} not managed by any form",
+                            all_settings.len(),
+                            unmanaged,
+                        );
+                        Some(
+                            view! {
+                                <p class="mt-4 text-sm text-gray-500">{summary}</p>
+                                <div class="mt-2 divide-y divide-gray-200 border border-gray-200 rounded-lg dark:divide-gray-700 dark:border-gray-700">
+                                    {move || {
+                                        rows.get()
+                                            .into_iter()
+                                            .map(|row| match row {
+                                                TreeRow::Group { prefix, name, depth, total } => {
+                                                    let is_open = filter.with(|f| !f.trim().is_empty())
+                                                        || expanded.with(|e| e.contains(&prefix));
+                                                    let icon_class = if is_open {
+                                                        "flex-shrink-0 size-3 rotate-90"
+                                                    } else {
+                                                        "flex-shrink-0 size-3"
+                                                    };
+                                                    view! {
+                                                        <button
+                                                            type="button"
+                                                            class="w-full flex items-center gap-x-2 py-2 pe-3 text-sm font-semibold text-gray-800 hover:bg-gray-50 dark:text-gray-200 dark:hover:bg-slate-800"
+                                                            style=indent(depth)
+                                                            on:click=move |_| {
+                                                                expanded
+                                                                    .update(|expanded| {
+                                                                        if !expanded.remove(&prefix) {
+                                                                            expanded.insert(prefix.clone());
+                                                                        }
+                                                                    })
+                                                            }
+                                                        >
+
+                                                            <IconArrowRight attr:class=icon_class/>
+                                                            <span class="font-mono">{name}</span>
+                                                            <span class="text-xs font-normal text-gray-500">
+                                                                {total}
+                                                            </span>
+                                                        </button>
+                                                    }
+                                                        .into_view()
+                                                }
+                                                TreeRow::Key { key, name, depth, value, managed } => {
+                                                    view! {
+                                                        <SettingRow
+                                                            key_=key
+                                                            name
+                                                            depth
+                                                            value
+                                                            managed
+                                                            can_edit
+                                                            can_delete
+                                                            revealed
+                                                            editing
+                                                            on_save=save_edit
+                                                            on_delete=confirm_delete
+                                                        />
+                                                    }
+                                                }
+                                            })
+                                            .collect_view()
+                                    }}
+
+                                </div>
+                            }
+                                .into_view(),
+                        )
+                    }
+                }}
+
+            </Transition>
+        </Form>
+    }
+}
+
+#[component]
+#[allow(clippy::too_many_arguments)]
+fn SettingRow(
+    key_: String,
+    name: String,
+    depth: usize,
+    value: String,
+    managed: Option<(String, String)>,
+    can_edit: bool,
+    can_delete: bool,
+    revealed: RwSignal<AHashSet<String>>,
+    editing: RwSignal<Option<(String, String)>>,
+    #[prop(into)] on_save: Callback<(String, String), ()>,
+    #[prop(into)] on_delete: Callback<(String, String), ()>,
+) -> impl IntoView {
+    let is_secret = is_secret_key(&key_);
+    let key = store_value(key_);
+    let value = store_value(value);
+    let is_managed = managed.is_some();
+    let is_editing = create_memo(move |_| {
+        editing.with(|editing| {
+            editing
+                .as_ref()
+                .is_some_and(|(editing, _)| key.with_value(|key| editing == key))
+        })
+    });
+    let is_revealed = create_memo(move |_| {
+        revealed.with(|revealed| key.with_value(|key| revealed.contains(key)))
+    });
+
+    let managed = managed.map(|(label, url)| {
+        view! {
+            <a
+                class="shrink-0 inline-flex items-center py-0.5 px-2 rounded-full text-xs font-medium bg-blue-100 text-blue-800 hover:underline dark:bg-blue-800/30 dark:text-blue-500"
+                href=url
+            >
+                {format!("Managed by {label}")}
+            </a>
+        }
+    });
+
+    view! {
+        <div class="py-2 pe-3 text-sm" style=indent(depth) title=key.get_value()>
+            <div class="flex flex-wrap items-start gap-x-3 gap-y-1">
+                <span class="font-mono text-gray-800 dark:text-gray-200">{name}</span>
+                <Show
+                    when=move || is_editing.get()
+                    fallback=move || {
+                        view! {
+                            <span class="grow font-mono break-all text-gray-600 dark:text-gray-400">
+                                {move || {
+                                    if is_secret && !is_revealed.get() {
+                                        MASKED.to_string()
+                                    } else {
+                                        value.get_value()
+                                    }
+                                }}
+
+                            </span>
+                        }
+                    }
+                >
+
+                    <textarea
+                        class=INPUT_CLASS
+                        rows=2
+                        prop:value=move || {
+                            editing
+                                .with(|editing| {
+                                    editing.as_ref().map(|(_, value)| value.clone()).unwrap_or_default()
+                                })
+                        }
+
+                        on:input=move |ev| {
+                            let draft = event_target_value(&ev);
+                            editing
+                                .update(|editing| {
+                                    if let Some((_, value)) = editing {
+                                        *value = draft;
+                                    }
+                                })
+                        }
+                    ></textarea>
+                </Show>
+                {managed}
+                <div class="shrink-0 flex gap-x-3">
+                    <Show when=move || is_secret && !is_editing.get()>
+                        <button
+                            type="button"
+                            class="text-xs text-blue-600 hover:underline"
+                            on:click=move |_| {
+                                revealed
+                                    .update(|revealed| {
+                                        let key = key.get_value();
+                                        if !revealed.remove(&key) {
+                                            revealed.insert(key);
+                                        }
+                                    })
+                            }
+                        >
+
+                            {move || if is_revealed.get() { "Hide" } else { "Reveal" }}
+                        </button>
+                    </Show>
+                    <Show when=move || !is_managed && can_edit && !is_editing.get()>
+                        <button
+                            type="button"
+                            class="text-xs text-blue-600 hover:underline"
+                            on:click=move |_| {
+                                editing.set(Some((key.get_value(), value.get_value())))
+                            }
+                        >
+
+                            "Edit"
+                        </button>
+                    </Show>
+                    <Show when=move || is_editing.get()>
+                        <button
+                            type="button"
+                            class="text-xs text-blue-600 hover:underline"
+                            on:click=move |_| on_save.call((key.get_value(), value.get_value()))
+                        >
+                            "Save"
+                        </button>
+                        <button
+                            type="button"
+                            class="text-xs text-gray-600 hover:underline dark:text-gray-400"
+                            on:click=move |_| editing.set(None)
+                        >
+                            "Cancel"
+                        </button>
+                    </Show>
+                    <Show when=move || !is_managed && can_delete && !is_editing.get()>
+                        <button
+                            type="button"
+                            class="text-xs text-red-600 hover:underline"
+                            on:click=move |_| on_delete.call((key.get_value(), value.get_value()))
+                        >
+                            "Delete"
+                        </button>
+                    </Show>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+async fn fetch_all_settings(auth: &AccessToken) -> http::Result<BTreeMap<String, String>> {
+    let mut settings = BTreeMap::new();
+    let mut page = 1;
+
+    loop {
+        let list = HttpRequest::get("/api/settings/list")
+            .with_authorization(auth)
+            .with_parameter("page", page.to_string())
+            .with_parameter("limit", PAGE_SIZE.to_string())
+            .send::<FetchSettings>()
+            .await?;
+        let is_last = list.items.len() < PAGE_SIZE as usize;
+        settings.extend(list.items);
+        if is_last || settings.len() as u64 >= list.total {
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(settings)
+}
+
+// Flattens the visible part of the key tree, groups are emitted before
+// their first key and a filter expands every group
+fn tree_rows(
+    schemas: &Schemas,
+    settings: &BTreeMap<String, String>,
+    expanded: &AHashSet<String>,
+    filter: &str,
+) -> Vec<TreeRow> {
+    let keys = settings
+        .iter()
+        .filter(|(key, _)| filter.is_empty() || key.to_lowercase().contains(filter))
+        .collect::<Vec<_>>();
+    let is_open = |groups: &[&str], depth: usize| {
+        !filter.is_empty() || (1..=depth).all(|depth| expanded.contains(&groups[..depth].join(".")))
+    };
+    let mut rows = Vec::new();
+    let mut previous: Vec<&str> = Vec::new();
+
+    for (idx, (key, value)) in keys.iter().enumerate() {
+        let segments = key.split(KEY_SEPARATOR).collect::<Vec<_>>();
+        let groups = &segments[..segments.len() - 1];
+        let common = groups
+            .iter()
+            .zip(previous.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        for depth in common..groups.len() {
+            if is_open(groups, depth) {
+                let prefix = groups[..=depth].join(".");
+                let child_prefix = format!("{prefix}{KEY_SEPARATOR}");
+                let total = keys[idx..]
+                    .iter()
+                    .take_while(|(key, _)| key.starts_with(&child_prefix))
+                    .count();
+                rows.push(TreeRow::Group {
+                    name: groups[depth].to_string(),
+                    prefix,
+                    depth,
+                    total,
+                });
+            }
+        }
+
+        if is_open(groups, groups.len()) {
+            rows.push(TreeRow::Key {
+                key: key.to_string(),
+                name: segments[segments.len() - 1].to_string(),
+                depth: groups.len(),
+                value: value.to_string(),
+                managed: schemas.find_field(key).map(|(schema, field, id)| {
+                    let label = if !field.label_form.is_empty() {
+                        field.label_form
+                    } else {
+                        field.id
+                    };
+                    let url = match schema.typ {
+                        SchemaType::List => format!("/settings/{}/edit", schema.id),
+                        _ => format!("/settings/{}/{id}/edit", schema.id),
+                    };
+                    (format!("{} › {label}", schema.name_singular), url)
+                }),
+            });
+        }
+        previous = groups.to_vec();
+    }
+
+    rows
+}
+
+fn indent(depth: usize) -> String {
+    format!("padding-left: {}rem", 0.75 + depth as f32 * 1.25)
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.contains(char::is_whitespace)
+        && key.split(KEY_SEPARATOR).all(|segment| !segment.is_empty())
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SECRET_PATTERNS.iter().any(|pattern| key.contains(pattern))
+}
@@ -0,0 +1,135 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::collections::BTreeMap;
+
+use ahash::AHashSet;
+
+use crate::core::schema::{Schema, SchemaType, KEY_SEPARATOR};
+
+use super::{
+    preview::{is_secret, toml_key, toml_string},
+    Settings,
+};
+
+#[derive(Debug, Default)]
+pub struct Export {
+    pub settings: BTreeMap<String, String>,
+    pub records: usize,
+    pub omitted: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ExportValue {
+    Single(String),
+    Array(Vec<String>),
+}
+
+// Selects the records to export from the settings under the schema prefix,
+// secret values are left out unless requested
+pub fn export_settings(
+    schema: &Schema,
+    settings: Settings,
+    ids: Option<&AHashSet<String>>,
+    include_secrets: bool,
+) -> Export {
+    let prefix = schema.unwrap_prefix();
+    let mut export = Export::default();
+    let mut records = AHashSet::new();
+
+    for (key, value) in settings {
+        let (id, field) = match schema.typ {
+            SchemaType::Entry { .. } => (key.as_str(), "_value"),
+            _ => match key.split_once(KEY_SEPARATOR) {
+                Some((id, field)) => (id, field),
+                None => continue,
+            },
+        };
+        if ids.is_some_and(|ids| !ids.contains(id)) {
+            continue;
+        }
+        if !include_secrets && is_secret(schema, field) {
+            export.omitted += 1;
+            continue;
+        }
+        if !records.contains(id) {
+            records.insert(id.to_string());
+        }
+        export
+            .settings
+            .insert(format!("{prefix}{KEY_SEPARATOR}{key}"), value);
+    }
+    export.records = records.len();
+
+    export
+}
+
+pub fn to_toml(settings: &BTreeMap<String, String>) -> String {
+    let mut toml = String::new();
+
+    for (key, value) in group_arrays(settings) {
+        let key = toml_key(&key);
+        let value = match value {
+            ExportValue::Single(value) => toml_string(&value),
+            ExportValue::Array(values) => format!(
+                "[{}]",
+                values
+                    .iter()
+                    .map(|value| toml_string(value))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        toml.push_str(&format!("{key} = {value}\n"));
+    }
+
+    toml
+}
+
+pub fn to_json(settings: &BTreeMap<String, String>) -> String {
+    let json = group_arrays(settings)
+        .into_iter()
+        .map(|(key, value)| {
+            (
+                key,
+                match value {
+                    ExportValue::Single(value) => serde_json::Value::String(value),
+                    ExportValue::Array(values) => serde_json::Value::Array(
+                        values.into_iter().map(serde_json::Value::String).collect(),
+                    ),
+                },
+            )
+        })
+        .collect::<serde_json::Map<_, _>>();
+
+    serde_json::to_string_pretty(&json).unwrap_or_default()
+}
+
+// Array items are stored as numbered keys such as "contact.0000"
+fn group_arrays(settings: &BTreeMap<String, String>) -> Vec<(String, ExportValue)> {
+    let mut result: Vec<(String, ExportValue)> = Vec::with_capacity(settings.len());
+
+    for (key, value) in settings {
+        match key
+            .rsplit_once(KEY_SEPARATOR)
+            .filter(|(_, idx)| !idx.is_empty() && idx.chars().all(|ch| ch.is_ascii_digit()))
+        {
+            Some((parent, _)) => match result.last_mut() {
+                Some((last_key, ExportValue::Array(values))) if last_key == parent => {
+                    values.push(value.clone());
+                }
+                _ => {
+                    result.push((parent.to_string(), ExportValue::Array(vec![value.clone()])));
+                }
+            },
+            None => {
+                result.push((key.clone(), ExportValue::Single(value.clone())));
+            }
+        }
+    }
+
+    result
+}
@@ -0,0 +1,221 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use leptos::*;
+
+use crate::{
+    components::{
+        form::{
+            input::{InputDuration, InputPassword, InputSize, InputSwitch, InputText},
+            select::{CheckboxGroup, Select},
+            stacked_badge::StackedBadge,
+            stacked_input::StackedInput,
+            stacked_map::StackedMap,
+            FormElement, FormItem,
+        },
+        icon::{IconPlus, IconTrash},
+        Color,
+    },
+    core::{
+        form::FormData,
+        schema::{Field, FieldGroup, SelectType, Type},
+    },
+};
+
+const ENTRY_BUTTON_CLASS: &str = "py-1 px-2 inline-flex items-center gap-x-1 text-xs font-medium rounded-lg border border-gray-200 bg-white text-gray-800 hover:bg-gray-50 disabled:opacity-50 disabled:pointer-events-none dark:bg-gray-800 dark:border-gray-700 dark:text-gray-300 dark:hover:bg-gray-700";
+
+#[component]
+pub fn FieldGroupEdit(group: Arc<FieldGroup>, data: RwSignal<FormData>) -> impl IntoView {
+    let group_id = group.id;
+    let total = create_memo(move |_| data.with(|data| data.group_items(group_id).len()));
+    let error = create_memo(move |_| {
+        data.with(|data| data.error_string(group_id).map(|error| error.to_string()))
+    });
+    // Entries are built again when they are added, removed or moved, so
+    // each one edits the entry at its current position
+    let revision = create_rw_signal(0usize);
+    let change = move |update: Box<dyn FnOnce(&mut FormData)>| {
+        data.update(update);
+        revision.update(|revision| *revision += 1);
+    };
+    let group_ = group.clone();
+
+    view! {
+        <div class="sm:col-span-12 space-y-3">
+            <div>
+                <h3 class="text-sm font-medium text-gray-800 dark:text-gray-200">{group.label}</h3>
+                {group
+                    .help
+                    .map(|help| {
+                        view! { <p class="text-xs text-gray-500 dark:text-gray-400">{help}</p> }
+                    })}
+            </div>
+            <For
+                each=move || {
+                    let revision = revision.get();
+                    (0..total.get()).map(move |idx| (revision, idx)).collect::<Vec<_>>()
+                }
+
+                key=|entry| *entry
+                children=move |(_, idx)| {
+                    let group = group_.clone();
+                    let item_name = group.item_name;
+                    let item = create_rw_signal(
+                        data
+                            .with_untracked(|data| data.group_items(group_id).get(idx).cloned())
+                            .unwrap_or_default(),
+                    );
+
+                    // Changes made in the entry are copied to the form and the
+                    // other way round, once validated or reset
+                    create_effect(move |_| {
+                        let item = item.get();
+                        if data
+                            .with_untracked(|data| {
+                                data.group_items(group_id).get(idx).is_some_and(|current| *current != item)
+                            })
+                        {
+                            data.update(|data| data.group_update(group_id, idx, item));
+                        }
+                    });
+                    create_effect(move |_| {
+                        let changed = data
+                            .with(|data| {
+                                data.group_items(group_id)
+                                    .get(idx)
+                                    .filter(|current| item.with_untracked(|item| item != *current))
+                                    .cloned()
+                            });
+                        if let Some(changed) = changed {
+                            item.set(changed);
+                        }
+                    });
+                    let inputs = group
+                        .fields
+                        .iter()
+                        .map(|field| {
+                            let field_ = field.clone();
+                            let hide = Signal::derive(move || item.with(|item| item.is_hidden(&field_)));
+                            let field_ = field.clone();
+                            let is_optional = Signal::derive(move || {
+                                !item.with(|item| field_.is_required(item))
+                            });
+                            let field_ = field.clone();
+                            let help = Signal::derive(move || {
+                                item.with(|item| field_.help(item).unwrap_or_default())
+                            });
+                            view! {
+                                <FormItem
+                                    label=field.label_form
+                                    tooltip=help
+                                    hide=hide
+                                    is_optional=is_optional
+                                >
+                                    {entry_input(field, item)}
+                                </FormItem>
+                            }
+                        })
+                        .collect_view();
+                    view! {
+                        <div class="p-4 space-y-4 border border-gray-200 rounded-lg dark:border-gray-700">
+                            <div class="flex items-center justify-between gap-x-2">
+                                <span class="text-sm font-medium text-gray-800 dark:text-gray-200">
+                                    {format!("{item_name} {}", idx + 1)}
+                                </span>
+                                <div class="flex gap-x-1">
+                                    <button
+                                        type="button"
+                                        class=ENTRY_BUTTON_CLASS
+                                        disabled=idx == 0
+                                        on:click=move |_| {
+                                            change(Box::new(move |data| {
+                                                data.group_move(group_id, idx, idx - 1);
+                                            }));
+                                        }
+                                    >
+
+                                        "Move up"
+                                    </button>
+                                    <button
+                                        type="button"
+                                        class=ENTRY_BUTTON_CLASS
+                                        disabled=move || idx + 1 >= total.get()
+                                        on:click=move |_| {
+                                            change(Box::new(move |data| {
+                                                data.group_move(group_id, idx, idx + 1);
+                                            }));
+                                        }
+                                    >
+
+                                        "Move down"
+                                    </button>
+                                    <button
+                                        type="button"
+                                        class=ENTRY_BUTTON_CLASS
+                                        on:click=move |_| {
+                                            change(Box::new(move |data| {
+                                                data.group_delete(group_id, idx);
+                                            }));
+                                        }
+                                    >
+
+                                        <IconTrash attr:class="flex-shrink-0 size-3.5"/>
+                                        <span class="sr-only">{format!("Remove {item_name} {}", idx + 1)}</span>
+                                    </button>
+                                </div>
+                            </div>
+                            <div class="grid sm:grid-cols-12 gap-2 sm:gap-4">{inputs}</div>
+                        </div>
+                    }
+                }
+            />
+
+            <p class="text-end">
+                <button
+                    type="button"
+                    class="py-1.5 px-2 inline-flex items-center gap-x-1 text-xs font-medium rounded-full border border-dashed border-gray-200 bg-white text-gray-800 hover:bg-gray-50 dark:bg-gray-800 dark:border-gray-700 dark:text-gray-300 dark:hover:bg-gray-700 dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                    on:click=move |_| {
+                        change(Box::new(move |data| data.group_push(group_id)));
+                    }
+                >
+
+                    <IconPlus attr:class="flex-shrink-0 size-3.5"/>
+                    {format!("Add {}", group.item_name.to_lowercase())}
+                </button>
+            </p>
+            {move || {
+                error
+                    .get()
+                    .map(|error| view! { <p class="text-xs text-red-600 mt-2">{error}</p> })
+            }}
+        </div>
+    }
+}
+
+fn entry_input(field: &Field, item: RwSignal<FormData>) -> View {
+    let element = FormElement::new(field.id, item);
+    match &field.typ_ {
+        Type::Boolean => view! { <InputSwitch element/> }.into_view(),
+        Type::Duration => view! { <InputDuration element/> }.into_view(),
+        Type::Size => view! { <InputSize element/> }.into_view(),
+        Type::Secret => view! { <InputPassword element/> }.into_view(),
+        Type::Select {
+            typ: SelectType::Single,
+            ..
+        } => view! { <Select element/> }.into_view(),
+        Type::Select { .. } => view! { <CheckboxGroup element/> }.into_view(),
+        Type::Array(_) => {
+            view! { <StackedInput element add_button_text="Add".to_string()/> }.into_view()
+        }
+        Type::Tag => {
+            view! { <StackedBadge element add_button_text="Add" color=Color::Gray/> }.into_view()
+        }
+        Type::Map => view! { <StackedMap element add_button_text="Add".to_string()/> }.into_view(),
+        _ => view! { <InputText element/> }.into_view(),
+    }
+}
@@ -0,0 +1,137 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::collections::BTreeMap;
+
+use leptos::*;
+
+use crate::{
+    components::{
+        form::button::Button,
+        messages::alert::{use_alerts, Alert},
+        Color,
+    },
+    core::{
+        form::{FormData, FormValue},
+        history::load_history,
+    },
+    pages::{maybe_plural, FormatDateTime},
+};
+
+// Previous versions of the settings saved from this browser, a restored
+// snapshot is only written once the form is saved
+#[component]
+pub fn SnapshotHistory(data: RwSignal<FormData>, is_open: RwSignal<bool>) -> impl IntoView {
+    let alert = use_alerts();
+    let snapshots = create_memo(move |_| {
+        if is_open.get() {
+            data.with_untracked(|data| {
+                let mut snapshots = load_history(data);
+                snapshots.reverse();
+                snapshots
+            })
+        } else {
+            Vec::new()
+        }
+    });
+
+    view! {
+        <Show when=move || is_open.get()>
+            <div class="mt-5 border border-gray-200 rounded-xl dark:border-gray-700">
+                <div class="px-4 py-3 text-sm font-semibold text-gray-800 dark:text-gray-200">
+                    "Saved versions"
+                </div>
+                <div class="px-4 pb-4 space-y-2">
+                    <Show
+                        when=move || snapshots.with(|snapshots| !snapshots.is_empty())
+                        fallback=|| {
+                            view! {
+                                <p class="text-sm text-gray-500 dark:text-gray-400">
+                                    "No versions of these settings have been saved from this browser yet."
+                                </p>
+                            }
+                        }
+                    >
+
+                        <For
+                            each=move || snapshots.get().into_iter().enumerate()
+                            key=|(_, snapshot)| snapshot.recorded_at
+                            children=move |(idx, snapshot)| {
+                                let recorded_at = snapshot.recorded_at.format_date_time();
+                                let differences = create_memo({
+                                    let values = snapshot.values.clone();
+                                    move |_| data.with(|data| snapshot_differences(data, &values))
+                                });
+                                let snapshot = store_value(snapshot);
+                                view! {
+                                    <div class="flex flex-wrap items-center justify-between gap-2 text-sm text-gray-800 dark:text-gray-200">
+                                        <span>
+                                            {if idx == 0 {
+                                                format!("{recorded_at} (latest)")
+                                            } else {
+                                                recorded_at.clone()
+                                            }}
+                                            <span class="ms-2 text-xs text-gray-500 dark:text-gray-400">
+                                                {move || match differences.get() {
+                                                    0 => "Matches the form".to_string(),
+                                                    total => {
+                                                        format!(
+                                                            "{} differ from the form",
+                                                            maybe_plural(total, "setting", "settings"),
+                                                        )
+                                                    }
+                                                }}
+
+                                            </span>
+                                        </span>
+                                        <Button
+                                            text="Revert"
+                                            color=Color::Gray
+                                            disabled=Signal::derive(move || differences.get() == 0)
+                                            on_click=move |_| {
+                                                let values = snapshot
+                                                    .with_value(|snapshot| snapshot.values.clone());
+                                                data.update(|data| data.restore_snapshot(values));
+                                                is_open.set(false);
+                                                alert
+                                                    .set(
+                                                        Alert::success(
+                                                            format!("Settings from {recorded_at} restored."),
+                                                        )
+                                                        .with_details(
+                                                            concat!(
+                                                                "Nothing is written until the form is saved. ",
+                                                                "Secrets are not kept in the history and are left unchanged."
+                                                            ),
+                                                        ),
+                                                    );
+                                            }
+                                        />
+
+                                    </div>
+                                }
+                            }
+                        />
+
+                    </Show>
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+// Secrets are left out of both sides, so they never count as a difference
+fn snapshot_differences(data: &FormData, values: &BTreeMap<String, FormValue>) -> usize {
+    let current = data.draft_values();
+    current
+        .iter()
+        .filter(|(id, value)| values.get(*id) != Some(*value))
+        .count()
+        + values
+            .keys()
+            .filter(|id| !current.contains_key(*id))
+            .count()
+}
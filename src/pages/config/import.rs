@@ -0,0 +1,470 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use ahash::AHashSet;
+use leptos::*;
+use leptos_router::*;
+
+use crate::{
+    components::{
+        form::{button::Button, Form, FormButtonBar, FormItem, FormSection},
+        messages::alert::{use_alerts, Alert},
+        Color,
+    },
+    core::{
+        form::{FormData, FormValue},
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        schema::{Schema, SchemaType, Schemas, Type, KEY_SEPARATOR},
+        toml::parse_flat,
+    },
+    pages::{
+        config::{list::fetch_ids, Settings},
+        maybe_plural,
+    },
+};
+
+const INPUT_CLASS: &str = "py-2 px-3 block w-full border-gray-200 shadow-sm text-sm font-mono rounded-lg focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600";
+
+#[derive(Clone, Debug)]
+struct ImportRecord {
+    id: String,
+    data: FormData,
+    exists: bool,
+    errors: Vec<String>,
+    values: Vec<(String, String)>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ImportPreview {
+    records: Vec<ImportRecord>,
+    warnings: Vec<String>,
+}
+
+#[component]
+pub fn SettingsImport() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let schemas = expect_context::<Arc<Schemas>>();
+    let params = use_params_map();
+    let current_schema = create_memo(move |_| {
+        if let Some(schema) = params
+            .get()
+            .get("object")
+            .and_then(|id| schemas.schemas.get(id.as_str()))
+            .filter(|schema| matches!(schema.typ, SchemaType::Record { .. }))
+        {
+            schema.clone()
+        } else {
+            use_navigate()("/404", Default::default());
+            Arc::new(Schema::default())
+        }
+    });
+
+    let contents = create_rw_signal(String::new());
+    let parse_error = create_rw_signal(None::<String>);
+    let preview = create_rw_signal(None::<ImportPreview>);
+    let overwrite = create_rw_signal(AHashSet::<String>::new());
+
+    let preview_action = create_action(move |contents: &String| {
+        let contents = contents.clone();
+        let auth = auth.get();
+        let schema = current_schema.get();
+
+        async move {
+            let entries = match parse_flat(&contents) {
+                Ok(entries) => entries,
+                Err(err) => {
+                    parse_error.set(Some(err.to_string()));
+                    return;
+                }
+            };
+            match fetch_ids(&auth, &schema, None).await {
+                Ok(existing) => {
+                    overwrite.set(AHashSet::new());
+                    preview.set(Some(build_preview(
+                        &schema,
+                        entries,
+                        &existing,
+                        auth.form_capabilities(),
+                    )));
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+
+    let import_action = create_action(move |records: &Vec<FormData>| {
+        let updates = records
+            .iter()
+            .flat_map(|data| data.build_update())
+            .collect::<Vec<_>>();
+        let total = records.len();
+        let auth = auth.get();
+        let schema = current_schema.get();
+
+        async move {
+            match HttpRequest::post("/api/settings")
+                .with_authorization(&auth)
+                .with_body(updates)
+                .unwrap()
+                .send::<serde_json::Value>()
+                .await
+            {
+                Ok(_) => {
+                    alert.set(Alert::success(format!(
+                        "Imported {}.",
+                        maybe_plural(total, schema.name_singular, schema.name_plural)
+                    )));
+                    use_navigate()(&format!("/settings/{}", schema.id), Default::default());
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+
+    // Existing records are only written once their overwrite is confirmed
+    let to_import = create_memo(move |_| {
+        preview.with(|preview| {
+            overwrite.with(|overwrite| {
+                preview
+                    .as_ref()
+                    .map(|preview| {
+                        preview
+                            .records
+                            .iter()
+                            .filter(|record| !record.exists || overwrite.contains(&record.id))
+                            .map(|record| record.data.clone())
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default()
+            })
+        })
+    });
+    let has_errors = create_memo(move |_| {
+        preview.with(|preview| {
+            preview
+                .as_ref()
+                .is_some_and(|preview| preview.records.iter().any(|r| !r.errors.is_empty()))
+        })
+    });
+
+    view! {
+        <Form
+            title=Signal::derive(move || format!("Import {}", current_schema.get().name_plural))
+            subtitle="Paste a TOML fragment to create or overwrite records"
+        >
+            <FormSection>
+                <FormItem label="TOML">
+                    <textarea
+                        class=INPUT_CLASS
+                        rows=12
+                        placeholder=move || {
+                            format!(
+                                "[{}.example]\n...",
+                                current_schema.get().unwrap_prefix(),
+                            )
+                        }
+
+                        prop:value=move || contents.get()
+                        on:input=move |ev| {
+                            contents.set(event_target_value(&ev));
+                            parse_error.set(None);
+                            preview.set(None);
+                        }
+                    ></textarea>
+                    {move || {
+                        parse_error
+                            .get()
+                            .map(|error| {
+                                view! { <p class="text-xs text-red-600 mt-2">{error}</p> }
+                            })
+                    }}
+
+                </FormItem>
+            </FormSection>
+
+            {move || {
+                preview
+                    .get()
+                    .map(|preview| {
+                        let warnings = (!preview.warnings.is_empty())
+                            .then(|| {
+                                view! {
+                                    <div class="mt-6 p-4 text-sm text-yellow-800 border border-yellow-200 rounded-lg bg-yellow-50 dark:bg-yellow-800/10 dark:border-yellow-900 dark:text-yellow-500">
+                                        <p class="font-semibold">"These keys do not match any field and will not be imported:"</p>
+                                        <ul class="list-disc ps-5 mt-2 space-y-1 font-mono">
+                                            {preview
+                                                .warnings
+                                                .into_iter()
+                                                .map(|warning| view! { <li>{warning}</li> })
+                                                .collect_view()}
+                                        </ul>
+                                    </div>
+                                }
+                            });
+                        let records = preview
+                            .records
+                            .into_iter()
+                            .map(|record| view! { <ImportRecordPreview record overwrite/> })
+                            .collect_view();
+                        view! {
+                            {warnings}
+                            <div class="mt-6 space-y-4">{records}</div>
+                        }
+                    })
+            }}
+
+            <FormButtonBar>
+                <Button
+                    text="Cancel"
+                    color=Color::Gray
+                    on_click=move |_| {
+                        use_navigate()(
+                            &format!("/settings/{}", current_schema.get().id),
+                            Default::default(),
+                        );
+                    }
+                />
+
+                <Button
+                    text="Preview"
+                    color=Color::Gray
+                    disabled=Signal::derive(move || contents.get().trim().is_empty())
+                    loading=preview_action.pending()
+                    on_click=move |_| {
+                        preview_action.dispatch(contents.get());
+                    }
+                />
+
+                <Button
+                    text=Signal::derive(move || {
+                        let schema = current_schema.get();
+                        format!(
+                            "Import {}",
+                            maybe_plural(
+                                to_import.get().len(),
+                                schema.name_singular,
+                                schema.name_plural,
+                            ),
+                        )
+                    })
+
+                    color=Color::Blue
+                    disabled=Signal::derive(move || {
+                        has_errors.get() || to_import.get().is_empty()
+                    })
+
+                    loading=import_action.pending()
+                    on_click=move |_| {
+                        import_action.dispatch(to_import.get());
+                    }
+                />
+
+            </FormButtonBar>
+        </Form>
+    }
+}
+
+#[component]
+fn ImportRecordPreview(
+    record: ImportRecord,
+    overwrite: RwSignal<AHashSet<String>>,
+) -> impl IntoView {
+    let id = record.id.clone();
+    let (status, class) = if !record.errors.is_empty() {
+        (
+            "Invalid",
+            "bg-red-100 text-red-800 dark:bg-red-800/30 dark:text-red-500",
+        )
+    } else if record.exists {
+        (
+            "Overwrites existing",
+            "bg-yellow-100 text-yellow-800 dark:bg-yellow-800/30 dark:text-yellow-500",
+        )
+    } else {
+        (
+            "New",
+            "bg-teal-100 text-teal-800 dark:bg-teal-800/30 dark:text-teal-500",
+        )
+    };
+    let confirm = (record.exists && record.errors.is_empty()).then(|| {
+        let id = id.clone();
+        let id_ = id.clone();
+        view! {
+            <label class="flex mt-3 text-sm text-gray-800 dark:text-gray-400">
+                <input
+                    type="checkbox"
+                    class="shrink-0 mt-0.5 border-gray-200 rounded text-blue-600 focus:ring-blue-500 dark:bg-gray-800 dark:border-gray-700"
+                    prop:checked=move || overwrite.with(|overwrite| overwrite.contains(&id))
+                    on:change=move |ev| {
+                        let checked = event_target_checked(&ev);
+                        overwrite
+                            .update(|overwrite| {
+                                if checked {
+                                    overwrite.insert(id_.clone());
+                                } else {
+                                    overwrite.remove(&id_);
+                                }
+                            });
+                    }
+                />
+
+                <span class="ms-3">"Replace the existing record with this one"</span>
+            </label>
+        }
+    });
+
+    view! {
+        <div class="p-4 border border-gray-200 rounded-lg dark:border-gray-700">
+            <div class="flex items-center gap-x-2">
+                <span class="font-mono font-semibold text-gray-800 dark:text-gray-200">{id}</span>
+                <span class=format!(
+                    "py-1 px-2 inline-flex items-center text-xs font-medium rounded-full {class}",
+                )>{status}</span>
+            </div>
+            <ul class="mt-2 space-y-1 text-xs text-red-600">
+                {record
+                    .errors
+                    .into_iter()
+                    .map(|error| view! { <li>{error}</li> })
+                    .collect_view()}
+            </ul>
+            <dl class="mt-2 grid grid-cols-3 gap-x-4 gap-y-1 text-sm">
+                {record
+                    .values
+                    .into_iter()
+                    .map(|(label, value)| {
+                        view! {
+                            <dt class="text-gray-500">{label}</dt>
+                            <dd class="col-span-2 font-mono text-gray-800 dark:text-gray-200 break-all">
+                                {value}
+                            </dd>
+                        }
+                    })
+                    .collect_view()}
+            </dl>
+            {confirm}
+        </div>
+    }
+}
+
+fn build_preview(
+    schema: &Arc<Schema>,
+    entries: Vec<(String, String)>,
+    existing: &AHashSet<String>,
+    capabilities: Arc<AHashSet<String>>,
+) -> ImportPreview {
+    let prefix = schema.unwrap_prefix();
+    let mut records: BTreeMap<String, Settings> = BTreeMap::new();
+    let mut warnings = Vec::new();
+
+    for (key, value) in entries {
+        let Some((id, field_key)) = key
+            .strip_prefix(prefix)
+            .and_then(|key| key.strip_prefix(KEY_SEPARATOR))
+            .and_then(|key| key.split_once(KEY_SEPARATOR))
+        else {
+            warnings.push(key);
+            continue;
+        };
+        records
+            .entry(id.to_string())
+            .or_default()
+            .insert(normalize_key(schema, field_key), value);
+    }
+
+    let records = records
+        .into_iter()
+        .map(|(id, mut settings)| {
+            settings.insert("_id".to_string(), id.clone());
+            let mut data = FormData::from_settings(schema.clone(), Some(settings))
+                .with_capabilities(capabilities.clone());
+            warnings.extend(
+                std::mem::take(&mut data.unknown)
+                    .into_keys()
+                    .map(|key| format!("{}{KEY_SEPARATOR}{key}", schema.record_key(&id))),
+            );
+            let exists = existing.contains(&id);
+            data.is_update = exists;
+            data.validate_form();
+
+            let mut errors = data
+                .errors
+                .iter()
+                .map(|(field, error)| {
+                    let label = schema
+                        .fields
+                        .get(field.as_str())
+                        .map_or(field.as_str(), |field| field.label_form);
+                    format!("{label}: {}", error.error)
+                })
+                .collect::<Vec<_>>();
+            errors.sort();
+
+            ImportRecord {
+                values: preview_values(schema, &data),
+                id,
+                data,
+                exists,
+                errors,
+            }
+        })
+        .collect();
+
+    ImportPreview { records, warnings }
+}
+
+// Numbered array items are renumbered so they sort in their original order
+fn normalize_key(schema: &Schema, key: &str) -> String {
+    if let Some((field, idx)) = key
+        .rsplit_once(KEY_SEPARATOR)
+        .filter(|(field, _)| schema.fields.get(field).is_some_and(|f| f.is_multivalue()))
+        .and_then(|(field, idx)| idx.parse::<usize>().ok().map(|idx| (field, idx)))
+    {
+        format!("{field}{KEY_SEPARATOR}{idx:04}")
+    } else {
+        key.to_string()
+    }
+}
+
+fn preview_values(schema: &Schema, data: &FormData) -> Vec<(String, String)> {
+    let mut seen = AHashSet::new();
+
+    schema
+        .form
+        .sections
+        .iter()
+        .flat_map(|section| section.fields.iter())
+        .filter(|field| field.id != "_id" && seen.insert(field.id))
+        .filter_map(|field| {
+            let value = match data.values.get(field.id)? {
+                _ if matches!(field.typ_, Type::Secret) => "********".to_string(),
+                FormValue::Value(value) => value.clone(),
+                FormValue::Array(values) => values.join(", "),
+                FormValue::Expression(expr) => expr.else_.clone(),
+                FormValue::Map(entries) => entries
+                    .iter()
+                    .map(|(key, value)| format!("{key} = {value}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+            Some((field.label_form.to_string(), value))
+        })
+        .collect()
+}
@@ -4,16 +4,22 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use std::sync::Arc;
+use std::{cmp::Ordering, collections::BTreeMap, sync::Arc};
 
+use ahash::{AHashMap, AHashSet};
+use gloo_storage::{LocalStorage, Storage};
 use leptos::*;
 use leptos_router::*;
 
 use crate::{
     components::{
-        icon::{IconAdd, IconRefresh, IconTrash},
+        badge::Badge,
+        icon::{
+            IconAdd, IconArrowDownTray, IconArrowUpTray, IconCheckCircle, IconPencilSquare,
+            IconRefresh, IconTrash,
+        },
         list::{
-            header::ColumnList,
+            header::{ColumnFilter, ColumnFilters, ColumnList, ColumnSort},
             pagination::Pagination,
             row::SelectItem,
             toolbar::{SearchBox, ToolbarButton},
@@ -28,21 +34,64 @@ use crate::{
         Color,
     },
     core::{
+        form::comparable_value,
         http::{self, HttpRequest},
         oauth::use_authorization,
-        url::UrlBuilder,
+        schema::{Action, Field, ListToggle, SortOrder, Source, Type, KEY_SEPARATOR},
+        undo::use_undo_stack,
+        url::{docs_url, use_list_state, UrlBuilder},
+        AccessToken,
     },
     pages::{
-        config::{ReloadSettings, SchemaType, Schemas, SettingsValues},
+        config::{
+            bulk::{BulkChanges, BulkEdit},
+            edit::FetchSettings,
+            export::{export_settings, to_json, to_toml},
+            references::fetch_references,
+            DisplayValue, ReloadSettings, SchemaType, Schemas, SettingsValues,
+        },
+        manage::support::download,
         maybe_plural, List,
     },
+    STATE_PAGE_SIZES_KEY,
 };
 
 use super::{Schema, Settings, UpdateSettings};
 
+// Rows are rendered in a window around the scroll position
+const ROW_HEIGHT: i32 = 45;
+const VISIBLE_ROWS: usize = 13;
+const OVERSCAN_ROWS: usize = 5;
+
+// Deleting more items than this at once cannot be undone
+const MAX_UNDO_ITEMS: u32 = 1000;
+
+// Selected records are deleted or changed this many at a time
+pub(crate) const BULK_BATCH_SIZE: usize = 25;
+
+// Column filters are kept in the query string as "filter.<field>=<value>"
+const COLUMN_FILTER_PREFIX: &str = "filter.";
+
+// Page sizes offered in the list footer, the last choice is kept per schema
+const PAGE_SIZES: &[u32] = &[10, 25, 50, 100];
+
+const SWITCH_CLASS: &str = "relative w-11 h-6 p-px bg-gray-100 border-transparent text-transparent rounded-full cursor-pointer transition-colors ease-in-out duration-200 focus:ring-blue-600 disabled:opacity-50 disabled:pointer-events-none checked:bg-none checked:text-blue-600 checked:border-blue-600 focus:checked:border-blue-600 dark:bg-gray-800 dark:border-gray-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-600 before:inline-block before:size-5 before:bg-white checked:before:bg-blue-200 before:translate-x-0 checked:before:translate-x-full before:rounded-full before:shadow before:transform before:ring-0 before:transition before:ease-in-out before:duration-200 dark:before:bg-gray-400 dark:checked:before:bg-blue-200";
+
+const SELECTION_BUTTON_CLASS: &str =
+    "text-blue-600 decoration-2 hover:underline font-medium dark:text-blue-500";
+
+// Sorting and column filters need every record, which are then paged locally
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FetchMode {
+    Page(u32),
+    All,
+}
+
 #[component]
 pub fn SettingsList() -> impl IntoView {
+    use_list_state();
     let schemas = expect_context::<Arc<Schemas>>();
+    let schemas_ = schemas.clone();
     let query = use_query_map();
     let page = create_memo(move |_| {
         query
@@ -63,46 +112,265 @@ pub fn SettingsList() -> impl IntoView {
         })
     });
     let selected = create_rw_signal::<ItemSelection>(ItemSelection::None);
+    let bulk_edit = create_rw_signal(None::<ItemSelection>);
+    let scroll_top = create_rw_signal(0);
+    let window = create_memo(move |_| {
+        let first = (scroll_top.get() / ROW_HEIGHT).max(0) as usize;
+        (
+            first.saturating_sub(OVERSCAN_ROWS),
+            first + VISIBLE_ROWS + OVERSCAN_ROWS,
+        )
+    });
     let params = use_params_map();
     let current_schema = create_memo(move |_| {
-        if let Some(schema) = params
+        // Unknown sections are handled by SchemaVersionGate
+        selected.set(ItemSelection::None);
+        bulk_edit.set(None);
+        params
             .get()
             .get("object")
-            .and_then(|id| schemas.schemas.get(id.as_str()))
-        {
-            selected.set(ItemSelection::None);
-            schema.clone()
+            .and_then(|id| schemas.get(id.as_str()))
+            .unwrap_or_default()
+    });
+
+    let page_size = create_memo(move |_| {
+        let schema = current_schema.get();
+        let is_valid = |size: &u32| PAGE_SIZES.contains(size);
+        if schema.list.fixed_page_size {
+            schema.list.page_size
         } else {
-            use_navigate()("/404", Default::default());
-            Arc::new(Schema::default())
+            query
+                .with(|q| q.get("size").and_then(|size| size.parse::<u32>().ok()))
+                .filter(is_valid)
+                .or_else(|| stored_page_size(schema.id).filter(is_valid))
+                .unwrap_or(schema.list.page_size)
         }
     });
 
+    let sort = create_memo(move |_| {
+        let schema = current_schema.get();
+        query
+            .with(|q| {
+                q.get("sort")
+                    .filter(|key| {
+                        schema
+                            .list
+                            .fields
+                            .iter()
+                            .any(|field| field.sortable && field.id == key.as_str())
+                    })
+                    .map(|key| {
+                        (
+                            key.to_string(),
+                            q.get("order")
+                                .and_then(|order| SortOrder::parse(order))
+                                .unwrap_or_default(),
+                        )
+                    })
+            })
+            .or_else(|| {
+                schema
+                    .list
+                    .default_sort
+                    .map(|(key, order)| (key.to_string(), order))
+            })
+    });
+    let column_filters = create_memo(move |_| {
+        let schema = current_schema.get();
+        query.with(|q| {
+            schema
+                .list
+                .fields
+                .iter()
+                .filter(|field| field.filterable)
+                .filter_map(|field| {
+                    q.get(&format!("{COLUMN_FILTER_PREFIX}{}", field.id))
+                        .map(|value| value.trim())
+                        .filter(|value| !value.is_empty())
+                        .map(|value| (field.id.to_string(), value.to_string()))
+                })
+                .collect::<Vec<_>>()
+        })
+    });
     let auth = use_authorization();
     let alert = use_alerts();
     let modal = use_modals();
+    let undo = use_undo_stack();
     provide_context(selected);
 
+    // Selections survive paging, a different search starts a new one
+    create_effect(move |prev: Option<()>| {
+        filter.track();
+        column_filters.track();
+        if prev.is_some() {
+            selected.set(ItemSelection::None);
+        }
+    });
+
+    create_effect(move |_| {
+        page.track();
+        filter.track();
+        sort.track();
+        column_filters.track();
+        scroll_top.set(0);
+    });
+
+    // Once every record is loaded, paging and clearing filters work on the loaded list.
+    // Only a sort picked by the user loads everything, the schema default applies
+    // to pages the server already returns in order
+    let loaded_all = create_rw_signal(None::<(&'static str, Option<String>, u32)>);
+    let fetch_mode = create_memo(move |_| {
+        let is_loaded = loaded_all.with(|loaded| {
+            loaded.as_ref().is_some_and(|(id, loaded_filter, version)| {
+                *id == current_schema.get().id
+                    && *loaded_filter == filter.get()
+                    && *version == undo.version.get()
+            })
+        });
+        if is_loaded
+            || current_schema
+                .with(|schema| schema.list.load_all || matches!(schema.typ, SchemaType::Map { .. }))
+            || (sort.with(Option::is_some) && query.with(|q| q.get("sort").is_some()))
+            || column_filters.with(|f| !f.is_empty())
+        {
+            FetchMode::All
+        } else {
+            FetchMode::Page(page.get())
+        }
+    });
+    let filtered_ids = store_value(None::<AHashSet<String>>);
+    let page_ids = store_value(Vec::<String>::new());
+
+    // List URLs keep the search, sort order and column filters unless changed
+    let list_url = move |changes: Vec<(String, Option<String>)>| {
+        let mut params = AHashMap::new();
+        query.with_untracked(|q| {
+            for key in ["filter", "sort", "order", "size"] {
+                if let Some(value) = q.get(key) {
+                    params.insert(key.to_string(), value.clone());
+                }
+            }
+        });
+        for (id, value) in column_filters.get_untracked() {
+            params.insert(format!("{COLUMN_FILTER_PREFIX}{id}"), value);
+        }
+        for (key, value) in changes {
+            match value {
+                Some(value) => {
+                    params.insert(key, value);
+                }
+                None => {
+                    params.remove(&key);
+                }
+            }
+        }
+        UrlBuilder::new("/settings")
+            .with_subpath(current_schema.get_untracked().id)
+            .with_parameters(params)
+            .finish()
+    };
+
+    // Responses that arrive after the search or page changed again are
+    // dropped by the resource, the counter keeps them from marking the
+    // list as fully loaded
+    let latest_fetch = store_value(0u64);
     let settings = create_resource(
-        move || (page.get(), filter.get()),
-        move |(page, filter)| {
+        move || {
+            (
+                current_schema.with(|schema| schema.id),
+                fetch_mode.get(),
+                filter.get(),
+                undo.version.get(),
+                page_size.get(),
+            )
+        },
+        move |(_, mode, filter, version, page_size)| {
             let auth = auth.get_untracked();
-            let schema = current_schema.get();
+            let schema = current_schema.get_untracked();
+            let fetch_id = latest_fetch.get_value() + 1;
+            latest_fetch.set_value(fetch_id);
 
             async move {
-                HttpRequest::get("/api/settings/group")
-                    .with_authorization(&auth)
-                    .with_parameter("page", page.to_string())
-                    .with_parameter("limit", schema.list.page_size.to_string())
-                    .with_parameter("prefix", schema.unwrap_prefix())
-                    .with_parameter("suffix", schema.try_unwrap_suffix().unwrap_or_default())
-                    .with_optional_parameter("filter", filter)
-                    .send::<List<Settings>>()
-                    .await
+                match mode {
+                    FetchMode::Page(page) => HttpRequest::get("/api/settings/group")
+                        .with_authorization(&auth)
+                        .with_parameter("prefix", schema.unwrap_prefix())
+                        .with_parameter("suffix", schema.try_unwrap_suffix().unwrap_or_default())
+                        .with_optional_parameter("filter", filter)
+                        .with_parameter("page", page.to_string())
+                        .with_parameter("limit", page_size.to_string())
+                        .send::<List<Settings>>()
+                        .await
+                        .map(|list| (mode, list)),
+                    FetchMode::All => {
+                        let list = fetch_group(&auth, &schema, filter.clone()).await?;
+                        if latest_fetch.try_get_value() == Some(fetch_id) {
+                            loaded_all.set(Some((schema.id, filter, version)));
+                        }
+                        Ok((mode, list))
+                    }
+                }
             }
         },
     );
 
+    // The server has no sort order or column filters, those are applied here
+    let current_page = move || {
+        settings.get().map(|result| {
+            result.map(|(mode, mut list)| {
+                if mode == FetchMode::All {
+                    let schema = current_schema.get();
+                    let column_filters = column_filters.get();
+                    if !column_filters.is_empty() {
+                        list.items.retain(|settings| {
+                            column_filters.iter().all(|(id, value)| {
+                                schema.fields.get(id.as_str()).map_or(true, |field| {
+                                    matches_column_filter(field, settings, value)
+                                })
+                            })
+                        });
+                        filtered_ids.set_value(Some(
+                            list.items
+                                .iter()
+                                .filter_map(|settings| settings.get("_id").cloned())
+                                .collect(),
+                        ));
+                    } else {
+                        filtered_ids.set_value(None);
+                    }
+                    if let Some((field, order)) = sort
+                        .get()
+                        .and_then(|(key, order)| Some((schema.fields.get(key.as_str())?, order)))
+                    {
+                        list.items.sort_by(|a, b| {
+                            let ordering = compare_settings(field, a, b);
+                            match order {
+                                SortOrder::Ascending => ordering,
+                                SortOrder::Descending => ordering.reverse(),
+                            }
+                        });
+                    }
+                    let page_size = page_size.get() as usize;
+                    let start = (page.get() as usize - 1) * page_size;
+                    list.total = list.items.len() as u64;
+                    list.items = list.items.into_iter().skip(start).take(page_size).collect();
+                } else {
+                    filtered_ids.set_value(None);
+                }
+                list
+            })
+        })
+    };
+
+    // Selecting all rows only covers the rows left by the column filters
+    let resolve_selection = move |selection: ItemSelection| match selection {
+        ItemSelection::All => filtered_ids
+            .get_value()
+            .map(ItemSelection::Some)
+            .unwrap_or(ItemSelection::All),
+        selection => selection,
+    };
+
     let reload_config_action = create_action(move |()| {
         let schema = current_schema.get();
         let auth = auth.get();
@@ -130,67 +398,429 @@ pub fn SettingsList() -> impl IntoView {
     });
 
     let total_results = create_rw_signal(None::<u32>);
+    // Running bulk action, with the records processed so far and in total
+    let bulk_progress = create_rw_signal(None::<(&'static str, usize, usize)>);
     let delete_action = create_action(move |items: &Arc<ItemSelection>| {
         let items = items.clone();
         let auth = auth.get();
         let schema = current_schema.get();
         let filter = filter.get();
+        let is_filtered = filter.is_some();
+        let total = total_results.get();
 
         async move {
-            let updates = match items.as_ref() {
+            let restore = match capture_settings(&auth, &schema, &items, is_filtered, total).await {
+                Ok(restore) => restore,
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                    return;
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                    return;
+                }
+            };
+
+            let (deleted, failures) = match items.as_ref() {
                 ItemSelection::All => {
-                    vec![match schema.typ {
-                        SchemaType::Record { prefix, .. } | SchemaType::Entry { prefix } => {
-                            UpdateSettings::Clear {
-                                prefix: format!("{prefix}."),
-                                filter,
-                            }
-                        }
+                    let update = match schema.typ {
+                        SchemaType::Record { prefix, .. }
+                        | SchemaType::Entry { prefix }
+                        | SchemaType::Map { prefix } => UpdateSettings::Clear {
+                            prefix: format!("{prefix}."),
+                            filter,
+                        },
                         SchemaType::List => panic!("List schema type is not supported."),
-                    }]
+                    };
+                    match post_updates(&auth, vec![update]).await {
+                        Ok(_) => (items.total_selected(total), vec![]),
+                        Err(http::Error::Unauthorized) => {
+                            use_navigate()("/login", Default::default());
+                            return;
+                        }
+                        Err(err) => {
+                            alert.set(Alert::from(err));
+                            return;
+                        }
+                    }
                 }
                 ItemSelection::Some(items) => {
-                    let mut updates = Vec::with_capacity(items.len());
-                    for item in items.iter() {
-                        if !item.is_empty() {
-                            match schema.typ {
-                                SchemaType::Record { prefix, .. } => {
-                                    updates.push(UpdateSettings::Clear {
-                                        prefix: format!("{prefix}.{item}."),
+                    let updates = items
+                        .iter()
+                        .filter(|item| !item.is_empty())
+                        .map(|item| {
+                            let update = match schema.typ {
+                                SchemaType::Record { .. } | SchemaType::Map { .. } => {
+                                    UpdateSettings::Clear {
+                                        prefix: schema.record_prefix(item),
                                         filter: None,
-                                    });
-                                }
-                                SchemaType::Entry { prefix } => {
-                                    updates.push(UpdateSettings::Delete {
-                                        keys: vec![format!("{prefix}.{item}")],
-                                    });
+                                    }
                                 }
+                                SchemaType::Entry { .. } => UpdateSettings::Delete {
+                                    keys: vec![schema.record_key(item)],
+                                },
                                 SchemaType::List => panic!("List schema type is not supported."),
+                            };
+                            (item.clone(), update)
+                        })
+                        .collect::<Vec<_>>();
+
+                    let mut deleted = 0;
+                    let mut failures = Vec::new();
+                    bulk_progress.set(Some(("Deleting", 0, updates.len())));
+                    for batch in updates.chunks(BULK_BATCH_SIZE) {
+                        match post_batch(&auth, batch).await {
+                            Ok(failed) => {
+                                deleted += batch.len() - failed.len();
+                                failures.extend(failed);
+                            }
+                            Err(_) => {
+                                bulk_progress.set(None);
+                                use_navigate()("/login", Default::default());
+                                return;
                             }
                         }
+                        bulk_progress.set(Some((
+                            "Deleting",
+                            deleted + failures.len(),
+                            updates.len(),
+                        )));
                     }
-                    updates
+                    bulk_progress.set(None);
+                    (deleted, failures)
                 }
                 ItemSelection::None => unreachable!(),
             };
 
-            match HttpRequest::post("/api/settings")
-                .with_authorization(&auth)
-                .with_body(updates)
-                .unwrap()
-                .send::<serde_json::Value>()
-                .await
-            {
-                Ok(_) => {
-                    settings.refetch();
-                    alert.set(Alert::success(format!(
-                        "Deleted {}.",
-                        maybe_plural(
-                            items.total_selected(total_results.get_untracked()),
-                            schema.name_singular,
-                            schema.name_plural,
+            let message = format!(
+                "Deleted {}",
+                maybe_plural(deleted, schema.name_singular, schema.name_plural)
+            );
+            settings.refetch();
+            if failures.is_empty() {
+                alert.set(Alert::success(format!("{message}.")));
+            } else {
+                // Failed records stay selected so the deletion can be retried
+                selected.set(ItemSelection::Some(
+                    failures.iter().map(|(id, _)| id.clone()).collect(),
+                ));
+                alert.set(
+                    Alert::warning(format!(
+                        "{message}, {} could not be deleted.",
+                        maybe_plural(failures.len(), schema.name_singular, schema.name_plural)
+                    ))
+                    .without_timeout()
+                    .with_details_list(
+                        failures
+                            .into_iter()
+                            .map(|(id, reason)| format!("{id}: {reason}")),
+                    ),
+                );
+            }
+            if let Some(restore) = restore.filter(|_| deleted > 0) {
+                undo.push(message, restore);
+            }
+        }
+    });
+
+    let enable_action = create_action(move |(items, enable): &(ItemSelection, bool)| {
+        let items = items.clone();
+        let enable = *enable;
+        let auth = auth.get();
+        let schema = current_schema.get();
+        let filter = filter.get();
+
+        async move {
+            let Some(field) = schema.enable_field().cloned() else {
+                return;
+            };
+            // Current values are kept so the change can be undone
+            let filter = match items {
+                ItemSelection::Some(_) => None,
+                _ => filter,
+            };
+            let previous = match fetch_field_values(&auth, &schema, filter, field.id).await {
+                Ok(previous) => previous,
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                    return;
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                    return;
+                }
+            };
+            let mut ids = match items {
+                ItemSelection::Some(ids) => ids.into_iter().collect::<Vec<_>>(),
+                _ => previous.keys().cloned().collect::<Vec<_>>(),
+            };
+            ids.sort();
+            let updates = ids
+                .into_iter()
+                .filter(|id| !id.is_empty())
+                .map(|id| {
+                    let update = UpdateSettings::Insert {
+                        prefix: schema.record_key(&id).into(),
+                        values: vec![(field.id.to_string(), enable.to_string())],
+                        assert_empty: false,
+                    };
+                    (id, update)
+                })
+                .collect::<Vec<_>>();
+
+            let verb = if enable { "Enabling" } else { "Disabling" };
+            let mut changed = 0;
+            let mut failures = Vec::new();
+            bulk_progress.set(Some((verb, 0, updates.len())));
+            for batch in updates.chunks(BULK_BATCH_SIZE) {
+                match post_batch(&auth, batch).await {
+                    Ok(failed) => {
+                        changed += batch.len() - failed.len();
+                        failures.extend(failed);
+                    }
+                    Err(_) => {
+                        bulk_progress.set(None);
+                        use_navigate()("/login", Default::default());
+                        return;
+                    }
+                }
+                bulk_progress.set(Some((verb, changed + failures.len(), updates.len())));
+            }
+            bulk_progress.set(None);
+
+            let message = format!(
+                "{} {}",
+                if enable { "Enabled" } else { "Disabled" },
+                maybe_plural(changed, schema.name_singular, schema.name_plural)
+            );
+            let restore = restore_field(
+                &schema,
+                field.id,
+                updates
+                    .into_iter()
+                    .map(|(id, _)| id)
+                    .filter(|id| !failures.iter().any(|(failed, _)| failed == id))
+                    .map(|id| {
+                        let value = previous.get(&id).cloned().flatten();
+                        (id, value)
+                    }),
+            );
+            undo.push(message.clone(), restore);
+            settings.refetch();
+            if failures.is_empty() {
+                selected.set(ItemSelection::None);
+                alert.set(Alert::success(format!("{message}.")));
+            } else {
+                // Failed records stay selected so the change can be retried
+                selected.set(ItemSelection::Some(
+                    failures.iter().map(|(id, _)| id.clone()).collect(),
+                ));
+                alert.set(
+                    Alert::warning(format!(
+                        "{message}, {} could not be changed.",
+                        maybe_plural(failures.len(), schema.name_singular, schema.name_plural)
+                    ))
+                    .without_timeout()
+                    .with_details_list(
+                        failures
+                            .into_iter()
+                            .map(|(id, reason)| format!("{id}: {reason}")),
+                    ),
+                );
+            }
+        }
+    });
+
+    let confirm_delete = create_action(move |(items, to_delete): &(ItemSelection, usize)| {
+        let ids = match items {
+            ItemSelection::Some(items) => items.iter().cloned().collect::<Vec<_>>(),
+            _ => vec![],
+        };
+        let is_all = matches!(items, ItemSelection::All);
+        let filter = filter.get();
+        let is_filtered = filter.is_some();
+        let to_delete = *to_delete;
+        let auth = auth.get();
+        let schema = current_schema.get();
+        let schemas = schemas_.clone();
+
+        async move {
+            // Deleting all records, or all matching the search, checks the
+            // references of every record that is removed
+            let referenced_ids = if is_all {
+                match fetch_ids(&auth, &schema, filter).await {
+                    Ok(ids) => ids.into_iter().collect::<Vec<_>>(),
+                    Err(err) => {
+                        alert.set(Alert::from(err));
+                        return;
+                    }
+                }
+            } else {
+                ids.clone()
+            };
+            let references = if !referenced_ids.is_empty() {
+                match fetch_references(&auth, &schemas, schema.id, &referenced_ids).await {
+                    Ok(references) => references,
+                    Err(err) => {
+                        alert.set(Alert::from(err));
+                        return;
+                    }
+                }
+            } else {
+                vec![]
+            };
+            let text = maybe_plural(to_delete, schema.name_singular, schema.name_plural);
+            let modal_ = if schema.list.confirm_delete {
+                // Name every key going away and have the operator type the
+                // record id, or "delete" when removing several records
+                let mut keys = ids
+                    .iter()
+                    .flat_map(|id| schema.record_keys(id))
+                    .collect::<Vec<_>>();
+                keys.sort();
+                if is_all {
+                    keys.push(format!("{}{KEY_SEPARATOR}*", schema.unwrap_prefix()));
+                }
+                let confirm_text = match ids.as_slice() {
+                    [id] => id.clone(),
+                    _ => "delete".to_string(),
+                };
+                let mut message = if is_all && is_filtered {
+                    format!("Deleting all {text} matching the search removes their keys under:")
+                } else {
+                    format!("Deleting the {text} removes the following keys:")
+                };
+                if !references.is_empty() {
+                    message = format!(
+                        concat!(
+                            "{} The settings listed after them still reference the {} ",
+                            "and will stop working unless they are updated first."
+                        ),
+                        message, text
+                    );
+                }
+                let modal_ = Modal::with_title("Confirm deletion")
+                    .with_message(message)
+                    .with_details(keys)
+                    .with_typed_confirmation(confirm_text);
+                if references.is_empty() {
+                    modal_
+                } else {
+                    modal_
+                        .with_links(references.into_iter().map(|(id, reference)| {
+                            (
+                                format!("{} (uses {id})", reference.label()),
+                                reference.url(),
+                            )
+                        }))
+                        .with_acknowledgement("Delete anyway, I will update the references")
+                }
+            } else if references.is_empty() {
+                let message = if is_all {
+                    format!(
+                        concat!(
+                            "Are you sure you want to delete all {}, including those ",
+                            "not shown on this page? This action cannot be undone."
+                        ),
+                        text
+                    )
+                } else {
+                    format!(
+                        concat!(
+                            "Are you sure you want to delete the {} selected? ",
+                            "This action cannot be undone."
+                        ),
+                        text
+                    )
+                };
+                Modal::with_title("Confirm deletion").with_message(message)
+            } else {
+                Modal::with_title("Confirm deletion")
+                    .with_message(format!(
+                        concat!(
+                            "The following settings still reference the {} you are about ",
+                            "to delete and will stop working unless they are updated first:"
+                        ),
+                        text
+                    ))
+                    .with_links(references.into_iter().map(|(id, reference)| {
+                        (
+                            format!("{} (uses {id})", reference.label()),
+                            reference.url(),
                         )
-                    )));
+                    }))
+                    .with_acknowledgement("Delete anyway, I will update the references")
+            };
+            modal.set(
+                modal_
+                    .with_button(format!("Delete {text}"))
+                    .with_dangerous_callback(move || {
+                        delete_action.dispatch(Arc::new(resolve_selection(
+                            selected.try_update(std::mem::take).unwrap_or_default(),
+                        )));
+                    }),
+            );
+        }
+    });
+
+    let export_action = create_action(move |(as_json, include_secrets): &(bool, bool)| {
+        let (as_json, include_secrets) = (*as_json, *include_secrets);
+        let auth = auth.get();
+        let schema = current_schema.get();
+        let filter = filter.get();
+        let selection = resolve_selection(selected.get());
+
+        async move {
+            let ids = match selection {
+                ItemSelection::Some(items) if !items.is_empty() => Ok(Some(items)),
+                _ => match filter {
+                    Some(filter) => fetch_ids(&auth, &schema, Some(filter)).await.map(Some),
+                    None => Ok(None),
+                },
+            };
+            let result = match ids {
+                Ok(ids) => fetch_prefix(&auth, schema.unwrap_prefix())
+                    .await
+                    .map(|settings| {
+                        export_settings(&schema, settings, ids.as_ref(), include_secrets)
+                    }),
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Ok(export) => {
+                    let (contents, extension, content_type) = if as_json {
+                        (to_json(&export.settings), "json", "application/json")
+                    } else {
+                        (to_toml(&export.settings), "toml", "application/toml")
+                    };
+                    if download(
+                        &format!("{}.{extension}", schema.id),
+                        content_type,
+                        &contents,
+                    )
+                    .is_ok()
+                    {
+                        let mut message = format!(
+                            "Exported {}",
+                            maybe_plural(export.records, schema.name_singular, schema.name_plural)
+                        );
+                        if export.omitted > 0 {
+                            message.push_str(&format!(
+                                ", {} left out",
+                                maybe_plural(export.omitted, "secret", "secrets")
+                            ));
+                        }
+                        alert.set(Alert::success(format!("{message}.")));
+                    } else {
+                        alert.set(Alert::error(format!(
+                            "Failed to download the {}",
+                            schema.name_plural
+                        )));
+                    }
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
                 }
                 Err(err) => {
                     alert.set(Alert::from(err));
@@ -198,67 +828,314 @@ pub fn SettingsList() -> impl IntoView {
             }
         }
     });
+    let toggle_action = create_action(move |(id, field, value): &(String, Arc<Field>, bool)| {
+        let id = id.clone();
+        let field = field.clone();
+        let value = *value;
+        let auth = auth.get();
+        let schema = current_schema.get();
+        let is_exclusive = value && schema.toggle_mode(&field) == Some(ListToggle::Exclusive);
+
+        // Rows change right away and are restored if the change is not saved
+        let mut previous = Vec::new();
+        settings.update(|result| {
+            if let Some(Ok((_, list))) = result {
+                for item in &mut list.items {
+                    let Some(item_id) = item.get("_id").cloned() else {
+                        continue;
+                    };
+                    if item_id == id {
+                        previous.push((item_id, item.get(field.id).cloned()));
+                        item.insert(field.id.to_string(), value.to_string());
+                    } else if is_exclusive {
+                        previous.push((item_id, item.get(field.id).cloned()));
+                        item.remove(field.id);
+                    }
+                }
+            }
+        });
+
+        async move {
+            // Values before the change, written back on undo
+            let mut restore = vec![(
+                id.clone(),
+                previous
+                    .iter()
+                    .find(|(item_id, _)| *item_id == id)
+                    .and_then(|(_, value)| value.clone()),
+            )];
+            let result: http::Result<()> = async {
+                let mut updates = Vec::with_capacity(2);
+                if is_exclusive {
+                    let others = fetch_field_values(&auth, &schema, None, field.id)
+                        .await?
+                        .into_iter()
+                        .filter(|(other, _)| *other != id)
+                        .collect::<Vec<_>>();
+                    let keys = others
+                        .iter()
+                        .map(|(other, _)| schema.field_key(other, field.id))
+                        .collect::<Vec<_>>();
+                    if !keys.is_empty() {
+                        updates.push(UpdateSettings::Delete { keys });
+                    }
+                    restore.extend(others);
+                }
+                updates.push(UpdateSettings::Insert {
+                    prefix: schema.record_key(&id).into(),
+                    values: vec![(field.id.to_string(), value.to_string())],
+                    assert_empty: false,
+                });
+
+                HttpRequest::post("/api/settings")
+                    .with_authorization(&auth)
+                    .with_body(updates)
+                    .unwrap()
+                    .send::<serde_json::Value>()
+                    .await
+                    .map(|_| ())
+            }
+            .await;
+
+            match result {
+                Ok(()) => {
+                    undo.push(
+                        format!("Changed {} of {id}", field.label_form),
+                        restore_field(&schema, field.id, restore),
+                    );
+                }
+                Err(err) => {
+                    settings.update(|result| {
+                        if let Some(Ok((_, list))) = result {
+                            for item in &mut list.items {
+                                let Some((_, value)) = item.get("_id").and_then(|item_id| {
+                                    previous.iter().find(|(changed, _)| changed == item_id)
+                                }) else {
+                                    continue;
+                                };
+                                match value {
+                                    Some(value) => {
+                                        item.insert(field.id.to_string(), value.clone());
+                                    }
+                                    None => {
+                                        item.remove(field.id);
+                                    }
+                                }
+                            }
+                        }
+                    });
+                    match err {
+                        http::Error::Unauthorized => {
+                            use_navigate()("/login", Default::default());
+                        }
+                        err => {
+                            alert.set(Alert::from(err));
+                        }
+                    }
+                }
+            }
+        }
+    });
+    let toggling = toggle_action.pending();
+    let on_toggle = Callback::new(move |toggle: (String, Arc<Field>, bool)| {
+        toggle_action.dispatch(toggle);
+    });
+
+    let export_secrets = create_rw_signal(false);
+    let export_json = create_rw_signal(false);
 
     view! {
         <ListSection>
+            {move || {
+                bulk_edit
+                    .get()
+                    .map(|selection| {
+                        view! {
+                            <BulkEdit
+                                schema=current_schema.get_untracked()
+                                selection
+                                filter=filter.get_untracked()
+                                on_close=move |_| bulk_edit.set(None)
+                                on_applied=move |(field, changes): (&'static str, BulkChanges)| {
+                                    // Only the edited rows are updated, the page is not fetched again
+                                    settings
+                                        .update(|result| {
+                                            if let Some(Ok((_, list))) = result {
+                                                for item in &mut list.items {
+                                                    let change = item
+                                                        .get("_id")
+                                                        .and_then(|id| changes.iter().find(|(changed, _)| changed == id))
+                                                        .map(|(_, value)| value.clone());
+                                                    match change {
+                                                        Some(Some(value)) => {
+                                                            item.insert(field.to_string(), value);
+                                                        }
+                                                        Some(None) => {
+                                                            item.remove(field);
+                                                        }
+                                                        None => {}
+                                                    }
+                                                }
+                                            }
+                                        });
+                                }
+                            />
+                        }
+                    })
+            }}
+
             <ListTable
                 title=Signal::derive(move || { current_schema.get().list.title.to_string() })
                 subtitle=Signal::derive(move || { current_schema.get().list.subtitle.to_string() })
+                scroll_top=scroll_top
             >
                 <Toolbar slot>
                     <SearchBox
                         value=filter
                         on_search=move |value| {
                             use_navigate()(
-                                &UrlBuilder::new("/settings")
-                                    .with_subpath(current_schema.get().id)
-                                    .with_parameter("filter", value)
-                                    .finish(),
+                                &list_url(vec![("filter".to_string(), Some(value))]),
                                 Default::default(),
                             );
                         }
                     />
 
-                    <ToolbarButton
-                        text=Signal::derive(move || {
-                            let ns = selected.get().total_selected(total_results.get());
-                            if ns > 0 { format!("Delete ({ns})") } else { "Delete".to_string() }
-                        })
+                    <Show when=move || current_schema.get().has_bulk_action(Action::Delete)>
+                        <ToolbarButton
+                            text=Signal::derive(move || {
+                                let selected = selected.get();
+                                let ns = selected.total_selected(total_results.get());
+                                if ns > 0 && selected.is_all() {
+                                    format!("Delete all ({ns})")
+                                } else if ns > 0 {
+                                    format!("Delete ({ns})")
+                                } else {
+                                    "Delete".to_string()
+                                }
+                            })
 
-                        color=Color::Red
-                        on_click=Callback::new(move |_| {
-                            let to_delete = selected.get().total_selected(total_results.get());
-                            if to_delete > 0 {
+                            color=Color::Red
+                            on_click=Callback::new(move |_| {
+                                let to_delete = selected.get().total_selected(total_results.get());
+                                if to_delete > 0 && bulk_progress.get().is_none() {
+                                    confirm_delete
+                                        .dispatch((resolve_selection(selected.get()), to_delete));
+                                }
+                            })
+
+                        >
+
+                            <IconTrash/>
+                        </ToolbarButton>
+                    </Show>
+
+                    <Show when=move || current_schema.get().has_bulk_action(Action::Modify)>
+                        <ToolbarButton
+                            text=Signal::derive(move || {
+                                let ns = selected.get().total_selected(total_results.get());
+                                if ns > 0 { format!("Edit field ({ns})") } else { "Edit field".to_string() }
+                            })
+
+                            color=Color::Gray
+                            on_click=Callback::new(move |_| {
+                                if selected.get().total_selected(total_results.get()) > 0 {
+                                    bulk_edit.set(Some(resolve_selection(selected.get())));
+                                }
+                            })
+                        >
+
+                            <IconPencilSquare/>
+                        </ToolbarButton>
+                    </Show>
+
+                    <Show when=move || {
+                        let schema = current_schema.get();
+                        schema.has_list_action(Action::Export)
+                            && matches!(
+                                schema.typ,
+                                SchemaType::Record { .. } | SchemaType::Entry { .. }
+                            )
+                    }>
+                        <ToolbarButton
+                            text="Export"
+
+                            color=Color::Gray
+                            on_click=Callback::new(move |_| {
                                 let schema = current_schema.get();
-                                let text = maybe_plural(
-                                    to_delete,
-                                    schema.name_singular,
-                                    schema.name_plural,
-                                );
+                                let message = match resolve_selection(selected.get()) {
+                                    ItemSelection::Some(items) if !items.is_empty() => {
+                                        format!(
+                                            "Download the {} selected.",
+                                            maybe_plural(
+                                                items.len(),
+                                                schema.name_singular,
+                                                schema.name_plural,
+                                            ),
+                                        )
+                                    }
+                                    _ if filter.get().is_some() => {
+                                        format!(
+                                            "Download all {} matching the current search.",
+                                            schema.name_plural,
+                                        )
+                                    }
+                                    _ => {
+                                        format!(
+                                            "Download all {}, including those not shown on this page.",
+                                            schema.name_plural,
+                                        )
+                                    }
+                                };
+                                export_secrets.set(false);
+                                export_json.set(false);
                                 modal
                                     .set(
-                                        Modal::with_title("Confirm deletion")
-                                            .with_message(
-                                                format!(
-                                                    "Are you sure you want to delete {text}? This action cannot be undone.",
-                                                ),
+                                        Modal::with_title(format!("Export {}", schema.name_plural))
+                                            .with_message(message)
+                                            .with_option(
+                                                "Include secrets such as private keys and passwords",
+                                                export_secrets,
                                             )
-                                            .with_button(format!("Delete {text}"))
-                                            .with_dangerous_callback(move || {
-                                                delete_action
-                                                    .dispatch(
-                                                        Arc::new(
-                                                            selected.try_update(std::mem::take).unwrap_or_default(),
-                                                        ),
-                                                    );
+                                            .with_option(
+                                                "Download as JSON instead of TOML",
+                                                export_json,
+                                            )
+                                            .with_button("Export")
+                                            .with_callback(move || {
+                                                export_action
+                                                    .dispatch((
+                                                        export_json.get_untracked(),
+                                                        export_secrets.get_untracked(),
+                                                    ));
                                             }),
-                                    )
+                                    );
+                            })
+                        >
+
+                            <IconArrowDownTray/>
+                        </ToolbarButton>
+                    </Show>
+
+                    <Show when=move || {
+                        let schema = current_schema.get();
+                        schema.has_list_action(Action::Import)
+                            && matches!(schema.typ, SchemaType::Record { .. })
+                    }>
+                        <ToolbarButton
+                            text="Import"
+
+                            color=Color::Gray
+                            on_click=move |_| {
+                                use_navigate()(
+                                    &format!("/settings/{}/import", current_schema.get().id),
+                                    Default::default(),
+                                );
                             }
-                        })
-                    >
+                        >
 
-                        <IconTrash/>
-                    </ToolbarButton>
+                            <IconArrowUpTray/>
+                        </ToolbarButton>
+                    </Show>
 
                     <ToolbarButton
                         text="Reload config"
@@ -292,7 +1169,7 @@ pub fn SettingsList() -> impl IntoView {
                 </Toolbar>
 
                 <Transition fallback=Skeleton>
-                    {move || match settings.get() {
+                    {move || match current_page() {
                         None => None,
                         Some(Err(http::Error::Unauthorized)) => {
                             use_navigate()("/login", Default::default());
@@ -312,15 +1189,86 @@ pub fn SettingsList() -> impl IntoView {
                                 .iter()
                                 .map(|f| f.label_column.to_string())
                                 .collect::<Vec<_>>();
-                            if schema.can_edit() {
+                            let sort_keys = schema
+                                .list
+                                .fields
+                                .iter()
+                                .map(|f| f.sortable.then(|| f.id.to_string()))
+                                .collect::<Vec<_>>();
+                            if schema.can_edit() || schema.can_clone() {
                                 headers.push("".to_string());
                             }
+                            let column_sort = ColumnSort {
+                                keys: sort_keys,
+                                current: sort.into(),
+                                on_sort: Callback::new(move |key: String| {
+                                    let order = match sort.get_untracked() {
+                                        Some((current, order)) if current == key => order.reverse(),
+                                        _ => SortOrder::Ascending,
+                                    };
+                                    use_navigate()(
+                                        &list_url(
+                                            vec![
+                                                ("sort".to_string(), Some(key)),
+                                                ("order".to_string(), Some(order.as_str().to_string())),
+                                            ],
+                                        ),
+                                        Default::default(),
+                                    );
+                                }),
+                            };
+                            let column_filters_ = ColumnFilters {
+                                columns: schema
+                                    .list
+                                    .fields
+                                    .iter()
+                                    .map(|f| {
+                                        f.filterable.then(|| (f.id.to_string(), column_filter(f)))
+                                    })
+                                    .collect(),
+                                current: column_filters.into(),
+                                on_filter: Callback::new(move |(id, value): (String, Option<String>)| {
+                                    use_navigate()(
+                                        &list_url(vec![(format!("{COLUMN_FILTER_PREFIX}{id}"), value)]),
+                                        Default::default(),
+                                    );
+                                }),
+                            };
+                            page_ids
+                                .set_value(
+                                    settings
+                                        .items
+                                        .iter()
+                                        .filter_map(|settings| settings.get("_id").cloned())
+                                        .collect(),
+                                );
+                            let items = store_value(settings.items);
+                            let total_rows = items.with_value(|items| items.len());
                             Some(
                                 view! {
-                                    <ColumnList headers=headers has_select_all=true>
+                                    <ColumnList
+                                        headers=headers
+                                        has_select_all=true
+                                        sort=column_sort
+                                        filters=column_filters_
+                                    >
+                                        <tr style=move || {
+                                            format!(
+                                                "height: {}px",
+                                                window.get().0.min(total_rows) as i32 * ROW_HEIGHT,
+                                            )
+                                        }></tr>
 
                                         <For
-                                            each=move || settings.items.clone()
+                                            each=move || {
+                                                let (start, end) = window.get();
+                                                items
+                                                    .with_value(|items| {
+                                                        items[start.min(total_rows)..end.min(total_rows)]
+                                                            .to_vec()
+                                                    })
+                                            }
+
                                             key=|setting| {
                                                 setting
                                                     .get("_id")
@@ -330,8 +1278,19 @@ pub fn SettingsList() -> impl IntoView {
 
                                             let:settings
                                         >
-                                            <SettingsItem settings schema=schema.clone()/>
+                                            <SettingsItem
+                                                settings
+                                                schema=schema.clone()
+                                                on_toggle
+                                                toggling
+                                            />
                                         </For>
+                                        <tr style=move || {
+                                            format!(
+                                                "height: {}px",
+                                                total_rows.saturating_sub(window.get().1) as i32 * ROW_HEIGHT,
+                                            )
+                                        }></tr>
 
                                     </ColumnList>
                                 }
@@ -340,6 +1299,58 @@ pub fn SettingsList() -> impl IntoView {
                         }
                         Some(Ok(_)) => {
                             total_results.set(Some(0));
+                            page_ids.set_value(vec![]);
+                            let schema = current_schema.get();
+                            if let Some(empty) = schema
+                                .list
+                                .empty
+                                .clone()
+                                .filter(|_| {
+                                    filter.get().is_none() && column_filters.get().is_empty()
+                                })
+                            {
+                                let (title, subtitle) = if empty.optional {
+                                    (
+                                        format!("No {} configured", schema.name_plural),
+                                        format!(
+                                            "{} These {} are optional, nothing is missing if you do not need them.",
+                                            empty.description,
+                                            schema.name_plural,
+                                        ),
+                                    )
+                                } else {
+                                    (
+                                        format!("No {} yet", schema.name_plural),
+                                        empty.description.to_string(),
+                                    )
+                                };
+                                let button_text = format!(
+                                    "Create your first {}",
+                                    schema.name_singular,
+                                );
+                                let url = UrlBuilder::new(format!("/settings/{}/edit", schema.id))
+                                    .with_optional_parameter("template", empty.template)
+                                    .finish();
+                                let button_action = schema
+                                    .can_create()
+                                    .then(|| {
+                                        Callback::new(move |_| {
+                                            use_navigate()(&url, Default::default());
+                                        })
+                                    });
+                                return Some(
+                                    view! {
+                                        <ZeroResults
+                                            title=title
+                                            subtitle=subtitle
+                                            button_text=button_text
+                                            button_action=button_action
+                                            doc_url=schema.doc.map(docs_url)
+                                        />
+                                    }
+                                        .into_view(),
+                                );
+                            }
                             Some(
                                 view! {
                                     <ZeroResults
@@ -369,17 +1380,137 @@ pub fn SettingsList() -> impl IntoView {
 
                 <Footer slot>
 
+                    {move || {
+                        let progress = bulk_progress.get();
+                        let ns = selected.get().total_selected(total_results.get());
+                        (ns > 0 || progress.is_some())
+                            .then(|| {
+                                let status = match progress {
+                                    Some((verb, done, total)) => {
+                                        format!("{verb} {done} of {total}...")
+                                    }
+                                    None => format!("{ns} selected"),
+                                };
+                                let is_busy = progress.is_some();
+                                view! {
+                                    <div class="px-6 py-3 flex flex-wrap items-center gap-x-4 gap-y-2 text-sm border-t border-gray-200 dark:border-gray-700">
+                                        <span class="font-semibold text-gray-800 dark:text-gray-200">
+                                            {status}
+                                        </span>
+                                        <Show when=move || {
+                                            !is_busy && current_schema.get().has_bulk_action(Action::Enable)
+                                        }>
+                                            <button
+                                                type="button"
+                                                class=SELECTION_BUTTON_CLASS
+                                                disabled=move || enable_action.pending().get()
+                                                on:click=move |_| {
+                                                    enable_action
+                                                        .dispatch((resolve_selection(selected.get()), true));
+                                                }
+                                            >
+
+                                                "Enable"
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class=SELECTION_BUTTON_CLASS
+                                                disabled=move || enable_action.pending().get()
+                                                on:click=move |_| {
+                                                    enable_action
+                                                        .dispatch((resolve_selection(selected.get()), false));
+                                                }
+                                            >
+
+                                                "Disable"
+                                            </button>
+                                        </Show>
+                                        <Show when=move || !is_busy>
+                                            <button
+                                                type="button"
+                                                class=SELECTION_BUTTON_CLASS
+                                                on:click=move |_| {
+                                                    let ids = page_ids.get_value();
+                                                    selected.update(|selected| selected.select_items(ids));
+                                                }
+                                            >
+
+                                                "Select all on this page"
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class=SELECTION_BUTTON_CLASS
+                                                on:click=move |_| {
+                                                    selected.set(ItemSelection::All);
+                                                }
+                                            >
+
+                                                {move || {
+                                                    if filter.get().is_some()
+                                                        || !column_filters.get().is_empty()
+                                                    {
+                                                        format!(
+                                                            "Select all {} matching the search",
+                                                            total_results.get().unwrap_or_default(),
+                                                        )
+                                                    } else {
+                                                        format!(
+                                                            "Select all {}",
+                                                            total_results.get().unwrap_or_default(),
+                                                        )
+                                                    }
+                                                }}
+
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class=SELECTION_BUTTON_CLASS
+                                                on:click=move |_| {
+                                                    selected.set(ItemSelection::None);
+                                                }
+                                            >
+
+                                                "Clear selection"
+                                            </button>
+                                        </Show>
+                                    </div>
+                                }
+                            })
+                    }}
+
                     <Pagination
                         current_page=page
                         total_results=total_results.read_only()
-                        page_size=Signal::derive(move || current_schema.get().list.page_size)
+                        page_size=page_size
+                        show_range=true
                         on_page_change=move |page: u32| {
                             use_navigate()(
-                                &UrlBuilder::new("/settings")
-                                    .with_subpath(current_schema.get().id)
-                                    .with_parameter("page", page.to_string())
-                                    .with_optional_parameter("filter", filter.get())
-                                    .finish(),
+                                &list_url(vec![("page".to_string(), Some(page.to_string()))]),
+                                Default::default(),
+                            );
+                        }
+                        page_sizes=Signal::derive(move || {
+                            if current_schema.get().list.fixed_page_size {
+                                &[][..]
+                            } else {
+                                PAGE_SIZES
+                            }
+                        })
+                        on_page_size_change=move |size: u32| {
+                            store_page_size(current_schema.get_untracked().id, size);
+                            // Stay on the page holding the first record shown so far
+                            let first = (page.get_untracked() - 1) * page_size.get_untracked();
+                            let last_page = total_results
+                                .get_untracked()
+                                .unwrap_or_default()
+                                .div_ceil(size)
+                                .max(1);
+                            let page = (first / size + 1).min(last_page);
+                            use_navigate()(
+                                &list_url(vec![
+                                    ("page".to_string(), Some(page.to_string())),
+                                    ("size".to_string(), Some(size.to_string())),
+                                ]),
                                 Default::default(),
                             );
                         }
@@ -391,31 +1522,387 @@ pub fn SettingsList() -> impl IntoView {
     }
 }
 
+async fn post_updates(auth: &AccessToken, updates: Vec<UpdateSettings>) -> http::Result<()> {
+    HttpRequest::post("/api/settings")
+        .with_authorization(auth)
+        .with_body(updates)
+        .unwrap()
+        .send::<serde_json::Value>()
+        .await
+        .map(|_| ())
+}
+
+// Writes the updates of a batch of records in one request, when the server
+// rejects it each record is retried on its own so only the failing ones are
+// reported
+pub(crate) async fn post_batch(
+    auth: &AccessToken,
+    batch: &[(String, UpdateSettings)],
+) -> http::Result<Vec<(String, String)>> {
+    match post_updates(
+        auth,
+        batch.iter().map(|(_, update)| update.clone()).collect(),
+    )
+    .await
+    {
+        Ok(_) => return Ok(vec![]),
+        Err(http::Error::Unauthorized) => return Err(http::Error::Unauthorized),
+        Err(err) if batch.len() == 1 => {
+            return Ok(vec![(batch[0].0.clone(), Alert::from(err).message)]);
+        }
+        Err(_) => {}
+    }
+
+    let mut failures = Vec::new();
+    for (id, update) in batch {
+        match post_updates(auth, vec![update.clone()]).await {
+            Ok(_) => {}
+            Err(http::Error::Unauthorized) => return Err(http::Error::Unauthorized),
+            Err(err) => failures.push((id.clone(), Alert::from(err).message)),
+        }
+    }
+    Ok(failures)
+}
+
+fn stored_page_size(schema_id: &str) -> Option<u32> {
+    LocalStorage::get::<BTreeMap<String, u32>>(STATE_PAGE_SIZES_KEY)
+        .ok()?
+        .remove(schema_id)
+}
+
+fn store_page_size(schema_id: &str, size: u32) {
+    let mut sizes =
+        LocalStorage::get::<BTreeMap<String, u32>>(STATE_PAGE_SIZES_KEY).unwrap_or_default();
+    sizes.insert(schema_id.to_string(), size);
+    let _ = LocalStorage::set(STATE_PAGE_SIZES_KEY, sizes);
+}
+
+// Fetches the settings about to be deleted so the deletion can be undone
+async fn capture_settings(
+    auth: &AccessToken,
+    schema: &Schema,
+    items: &ItemSelection,
+    is_filtered: bool,
+    total: Option<u32>,
+) -> http::Result<Option<Vec<UpdateSettings>>> {
+    let prefix = match schema.typ {
+        SchemaType::Record { prefix, .. }
+        | SchemaType::Entry { prefix }
+        | SchemaType::Map { prefix } => prefix,
+        SchemaType::List => return Ok(None),
+    };
+    let mut restore = Vec::new();
+
+    match items {
+        ItemSelection::All if !is_filtered && total.is_some_and(|t| t <= MAX_UNDO_ITEMS) => {
+            let items = fetch_prefix(auth, prefix).await?;
+            restore.push(UpdateSettings::Clear {
+                prefix: format!("{prefix}."),
+                filter: None,
+            });
+            restore.push(UpdateSettings::Insert {
+                prefix: Some(prefix.to_string()),
+                values: items.into_iter().collect(),
+                assert_empty: false,
+            });
+        }
+        ItemSelection::Some(items) => {
+            for item in items.iter().filter(|item| !item.is_empty()) {
+                if let SchemaType::Entry { .. } = schema.typ {
+                    let key = schema.record_key(item);
+                    if let Some(value) = HttpRequest::get("/api/settings/keys")
+                        .with_authorization(auth)
+                        .with_parameter("keys", key.clone())
+                        .send::<AHashMap<String, Option<String>>>()
+                        .await?
+                        .into_values()
+                        .next()
+                        .flatten()
+                    {
+                        restore.push(UpdateSettings::Insert {
+                            prefix: None,
+                            values: vec![(key, value)],
+                            assert_empty: false,
+                        });
+                    }
+                } else {
+                    let item_prefix = schema.record_key(item);
+                    let items = fetch_prefix(auth, &item_prefix).await?;
+                    restore.push(UpdateSettings::Clear {
+                        prefix: schema.record_prefix(item),
+                        filter: None,
+                    });
+                    restore.push(UpdateSettings::Insert {
+                        prefix: Some(item_prefix),
+                        values: items.into_iter().collect(),
+                        assert_empty: false,
+                    });
+                }
+            }
+        }
+        // Filtered or very large bulk deletions are not captured
+        _ => return Ok(None),
+    }
+
+    Ok(Some(restore))
+}
+
+// Records matching a search, across all pages. The server groups records
+// by the key named by the schema suffix, map records have no such key so
+// they are grouped and searched here
+pub(crate) async fn fetch_group(
+    auth: &AccessToken,
+    schema: &Schema,
+    filter: Option<String>,
+) -> http::Result<List<Settings>> {
+    if let SchemaType::Map { prefix } = &schema.typ {
+        let filter = filter.map(|filter| filter.to_lowercase());
+        let items = schema
+            .map_records(fetch_prefix(auth, prefix).await?)
+            .into_iter()
+            .filter(|record| {
+                filter.as_ref().map_or(true, |filter| {
+                    record
+                        .values()
+                        .any(|value| value.to_lowercase().contains(filter.as_str()))
+                })
+            })
+            .collect::<Vec<_>>();
+        return Ok(List {
+            total: items.len() as u64,
+            items,
+        });
+    }
+
+    HttpRequest::get("/api/settings/group")
+        .with_authorization(auth)
+        .with_parameter("prefix", schema.unwrap_prefix())
+        .with_parameter("suffix", schema.try_unwrap_suffix().unwrap_or_default())
+        .with_optional_parameter("filter", filter)
+        .send::<List<Settings>>()
+        .await
+}
+
+// Ids of the records matching a search, across all pages
+pub(crate) async fn fetch_ids(
+    auth: &AccessToken,
+    schema: &Schema,
+    filter: Option<String>,
+) -> http::Result<AHashSet<String>> {
+    fetch_group(auth, schema, filter).await.map(|list| {
+        list.items
+            .into_iter()
+            .filter_map(|mut item| item.remove("_id"))
+            .collect()
+    })
+}
+
+// Ids of the records matching a search with their value of a field
+async fn fetch_field_values(
+    auth: &AccessToken,
+    schema: &Schema,
+    filter: Option<String>,
+    field: &str,
+) -> http::Result<AHashMap<String, Option<String>>> {
+    fetch_group(auth, schema, filter).await.map(|list| {
+        list.items
+            .into_iter()
+            .filter_map(|mut item| Some((item.remove("_id")?, item.remove(field))))
+            .collect()
+    })
+}
+
+// Writes back the values a field had in each record before a change
+pub(crate) fn restore_field(
+    schema: &Schema,
+    field: &str,
+    previous: impl IntoIterator<Item = (String, Option<String>)>,
+) -> Vec<UpdateSettings> {
+    let mut restore = Vec::new();
+    let mut delete_keys = Vec::new();
+    for (id, value) in previous {
+        match value {
+            Some(value) => restore.push(UpdateSettings::Insert {
+                prefix: schema.record_key(&id).into(),
+                values: vec![(field.to_string(), value)],
+                assert_empty: false,
+            }),
+            None => delete_keys.push(schema.field_key(&id, field)),
+        }
+    }
+    if !delete_keys.is_empty() {
+        restore.push(UpdateSettings::Delete { keys: delete_keys });
+    }
+    restore
+}
+
+pub(crate) async fn fetch_prefix(auth: &AccessToken, prefix: &str) -> http::Result<Settings> {
+    HttpRequest::get("/api/settings/list")
+        .with_authorization(auth)
+        .with_parameter("prefix", prefix.to_string())
+        .send::<FetchSettings>()
+        .await
+        .map(|list| list.items)
+}
+
+fn column_filter(field: &Field) -> ColumnFilter {
+    match &field.typ_ {
+        Type::Boolean => ColumnFilter::Boolean,
+        Type::Select {
+            source: Source::Static(options),
+            ..
+        } => ColumnFilter::Options(
+            options
+                .iter()
+                .map(|(value, label)| (value.to_string(), label.to_string()))
+                .collect(),
+        ),
+        _ => ColumnFilter::Text,
+    }
+}
+
+fn matches_column_filter(field: &Field, settings: &Settings, value: &str) -> bool {
+    let id = settings.field_key(field);
+    match &field.typ_ {
+        Type::Boolean => settings.get(id).is_some_and(|v| v == "true") == (value == "true"),
+        Type::Select {
+            source: Source::Static(_),
+            ..
+        } => settings.array_values(id).iter().any(|(_, v)| *v == value),
+        _ => {
+            let value = value.to_lowercase();
+            settings
+                .array_values(id)
+                .iter()
+                .any(|(_, v)| v.to_lowercase().contains(&value))
+        }
+    }
+}
+
+// Numeric types are compared by value, everything else by its displayed text
+fn compare_settings(field: &Field, a: &Settings, b: &Settings) -> Ordering {
+    let numeric = |settings: &Settings| {
+        settings
+            .get(settings.field_key(field))
+            .and_then(|value| comparable_value(&field.typ_, value))
+    };
+    match (numeric(a), numeric(b)) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        _ => a
+            .format(field)
+            .to_lowercase()
+            .cmp(&b.format(field).to_lowercase()),
+    }
+}
+
+// Only changes how the value looks, an em dash keeps empty cells visible
+fn display_value(value: DisplayValue) -> View {
+    match value {
+        DisplayValue::Empty => {
+            view! { <span class="text-gray-300 dark:text-gray-600">"—"</span> }.into_view()
+        }
+        DisplayValue::Text(text) => text.into_view(),
+        DisplayValue::Boolean(true) => view! {
+            <Badge color=Color::Green>
+                <IconCheckCircle attr:class="flex-shrink-0 size-3"/>
+                <span class="sr-only">"Yes"</span>
+            </Badge>
+        }
+        .into_view(),
+        DisplayValue::Boolean(false) => view! {
+            <Badge color=Color::Gray>
+                "—"
+                <span class="sr-only">"No"</span>
+            </Badge>
+        }
+        .into_view(),
+        DisplayValue::Unknown(value) => view! {
+            <span class="italic text-gray-400 dark:text-gray-600" title="Not a known value">
+                {value}
+            </span>
+        }
+        .into_view(),
+    }
+}
+
 #[component]
-fn SettingsItem(settings: Settings, schema: Arc<Schema>) -> impl IntoView {
+fn SettingsItem(
+    settings: Settings,
+    schema: Arc<Schema>,
+    on_toggle: Callback<(String, Arc<Field>, bool)>,
+    #[prop(into)] toggling: Signal<bool>,
+) -> impl IntoView {
+    let setting_id = settings
+        .get("_id")
+        .map(|s| s.to_string())
+        .unwrap_or_default();
     let columns = schema
         .list
         .fields
         .iter()
         .map(|field| {
-            let value = settings.format(field);
-            view! { <ListTextItem>{value}</ListTextItem> }
+            if schema.toggle_mode(field).is_some() {
+                let id = setting_id.clone();
+                let field = field.clone();
+                let checked = settings.get(field.id).is_some_and(|v| v == "true");
+                let label = field.label_column;
+                view! {
+                    <ListItem>
+                        <input
+                            type="checkbox"
+                            class=SWITCH_CLASS
+                            aria-label=label
+                            prop:checked=checked
+                            disabled=move || toggling.get()
+                            on:change=move |ev| {
+                                on_toggle.call((id.clone(), field.clone(), event_target_checked(&ev)));
+                            }
+                        />
+
+                    </ListItem>
+                }
+                .into_view()
+            } else {
+                let value = display_value(settings.display_value(field));
+                view! { <ListTextItem>{value}</ListTextItem> }.into_view()
+            }
         })
         .collect_view();
-    let setting_id = settings
-        .get("_id")
-        .map(|s| s.to_string())
-        .unwrap_or_default();
-    let edit_link = if schema.can_edit() {
-        let edit_url = format!("/settings/{}/{}/edit", schema.id, setting_id);
+    let edit_link = if schema.can_edit() || schema.can_clone() {
+        let edit_url = schema
+            .can_edit()
+            .then(|| format!("/settings/{}/{}/edit", schema.id, setting_id));
+        let clone_url = schema.can_clone().then(|| {
+            UrlBuilder::new(format!("/settings/{}/edit", schema.id))
+                .with_parameter("clone", setting_id.clone())
+                .finish()
+        });
         Some(view! {
             <ListItem subclass="px-6 py-1.5">
-                <a
-                    class="inline-flex items-center gap-x-1 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
-                    href=edit_url
-                >
-                    Edit
-                </a>
+                <div class="inline-flex items-center gap-x-3">
+                    {clone_url
+                        .map(|clone_url| {
+                            view! {
+                                <a
+                                    class="inline-flex items-center gap-x-1 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                                    href=clone_url
+                                >
+                                    Duplicate
+                                </a>
+                            }
+                        })}
+                    {edit_url
+                        .map(|edit_url| {
+                            view! {
+                                <a
+                                    class="inline-flex items-center gap-x-1 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                                    href=edit_url
+                                >
+                                    Edit
+                                </a>
+                            }
+                        })}
+                </div>
             </ListItem>
         })
     } else {
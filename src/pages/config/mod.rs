@@ -4,10 +4,29 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+pub mod bulk;
+pub mod certificate;
+#[cfg(feature = "form-debug")]
+pub mod debug;
+pub mod defaults;
+pub mod dnsbl;
+pub mod download;
+pub mod draft;
 pub mod edit;
+pub mod errors;
+pub mod explorer;
+pub mod export;
+pub mod group;
+pub mod history;
+pub mod import;
 pub mod list;
+pub mod notify;
+pub mod placeholders;
+pub mod preview;
+pub mod references;
 pub mod schema;
 pub mod search;
+pub mod version;
 
 use std::{collections::BTreeMap, str::FromStr};
 
@@ -16,24 +35,25 @@ use crate::{
         form::input::{Duration, Rate},
         icon::{
             IconCalendarDays, IconCircleStack, IconCodeBracket, IconHandRaised, IconInbox,
-            IconInboxArrowDown, IconInboxStack, IconKey, IconServer, IconServerStack,
-            IconShieldCheck, IconSignal,
+            IconInboxArrowDown, IconInboxStack, IconKey, IconQueueList, IconServer,
+            IconServerStack, IconShieldCheck, IconSignal,
         },
         layout::{LayoutBuilder, MenuItem},
     },
     core::{
-        form::{FormData, FormValue},
+        form::{parse_size, FormData, FormValue},
         schema::*,
     },
+    pages::maybe_plural,
 };
 use ahash::AHashMap;
-use humansize::{format_size, DECIMAL};
+use humansize::{format_size, BINARY};
 use leptos::view;
 use serde::{Deserialize, Serialize};
 
 pub type Settings = AHashMap<String, String>;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 #[serde(rename_all = "camelCase")]
 pub enum UpdateSettings {
@@ -85,24 +105,25 @@ impl FormData {
         let mut assert_empty = false;
 
         match &self.schema.typ {
-            SchemaType::Record { prefix, .. } => {
+            SchemaType::Record { .. } | SchemaType::Map { .. } => {
+                let id = self.value_as_str("_id").unwrap();
                 if self.is_update {
                     updates.push(UpdateSettings::Clear {
-                        prefix: format!("{prefix}.{}.", self.value_as_str("_id").unwrap()),
+                        prefix: self.schema.record_prefix(id),
                         filter: None,
                     });
                 } else {
                     assert_empty = true;
                 }
 
-                insert_prefix = format!("{prefix}.{}", self.value_as_str("_id").unwrap()).into();
+                insert_prefix = self.schema.record_key(id).into();
             }
-            SchemaType::Entry { prefix } => {
+            SchemaType::Entry { .. } => {
                 updates.push(UpdateSettings::Insert {
                     prefix: None,
                     assert_empty: !self.is_update,
                     values: vec![(
-                        format!("{prefix}.{}", self.value_as_str("_id").unwrap()),
+                        self.schema.record_key(self.value_as_str("_id").unwrap()),
                         self.value_as_str("_value").unwrap_or_default().to_string(),
                     )],
                 });
@@ -112,17 +133,29 @@ impl FormData {
                 if self.is_update {
                     let mut delete_keys = Vec::new();
                     for field in self.schema.fields.values() {
-                        if field.is_multivalue() {
+                        if !self.is_supported(field) || self.missing_capability(field).is_some() {
+                            continue;
+                        } else if field.is_multivalue() {
                             updates.push(UpdateSettings::Clear {
                                 prefix: format!("{}.", field.id),
                                 filter: None,
                             });
                             delete_keys.push(field.id.to_string());
-                        } else if self.value_is_empty(field.id) {
+                        } else if self.value_is_empty(field.id)
+                            || self.is_hidden(field)
+                            || self.is_defaulted(field)
+                        {
                             delete_keys.push(field.id.to_string());
                         }
                     }
 
+                    for group in self.schema.groups.iter() {
+                        updates.push(UpdateSettings::Clear {
+                            prefix: format!("{}.", group.id),
+                            filter: None,
+                        });
+                    }
+
                     if !delete_keys.is_empty() {
                         updates.push(UpdateSettings::Delete { keys: delete_keys });
                     }
@@ -130,9 +163,68 @@ impl FormData {
             }
         }
 
+        let mut key_values = self.key_values();
+
+        // Entries are numbered again, so removed ones leave no gaps
+        for group in self.schema.groups.iter() {
+            let items = self
+                .group_items(group.id)
+                .iter()
+                .map(|item| item.key_values())
+                .filter(|values| !values.is_empty())
+                .collect::<Vec<_>>();
+            let pad_len = items.len().saturating_sub(1).to_string().len();
+            for (idx, values) in items.into_iter().enumerate() {
+                key_values.extend(values.into_iter().map(|(key, value)| {
+                    (group.item_key(format!("{idx:0>pad_len$}"), &key), value)
+                }));
+            }
+        }
+
+        for legacy in self.legacy.values() {
+            if legacy.keep {
+                key_values.extend(legacy.values.iter().cloned());
+            } else if matches!(self.schema.typ, SchemaType::List) {
+                updates.push(UpdateSettings::Clear {
+                    prefix: format!("{}.", legacy.key),
+                    filter: None,
+                });
+                updates.push(UpdateSettings::Delete {
+                    keys: vec![legacy.key.to_string()],
+                });
+            }
+        }
+
+        if insert_prefix.is_some() {
+            key_values.extend(
+                self.unknown
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone())),
+            );
+        }
+
+        if !key_values.is_empty() {
+            updates.push(UpdateSettings::Insert {
+                prefix: insert_prefix,
+                values: key_values,
+                assert_empty,
+            });
+        }
+
+        updates
+    }
+
+    fn key_values(&self) -> Vec<(String, String)> {
         let mut key_values = Vec::new();
         for (key, value) in &self.values {
-            if key.starts_with('_') {
+            // Hidden and gated fields are not validated, so their values are not written
+            if key.starts_with('_')
+                || self.schema.fields.get(key.as_str()).is_some_and(|field| {
+                    self.is_hidden(field)
+                        || self.missing_capability(field).is_some()
+                        || self.is_defaulted(field)
+                })
+            {
                 continue;
             }
 
@@ -152,6 +244,16 @@ impl FormData {
                         key_values.push((key.to_string(), values.first().unwrap().to_string()));
                     }
                 }
+                FormValue::Map(entries) => {
+                    key_values.extend(
+                        entries
+                            .iter()
+                            .filter(|(entry_key, _)| !entry_key.is_empty())
+                            .map(|(entry_key, value)| {
+                                (format!("{key}.{entry_key}"), value.clone())
+                            }),
+                    );
+                }
                 FormValue::Expression(expr) if !expr.is_empty() => {
                     if !expr.if_thens.is_empty() {
                         let total_values = expr.if_thens.len();
@@ -180,21 +282,38 @@ impl FormData {
             }
         }
 
-        if !key_values.is_empty() {
-            updates.push(UpdateSettings::Insert {
-                prefix: insert_prefix,
-                values: key_values,
-                assert_empty,
-            });
+        // The record prefix is cleared on update, so gated fields keep the
+        // values they were loaded with
+        for (id, values) in &self.stored {
+            if self
+                .schema
+                .fields
+                .get(id)
+                .is_some_and(|field| self.missing_capability(field).is_some())
+            {
+                key_values.extend(values.iter().cloned());
+            }
         }
-
-        updates
+        key_values
     }
 }
 
 pub trait SettingsValues {
     fn array_values(&self, prefix: &str) -> Vec<(&str, &str)>;
+    fn field_key<'x>(&self, field: &'x Field) -> &'x str;
     fn format(&self, field: &Field) -> String;
+    fn display_value(&self, field: &Field) -> DisplayValue;
+}
+
+// How a stored value is shown in a list column, sorting and filtering keep
+// using the stored value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DisplayValue {
+    Empty,
+    Text(String),
+    Boolean(bool),
+    // Values outside the options of the field, shown as stored
+    Unknown(String),
 }
 
 impl SettingsValues for Settings {
@@ -218,57 +337,171 @@ impl SettingsValues for Settings {
         results
     }
 
+    // Records saved by older server versions may only have an alias set
+    fn field_key<'x>(&self, field: &'x Field) -> &'x str {
+        std::iter::once(field.id)
+            .chain(field.aliases.iter().copied())
+            .find(|key| !self.array_values(key).is_empty())
+            .unwrap_or(field.id)
+    }
+
     fn format(&self, field: &Field) -> String {
+        let id = self.field_key(field);
         match &field.typ_ {
             Type::Select {
-                source: Source::Static(items),
+                source: Source::Static(_) | Source::Merged(_),
                 typ: SelectType::Single,
             } => {
-                let value = self
-                    .get(field.id)
-                    .map(|s| s.as_str())
-                    .unwrap_or_default()
-                    .to_string();
-                items
-                    .iter()
-                    .find_map(|(k, v)| if k == &value { Some(*v) } else { None })
-                    .map(|s| s.to_string())
-                    .unwrap_or(value)
+                let value = self.get(id).map(|s| s.as_str()).unwrap_or_default();
+                field.typ_.label(value).to_string()
             }
-            Type::Array(_) => self
-                .array_values(field.id)
+            Type::Array(_) | Type::Tag => self
+                .array_values(id)
                 .first()
                 .map(|(_, v)| v.to_string())
                 .unwrap_or_default(),
+            Type::Map => {
+                let prefix = format!("{id}.");
+                let total = self.keys().filter(|key| key.starts_with(&prefix)).count();
+                if total > 0 {
+                    maybe_plural(total, "entry", "entries")
+                } else {
+                    String::new()
+                }
+            }
             Type::Boolean => {
-                if self.get(field.id).is_some_and(|s| s == "true") {
+                if self.get(id).is_some_and(|s| s == "true") {
                     "Yes".to_string()
                 } else {
                     "No".to_string()
                 }
             }
             Type::Duration => self
-                .get(field.id)
+                .get(id)
                 .and_then(|s| Duration::from_str(s).ok())
                 .and_then(|d| d.format())
                 .unwrap_or_default(),
             Type::Rate => self
-                .get(field.id)
+                .get(id)
                 .and_then(|s| Rate::from_str(s).ok())
                 .and_then(|d| d.format())
                 .unwrap_or_default(),
             Type::Size => self
-                .get(field.id)
-                .and_then(|s| s.parse::<u64>().ok())
-                .map(|s| format_size(s, DECIMAL))
+                .get(id)
+                .and_then(|s| parse_size(s))
+                .map(|s| format_size(s, BINARY))
                 .unwrap_or_default(),
             _ => self
-                .get(field.id)
+                .get(id)
                 .map(|s| s.as_str())
                 .unwrap_or_default()
                 .to_string(),
         }
     }
+
+    fn display_value(&self, field: &Field) -> DisplayValue {
+        let id = self.field_key(field);
+        match &field.typ_ {
+            Type::Map | Type::Expression => {
+                let value = self.format(field);
+                if !value.is_empty() {
+                    DisplayValue::Text(value)
+                } else {
+                    DisplayValue::Empty
+                }
+            }
+            Type::Array(_)
+            | Type::Tag
+            | Type::Select {
+                typ: SelectType::Many | SelectType::ManyWithSearch,
+                ..
+            } => {
+                let mut values = self
+                    .array_values(id)
+                    .into_iter()
+                    .map(|(_, value)| field.display_value(value))
+                    .filter(|value| *value != DisplayValue::Empty)
+                    .collect::<Vec<_>>();
+                if values.len() > 1 {
+                    DisplayValue::Text(
+                        values
+                            .iter()
+                            .map(DisplayValue::to_text)
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    )
+                } else {
+                    values.pop().unwrap_or(DisplayValue::Empty)
+                }
+            }
+            _ => field.display_value(self.get(id).map(|s| s.as_str()).unwrap_or_default()),
+        }
+    }
+}
+
+impl Field {
+    // Formats a single stored value by the type of the field, values that
+    // cannot be read as that type are returned as unknown
+    pub fn display_value(&self, value: &str) -> DisplayValue {
+        if value.trim().is_empty() {
+            return DisplayValue::Empty;
+        }
+        let text = match &self.typ_ {
+            Type::Boolean => return DisplayValue::Boolean(value == "true"),
+            Type::Duration if value == "false" => Some("Disabled".to_string()),
+            Type::Duration | Type::Array(ArrayType::Duration) => {
+                Duration::millis(value).and_then(|millis| Duration::from_millis(millis).format())
+            }
+            Type::Size => parse_size(value).map(|size| format_size(size, BINARY)),
+            Type::Rate => Rate::from_str(value).ok().and_then(|rate| rate.format()),
+            Type::Select { source, .. } => {
+                let leaves = source.leaves();
+                leaves
+                    .iter()
+                    .find_map(|source| match source {
+                        Source::Static(items) => items
+                            .iter()
+                            .find(|(id, _)| *id == value)
+                            .map(|(_, label)| label.to_string()),
+                        Source::StaticId(items) => items
+                            .iter()
+                            .any(|id| *id == value)
+                            .then(|| value.to_string()),
+                        _ => None,
+                    })
+                    // Ids of other records are shown as stored
+                    .or_else(|| {
+                        leaves
+                            .iter()
+                            .any(|source| {
+                                matches!(
+                                    source,
+                                    Source::Dynamic { .. }
+                                        | Source::DynamicSelf { .. }
+                                        | Source::DynamicRemote { .. }
+                                )
+                            })
+                            .then(|| value.to_string())
+                    })
+            }
+            _ => Some(value.to_string()),
+        };
+        text.map_or_else(
+            || DisplayValue::Unknown(value.to_string()),
+            DisplayValue::Text,
+        )
+    }
+}
+
+impl DisplayValue {
+    pub fn to_text(&self) -> String {
+        match self {
+            DisplayValue::Empty => String::new(),
+            DisplayValue::Text(text) | DisplayValue::Unknown(text) => text.clone(),
+            DisplayValue::Boolean(true) => "Yes".to_string(),
+            DisplayValue::Boolean(false) => "No".to_string(),
+        }
+    }
 }
 
 impl LayoutBuilder {
@@ -498,6 +731,9 @@ impl LayoutBuilder {
             .create("TLS")
             .route("/report-tls/edit")
             .insert(true)
+            .create("Message Templates")
+            .route("/message-templates/edit")
+            .insert(true)
             .insert(true)
             .insert(true)
             // Message Store
@@ -585,6 +821,9 @@ impl LayoutBuilder {
             .create("DNS blocklists")
             .route("/spam-dnsbl")
             .insert(true)
+            .create("Inbound filtering")
+            .route("/inbound-filtering/edit")
+            .insert(true)
             .create("Classifier")
             .route("/spam-classifier/edit")
             .insert(true)
@@ -631,6 +870,10 @@ impl LayoutBuilder {
             .route("/untrusted-script")
             .insert(true)
             .insert(true)
+            .create("All settings")
+            .icon(view! { <IconQueueList/> })
+            .route("/explorer")
+            .insert(true)
             .create("Management")
             .icon(view! { <IconServer/> })
             .raw_route(manage_url)
@@ -0,0 +1,190 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::collections::BTreeMap;
+
+use leptos::*;
+use leptos_router::use_navigate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{
+        form::button::Button,
+        messages::alert::{use_alerts, Alert},
+        Color,
+    },
+    core::{
+        form::FormData,
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        schema::NotificationTest,
+    },
+};
+
+#[derive(Debug, Clone, Serialize)]
+struct TestRequest {
+    #[serde(rename = "type")]
+    typ: &'static str,
+    destinations: Vec<String>,
+    settings: BTreeMap<&'static str, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum TestOutcome {
+    Success,
+    DnsFailure,
+    ConnectionRefused,
+    AuthRejected,
+    #[serde(other)]
+    Failed,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+struct TestResult {
+    result: TestOutcome,
+    #[serde(default)]
+    details: Option<String>,
+}
+
+impl NotificationTest {
+    fn id(&self) -> &'static str {
+        match self {
+            NotificationTest::Email => "email",
+            NotificationTest::Webhook => "webhook",
+        }
+    }
+
+    // Related fields sent along with the destination so unsaved changes are tested as well
+    fn settings(&self) -> &'static [&'static str] {
+        match self {
+            NotificationTest::Email => &[],
+            NotificationTest::Webhook => &[
+                "allow-invalid-certs",
+                "timeout",
+                "signature-key",
+                "headers",
+                "auth.username",
+                "auth.secret",
+            ],
+        }
+    }
+
+    fn request(&self, id: &str, data: &FormData) -> TestRequest {
+        TestRequest {
+            typ: self.id(),
+            destinations: data
+                .array_value(id)
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_string())
+                .collect(),
+            settings: self
+                .settings()
+                .iter()
+                .filter(|key| data.has_value(key))
+                .map(|key| (*key, data.array_value(key).map(String::from).collect()))
+                .collect(),
+        }
+    }
+}
+
+impl TestResult {
+    fn message(&self, test: NotificationTest) -> String {
+        let message = match (&self.result, test) {
+            (TestOutcome::Success, NotificationTest::Email) => {
+                "Test message accepted by the remote server."
+            }
+            (TestOutcome::Success, NotificationTest::Webhook) => {
+                "Test webhook delivered successfully."
+            }
+            (TestOutcome::DnsFailure, _) => "DNS lookup failed for the destination.",
+            (TestOutcome::ConnectionRefused, _) => "Connection refused by the destination.",
+            (TestOutcome::AuthRejected, NotificationTest::Email) => {
+                "The remote SMTP server rejected the authentication."
+            }
+            (TestOutcome::AuthRejected, NotificationTest::Webhook) => {
+                "The webhook endpoint rejected the credentials."
+            }
+            (TestOutcome::Failed, _) => "Test notification failed.",
+        };
+
+        match &self.details {
+            Some(details) if !details.is_empty() => format!("{message} {details}"),
+            _ => message.to_string(),
+        }
+    }
+}
+
+#[component]
+pub fn NotificationTestButton(
+    field_id: &'static str,
+    test: NotificationTest,
+    data: RwSignal<FormData>,
+) -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let result = create_rw_signal(None::<TestResult>);
+    let has_destination = create_memo(move |_| {
+        data.with(|data| data.array_value(field_id).any(|v| !v.trim().is_empty()))
+    });
+
+    let send_test = create_action(move |request: &TestRequest| {
+        let auth = auth.get();
+        let request = request.clone();
+
+        async move {
+            result.set(None);
+            match HttpRequest::post("/api/notification/test")
+                .with_authorization(&auth)
+                .with_body(request)
+                .unwrap()
+                .send::<TestResult>()
+                .await
+            {
+                Ok(response) => {
+                    result.set(Some(response));
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+
+    view! {
+        <div class="mt-2 flex flex-wrap items-center gap-x-3 gap-y-2">
+            <Button
+                text="Send test notification"
+                color=Color::Gray
+                disabled=Signal::derive(move || !has_destination.get())
+                loading=send_test.pending()
+                on_click=move |_| {
+                    send_test.dispatch(data.with_untracked(|data| test.request(field_id, data)));
+                }
+            />
+
+            <p class="text-sm" role="status" aria-live="polite">
+                {move || {
+                    result
+                        .get()
+                        .map(|result| {
+                            let class = if result.result == TestOutcome::Success {
+                                "text-green-600 dark:text-green-500"
+                            } else {
+                                "text-red-600 dark:text-red-500"
+                            };
+                            view! { <span class=class>{result.message(test)}</span> }
+                        })
+                }}
+
+            </p>
+        </div>
+    }
+}
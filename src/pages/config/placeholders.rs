@@ -0,0 +1,99 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use leptos::*;
+
+use crate::{
+    components::{form::button::Button, Color},
+    core::{
+        form::{render_template, unknown_placeholders, FormData},
+        schema::Field,
+    },
+};
+
+#[component]
+pub fn PlaceholderPreview(field: Arc<Field>, data: RwSignal<FormData>) -> impl IntoView {
+    let field_id = field.id;
+    let placeholders = field.placeholders();
+    let value =
+        create_memo(move |_| data.with(|data| data.value::<String>(field_id).unwrap_or_default()));
+    let unknown = create_memo(move |_| {
+        value.with(|value| {
+            unknown_placeholders(value, placeholders)
+                .map(|name| format!("{{{{{name}}}}}"))
+                .collect::<Vec<_>>()
+        })
+    });
+    let is_default = create_memo(move |_| {
+        data.with(|data| field.default(data).as_ref() == data.values.get(field_id))
+    });
+
+    view! {
+        <div class="mt-3 grid gap-4 sm:grid-cols-3">
+            <div class="sm:col-span-2">
+                <p class="text-xs font-semibold uppercase text-gray-500 dark:text-gray-400">
+                    Preview
+                </p>
+                <pre class="mt-1 p-3 whitespace-pre-wrap break-words text-sm bg-gray-50 border border-gray-200 rounded-lg text-gray-800 dark:bg-slate-800 dark:border-gray-700 dark:text-gray-200">
+                    {move || value.with(|value| render_template(value, placeholders))}
+                </pre>
+                <Show when=move || unknown.with(|unknown| !unknown.is_empty())>
+                    <p class="mt-2 text-xs text-yellow-600 dark:text-yellow-500">
+                        {move || format!("Unknown placeholders: {}", unknown.get().join(", "))}
+                    </p>
+                </Show>
+            </div>
+            <div>
+                <p class="text-xs font-semibold uppercase text-gray-500 dark:text-gray-400">
+                    Placeholders
+                </p>
+                <ul class="mt-1 space-y-1 text-xs">
+                    {placeholders
+                        .iter()
+                        .map(|placeholder| {
+                            let name = placeholder.name;
+                            view! {
+                                <li>
+                                    <button
+                                        type="button"
+                                        class="font-mono text-blue-600 decoration-2 hover:underline dark:text-blue-500"
+                                        title="Insert placeholder"
+                                        on:click=move |_| {
+                                            data.update(|data| {
+                                                let value = data
+                                                    .value::<String>(field_id)
+                                                    .unwrap_or_default();
+                                                data.update(field_id, format!("{value}{{{{{name}}}}}"));
+                                            });
+                                        }
+                                    >
+
+                                        {format!("{{{{{name}}}}}")}
+                                    </button>
+                                    <span class="ms-1 text-gray-500 dark:text-gray-400">
+                                        {placeholder.description}
+                                    </span>
+                                </li>
+                            }
+                        })
+                        .collect_view()}
+                </ul>
+            </div>
+        </div>
+        <div class="mt-2">
+            <Button
+                text="Restore default"
+                color=Color::Gray
+                disabled=is_default
+                on_click=move |_| {
+                    data.update(|data| data.reset_field(field_id));
+                }
+            />
+        </div>
+    }
+}
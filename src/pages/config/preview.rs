@@ -0,0 +1,296 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use leptos::*;
+
+use crate::{
+    components::{
+        diff::DiffEntry,
+        form::button::Button,
+        icon::IconCodeBracket,
+        messages::alert::{use_alerts, Alert},
+        Color,
+    },
+    core::{
+        form::FormData,
+        schema::{Schema, SchemaType, Type, KEY_SEPARATOR},
+    },
+    pages::config::{Settings, UpdateSettings},
+};
+
+const MASKED: &str = "********";
+const SECRET: &str = "•••";
+
+// Setting keys a save adds, modifies (old and new value) and removes
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KeyChanges {
+    pub added: Vec<(String, String)>,
+    pub modified: Vec<(String, String, String)>,
+    pub removed: Vec<(String, String)>,
+}
+
+#[component]
+pub fn ConfigPreview(data: RwSignal<FormData>) -> impl IntoView {
+    let alert = use_alerts();
+    let is_open = create_rw_signal(false);
+    let toml = create_memo(move |_| {
+        if is_open.get() {
+            data.with(|data| format_toml(&data.schema, &data.build_update()))
+        } else {
+            String::new()
+        }
+    });
+
+    view! {
+        <div class="mt-5 border border-gray-200 rounded-xl dark:border-gray-700">
+            <button
+                type="button"
+                class="w-full flex items-center gap-x-2 px-4 py-3 text-sm font-semibold text-gray-800 hover:text-blue-600 dark:text-gray-200 dark:hover:text-gray-400"
+                on:click=move |_| is_open.update(|is_open| *is_open = !*is_open)
+            >
+                <IconCodeBracket attr:class="flex-shrink-0 size-4"/>
+                {move || if is_open.get() { "Hide config preview" } else { "Preview config" }}
+            </button>
+            <Show when=move || is_open.get()>
+                <div class="px-4 pb-4">
+                    <pre class="p-3 overflow-x-auto text-xs font-mono bg-gray-50 border border-gray-200 rounded-lg text-gray-800 dark:bg-slate-800 dark:border-gray-700 dark:text-gray-200">
+                        {move || {
+                            let toml = toml.get();
+                            if toml.is_empty() {
+                                "# No changes to write".to_string()
+                            } else {
+                                toml
+                            }
+                        }}
+                    </pre>
+                    <div class="mt-2">
+                        <Button
+                            text="Copy"
+                            color=Color::Gray
+                            on_click=move |_| {
+                                let _ = window()
+                                    .navigator()
+                                    .clipboard()
+                                    .write_text(&toml.get_untracked());
+                                alert.set(Alert::success("Configuration copied to clipboard"));
+                            }
+                        />
+                    </div>
+                </div>
+            </Show>
+        </div>
+    }
+}
+
+// Renders the updates built for saving as TOML, secrets are masked and
+// removals are listed as comments
+pub fn format_toml(schema: &Schema, updates: &[UpdateSettings]) -> String {
+    let mut toml = String::new();
+
+    for update in updates {
+        match update {
+            UpdateSettings::Delete { keys } => {
+                for key in keys {
+                    let _ = writeln!(toml, "# delete {key}");
+                }
+            }
+            UpdateSettings::Clear { prefix, .. } => {
+                let _ = writeln!(toml, "# delete {prefix}*");
+            }
+            UpdateSettings::Insert { prefix, values, .. } => {
+                for (key, value) in values {
+                    let field_key =
+                        if prefix.is_none() && matches!(schema.typ, SchemaType::Entry { .. }) {
+                            "_value"
+                        } else {
+                            key.as_str()
+                        };
+                    let value = if is_secret(schema, field_key) {
+                        MASKED
+                    } else {
+                        value.as_str()
+                    };
+                    let key = match prefix {
+                        Some(prefix) => format!("{prefix}.{key}"),
+                        None => key.to_string(),
+                    };
+                    let _ = writeln!(toml, "{} = {}", toml_key(&key), toml_string(value));
+                }
+            }
+        }
+    }
+
+    toml
+}
+
+// Keys written by a save compared with the settings the form was loaded
+// from, array items are compared by their numbered keys
+pub fn key_changes(data: &FormData, original: &Settings, updates: &[UpdateSettings]) -> KeyChanges {
+    let schema = &data.schema;
+    let before = stored_keys(schema, original);
+    let mut after = before.clone();
+    for update in updates {
+        match update {
+            UpdateSettings::Delete { keys } => {
+                for key in keys {
+                    after.remove(key);
+                }
+            }
+            UpdateSettings::Clear { prefix, .. } => {
+                after.retain(|key, _| !key.starts_with(prefix.as_str()));
+            }
+            UpdateSettings::Insert { prefix, values, .. } => {
+                for (key, value) in values {
+                    let key = match prefix {
+                        Some(prefix) => format!("{prefix}{KEY_SEPARATOR}{key}"),
+                        None => key.to_string(),
+                    };
+                    after.insert(key, value.to_string());
+                }
+            }
+        }
+    }
+
+    let record_prefix = match schema.typ {
+        SchemaType::Record { .. } | SchemaType::Map { .. } => {
+            data.value_as_str("_id").map(|id| schema.record_prefix(id))
+        }
+        _ => None,
+    };
+    let mask = |key: &str, value: &str| {
+        let field_key = match (&schema.typ, &record_prefix) {
+            (SchemaType::Entry { .. }, _) => "_value",
+            (_, Some(prefix)) => key.strip_prefix(prefix.as_str()).unwrap_or(key),
+            _ => key,
+        };
+        if is_secret(schema, field_key) {
+            SECRET.to_string()
+        } else {
+            value.to_string()
+        }
+    };
+
+    let mut changes = KeyChanges::default();
+    for (key, value) in &after {
+        match before.get(key) {
+            Some(old) if old == value => (),
+            Some(old) => changes
+                .modified
+                .push((key.clone(), mask(key, old), mask(key, value))),
+            None => changes.added.push((key.clone(), mask(key, value))),
+        }
+    }
+    for (key, value) in &before {
+        if !after.contains_key(key) {
+            changes.removed.push((key.clone(), mask(key, value)));
+        }
+    }
+
+    changes
+}
+
+// Settings are fetched relative to the record, keys are stored in full
+fn stored_keys(schema: &Schema, settings: &Settings) -> BTreeMap<String, String> {
+    match schema.typ {
+        SchemaType::Record { .. } | SchemaType::Map { .. } => settings
+            .get("_id")
+            .map(|id| {
+                let record_prefix = schema.record_prefix(id);
+                settings
+                    .iter()
+                    .filter(|(key, _)| !key.starts_with('_'))
+                    .map(|(key, value)| (format!("{record_prefix}{key}"), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        SchemaType::Entry { .. } => settings
+            .get("_id")
+            .zip(settings.get("_value"))
+            .map(|(id, value)| (schema.record_key(id), value.clone()))
+            .into_iter()
+            .collect(),
+        SchemaType::List => settings
+            .iter()
+            .filter(|(key, _)| !key.starts_with('_'))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+    }
+}
+
+impl KeyChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+
+    // Rows for the diff table, grouped by the kind of change
+    pub fn entries(&self) -> Vec<DiffEntry> {
+        self.added
+            .iter()
+            .map(|(key, value)| DiffEntry {
+                key: key.clone(),
+                old: None,
+                new: Some(value.clone()),
+            })
+            .chain(self.modified.iter().map(|(key, old, new)| DiffEntry {
+                key: key.clone(),
+                old: Some(old.clone()),
+                new: Some(new.clone()),
+            }))
+            .chain(self.removed.iter().map(|(key, value)| DiffEntry {
+                key: key.clone(),
+                old: Some(value.clone()),
+                new: None,
+            }))
+            .collect()
+    }
+}
+
+pub(crate) fn is_secret(schema: &Schema, key: &str) -> bool {
+    schema
+        .fields
+        .values()
+        .filter(|field| matches!(field.typ_, Type::Secret))
+        .any(|field| field.matches_key(key))
+}
+
+pub(crate) fn toml_key(key: &str) -> String {
+    key.split('.')
+        .map(|segment| {
+            if !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+            {
+                segment.to_string()
+            } else {
+                toml_string(segment)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+pub(crate) fn toml_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            ch if ch.is_control() => {
+                let _ = write!(result, "\\u{:04X}", ch as u32);
+            }
+            ch => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
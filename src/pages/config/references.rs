@@ -0,0 +1,237 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        schema::{Field, Schema, SchemaType, Schemas, SelectType, Source, Type},
+        AccessToken,
+    },
+    pages::config::{
+        list::{fetch_group, fetch_prefix},
+        Settings, SettingsValues,
+    },
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Reference {
+    pub schema_id: String,
+    pub schema_name: String,
+    pub id: Option<String>,
+}
+
+impl Schemas {
+    pub fn referencing_fields(&self, schema_id: &str) -> Vec<(Arc<Schema>, Arc<Field>)> {
+        let mut results = Vec::new();
+        for schema in self.schemas.values() {
+            for field in schema.fields.values() {
+                if let Type::Select { source, .. } = &field.typ_ {
+                    if source.leaves().into_iter().any(|source| {
+                        matches!(source, Source::Dynamic { schema, .. } if schema.id == schema_id)
+                    }) {
+                        results.push((schema.clone(), field.clone()));
+                    }
+                }
+            }
+        }
+        results.sort_by_key(|(schema, field)| (schema.id, field.id));
+        results
+    }
+}
+
+impl Reference {
+    pub fn url(&self) -> String {
+        match &self.id {
+            Some(id) => format!("/settings/{}/{}/edit", self.schema_id, id),
+            None => format!("/settings/{}/edit", self.schema_id),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match &self.id {
+            Some(id) => format!("{} {id}", self.schema_name),
+            None => self.schema_name.clone(),
+        }
+    }
+}
+
+pub async fn fetch_references(
+    auth: &AccessToken,
+    schemas: &Schemas,
+    schema_id: &str,
+    ids: &[String],
+) -> Result<Vec<(String, Reference)>, http::Error> {
+    let mut references = Vec::new();
+
+    for (schema, field) in schemas.referencing_fields(schema_id) {
+        let matches = |settings: &Settings, id: &str| match &field.typ_ {
+            Type::Select {
+                typ: SelectType::Single,
+                ..
+            } => settings.get(field.id).is_some_and(|v| v == id),
+            _ => settings
+                .array_values(field.id)
+                .iter()
+                .any(|(_, value)| *value == id),
+        };
+
+        match schema.typ {
+            SchemaType::Record { .. } | SchemaType::Map { .. } => {
+                let items = fetch_group(auth, &schema, None).await?.items;
+
+                for item in items {
+                    for id in ids {
+                        if matches(&item, id) {
+                            references.push((
+                                id.clone(),
+                                Reference {
+                                    schema_id: schema.id.to_string(),
+                                    schema_name: schema.name_singular.to_string(),
+                                    id: item.get("_id").cloned(),
+                                },
+                            ));
+                        }
+                    }
+                }
+            }
+            SchemaType::Entry { prefix } => {
+                // Entries are stored as a single key per id under the prefix
+                let entries = fetch_prefix(auth, prefix).await?;
+
+                for (entry_id, value) in entries {
+                    let item = Settings::from_iter([(field.id.to_string(), value)]);
+                    for id in ids {
+                        if matches(&item, id) {
+                            references.push((
+                                id.clone(),
+                                Reference {
+                                    schema_id: schema.id.to_string(),
+                                    schema_name: schema.name_singular.to_string(),
+                                    id: Some(entry_id.clone()),
+                                },
+                            ));
+                        }
+                    }
+                }
+            }
+            SchemaType::List => {
+                let settings = HttpRequest::get("/api/settings/keys")
+                    .with_authorization(auth)
+                    .with_parameter("keys", field.id)
+                    .with_parameter("prefixes", field.id)
+                    .send::<Settings>()
+                    .await?;
+
+                for id in ids {
+                    if matches(&settings, id) {
+                        references.push((
+                            id.clone(),
+                            Reference {
+                                schema_id: schema.id.to_string(),
+                                schema_name: field.label_form.to_string(),
+                                id: None,
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    references.sort();
+    references.dedup();
+
+    Ok(references)
+}
+
+#[component]
+pub fn ReferencesPanel(schema: Arc<Schema>, id: String) -> impl IntoView {
+    let auth = use_authorization();
+    let schemas = expect_context::<Arc<Schemas>>();
+    let has_references = !schemas.referencing_fields(schema.id).is_empty();
+    let schema_id = schema.id;
+    let name_singular = schema.name_singular;
+
+    let references = create_resource(
+        move || id.clone(),
+        move |id| {
+            let auth = auth.get_untracked();
+            let schemas = schemas.clone();
+
+            async move {
+                fetch_references(&auth, &schemas, schema_id, &[id])
+                    .await
+                    .map(|references| {
+                        references
+                            .into_iter()
+                            .map(|(_, reference)| reference)
+                            .collect::<Vec<_>>()
+                    })
+            }
+        },
+    );
+
+    view! {
+        <Show when=move || has_references>
+            <div class="mt-8 pt-6 border-t border-gray-200 dark:border-gray-700">
+                <h2 class="text-sm font-semibold text-gray-800 dark:text-gray-200">References</h2>
+                <Transition>
+                    {move || match references.get() {
+                        Some(Ok(references)) if !references.is_empty() => {
+                            let items = references
+                                .into_iter()
+                                .map(|reference| {
+                                    view! {
+                                        <li>
+                                            <a
+                                                class="text-blue-600 decoration-2 hover:underline dark:text-blue-500"
+                                                href=reference.url()
+                                            >
+                                                {reference.label()}
+                                            </a>
+                                        </li>
+                                    }
+                                })
+                                .collect_view();
+                            view! {
+                                <ul class="list-disc space-y-1 ps-5 mt-2 text-sm text-gray-600 dark:text-gray-400">
+                                    {items}
+                                </ul>
+                            }
+                                .into_view()
+                        }
+                        Some(Ok(_)) => {
+                            view! {
+                                <p class="mt-2 text-sm text-gray-600 dark:text-gray-400">
+                                    {format!(
+                                        "This {name_singular} is not referenced by any other settings.",
+                                    )}
+                                </p>
+                            }
+                                .into_view()
+                        }
+                        Some(Err(_)) => {
+                            view! {
+                                <p class="mt-2 text-sm text-gray-600 dark:text-gray-400">
+                                    Failed to fetch references.
+                                </p>
+                            }
+                                .into_view()
+                        }
+                        None => ().into_view(),
+                    }}
+
+                </Transition>
+            </div>
+        </Show>
+    }
+}
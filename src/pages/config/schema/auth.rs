@@ -52,7 +52,15 @@ impl Builder<Schemas, ()> {
             .label("Domain Name")
             .help(concat!("Domain name associated with the DKIM signature"))
             .placeholder("example.com")
-            .typ(Type::Input)
+            .typ(Type::Select {
+                typ: SelectType::Single,
+                source: Source::DynamicRemote {
+                    endpoint: "/api/principal?types=domain&fields=name,description",
+                    id_field: "name",
+                    label_field: "description",
+                },
+            })
+            .allow_custom_values()
             .input_check(
                 [Transformer::Trim],
                 [Validator::Required, Validator::IsDomain],
@@ -63,7 +71,10 @@ impl Builder<Schemas, ()> {
             .help(concat!("Selector used to identify the DKIM public key"))
             .default("stalwart")
             .typ(Type::Input)
-            .input_check([Transformer::Trim], [Validator::Required, Validator::IsId])
+            .input_check(
+                [Transformer::Trim],
+                [Validator::Required, Validator::IsId(IdKind::Default)],
+            )
             .build()
             .new_field("headers")
             .label("Headers")
@@ -318,7 +329,7 @@ impl Builder<Schemas, ()> {
                 "reports will be intercepted and analyzed"
             ))
             .typ(Type::Array(ArrayType::Text))
-            .input_check([Transformer::Trim], [])
+            .input_check([Transformer::Trim], [Validator::IsEmailOrWildcard])
             .build()
             .new_field("report.analysis.forward")
             .label("Forward")
@@ -337,6 +348,7 @@ impl Builder<Schemas, ()> {
             ))
             .default("30d")
             .typ(Type::Duration)
+            .allow_disabled()
             .build()
             .new_form_section()
             .title("Inbound Report Analysis")
@@ -756,5 +768,254 @@ impl Builder<Schemas, ()> {
             ])
             .build()
             .build()
+            // Message templates
+            .new_schema("message-templates")
+            .new_field("report.dsn.template.failure.subject")
+            .label("Subject")
+            .help("Subject of the notification sent when a message could not be delivered")
+            .default("Undelivered Mail Returned to Sender")
+            .typ(Type::Text)
+            .input_check(
+                [Transformer::Trim],
+                [
+                    Validator::Required,
+                    Validator::IsTemplate(DSN_FAILURE_PLACEHOLDERS),
+                ],
+            )
+            .new_field("report.dsn.template.failure.body")
+            .label("Body")
+            .help("Text of the notification sent when a message could not be delivered")
+            .default(concat!(
+                "Your message could not be delivered to the following recipients:\n\n",
+                "{{recipients}}\n\n",
+                "Reason: {{reason}}\n\n",
+                "The original message was received on {{date}} with queue ID {{queue_id}}.\n",
+                "-- \n{{reporting_mta}}"
+            ))
+            .typ(Type::Text)
+            .input_check(
+                [],
+                [
+                    Validator::Required,
+                    Validator::IsTemplate(DSN_FAILURE_PLACEHOLDERS),
+                ],
+            )
+            .new_field("report.dsn.template.delay.subject")
+            .label("Subject")
+            .help("Subject of the notification sent when the delivery of a message is delayed")
+            .default("Delivery Status Notification (Delay)")
+            .typ(Type::Text)
+            .input_check(
+                [Transformer::Trim],
+                [
+                    Validator::Required,
+                    Validator::IsTemplate(DSN_DELAY_PLACEHOLDERS),
+                ],
+            )
+            .new_field("report.dsn.template.delay.body")
+            .label("Body")
+            .help("Text of the notification sent when the delivery of a message is delayed")
+            .default(concat!(
+                "Delivery of your message to the following recipients has been delayed:\n\n",
+                "{{recipients}}\n\n",
+                "Reason: {{reason}}\n\n",
+                "No action is required on your part. Delivery attempts will continue ",
+                "until {{retry_until}}.\n",
+                "-- \n{{reporting_mta}}"
+            ))
+            .typ(Type::Text)
+            .input_check(
+                [],
+                [
+                    Validator::Required,
+                    Validator::IsTemplate(DSN_DELAY_PLACEHOLDERS),
+                ],
+            )
+            .new_field("report.template.aggregate.body")
+            .label("Aggregate reports")
+            .help(concat!(
+                "Text included with DMARC and TLS aggregate reports, the report ",
+                "itself is sent as an attachment"
+            ))
+            .default(concat!(
+                "This is a {{report_type}} aggregate report for {{domain}} covering ",
+                "the period from {{begin}} to {{end}}.\n\n",
+                "Report ID: {{report_id}}\n",
+                "Submitted by: {{org_name}}"
+            ))
+            .typ(Type::Text)
+            .input_check(
+                [],
+                [
+                    Validator::Required,
+                    Validator::IsTemplate(AGGREGATE_REPORT_PLACEHOLDERS),
+                ],
+            )
+            .new_field("report.template.failure.body")
+            .label("Failure reports")
+            .help("Text included with DKIM, SPF and DMARC authentication failure reports")
+            .default(concat!(
+                "This is a {{report_type}} authentication failure report for a message ",
+                "received from {{remote_ip}} claiming to be from {{domain}}.\n\n",
+                "Submitted by: {{org_name}}"
+            ))
+            .typ(Type::Text)
+            .input_check(
+                [],
+                [
+                    Validator::Required,
+                    Validator::IsTemplate(FAILURE_REPORT_PLACEHOLDERS),
+                ],
+            )
+            .build()
+            .new_form_section()
+            .title("Delivery Failure Notification")
+            .fields([
+                "report.dsn.template.failure.subject",
+                "report.dsn.template.failure.body",
+            ])
+            .build()
+            .new_form_section()
+            .title("Delivery Delay Notification")
+            .fields([
+                "report.dsn.template.delay.subject",
+                "report.dsn.template.delay.body",
+            ])
+            .build()
+            .new_form_section()
+            .title("Report Emails")
+            .fields([
+                "report.template.aggregate.body",
+                "report.template.failure.body",
+            ])
+            .build()
+            .build()
     }
 }
+
+const DSN_FAILURE_PLACEHOLDERS: &[Placeholder] = &[
+    Placeholder {
+        name: "recipients",
+        description: "Recipients that could not be reached",
+        sample: "jane@example.org",
+    },
+    Placeholder {
+        name: "sender",
+        description: "Envelope sender of the original message",
+        sample: "john@example.com",
+    },
+    Placeholder {
+        name: "reason",
+        description: "Last error reported by the remote server",
+        sample: "550 5.1.1 Mailbox does not exist",
+    },
+    Placeholder {
+        name: "date",
+        description: "Date the original message was received",
+        sample: "Mon, 12 Oct 2026 10:15:00 +0000",
+    },
+    Placeholder {
+        name: "queue_id",
+        description: "Queue identifier of the original message",
+        sample: "1a2b3c4d",
+    },
+    Placeholder {
+        name: "reporting_mta",
+        description: "Hostname of this server",
+        sample: "mail.example.com",
+    },
+];
+
+const DSN_DELAY_PLACEHOLDERS: &[Placeholder] = &[
+    Placeholder {
+        name: "recipients",
+        description: "Recipients with delayed delivery",
+        sample: "jane@example.org",
+    },
+    Placeholder {
+        name: "sender",
+        description: "Envelope sender of the original message",
+        sample: "john@example.com",
+    },
+    Placeholder {
+        name: "reason",
+        description: "Last error reported by the remote server",
+        sample: "421 4.7.0 Try again later",
+    },
+    Placeholder {
+        name: "date",
+        description: "Date the original message was received",
+        sample: "Mon, 12 Oct 2026 10:15:00 +0000",
+    },
+    Placeholder {
+        name: "retry_until",
+        description: "Date after which delivery will no longer be attempted",
+        sample: "Mon, 17 Oct 2026 10:15:00 +0000",
+    },
+    Placeholder {
+        name: "queue_id",
+        description: "Queue identifier of the original message",
+        sample: "1a2b3c4d",
+    },
+    Placeholder {
+        name: "reporting_mta",
+        description: "Hostname of this server",
+        sample: "mail.example.com",
+    },
+];
+
+const AGGREGATE_REPORT_PLACEHOLDERS: &[Placeholder] = &[
+    Placeholder {
+        name: "report_type",
+        description: "Report type, either DMARC or TLS",
+        sample: "DMARC",
+    },
+    Placeholder {
+        name: "domain",
+        description: "Domain the report is about",
+        sample: "example.org",
+    },
+    Placeholder {
+        name: "begin",
+        description: "Start of the reporting period",
+        sample: "Sun, 11 Oct 2026 00:00:00 +0000",
+    },
+    Placeholder {
+        name: "end",
+        description: "End of the reporting period",
+        sample: "Mon, 12 Oct 2026 00:00:00 +0000",
+    },
+    Placeholder {
+        name: "report_id",
+        description: "Unique identifier of the report",
+        sample: "8a7e1c03f2b9",
+    },
+    Placeholder {
+        name: "org_name",
+        description: "Organization submitting the report",
+        sample: "example.com",
+    },
+];
+
+const FAILURE_REPORT_PLACEHOLDERS: &[Placeholder] = &[
+    Placeholder {
+        name: "report_type",
+        description: "Report type, either DKIM, SPF or DMARC",
+        sample: "DKIM",
+    },
+    Placeholder {
+        name: "domain",
+        description: "Domain that failed authentication",
+        sample: "example.org",
+    },
+    Placeholder {
+        name: "remote_ip",
+        description: "IP address of the sending server",
+        sample: "192.0.2.25",
+    },
+    Placeholder {
+        name: "org_name",
+        description: "Organization submitting the report",
+        sample: "example.com",
+    },
+];
@@ -82,6 +82,7 @@ impl Builder<Schemas, ()> {
             .help("Encryption key to use for OAuth")
             .typ(Type::Secret)
             .input_check([], [Validator::Required])
+            .generate_secret(64, SecretCharset::Alphanumeric)
             .build()
             .new_field("oauth.auth.max-attempts")
             .label("Max attempts")
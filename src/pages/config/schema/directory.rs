@@ -229,7 +229,10 @@ impl Builder<Schemas, ()> {
             .display_if_eq("type", ["ldap"])
             .default("ldap://localhost:389")
             .typ(Type::Input)
-            .input_check([Transformer::Trim], [Validator::Required, Validator::IsUrl])
+            .input_check(
+                [Transformer::Trim, Transformer::NormalizeUrl],
+                [Validator::Required, Validator::IsUrl],
+            )
             .new_field("base-dn")
             .label("Base DN")
             .help("The base distinguished name (DN) from where searches should begin")
@@ -363,7 +366,10 @@ impl Builder<Schemas, ()> {
             .display_if_eq("type", ["oidc"])
             .placeholder("https://accounts.example.org/userinfo")
             .typ(Type::Input)
-            .input_check([Transformer::Trim], [Validator::Required, Validator::IsUrl])
+            .input_check(
+                [Transformer::Trim, Transformer::NormalizeUrl],
+                [Validator::Required, Validator::IsUrl],
+            )
             .build()
             .new_field("endpoint.method")
             .label("Type")
@@ -197,6 +197,7 @@ impl Builder<Schemas, ()> {
             .typ(Type::Input)
             .input_check([Transformer::Trim], [Validator::IsEmail])
             .default("postmaster@localhost")
+            .notification_test(NotificationTest::Email)
             .build()
             .new_field("form.subject.default")
             .label("Subject default")
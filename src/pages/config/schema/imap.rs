@@ -14,7 +14,14 @@ impl Builder<Schemas, ()> {
             .help("Maximum size of an IMAP request that the server will accept")
             .default("52428800")
             .typ(Type::Size)
-            .input_check([], [Validator::Required])
+            .input_check(
+                [],
+                [
+                    Validator::Required,
+                    Validator::MinSize(1024 * 1024),
+                    Validator::MaxSize(1024 * 1024 * 1024),
+                ],
+            )
             .build()
             .new_field("imap.timeout.authenticated")
             .label("Authenticated")
@@ -4,14 +4,26 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::core::schema::*;
+use crate::{
+    core::{
+        form::{FormData, FormValue},
+        schema::*,
+    },
+    pages::config::SettingsValues,
+};
 
 impl Builder<Schemas, ()> {
     pub fn build_listener(self) -> Self {
         self.new_schema("listener")
             .names("listener", "listeners")
+            .empty_state(
+                "Listeners define the addresses and protocols the server accepts connections on.",
+            )
+            .empty_state_template("smtp")
             .prefix("server.listener")
             .suffix("protocol")
+            .doc("server/general/listener")
+            .form_confirm_changes()
             // Id
             .new_id_field()
             .label("Listener Id")
@@ -34,6 +46,8 @@ impl Builder<Schemas, ()> {
             .help("The protocol used by the listener")
             .input_check([], [Validator::Required])
             .default("smtp")
+            .disruptive()
+            .requires(Requires::Restart)
             .build()
             // Bind addresses
             .new_field("bind")
@@ -44,6 +58,8 @@ impl Builder<Schemas, ()> {
                 [Transformer::Trim],
                 [Validator::Required, Validator::IsSocketAddr],
             )
+            .disruptive()
+            .requires(Requires::Restart)
             .build()
             // Override proxy protocol
             .new_field("proxy.override")
@@ -71,6 +87,24 @@ impl Builder<Schemas, ()> {
             .help("Whether to use implicit TLS")
             .typ(Type::Boolean)
             .default("false")
+            .disruptive()
+            .requires(Requires::Restart)
+            .build()
+            // Certificate
+            .new_field("tls.certificate")
+            .label("Certificate")
+            .help(concat!(
+                "The certificate presented by this listener, by default it is ",
+                "selected from the server name requested by the client"
+            ))
+            .typ(Type::Select {
+                typ: SelectType::Single,
+                source: Source::static_and_records(
+                    &[("", "Use default certificate")],
+                    ["certificate", "acme"],
+                ),
+            })
+            .input_check([], [])
             .build()
             // Add common fields
             .add_network_fields(true)
@@ -84,6 +118,7 @@ impl Builder<Schemas, ()> {
             .title("TLS options")
             .fields([
                 "tls.implicit",
+                "tls.certificate",
                 "tls.override",
                 "tls.disable-protocols",
                 "tls.disable-ciphers",
@@ -112,7 +147,193 @@ impl Builder<Schemas, ()> {
             .build()
             .list_title("Listeners")
             .list_subtitle("Manage SMTP, IMAP, HTTP, and other listeners")
-            .list_fields(["_id", "protocol", "bind", "tls.implicit"])
+            .list_fields(["_id", "protocol", "bind", "tls.implicit", "tls.certificate"])
+            .list_load_all()
+            .default_provider(listener_defaults)
+            .template(
+                "smtp",
+                "SMTP (port 25)",
+                "Inbound mail from other servers",
+                &[
+                    ("_id", "smtp"),
+                    ("protocol", "smtp"),
+                    ("bind", "[::]:25"),
+                    ("tls.implicit", "false"),
+                ],
+            )
+            .template(
+                "submissions",
+                "Submission over TLS (port 465)",
+                "Authenticated submission from clients using implicit TLS",
+                &[
+                    ("_id", "submissions"),
+                    ("protocol", "smtp"),
+                    ("bind", "[::]:465"),
+                    ("tls.implicit", "true"),
+                ],
+            )
+            .template(
+                "submission",
+                "Submission (port 587)",
+                "Authenticated submission from clients using STARTTLS",
+                &[
+                    ("_id", "submission"),
+                    ("protocol", "smtp"),
+                    ("bind", "[::]:587"),
+                    ("tls.implicit", "false"),
+                ],
+            )
+            .template(
+                "imaptls",
+                "IMAP over TLS (port 993)",
+                "Mailbox access for e-mail clients using implicit TLS",
+                &[
+                    ("_id", "imaptls"),
+                    ("protocol", "imap"),
+                    ("bind", "[::]:993"),
+                    ("tls.implicit", "true"),
+                ],
+            )
+            .template(
+                "https",
+                "HTTPS (port 443)",
+                "JMAP, WebDAV and the management interface over TLS",
+                &[
+                    ("_id", "https"),
+                    ("protocol", "http"),
+                    ("bind", "[::]:443"),
+                    ("tls.implicit", "true"),
+                ],
+            )
+            .build()
+    }
+}
+
+// Suggests the first standard port of the protocol that no other listener
+// is bound to
+fn listener_defaults(data: &FormData, records: &DefaultRecords) -> Vec<(&'static str, FormValue)> {
+    let implicit_tls = data.value::<bool>("tls.implicit").unwrap_or_default();
+    let ports: &[u16] = match (
+        data.value_as_str("protocol").unwrap_or_default(),
+        implicit_tls,
+    ) {
+        ("smtp", false) => &[25, 587],
+        ("smtp", true) => &[465],
+        ("lmtp", _) => &[24],
+        ("imap", false) => &[143],
+        ("imap", true) => &[993],
+        ("pop3", false) => &[110],
+        ("pop3", true) => &[995],
+        ("managesieve", _) => &[4190],
+        ("http", false) => &[8080, 80],
+        ("http", true) => &[443, 8443],
+        _ => &[],
+    };
+
+    ports
+        .iter()
+        .find(|port| {
+            let suffix = format!(":{port}");
+            !records.records.iter().any(|listener| {
+                listener
+                    .array_values("bind")
+                    .iter()
+                    .any(|(_, addr)| addr.trim().ends_with(&suffix))
+            })
+        })
+        .map(|port| vec![("bind", FormValue::Array(vec![format!("[::]:{port}")]))])
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pages::config::Settings;
+
+    fn form(protocol: &str, implicit_tls: bool) -> FormData {
+        let schemas = Schemas::builder()
+            .new_schema("listener")
+            .new_field("protocol")
+            .build()
+            .new_field("tls.implicit")
+            .typ(Type::Boolean)
+            .build()
+            .new_field("bind")
+            .typ(Type::Array(ArrayType::Text))
+            .build()
             .build()
+            .build();
+        let mut data = FormData::from(schemas.get("listener").unwrap());
+        data.update("protocol", protocol);
+        data.update("tls.implicit", implicit_tls.to_string());
+        data
+    }
+
+    fn records(binds: &[&str]) -> DefaultRecords {
+        DefaultRecords {
+            records: binds
+                .iter()
+                .enumerate()
+                .map(|(idx, bind)| {
+                    Settings::from_iter([
+                        ("_id".to_string(), format!("listener-{idx}")),
+                        ("bind".to_string(), bind.to_string()),
+                    ])
+                })
+                .collect(),
+            related: vec![],
+        }
+    }
+
+    fn suggested_bind(protocol: &str, implicit_tls: bool, binds: &[&str]) -> Option<String> {
+        listener_defaults(&form(protocol, implicit_tls), &records(binds))
+            .into_iter()
+            .find_map(|(id, value)| match (id, value) {
+                ("bind", FormValue::Array(values)) => values.into_iter().next(),
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn suggests_the_standard_port_of_each_protocol() {
+        for (protocol, implicit_tls, expected) in [
+            ("smtp", false, "[::]:25"),
+            ("smtp", true, "[::]:465"),
+            ("lmtp", false, "[::]:24"),
+            ("imap", false, "[::]:143"),
+            ("imap", true, "[::]:993"),
+            ("pop3", false, "[::]:110"),
+            ("pop3", true, "[::]:995"),
+            ("managesieve", false, "[::]:4190"),
+            ("http", false, "[::]:8080"),
+            ("http", true, "[::]:443"),
+        ] {
+            assert_eq!(
+                suggested_bind(protocol, implicit_tls, &[]).as_deref(),
+                Some(expected),
+                "{protocol} {implicit_tls}"
+            );
+        }
+    }
+
+    #[test]
+    fn skips_ports_bound_by_other_listeners() {
+        assert_eq!(
+            suggested_bind("smtp", false, &["[::]:25"]).as_deref(),
+            Some("[::]:587")
+        );
+        assert_eq!(
+            suggested_bind("http", true, &["0.0.0.0:443"]).as_deref(),
+            Some("[::]:8443")
+        );
+        assert_eq!(
+            suggested_bind("imap", false, &["[::]:1143"]).as_deref(),
+            Some("[::]:143")
+        );
+        assert_eq!(suggested_bind("imap", true, &["[::]:993"]), None);
+        assert_eq!(
+            suggested_bind("smtp", false, &["[::]:25", "[::]:587"]),
+            None
+        );
     }
 }
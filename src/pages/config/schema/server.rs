@@ -18,6 +18,7 @@ impl Builder<Schemas, ()> {
             .help("The default fully-qualified system hostname")
             .placeholder("mail.example.com")
             .typ(Type::Input)
+            .alias("lookup.default.hostname")
             .input_check(
                 [Transformer::Trim],
                 [Validator::Required, Validator::IsHost],
@@ -226,8 +227,8 @@ impl Builder<Schemas, ()> {
             .label("IP Address(es)")
             .help("The IP address or mask to block")
             .input_check(
-                [Transformer::Trim],
-                [Validator::Required, Validator::IsIpOrMask],
+                [Transformer::Trim, Transformer::NormalizeIp],
+                [Validator::Required, Validator::IsIpMask],
             )
             .build()
             .new_form_section()
@@ -247,8 +248,8 @@ impl Builder<Schemas, ()> {
             .label("IP Address(es)")
             .help("The IP address or mask to allow")
             .input_check(
-                [Transformer::Trim],
-                [Validator::Required, Validator::IsIpOrMask],
+                [Transformer::Trim, Transformer::NormalizeIp],
+                [Validator::Required, Validator::IsIpMask],
             )
             .build()
             .new_form_section()
@@ -296,8 +297,24 @@ impl Builder<Schemas, ()> {
                 "The paths that will trigger an immediate ban if accessed. ",
                 "Each path should be a glob expression"
             ))
-            .typ(Type::Array(ArrayType::Text))
+            .typ(Type::Tag)
             .input_check([Transformer::Trim], [])
+            .suggestions(&[
+                "*.php*",
+                "*.cgi*",
+                "*.asp*",
+                "*/wp-*",
+                "*/php*",
+                "*/cgi-bin*",
+                "*xmlrpc*",
+                "*../*",
+                "*/..*",
+                "*joomla*",
+                "*wordpress*",
+                "*drupal*",
+                "*/.env*",
+                "*/.git/*",
+            ])
             .default(
                 &[
                     "*.php*",
@@ -441,6 +458,7 @@ impl Builder<Schemas, ()> {
             .new_id_field()
             .label("Webhook Id")
             .help("Unique identifier for this webhook")
+            .auto_generate("webhook-{n}")
             .build()
             .new_field("url")
             .label("Endpoint URL")
@@ -448,6 +466,7 @@ impl Builder<Schemas, ()> {
             .placeholder("https://127.0.0.1/webhook")
             .typ(Type::Input)
             .input_check([Transformer::Trim], [Validator::Required, Validator::IsUrl])
+            .notification_test(NotificationTest::Webhook)
             .build()
             .new_field("allow-invalid-certs")
             .label("Allow Invalid Certs")
@@ -486,6 +505,7 @@ impl Builder<Schemas, ()> {
                 "to prevent tampering"
             ))
             .typ(Type::Secret)
+            .generate_secret(32, SecretCharset::Alphanumeric)
             .build()
             .new_field("headers")
             .typ(Type::Array(ArrayType::Text))
@@ -702,7 +722,10 @@ impl Builder<Schemas, Schema> {
         .label("Proxy networks")
         .help("Enable proxy protocol for connections from these networks")
         .typ(Type::Array(ArrayType::Text))
-        .input_check([Transformer::Trim], [Validator::IsIpOrMask])
+        .input_check(
+            [Transformer::Trim, Transformer::NormalizeIp],
+            [Validator::IsIpMask],
+        )
         .display_if_eq("proxy.override", do_override.iter().copied())
         .build()
         // Socket options
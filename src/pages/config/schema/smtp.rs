@@ -4,7 +4,10 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::core::{form::Expression, schema::*};
+use crate::core::{
+    form::{Expression, FormData},
+    schema::*,
+};
 
 use super::*;
 
@@ -370,7 +373,7 @@ impl Builder<Schemas, ()> {
                 [Transformer::Trim],
                 [
                     Validator::Required,
-                    Validator::IsId,
+                    Validator::IsId(IdKind::Strict),
                     Validator::MaxLength(8),
                 ],
             )
@@ -509,7 +512,7 @@ impl Builder<Schemas, ()> {
             // TLS strategies
             .new_schema("smtp-out-tls")
             .prefix("queue.tls")
-            .suffix("allow-invalid-certs")
+            .map()
             .names("TLS strategy", "TLS strategies")
             .new_id_field()
             .label("Name")
@@ -616,7 +619,10 @@ impl Builder<Schemas, ()> {
                 "delivering emails to remote SMTP servers"
             ))
             .typ(Type::Array(ArrayType::Text))
-            .input_check([], [Validator::IsIpOrMask])
+            .input_check(
+                [Transformer::Trim, Transformer::NormalizeIp],
+                [Validator::IsIpAddr],
+            )
             .build()
             .new_field("ehlo-hostname")
             .label("EHLO Hostname")
@@ -714,10 +720,12 @@ impl Builder<Schemas, ()> {
             .new_schema("smtp-out-throttle")
             .prefix("queue.limiter.outbound")
             .names("throttle", "throttles")
+            .bulk_action(Action::Enable)
             .suffix("enable")
             .new_id_field()
             .label("Limiter ID")
             .help("Unique identifier for the throttle")
+            .auto_generate("throttle-{n}")
             .build()
             .new_field("enable")
             .label("Enabled")
@@ -782,6 +790,7 @@ impl Builder<Schemas, ()> {
             .new_schema("smtp-out-quota")
             .prefix("queue.quota")
             .names("quota", "quotas")
+            .bulk_action(Action::Enable)
             .suffix("enable")
             .new_id_field()
             .label("Quota ID")
@@ -1203,7 +1212,21 @@ impl Builder<Schemas, ()> {
                     has_conn_vars.constants(AUTH_CONSTANTS),
                 )],
             )
+            .new_field("session.auth.require-tls")
+            .label("Require TLS")
+            .help(concat!(
+                "Whether clients must establish an encrypted connection, either ",
+                "implicit TLS or STARTTLS, before authenticating"
+            ))
+            .default("true")
+            .typ(Type::Boolean)
+            .new_field("session.auth.allow-plain-text")
+            .label("Allow plain text authentication")
+            .help("Whether to allow PLAIN and LOGIN authentication on unencrypted connections")
+            .default("false")
+            .typ(Type::Boolean)
             .build()
+            .warnings(auth_warnings)
             .new_form_section()
             .title("AUTH Stage")
             .fields([
@@ -1214,6 +1237,10 @@ impl Builder<Schemas, ()> {
             ])
             .build()
             .new_form_section()
+            .title("Transport Security")
+            .fields(["session.auth.require-tls", "session.auth.allow-plain-text"])
+            .build()
+            .new_form_section()
             .title("Authentication Errors")
             .fields(["session.auth.errors.total", "session.auth.errors.wait"])
             .build()
@@ -1425,10 +1452,12 @@ impl Builder<Schemas, ()> {
             .new_schema("smtp-in-throttle")
             .prefix("queue.limiter.inbound")
             .names("throttle", "throttles")
+            .bulk_action(Action::Enable)
             .suffix("enable")
             .new_id_field()
             .label("Limiter ID")
             .help("Unique identifier for the throttle")
+            .auto_generate("throttle-{n}")
             .build()
             .new_field("enable")
             .label("Enabled")
@@ -1494,9 +1523,14 @@ impl Builder<Schemas, ()> {
             .prefix("session.milter")
             .suffix("hostname")
             .names("milter", "milters")
+            .empty_state(
+                "Milters let external filters inspect and modify messages during the SMTP session.",
+            )
+            .optional_records()
             .new_id_field()
             .label("Milter Id")
             .help("Unique identifier for this milter")
+            .auto_generate("milter-{n}")
             .build()
             .new_field("enable")
             .label("Enable")
@@ -1660,9 +1694,14 @@ impl Builder<Schemas, ()> {
             .prefix("session.hook")
             .suffix("url")
             .names("hook", "hooks")
+            .empty_state(
+                "MTA hooks call an external HTTP service at each stage of the SMTP session.",
+            )
+            .optional_records()
             .new_id_field()
             .label("Hook Id")
             .help("Unique identifier for this hook")
+            .auto_generate("hook-{n}")
             .build()
             .new_field("enable")
             .label("Enable")
@@ -1980,6 +2019,73 @@ impl Builder<Schemas, ()> {
     }
 }
 
+fn auth_warnings(data: &FormData) -> Vec<&'static str> {
+    let require_tls = data
+        .value::<bool>("session.auth.require-tls")
+        .unwrap_or(true);
+    let allow_plain_text = data
+        .value::<bool>("session.auth.allow-plain-text")
+        .unwrap_or_default();
+    let mut offered = false;
+    let mut plain_without_tls = false;
+
+    for (condition, mechanisms) in data
+        .expr_if_thens("session.auth.mechanisms")
+        .map(|if_then| (Some(if_then.if_.as_str()), if_then.then_.as_str()))
+        .chain(
+            data.expr_else("session.auth.mechanisms")
+                .map(|else_| (None, else_)),
+        )
+    {
+        let mechanisms = auth_mechanisms(mechanisms);
+        let requires_tls = condition.is_some_and(|condition| {
+            condition.contains(V_TLS) && !condition.contains(&format!("!{V_TLS}"))
+        });
+        offered |= !mechanisms.is_empty();
+        plain_without_tls |= !requires_tls
+            && mechanisms
+                .iter()
+                .any(|mechanism| matches!(mechanism.as_str(), "plain" | "login"));
+    }
+
+    let mut warnings = Vec::new();
+    if !offered {
+        warnings.push(concat!(
+            "No authentication mechanisms are enabled, clients will not be able ",
+            "to authenticate in order to submit messages."
+        ));
+    }
+    if plain_without_tls && !require_tls {
+        warnings.push(concat!(
+            "PLAIN or LOGIN is offered on connections that may not use TLS while ",
+            "TLS is not required for authentication."
+        ));
+    }
+    if allow_plain_text && !require_tls {
+        warnings.push(concat!(
+            "Plain text authentication is allowed on unencrypted connections, ",
+            "passwords may be sent in clear text."
+        ));
+    }
+    warnings
+}
+
+fn auth_mechanisms(value: &str) -> Vec<String> {
+    value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|mechanism| {
+            mechanism
+                .trim()
+                .trim_matches(|c| c == '\'' || c == '"')
+                .to_ascii_lowercase()
+        })
+        .filter(|mechanism| AUTH_CONSTANTS.contains(&mechanism.as_str()))
+        .collect()
+}
+
 pub const VERIFY_CONSTANTS: &[&str] =
     &["relaxed", "strict", "disable", "disabled", "never", "none"];
 pub const AUTH_CONSTANTS: &[&str] = &["plain", "login", "xoauth2", "oauthbearer"];
@@ -1999,3 +2105,100 @@ pub static SMTP_STAGES: &[(&str, &str)] = &[
     ("rcpt", "RCPT TO"),
     ("data", "DATA"),
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::form::{Expression as FormExpression, ExpressionIfThen, FormValue};
+
+    const NO_MECHANISMS: &str = "No authentication mechanisms";
+    const PLAIN_WITHOUT_TLS: &str = "PLAIN or LOGIN is offered";
+    const PLAIN_TEXT_ALLOWED: &str = "Plain text authentication is allowed";
+
+    // Mechanisms expression, whether any mechanism is offered and whether
+    // PLAIN or LOGIN may be offered without TLS
+    fn mechanism_cases() -> Vec<(FormExpression, bool, bool)> {
+        let expr = |if_thens: &[(&str, &str)], else_: &str| FormExpression {
+            if_thens: if_thens
+                .iter()
+                .map(|(if_, then_)| ExpressionIfThen {
+                    if_: if_.to_string(),
+                    then_: then_.to_string(),
+                })
+                .collect(),
+            else_: else_.to_string(),
+        };
+
+        vec![
+            (expr(&[], "false"), false, false),
+            (expr(&[], "[oauthbearer, xoauth2]"), true, false),
+            (expr(&[], "[plain, login]"), true, true),
+            (
+                expr(&[("local_port != 25 && is_tls", "[plain, login]")], "false"),
+                true,
+                false,
+            ),
+            (
+                expr(
+                    &[("local_port != 25 && is_tls", "[plain, login]")],
+                    "[oauthbearer]",
+                ),
+                true,
+                false,
+            ),
+            (expr(&[("!is_tls", "[plain]")], "false"), true, true),
+            (
+                expr(&[("local_port != 25", "[login]")], "false"),
+                true,
+                true,
+            ),
+        ]
+    }
+
+    #[test]
+    fn auth_warnings_matrix() {
+        let form = crate::build_schemas().build_form("smtp-in-auth");
+
+        for (mechanisms, offered, plain_without_tls) in mechanism_cases() {
+            for require_tls in [true, false] {
+                for allow_plain_text in [true, false] {
+                    let mut data = form.clone();
+                    data.set(
+                        "session.auth.mechanisms",
+                        FormValue::Expression(mechanisms.clone()),
+                    );
+                    data.set("session.auth.require-tls", require_tls.to_string());
+                    data.set(
+                        "session.auth.allow-plain-text",
+                        allow_plain_text.to_string(),
+                    );
+
+                    let warnings = auth_warnings(&data);
+                    let has = |warning: &str| warnings.iter().any(|w| w.starts_with(warning));
+                    let case = format!(
+                        "{mechanisms:?} require_tls={require_tls} allow_plain_text={allow_plain_text}"
+                    );
+                    assert_eq!(has(NO_MECHANISMS), !offered, "{case}");
+                    assert_eq!(
+                        has(PLAIN_WITHOUT_TLS),
+                        plain_without_tls && !require_tls,
+                        "{case}"
+                    );
+                    assert_eq!(
+                        has(PLAIN_TEXT_ALLOWED),
+                        allow_plain_text && !require_tls,
+                        "{case}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn auth_warnings_default_to_requiring_tls() {
+        let mut data = crate::build_schemas().build_form("smtp-in-auth");
+        data.set("session.auth.mechanisms", "[plain, login]");
+        data.set("session.auth.allow-plain-text", "true");
+        assert!(auth_warnings(&data).is_empty());
+    }
+}
@@ -4,6 +4,8 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use crate::core::form::FormData;
+
 use super::*;
 
 const SCOPES: &[(&str, &str)] = &[
@@ -102,15 +104,6 @@ impl Builder<Schemas, ()> {
                 ],
             )
             .build()
-            .new_field("spam-filter.grey-list.duration")
-            .label("Duration")
-            .help(concat!(
-                "Time to keep an IP address in the grey list. ",
-                "The grey list is used to delay messages from unknown senders."
-            ))
-            .typ(Type::Duration)
-            .input_check([], [])
-            .build()
             .new_field("spam-filter.dnsbl.max-check.ip")
             .label("IP Checks")
             .help("Maximum number of DNSBL checks for IP addresses")
@@ -168,10 +161,6 @@ impl Builder<Schemas, ()> {
             ])
             .build()
             .new_form_section()
-            .title("Greylisting")
-            .fields(["spam-filter.grey-list.duration"])
-            .build()
-            .new_form_section()
             .title("DNSBL Limits")
             .fields([
                 "spam-filter.dnsbl.max-check.ip",
@@ -185,6 +174,78 @@ impl Builder<Schemas, ()> {
             .fields(["spam-filter.resource", "spam-filter.auto-update"])
             .build()
             .build()
+            // Inbound filtering
+            .new_schema("inbound-filtering")
+            .warnings(inbound_filtering_warnings)
+            .new_field("session.connect.dnsbl.enable")
+            .label("Enable DNSBL scoring")
+            .help(concat!(
+                "Whether to score connecting IP addresses against the DNS ",
+                "blocklists below"
+            ))
+            .default("false")
+            .typ(Type::Boolean)
+            .build()
+            .new_field("session.connect.dnsbl.zones")
+            .label("Zones")
+            .help(concat!(
+                "DNS blocklist zones to query, in order. Append ':weight' to a zone ",
+                "to change the score it adds when it lists an address (default 1)"
+            ))
+            .typ(Type::Array(ArrayType::Text))
+            .input_check(
+                [Transformer::Trim, Transformer::Lowercase],
+                [Validator::IsDnsblZone],
+            )
+            .placeholder("zen.spamhaus.org:2")
+            .display_if_eq("session.connect.dnsbl.enable", ["true"])
+            .build()
+            .new_field("session.connect.dnsbl.reject-score")
+            .label("Reject score")
+            .help("Reject connections from addresses that reach this DNSBL score")
+            .default("2")
+            .typ(Type::Input)
+            .input_check(
+                [Transformer::Trim],
+                [Validator::Required, Validator::MinValue(0.0.into())],
+            )
+            .display_if_eq("session.connect.dnsbl.enable", ["true"])
+            .build()
+            .new_field("spam-filter.grey-list.duration")
+            .label("Duration")
+            .help(concat!(
+                "Time to keep an IP address in the grey list. ",
+                "The grey list is used to delay messages from unknown senders."
+            ))
+            .typ(Type::Duration)
+            .input_check([], [])
+            .build()
+            .new_field("spam-filter.grey-list.exceptions")
+            .label("Exceptions")
+            .help("IP addresses or networks that are never greylisted")
+            .typ(Type::Array(ArrayType::Text))
+            .input_check(
+                [Transformer::Trim, Transformer::NormalizeIp],
+                [Validator::IsIpMask],
+            )
+            .placeholder("192.168.0.0/16")
+            .build()
+            .new_form_section()
+            .title("DNS Blocklists")
+            .fields([
+                "session.connect.dnsbl.enable",
+                "session.connect.dnsbl.zones",
+                "session.connect.dnsbl.reject-score",
+            ])
+            .build()
+            .new_form_section()
+            .title("Greylisting")
+            .fields([
+                "spam-filter.grey-list.duration",
+                "spam-filter.grey-list.exceptions",
+            ])
+            .build()
+            .build()
             // Spam classifier settings
             .new_schema("spam-classifier")
             .new_field("spam-filter.classifier.model")
@@ -691,6 +752,7 @@ impl Builder<Schemas, ()> {
             // SPAM rules
             .new_schema("spam-rule")
             .names("rule", "rules")
+            .bulk_action(Action::Enable)
             .prefix("spam-filter.rule")
             .suffix("scope")
             .new_id_field()
@@ -750,6 +812,7 @@ impl Builder<Schemas, ()> {
             // SPAM DNSBls
             .new_schema("spam-dnsbl")
             .names("list", "lists")
+            .bulk_action(Action::Enable)
             .prefix("spam-filter.dnsbl.server")
             .suffix("scope")
             .new_id_field()
@@ -886,7 +949,7 @@ impl Builder<Schemas, ()> {
             .help("The spam tag name")
             .input_check(
                 [Transformer::RemoveSpaces, Transformer::Uppercase],
-                [Validator::Required, Validator::IsId],
+                [Validator::Required, Validator::IsId(IdKind::Default)],
             )
             .build()
             .new_value_field()
@@ -911,7 +974,7 @@ impl Builder<Schemas, ()> {
             .help("The file name extension")
             .input_check(
                 [Transformer::RemoveSpaces],
-                [Validator::Required, Validator::IsId],
+                [Validator::Required, Validator::IsId(IdKind::Default)],
             )
             .build()
             .new_value_field()
@@ -944,3 +1007,22 @@ pub static MODEL_SIZES: &[(&str, &str)] = &[
     ("27", "134M"),
     ("28", "268M"),
 ];
+
+fn inbound_filtering_warnings(data: &FormData) -> Vec<&'static str> {
+    let mut warnings = vec![];
+
+    if data
+        .value::<bool>("session.connect.dnsbl.enable")
+        .unwrap_or_default()
+        && data
+            .array_value("session.connect.dnsbl.zones")
+            .all(|zone| zone.trim().is_empty())
+    {
+        warnings.push(concat!(
+            "DNSBL scoring is enabled but no zones are configured, ",
+            "connecting addresses will not be checked against any blocklist."
+        ));
+    }
+
+    warnings
+}
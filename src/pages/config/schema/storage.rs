@@ -187,7 +187,10 @@ impl Builder<Schemas, ()> {
             ))
             .default("0 0 *")
             .typ(Type::Cron)
-            .input_check([], [Validator::Required])
+            .input_check(
+                [Transformer::Trim],
+                [Validator::Required, Validator::IsCron],
+            )
             .build()
             .new_field("changes.max-history")
             .label("Changes history")
@@ -216,6 +219,7 @@ impl Builder<Schemas, ()> {
             ))
             .default("false")
             .typ(Type::Duration)
+            .allow_disabled()
             .enterprise_feature()
             .build()
             .new_form_section()
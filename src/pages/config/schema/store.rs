@@ -200,7 +200,10 @@ impl Builder<Schemas, ()> {
             .display_if_ne("type", ["redis", "memory", "elasticsearch", "meilisearch"])
             .default("0 3 *")
             .typ(Type::Cron)
-            .input_check([Transformer::Trim], [Validator::Required])
+            .input_check(
+                [Transformer::Trim],
+                [Validator::Required, Validator::IsCron],
+            )
             .build()
             // Workers
             .new_field("pool.workers")
@@ -414,7 +417,7 @@ impl Builder<Schemas, ()> {
             .help("Machine ID in the FoundationDB cluster (optional)")
             .placeholder("my-server-id")
             .typ(Type::Input)
-            .input_check([Transformer::Trim], [Validator::IsId])
+            .input_check([Transformer::Trim], [Validator::IsId(IdKind::Default)])
             .new_field("ids.datacenter")
             .label("Data Center Id")
             .help("Data center ID (optional)")
@@ -922,10 +925,12 @@ impl Builder<Schemas, ()> {
             .list_title("Stores")
             .list_subtitle("Manage data, blob, full-text, and lookup stores")
             .list_fields(["_id", "type"])
+            .list_load_all()
             .build()
             // HTTP lookups
             .new_schema("http-lookup")
             .names("list", "lists")
+            .bulk_action(Action::Enable)
             .prefix("http-lookup")
             .suffix("url")
             .new_id_field()
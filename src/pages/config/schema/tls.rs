@@ -4,32 +4,91 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::core::schema::*;
+use crate::core::{
+    form::{FormData, FormValue},
+    pem::parse_certificates,
+    schema::*,
+};
 
 impl Builder<Schemas, ()> {
     pub fn build_tls(self) -> Self {
         self.new_schema("acme")
             .names("ACME provider", "ACME providers")
+            .empty_state(
+                "ACME providers such as Let's Encrypt issue and renew TLS certificates automatically.",
+            )
+            .empty_state_template("letsencrypt")
             .prefix("acme")
             .suffix("directory")
+            .doc("server/tls/acme")
             // Id
             .new_id_field()
             .label("Directory Id")
             .help("Unique identifier for the ACME provider")
+            .sortable()
+            .build()
+            // Certificate authority
+            .new_field("_authority")
+            .label("Provider")
+            .help("The certificate authority, its directory URL is filled in automatically")
+            .typ(Type::Select {
+                source: Source::Static(&[
+                    ("letsencrypt", "Let's Encrypt"),
+                    ("letsencrypt-staging", "Let's Encrypt (Staging)"),
+                    ("zerossl", "ZeroSSL"),
+                    ("buypass", "Buypass"),
+                    ("google", "Google Trust Services"),
+                    ("custom", "Custom"),
+                ]),
+                typ: SelectType::Single,
+            })
+            .input_check([], [Validator::Required])
+            .default("letsencrypt")
+            .preset_for("directory", "custom")
             .build()
             // Directory
             .new_field("directory")
             .label("Directory URL")
             .help("The URL of the ACME directory endpoint")
             .typ(Type::Input)
-            .input_check([Transformer::Trim], [Validator::Required, Validator::IsUrl])
-            .default("https://acme-v02.api.letsencrypt.org/directory")
+            .input_check(
+                [Transformer::Trim, Transformer::NormalizeUrl],
+                [Validator::Required, Validator::IsUrl],
+            )
+            .default_if_eq(
+                "_authority",
+                ["letsencrypt"],
+                "https://acme-v02.api.letsencrypt.org/directory",
+            )
+            .default_if_eq(
+                "_authority",
+                ["letsencrypt-staging"],
+                "https://acme-staging-v02.api.letsencrypt.org/directory",
+            )
+            .default_if_eq("_authority", ["zerossl"], "https://acme.zerossl.com/v2/DV90")
+            .default_if_eq(
+                "_authority",
+                ["buypass"],
+                "https://api.buypass.com/acme/directory",
+            )
+            .default_if_eq(
+                "_authority",
+                ["google"],
+                "https://dv.acme-v02.api.pki.goog/directory",
+            )
             .build()
             // EAB Key
             .new_field("eab.kid")
             .label("Key ID")
             .help("The External Account Binding (EAB) key ID")
             .typ(Type::Input)
+            .display_if_eq("_authority", ["zerossl", "google", "custom"])
+            .input_check_if_eq(
+                "_authority",
+                ["zerossl", "google"],
+                [Transformer::Trim],
+                [Validator::Required],
+            )
             .input_check([Transformer::Trim], [])
             .build()
             // EAB Key
@@ -37,14 +96,31 @@ impl Builder<Schemas, ()> {
             .label("HMAC Key")
             .help("The External Account Binding (EAB) HMAC key")
             .typ(Type::Secret)
+            .display_if_eq("_authority", ["zerossl", "google", "custom"])
+            .input_check_if_eq(
+                "_authority",
+                ["zerossl", "google"],
+                [Transformer::Trim],
+                [Validator::Required],
+            )
             .input_check([Transformer::Trim], [])
             .build()
             // Domains
             .new_field("domains")
-            .typ(Type::Array(ArrayType::Text))
-            .input_check([Transformer::Trim], [Validator::Required])
+            .typ(Type::Tag)
+            .input_check(
+                [Transformer::Trim, Transformer::IdnaToAscii],
+                [
+                    Validator::Required,
+                    Validator::IsDomainOrWildcard,
+                    Validator::WildcardsRequire {
+                        field: "challenge",
+                        value: "dns-01",
+                    },
+                ],
+            )
             .label("Subject names")
-            .help("Hostnames covered by this ACME manager")
+            .help("Hostnames covered by this ACME manager, wildcards require the DNS-01 challenge")
             .build()
             // Default provider
             .new_field("default")
@@ -54,6 +130,9 @@ impl Builder<Schemas, ()> {
                 "Whether the certificates generated by this provider ",
                 "should be the default when no SNI is provided"
             ))
+            .sortable()
+            .filterable()
+            .list_toggle_exclusive()
             .build()
             // Contact
             .new_field("contact")
@@ -62,19 +141,30 @@ impl Builder<Schemas, ()> {
                 "the contact email address, which is used for important ",
                 "communications regarding your ACME account and certificates"
             ))
-            .typ(Type::Array(ArrayType::Text))
+            .typ(Type::Tag)
             .input_check(
                 [Transformer::Trim],
                 [Validator::Required, Validator::IsEmail],
             )
+            .sortable()
+            .filterable()
             .build()
             // Renew before
             .new_field("renew-before")
             .typ(Type::Duration)
             .label("Renew before")
             .help("Determines how early before expiration the certificate should be renewed.")
-            .input_check([], [Validator::Required])
+            .advanced()
+            .input_check(
+                [],
+                [
+                    Validator::Required,
+                    Validator::MinDuration("1d"),
+                    Validator::MaxDuration("90d"),
+                ],
+            )
             .default("30d")
+            .sortable()
             .build()
             // Challenge type
             .new_field("challenge")
@@ -96,6 +186,7 @@ impl Builder<Schemas, ()> {
             .typ(Type::Duration)
             .label("Polling interval")
             .help("How often to check for DNS records to propagate")
+            .advanced()
             .display_if_eq("challenge", ["dns-01"])
             .input_check([], [Validator::Required])
             .default("15s")
@@ -103,13 +194,30 @@ impl Builder<Schemas, ()> {
             .new_field("propagation-timeout")
             .label("Propagation timeout")
             .help("How long to wait for DNS records to propagate")
+            .advanced()
             .default("1m")
             // TTL (DNS-01)
             .new_field("ttl")
             .label("TTL")
             .help("The TTL for the DNS record used in the DNS-01 challenge")
+            .advanced()
             .default("5m")
             .build()
+            // Zone override (DNS-01)
+            .new_field("origin")
+            .label("DNS zone")
+            .help(concat!(
+                "The DNS zone where the challenge records are created, ",
+                "when it differs from the zone of the subject names"
+            ))
+            .placeholder("example.org")
+            .advanced()
+            .display_if_eq("challenge", ["dns-01"])
+            .input_check(
+                [Transformer::Trim, Transformer::IdnaToAscii],
+                [Validator::IsDomain],
+            )
+            .build()
             // Provider
             .new_field("provider")
             .typ(Type::Select {
@@ -141,6 +249,7 @@ impl Builder<Schemas, ()> {
             .typ(Type::Duration)
             .label("Timeout")
             .help("Request timeout for the DNS provider")
+            .advanced()
             .display_if_eq("provider", ["cloudflare", "digitalocean", "desec", "ovh"])
             .input_check([], [Validator::Required])
             .default("30s")
@@ -163,6 +272,7 @@ impl Builder<Schemas, ()> {
                 typ: SelectType::Single,
             })
             .label("TSIG Algorithm")
+            .allow_custom_values()
             .help("The TSIG algorithm used to authenticate with the DNS provider")
             .input_check([], [Validator::Required])
             .default("hmac-sha512")
@@ -192,8 +302,8 @@ impl Builder<Schemas, ()> {
             .help("The IP address of the DNS server")
             .placeholder("127.0.0.1")
             .input_check(
-                [Transformer::Trim],
-                [Validator::Required, Validator::IsIpOrMask],
+                [Transformer::Trim, Transformer::NormalizeIp],
+                [Validator::Required, Validator::IsIpAddr],
             )
             .build()
             // Key
@@ -240,7 +350,11 @@ impl Builder<Schemas, ()> {
                 "The account key used to authenticate with the ACME ",
                 "provider (auto-generated)"
             ))
+            .advanced()
+            .readonly_always()
+            .skip_clone()
             .typ(Type::Secret)
+            .downloadable("{_id}-account-key.pem")
             .build()
             // Account key
             .new_field("cert")
@@ -249,17 +363,41 @@ impl Builder<Schemas, ()> {
                 "The TLS certificate generated by the ACME provider ",
                 "(auto-generated, do not modify)"
             ))
+            .advanced()
+            .readonly_always()
+            .skip_clone()
             .typ(Type::Secret)
+            .downloadable("{_id}.pem")
             .build()
             // Lists
             .list_title("ACME providers")
             .list_subtitle("Manage ACME TLS certificate providers")
+            .form_validator(acme_eab_complete)
             .list_fields(["_id", "contact", "renew-before", "default"])
+            .list_default_sort("_id", SortOrder::Ascending)
+            .list_action(Action::Clone)
+            .confirm_delete()
+            .template(
+                "letsencrypt",
+                "Let's Encrypt",
+                "Free certificates from the Let's Encrypt production directory",
+                &[
+                    ("_id", "letsencrypt"),
+                    ("_authority", "letsencrypt"),
+                    (
+                        "directory",
+                        "https://acme-v02.api.letsencrypt.org/directory",
+                    ),
+                    ("challenge", "tls-alpn-01"),
+                    ("renew-before", "30d"),
+                ],
+            )
             // Form
             .new_form_section()
             .title("ACME provider")
             .fields([
                 "_id",
+                "_authority",
                 "directory",
                 "challenge",
                 "contact",
@@ -288,6 +426,7 @@ impl Builder<Schemas, ()> {
                 "polling-interval",
                 "propagation-timeout",
                 "ttl",
+                "origin",
                 "timeout",
             ])
             .build()
@@ -302,10 +441,13 @@ impl Builder<Schemas, ()> {
             .names("certificate", "certificates")
             .prefix("certificate")
             .suffix("cert")
+            .doc("server/tls/certificates")
+            .form_confirm_changes()
             // Id
             .new_id_field()
             .label("Certificate Id")
             .help("Unique identifier for the TLS certificate")
+            .sortable()
             .build()
             // Default provider
             .new_field("default")
@@ -315,34 +457,57 @@ impl Builder<Schemas, ()> {
                 "Whether this certificate ",
                 "should be the default when no SNI is provided"
             ))
+            .sortable()
+            .filterable()
+            .list_toggle_exclusive()
             .build()
             // Cert
             .new_field("cert")
             .label("Certificate")
             .typ(Type::Text)
-            .help("TLS certificate in PEM format")
-            .input_check([Transformer::Trim], [Validator::Required])
+            .help(concat!(
+                "TLS certificate chain in PEM format, stored under certificate.{_id}.cert. ",
+                "The subject names are filled in from the certificate when left empty"
+            ))
+            .input_check(
+                [Transformer::Trim],
+                [Validator::Required, Validator::IsCertificate],
+            )
+            .certificate_names("subjects")
+            .downloadable("{_id}.pem")
             .build()
             // PK
             .new_field("private-key")
             .label("Private Key")
             .typ(Type::Text)
-            .help("Private key in PEM format")
-            .input_check([Transformer::Trim], [Validator::Required])
+            .help("Private key in PEM format, it must match the certificate public key")
+            .input_check(
+                [Transformer::Trim],
+                [Validator::Required, Validator::MatchesCertificate("cert")],
+            )
+            .downloadable("{_id}-key.pem")
             .build()
             .new_field("subjects")
-            .typ(Type::Array(ArrayType::Text))
-            .input_check([Transformer::Trim], [Validator::IsDomain])
+            .typ(Type::Tag)
+            .input_check(
+                [Transformer::Trim, Transformer::IdnaToAscii],
+                [Validator::IsDomainOrWildcard],
+            )
             .label("Subject Alternative Names")
             .help("Subject Alternative Names (SAN) for the certificate")
+            .filterable()
             .build()
             .list_title("TLS certificates")
             .list_subtitle("Manage TLS certificates")
+            .form_validator(certificate_covers_subjects)
             .list_fields(["_id", "subjects", "default"])
+            .list_default_sort("_id", SortOrder::Ascending)
+            .confirm_delete()
             .new_form_section()
             .title("TLS certificate")
             .fields(["_id", "cert", "private-key", "subjects", "default"])
             .build()
+            .default_provider(certificate_defaults)
             .build()
             // ---- TLS settings ----
             .new_schema("tls")
@@ -358,10 +523,69 @@ impl Builder<Schemas, ()> {
                 "server.tls.ignore-client-order",
             ])
             .build()
+            .form_action(Action::ResetToDefaults)
             .build()
     }
 }
 
+fn certificate_defaults(_: &FormData, records: &DefaultRecords) -> Vec<(&'static str, FormValue)> {
+    vec![(
+        "default",
+        FormValue::Value(records.records.is_empty().to_string()),
+    )]
+}
+
+// Providers that do not require External Account Binding still need both
+// of its values when one is given
+fn acme_eab_complete(data: &FormData) -> Option<(&'static str, String)> {
+    match (
+        data.value_is_empty("eab.kid"),
+        data.value_is_empty("eab.hmac-key"),
+    ) {
+        (false, true) => Some((
+            "eab.hmac-key",
+            "The HMAC key is required when a key ID is set".to_string(),
+        )),
+        (true, false) => Some((
+            "eab.kid",
+            "The key ID is required when an HMAC key is set".to_string(),
+        )),
+        _ => None,
+    }
+}
+
+// Names listed for SNI must be served by the certificate, certificates
+// loaded from macros are not checked
+fn certificate_covers_subjects(data: &FormData) -> Option<(&'static str, String)> {
+    let leaf = parse_certificates(data.get("cert")?)
+        .ok()?
+        .into_iter()
+        .next()?;
+    let missing = data
+        .array_value("subjects")
+        .filter(|name| {
+            !leaf.subjects.iter().any(|subject| {
+                subject.eq_ignore_ascii_case(name)
+                    || subject.strip_prefix("*.").is_some_and(|domain| {
+                        name.split_once('.')
+                            .is_some_and(|(_, parent)| parent.eq_ignore_ascii_case(domain))
+                    })
+            })
+        })
+        .collect::<Vec<_>>();
+    match missing.as_slice() {
+        [] => None,
+        [name] => Some((
+            "subjects",
+            format!("{name} is not covered by the certificate"),
+        )),
+        names => Some((
+            "subjects",
+            format!("{} are not covered by the certificate", names.join(", ")),
+        )),
+    }
+}
+
 impl Builder<Schemas, Schema> {
     pub fn add_tls_fields(self, is_listener: bool) -> Self {
         let do_override: &'static [&'static str] =
@@ -375,6 +599,7 @@ impl Builder<Schemas, Schema> {
         })
         .label("Ignore client order")
         .help("Whether to ignore the client's cipher order")
+        .advanced()
         .typ(Type::Boolean)
         .default("true")
         .display_if_eq("tls.override", do_override.iter().copied())
@@ -387,6 +612,7 @@ impl Builder<Schemas, Schema> {
         })
         .label("Handshake Timeout")
         .help("TLS handshake timeout")
+        .advanced()
         .typ(Type::Duration)
         .default("1m")
         .display_if_eq("tls.override", do_override.iter().copied())
@@ -404,6 +630,8 @@ impl Builder<Schemas, Schema> {
             source: Source::Static(TLS_PROTOCOLS),
         })
         .display_if_eq("tls.override", do_override.iter().copied())
+        .disruptive()
+        .requires(Requires::NewConnections)
         .build()
         // Ciphersuites
         .new_field(if is_listener {
@@ -413,11 +641,15 @@ impl Builder<Schemas, Schema> {
         })
         .label("Disabled Ciphersuites")
         .help("Which ciphersuites to disable")
+        .advanced()
         .typ(Type::Select {
             typ: SelectType::Many,
             source: Source::Static(TLS_CIPHERSUITES),
         })
+        .allow_custom_values()
         .display_if_eq("tls.override", do_override.iter().copied())
+        .disruptive()
+        .requires(Requires::NewConnections)
         .build()
     }
 }
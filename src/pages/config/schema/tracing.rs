@@ -11,6 +11,10 @@ impl Builder<Schemas, ()> {
     pub fn build_telemetry(self) -> Self {
         self.new_schema("tracing")
             .names("tracer", "tracers")
+            .bulk_action(Action::Enable)
+            .empty_state("Tracers export events to log files, the console or an OpenTelemetry collector.")
+            .empty_state_template("otel")
+            .optional_records()
             .prefix("tracer")
             .suffix("type")
             // Id
@@ -238,6 +242,18 @@ impl Builder<Schemas, ()> {
             .list_title("Logging & tracing methods")
             .list_subtitle("Manage logging and tracing methods")
             .list_fields(["_id", "type", "level", "enable"])
+            .template(
+                "otel",
+                "Open Telemetry collector",
+                "Export traces to an OTLP collector over HTTP",
+                &[
+                    ("_id", "otel"),
+                    ("type", "open-telemetry"),
+                    ("level", "info"),
+                    ("enable", "true"),
+                    ("transport", "http"),
+                ],
+            )
             .build()
             // Custom levels
             .new_schema("custom-levels")
@@ -481,11 +497,12 @@ impl Builder<Schemas, ()> {
             ))
             .default("0 * *")
             .typ(Type::Cron)
-            .input_check([], [Validator::Required])
+            .input_check([Transformer::Trim], [Validator::Required, Validator::IsCron])
             .enterprise_feature()
             .build()
             .new_form_section()
             .title("Tracing History")
+            .requires_capability("enterprise")
             .fields([
                 "tracing.history.store",
                 "tracing.history.retention",
@@ -494,6 +511,7 @@ impl Builder<Schemas, ()> {
             .build()
             .new_form_section()
             .title("Metrics History")
+            .requires_capability("enterprise")
             .fields([
                 "metrics.history.store",
                 "metrics.history.interval",
@@ -505,6 +523,7 @@ impl Builder<Schemas, ()> {
             // Alerts
             .new_schema("alerts")
             .names("alert", "alerts")
+            .bulk_action(Action::Enable)
             .prefix("metrics.alerts")
             .suffix("condition")
             // Id
@@ -585,6 +604,7 @@ impl Builder<Schemas, ()> {
             .help("The email address of the recipient(s)")
             .placeholder("recipient@example.com")
             .input_check_if_eq("notify.email.enable", ["true"], [], [Validator::Required, Validator::IsEmail])
+            .notification_test(NotificationTest::Email)
             .enterprise_feature()
             .build()
             // Message subject
@@ -204,6 +204,7 @@ impl Builder<Schemas, ()> {
             .build()
             // Scheduling
             .new_schema("scheduling")
+            .since("0.13.0")
             .new_field("calendar.scheduling.enable")
             .label("Enable Scheduling")
             .help(concat!(
@@ -370,6 +371,7 @@ impl Builder<Schemas, ()> {
             .build()
             // Alarms
             .new_schema("alarms")
+            .since("0.13.0")
             .new_field("calendar.alarms.enabled")
             .label("Enable E-mail Alarms")
             .help(concat!(
@@ -144,7 +144,14 @@ impl ContainsString for Field {
     fn contains_string(&self, query: &[String]) -> Option<&'static str> {
         self.label_form
             .contains_string(query)
-            .or_else(|| self.help.as_ref().and_then(|h| h.contains_string(query)))
+            .or_else(|| {
+                self.help
+                    .if_thens
+                    .iter()
+                    .map(|if_then| &if_then.value)
+                    .chain(self.help.default.as_ref())
+                    .find_map(|h| h.contains_string(query))
+            })
             .or_else(|| self.id.contains_string(query))
     }
 }
@@ -0,0 +1,71 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use leptos::*;
+use leptos_router::{use_navigate, use_params_map};
+
+use crate::{
+    components::list::ZeroResults,
+    core::{oauth::use_authorization, schema::Schemas},
+    pages::config::edit::DEFAULT_SETTINGS_URL,
+};
+
+// Shows an upgrade prompt instead of settings pages the connected server does not
+// support, and a not found state for sections that no longer exist
+#[component]
+pub fn SchemaVersionGate(children: ChildrenFn) -> impl IntoView {
+    let auth = use_authorization();
+    let params = use_params_map();
+    let schemas = expect_context::<Arc<Schemas>>();
+    let schema = create_memo(move |_| {
+        params
+            .get()
+            .get("object")
+            .and_then(|id| schemas.get(id.as_str()))
+    });
+    let since = create_memo(move |_| schema.with(|schema| schema.as_ref().and_then(|s| s.since)));
+    let children = store_value(children);
+
+    view! {
+        <Show
+            when=move || schema.with(Option::is_some)
+            fallback=move || {
+                let subtitle = format!(
+                    "The configuration section {:?} does not exist. It may have been renamed or removed in this version.",
+                    params.get().get("object").cloned().unwrap_or_default(),
+                );
+                view! {
+                    <ZeroResults
+                        title="Unknown configuration section"
+                        subtitle=subtitle
+                        button_text="Back to settings".to_string()
+                        button_action=Callback::new(move |_| {
+                            use_navigate()(DEFAULT_SETTINGS_URL, Default::default());
+                        })
+                    />
+                }
+            }
+        >
+
+            <Show
+                when=move || auth.with(|auth| auth.supports(since.get()))
+                fallback=move || {
+                    let subtitle = format!(
+                        "This page requires Stalwart Mail Server version {} or later. The connected server is running version {}.",
+                        since.get().unwrap_or_default(),
+                        auth.with(|auth| auth.server_version),
+                    );
+                    view! { <ZeroResults title="Server upgrade required" subtitle=subtitle/> }
+                }
+            >
+
+                {children.with_value(|children| children())}
+            </Show>
+        </Show>
+    }
+}
@@ -6,7 +6,7 @@
 
 use std::{sync::Arc, vec};
 
-use ahash::{AHashMap, AHashSet};
+use ahash::AHashMap;
 use base64::{engine::general_purpose, Engine};
 use chrono::Utc;
 use humansize::{format_size, DECIMAL};
@@ -27,7 +27,10 @@ use crate::{
             tab::Tab,
             Form, FormButtonBar, FormElement, FormItem, FormSection, ValidateCb,
         },
-        messages::alert::{use_alerts, Alert},
+        messages::{
+            alert::{use_alerts, Alert},
+            modal::{use_modals, Modal},
+        },
         skeleton::Skeleton,
         Color,
     },
@@ -36,10 +39,16 @@ use crate::{
         http::{self, HttpRequest},
         oauth::use_authorization,
         schema::{ArrayType, Builder, Schemas, Transformer, Type, Validator},
+        url::list_url,
         Permission,
     },
     pages::{
-        directory::{Principal, PrincipalType, PrincipalValue, PERMISSIONS},
+        directory::{
+            permissions::{removed_lockout_permissions, PermissionsEditor},
+            storage::AccountStorage,
+            vacation::AccountVacation,
+            Principal, PrincipalType, PrincipalValue,
+        },
         List,
     },
 };
@@ -67,6 +76,7 @@ struct DkimSignature {
 pub fn PrincipalEdit() -> impl IntoView {
     let auth = use_authorization();
     let alert = use_alerts();
+    let modal = use_modals();
     let params = use_params_map();
     let selected_type = create_memo(move |_| {
         match params
@@ -302,7 +312,10 @@ pub fn PrincipalEdit() -> impl IntoView {
             match result {
                 Ok(_) => {
                     use_navigate()(
-                        &format!("/manage/directory/{}", selected_type.resource_name()),
+                        &list_url(format!(
+                            "/manage/directory/{}",
+                            selected_type.resource_name()
+                        )),
                         Default::default(),
                     );
                 }
@@ -312,6 +325,38 @@ pub fn PrincipalEdit() -> impl IntoView {
             }
         }
     });
+    let save = Callback::new(move |_| {
+        if pending.get_untracked() || save_changes.pending().get_untracked() {
+            return;
+        }
+
+        data.update(|data| {
+            if let Some(changes) = data.to_principal(add_app_passwords) {
+                let current = current_principal.get_untracked();
+                let username = auth.get_untracked().username.to_string();
+                let removed = removed_lockout_permissions(&current, &changes);
+                let affects_self = current.name() == Some(username.as_str())
+                    || current.members.as_string_list().contains(&username);
+
+                if !removed.is_empty() && affects_self {
+                    modal.set(
+                        Modal::with_title("You may lock yourself out")
+                            .with_message(concat!(
+                                "These changes remove permissions your own account needs ",
+                                "to edit permissions, and you may not be able to undo them:"
+                            ))
+                            .with_details(removed)
+                            .with_button("Save changes")
+                            .with_dangerous_callback(move || {
+                                save_changes.dispatch(changes.clone());
+                            }),
+                    );
+                } else {
+                    save_changes.dispatch(changes);
+                }
+            }
+        });
+    });
 
     let title = create_memo(move |_| {
         if let Some(name) = params.get().get("id") {
@@ -359,7 +404,7 @@ pub fn PrincipalEdit() -> impl IntoView {
     });
 
     view! {
-        <Form title=title subtitle="".to_string()>
+        <Form title=title subtitle="".to_string() on_submit=save>
 
             <Transition fallback=Skeleton set_pending>
 
@@ -389,6 +434,13 @@ pub fn PrincipalEdit() -> impl IntoView {
                         let total_quota = principal.quota.as_int().unwrap_or_default();
                         current_principal.set(principal);
                         let typ = selected_type.get();
+                        let account = params
+                            .get_untracked()
+                            .get("id")
+                            .filter(|_| typ == PrincipalType::Individual)
+                            .cloned();
+                        let has_storage = account.is_some();
+                        let vacation_account = account.clone();
                         Some(
                             view! {
                                 <Tab tabs=Signal::derive(move || {
@@ -421,6 +473,8 @@ pub fn PrincipalEdit() -> impl IntoView {
                                             | PrincipalType::ApiKey
                                         )
                                             .then_some("Permissions".to_string()),
+                                        has_storage.then_some("Storage".to_string()),
+                                        has_storage.then_some("Vacation response".to_string()),
                                     ]
                                 })>
 
@@ -960,110 +1014,16 @@ pub fn PrincipalEdit() -> impl IntoView {
                                             })
                                         >
 
-                                            <div class="grid space-y-2">
-                                                {move || {
-                                                    let form_data = data.get();
-                                                    let enabled_permissions = form_data
-                                                        .array_value("enabled-permissions")
-                                                        .collect::<AHashSet<_>>();
-                                                    let disabled_permissions = form_data
-                                                        .array_value("disabled-permissions")
-                                                        .collect::<AHashSet<_>>();
-                                                    PERMISSIONS
-                                                        .iter()
-                                                        .map(|(id, name)| {
-                                                            view! {
-                                                                <div class="flex flex-col space-y-3 p-3 w-full bg-white border border-gray-200 rounded-lg focus:border-blue-500 focus:ring-blue-500 dark:bg-neutral-900 dark:border-neutral-700">
-                                                                    <div class="flex justify-between items-center">
-                                                                        <span
-                                                                            id="hs-radio-delete-description"
-                                                                            class="block text-sm text-gray-600 dark:text-neutral-500"
-                                                                        >
-                                                                            {name.to_string()}
-                                                                        </span>
-
-                                                                        <div class="flex gap-x-6">
-                                                                            <div class="flex">
-                                                                                <input
-                                                                                    type="radio"
-                                                                                    name=id.to_string()
-                                                                                    class="shrink-0 mt-0.5 border-gray-200 rounded-full text-blue-600 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-neutral-800 dark:border-neutral-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-800"
-                                                                                    id=format!("{id}-on")
-                                                                                    prop:checked=enabled_permissions.contains(id)
-                                                                                    on:input=move |_| {
-                                                                                        data.update_untracked(|data| {
-                                                                                            data.array_push(
-                                                                                                "enabled-permissions",
-                                                                                                id.to_string(),
-                                                                                                true,
-                                                                                            );
-                                                                                            data.array_delete_item("disabled-permissions", id);
-                                                                                        });
-                                                                                    }
-                                                                                />
-
-                                                                                <label class="text-sm text-gray-500 ms-2 dark:text-neutral-400">
-                                                                                    On
-                                                                                </label>
-                                                                            </div>
-
-                                                                            <div class="flex">
-                                                                                <input
-                                                                                    type="radio"
-                                                                                    name=id.to_string()
-                                                                                    class="shrink-0 mt-0.5 border-gray-200 rounded-full text-blue-600 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-neutral-800 dark:border-neutral-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-800"
-                                                                                    id=format!("{id}-off")
-                                                                                    prop:checked=disabled_permissions.contains(id)
-                                                                                    on:input=move |_| {
-                                                                                        data.update_untracked(|data| {
-                                                                                            data.array_push(
-                                                                                                "disabled-permissions",
-                                                                                                id.to_string(),
-                                                                                                true,
-                                                                                            );
-                                                                                            data.array_delete_item("enabled-permissions", id);
-                                                                                        });
-                                                                                    }
-                                                                                />
-
-                                                                                <label class="text-sm text-gray-500 ms-2 dark:text-neutral-400">
-                                                                                    Off
-                                                                                </label>
-                                                                            </div>
-
-                                                                            <div class="flex">
-                                                                                <input
-                                                                                    type="radio"
-                                                                                    name=id.to_string()
-                                                                                    class="shrink-0 mt-0.5 border-gray-200 rounded-full text-blue-600 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-neutral-800 dark:border-neutral-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-800"
-                                                                                    id=format!("{id}-inherit")
-                                                                                    prop:checked=!enabled_permissions.contains(id)
-                                                                                        && !disabled_permissions.contains(id)
-                                                                                    on:input=move |_| {
-                                                                                        data.update_untracked(|data| {
-                                                                                            data.array_delete_item("disabled-permissions", id);
-                                                                                            data.array_delete_item("enabled-permissions", id);
-                                                                                        });
-                                                                                    }
-                                                                                />
-
-                                                                                <label class="text-sm text-gray-500 ms-2 dark:text-neutral-400">
-                                                                                    Default
-                                                                                </label>
-                                                                            </div>
-                                                                        </div>
-                                                                    </div>
-                                                                </div>
-                                                            }
-                                                        })
-                                                        .collect_view()
-                                                }}
-
-                                            </div>
+                                            <PermissionsEditor data/>
                                         </FormItem>
 
                                     </FormSection>
 
+                                    {account.map(|account| view! { <AccountStorage account/> })}
+
+                                    {vacation_account
+                                        .map(|account| view! { <AccountVacation account/> })}
+
                                 </Tab>
                             }
                                 .into_view(),
@@ -1079,7 +1039,9 @@ pub fn PrincipalEdit() -> impl IntoView {
                     color=Color::Gray
                     on_click=move |_| {
                         use_navigate()(
-                            &format!("/manage/directory/{}", selected_type.get().resource_name()),
+                            &list_url(
+                                format!("/manage/directory/{}", selected_type.get().resource_name()),
+                            ),
                             Default::default(),
                         );
                     }
@@ -1088,15 +1050,9 @@ pub fn PrincipalEdit() -> impl IntoView {
                 <Button
                     text="Save changes"
                     color=Color::Blue
-                    on_click=Callback::new(move |_| {
-                        data.update(|data| {
-                            if let Some(changes) = data.to_principal(add_app_passwords) {
-                                save_changes.dispatch(changes);
-                            }
-                        });
-                    })
-
+                    on_click=save
                     disabled=pending
+                    loading=save_changes.pending()
                 />
             </FormButtonBar>
 
@@ -0,0 +1,739 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use ahash::AHashSet;
+use humansize::{format_size, DECIMAL};
+use leptos::*;
+use leptos_router::use_navigate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{
+        badge::Badge,
+        form::{
+            button::Button,
+            input::{InputSwitch, InputText},
+            select::Select,
+            Form, FormButtonBar, FormElement, FormItem, FormSection,
+        },
+        list::table::{Table, TableRow},
+        messages::alert::{use_alerts, Alert},
+        skeleton::Skeleton,
+        Color,
+    },
+    core::{
+        form::FormData,
+        http::{self, HttpRequest, ManagementApiError},
+        oauth::use_authorization,
+        schema::{Builder, Schemas, SelectType, Source, Transformer, Type, Validator},
+        url::list_url,
+        AccessToken,
+    },
+    pages::{
+        config::{edit::FetchSettings, Settings},
+        directory::{Principal, PrincipalType},
+        maybe_plural, List,
+    },
+};
+
+const PREVIEW_PAGE_SIZE: u32 = 25;
+const IMPORT_BATCH_SIZE: u32 = 100;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+struct ImportMapping {
+    name: String,
+    description: String,
+    email: String,
+    quota: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ImportCandidate {
+    #[serde(flatten)]
+    principal: Principal,
+    #[serde(default)]
+    exists: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ImportOutcome {
+    Created,
+    Skipped,
+    Conflict,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct ImportProgress {
+    processed: u64,
+    total: u64,
+}
+
+#[component]
+pub fn PrincipalImport() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let data = expect_context::<Arc<Schemas>>()
+        .build_form("principal-import")
+        .into_signal();
+
+    let preview = create_rw_signal(None::<List<ImportCandidate>>);
+    let preview_page = create_rw_signal(1u32);
+    let excluded = create_rw_signal(AHashSet::<String>::new());
+    let outcomes = create_rw_signal(BTreeMap::<String, ImportOutcome>::new());
+    let progress = create_rw_signal(None::<ImportProgress>);
+
+    let directories = create_resource(
+        move || (),
+        move |_| {
+            let auth = auth.get_untracked();
+
+            async move {
+                HttpRequest::get("/api/settings/group")
+                    .with_authorization(&auth)
+                    .with_parameter("prefix", "directory")
+                    .with_parameter("suffix", "type")
+                    .with_parameter("field", "type")
+                    .send::<List<Settings>>()
+                    .await
+                    .map(|list| {
+                        list.items
+                            .into_iter()
+                            .filter_map(|mut item| {
+                                let typ = match item.remove("type")?.as_str() {
+                                    "ldap" => "LDAP",
+                                    "sql" => "SQL",
+                                    _ => return None,
+                                };
+                                let id = item.remove("_id")?;
+                                Some((id.clone(), format!("{id} ({typ})")))
+                            })
+                            .collect::<Vec<_>>()
+                    })
+            }
+        },
+    );
+
+    let directory = create_memo(move |_| {
+        data.with(|data| data.value::<String>("directory").unwrap_or_default())
+    });
+    let mapping = create_memo(move |_| data.with(|data| data.import_mapping()));
+    let skip_existing = create_memo(move |_| {
+        data.with(|data| data.value::<bool>("skip-existing").unwrap_or_default())
+    });
+
+    // Prefill the mapping with the attributes configured for the directory
+    let load_mapping = create_action(move |id: &String| {
+        let id = id.clone();
+        let auth = auth.get();
+
+        async move {
+            match HttpRequest::get("/api/settings/list")
+                .with_authorization(&auth)
+                .with_parameter("prefix", format!("directory.{id}"))
+                .send::<FetchSettings>()
+                .await
+            {
+                Ok(settings) => {
+                    data.update(|data| data.set_import_mapping(&settings.items));
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+    create_effect(move |_| {
+        let directory = directory.get();
+        outcomes.set(BTreeMap::new());
+        progress.set(None);
+        if !directory.is_empty() {
+            load_mapping.dispatch(directory);
+        }
+    });
+    create_effect(move |_| {
+        directory.track();
+        mapping.track();
+        preview.set(None);
+        excluded.set(AHashSet::new());
+    });
+
+    let fetch_preview = create_action(move |page: &u32| {
+        let page = *page;
+        let auth = auth.get();
+        let directory = directory.get();
+        let mapping = mapping.get();
+
+        async move {
+            match fetch_candidates(&auth, &directory, &mapping, page, PREVIEW_PAGE_SIZE).await {
+                Ok(candidates) => {
+                    preview_page.set(page);
+                    preview.set(Some(candidates));
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+
+    let run_import = create_action(move |_: &()| {
+        let auth = auth.get();
+        let directory = directory.get();
+        let mapping = mapping.get();
+        let skip_existing = skip_existing.get();
+        let excluded = excluded.get();
+
+        async move {
+            outcomes.set(BTreeMap::new());
+            progress.set(Some(ImportProgress::default()));
+
+            let mut page = 1;
+            loop {
+                let candidates =
+                    match fetch_candidates(&auth, &directory, &mapping, page, IMPORT_BATCH_SIZE)
+                        .await
+                    {
+                        Ok(candidates) => candidates,
+                        Err(http::Error::Unauthorized) => {
+                            use_navigate()("/login", Default::default());
+                            return;
+                        }
+                        Err(err) => {
+                            alert.set(Alert::from(err));
+                            break;
+                        }
+                    };
+                let total = candidates.total;
+                let is_last = candidates.items.len() < IMPORT_BATCH_SIZE as usize
+                    || (page * IMPORT_BATCH_SIZE) as u64 >= total;
+
+                for candidate in candidates.items {
+                    let login = candidate.principal.name_or_empty();
+                    if excluded.contains(&login) {
+                        advance_progress(progress, total);
+                        continue;
+                    }
+
+                    let outcome = if candidate.exists && skip_existing {
+                        ImportOutcome::Skipped
+                    } else {
+                        match HttpRequest::post("/api/principal")
+                            .with_authorization(&auth)
+                            .with_body(Principal {
+                                typ: Some(PrincipalType::Individual),
+                                ..candidate.principal
+                            })
+                            .unwrap()
+                            .send::<u32>()
+                            .await
+                        {
+                            Ok(_) => ImportOutcome::Created,
+                            Err(http::Error::Unauthorized) => {
+                                use_navigate()("/login", Default::default());
+                                return;
+                            }
+                            Err(http::Error::Server(ManagementApiError::FieldAlreadyExists {
+                                ..
+                            })) => {
+                                if skip_existing {
+                                    ImportOutcome::Skipped
+                                } else {
+                                    ImportOutcome::Conflict
+                                }
+                            }
+                            Err(err) => ImportOutcome::Failed(failure_reason(err)),
+                        }
+                    };
+                    outcomes.update(|outcomes| {
+                        outcomes.insert(login, outcome);
+                    });
+                    advance_progress(progress, total);
+                }
+
+                if is_last {
+                    break;
+                }
+                page += 1;
+            }
+
+            let (created, skipped, failed) = outcomes.with_untracked(|outcomes| {
+                outcomes
+                    .values()
+                    .fold((0, 0, 0), |(c, s, f), outcome| match outcome {
+                        ImportOutcome::Created => (c + 1, s, f),
+                        ImportOutcome::Skipped => (c, s + 1, f),
+                        ImportOutcome::Conflict | ImportOutcome::Failed(_) => (c, s, f + 1),
+                    })
+            });
+            let summary = format!(
+                "{} created, {} skipped, {} not imported.",
+                maybe_plural(created, "account", "accounts"),
+                skipped,
+                failed
+            );
+            alert.set(if failed == 0 {
+                Alert::success("Import completed").with_details(summary)
+            } else {
+                Alert::warning("Import completed with errors")
+                    .with_details(summary)
+                    .without_timeout()
+            });
+        }
+    });
+
+    let is_busy = Signal::derive(move || {
+        fetch_preview.pending().get() || run_import.pending().get() || directory.get().is_empty()
+    });
+
+    view! {
+        <Form
+            title="Import principals"
+            subtitle="Create internal accounts from an existing LDAP or SQL directory"
+        >
+
+            <Transition fallback=Skeleton>
+                {move || match directories.get() {
+                    None => None,
+                    Some(Err(http::Error::Unauthorized)) => {
+                        use_navigate()("/login", Default::default());
+                        Some(view! { <div></div> }.into_view())
+                    }
+                    Some(Err(err)) => {
+                        alert.set(Alert::from(err));
+                        Some(view! { <div></div> }.into_view())
+                    }
+                    Some(Ok(directories)) => {
+                        let options = create_memo(move |_| directories.clone());
+                        Some(
+                            view! {
+                                <FormSection title="Source".to_string()>
+                                    <FormItem
+                                        label="Directory"
+                                        tooltip="The external directory to import principals from"
+                                    >
+                                        <Select
+                                            element=FormElement::new("directory", data)
+                                            options=options
+                                            add_none=true
+                                        />
+                                    </FormItem>
+                                    <FormItem label="">
+                                        <InputSwitch
+                                            label="Skip existing accounts"
+                                            tooltip="Skip principals that already exist in the internal directory instead of reporting them as conflicts. Enable this to resume an import that stopped."
+                                            element=FormElement::new("skip-existing", data)
+                                        />
+                                    </FormItem>
+                                </FormSection>
+                                <FormSection
+                                    title="Attribute mapping".to_string()
+                                    hide=Signal::derive(move || directory.get().is_empty())
+                                >
+                                    <FormItem
+                                        label="Login"
+                                        tooltip="Attribute or column holding the account name"
+                                    >
+                                        <InputText element=FormElement::new("mapping.name", data)/>
+                                    </FormItem>
+                                    <FormItem
+                                        label="Name"
+                                        tooltip="Attribute or column holding the full name"
+                                        is_optional=true
+                                    >
+                                        <InputText element=FormElement::new(
+                                            "mapping.description",
+                                            data,
+                                        )/>
+                                    </FormItem>
+                                    <FormItem
+                                        label="E-mail"
+                                        tooltip="Attribute or column holding the e-mail addresses"
+                                        is_optional=true
+                                    >
+                                        <InputText element=FormElement::new("mapping.email", data)/>
+                                    </FormItem>
+                                    <FormItem
+                                        label="Quota"
+                                        tooltip="Attribute or column holding the disk quota in bytes"
+                                        is_optional=true
+                                    >
+                                        <InputText element=FormElement::new("mapping.quota", data)/>
+                                    </FormItem>
+                                </FormSection>
+                            }
+                                .into_view(),
+                        )
+                    }
+                }}
+
+            </Transition>
+
+            {move || {
+                preview
+                    .get()
+                    .map(|candidates| {
+                        let page = preview_page.get();
+                        let total = candidates.total;
+                        let has_more = (page * PREVIEW_PAGE_SIZE) as u64 < total;
+                        let rows = candidates
+                            .items
+                            .into_iter()
+                            .map(|candidate| {
+                                let login = candidate.principal.name_or_empty();
+                                let login_ = login.clone();
+                                let login__ = login.clone();
+                                let quota = candidate
+                                    .principal
+                                    .quota
+                                    .as_int_non_zero()
+                                    .map(|quota| format_size(quota, DECIMAL))
+                                    .unwrap_or_else(|| "Unlimited".to_string());
+                                let status = move || {
+                                    match outcomes.with(|outcomes| outcomes.get(&login__).cloned())
+                                    {
+                                        Some(ImportOutcome::Created) => {
+                                            view! { <Badge color=Color::Green>Imported</Badge> }
+                                        }
+                                        Some(ImportOutcome::Skipped) => {
+                                            view! { <Badge color=Color::Gray>Skipped</Badge> }
+                                        }
+                                        Some(ImportOutcome::Conflict) => {
+                                            view! { <Badge color=Color::Yellow>Conflict</Badge> }
+                                        }
+                                        Some(ImportOutcome::Failed(reason)) => {
+                                            view! {
+                                                <Badge color=Color::Red attr:title=reason>
+                                                    Failed
+                                                </Badge>
+                                            }
+                                        }
+                                        None if candidate.exists => {
+                                            view! { <Badge color=Color::Yellow>Already exists</Badge> }
+                                        }
+                                        None => view! { <Badge color=Color::Blue>New</Badge> },
+                                    }
+                                };
+                                view! {
+                                    <TableRow>
+                                        <input
+                                            type="checkbox"
+                                            class="shrink-0 border-gray-300 rounded text-blue-600 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-600"
+                                            prop:checked=move || {
+                                                excluded.with(|excluded| !excluded.contains(&login_))
+                                            }
+                                            on:change=move |ev| {
+                                                let include = event_target_checked(&ev);
+                                                excluded
+                                                    .update(|excluded| {
+                                                        if include {
+                                                            excluded.remove(&login);
+                                                        } else {
+                                                            excluded.insert(login.clone());
+                                                        }
+                                                    });
+                                            }
+                                        />
+
+                                        <span>{candidate.principal.name_or_empty()}</span>
+                                        <span>
+                                            {candidate.principal.description().unwrap_or_default().to_string()}
+                                        </span>
+                                        <span>
+                                            {candidate.principal.emails.as_string_list().join(", ")}
+                                        </span>
+                                        <span>{quota}</span>
+                                        <span>{status}</span>
+                                    </TableRow>
+                                }
+                            })
+                            .collect_view();
+                        view! {
+                            <FormSection title="Preview".to_string()>
+                                <div class="sm:col-span-12">
+                                    <p class="mb-3 text-sm text-gray-600 dark:text-gray-400">
+                                        {format!(
+                                            "{} found in the directory. Uncheck the rows that should not be imported.",
+                                            maybe_plural(total as usize, "principal", "principals"),
+                                        )}
+
+                                    </p>
+                                    <Table headers=vec![
+                                        "Import".to_string(),
+                                        "Login".to_string(),
+                                        "Name".to_string(),
+                                        "E-mail".to_string(),
+                                        "Quota".to_string(),
+                                        "Status".to_string(),
+                                    ]>{rows}</Table>
+                                    <div class="mt-3 flex justify-end gap-x-2">
+                                        <Button
+                                            text="Previous"
+                                            color=Color::Gray
+                                            disabled=Signal::derive(move || {
+                                                page <= 1 || is_busy.get()
+                                            })
+                                            on_click=move |_| {
+                                                fetch_preview.dispatch(page - 1);
+                                            }
+                                        />
+
+                                        <Button
+                                            text="Next"
+                                            color=Color::Gray
+                                            disabled=Signal::derive(move || {
+                                                !has_more || is_busy.get()
+                                            })
+                                            on_click=move |_| {
+                                                fetch_preview.dispatch(page + 1);
+                                            }
+                                        />
+
+                                    </div>
+                                </div>
+                            </FormSection>
+                        }
+                    })
+            }}
+
+            {move || {
+                progress
+                    .get()
+                    .map(|progress| {
+                        let percent = if progress.total > 0 {
+                            (progress.processed * 100 / progress.total).min(100)
+                        } else {
+                            0
+                        };
+                        view! {
+                            <div class="mt-5">
+                                <div class="mb-2 flex justify-between text-sm text-gray-800 dark:text-white">
+                                    <span>
+                                        {if run_import.pending().get() {
+                                            "Importing principals..."
+                                        } else {
+                                            "Import finished"
+                                        }}
+
+                                    </span>
+                                    <span>
+                                        {format!("{} of {}", progress.processed, progress.total)}
+                                    </span>
+                                </div>
+                                <div
+                                    class="flex w-full h-2 bg-gray-200 rounded-full overflow-hidden dark:bg-gray-700"
+                                    role="progressbar"
+                                    aria-valuenow=percent
+                                    aria-valuemin="0"
+                                    aria-valuemax="100"
+                                >
+                                    <div
+                                        class="flex flex-col justify-center rounded-full overflow-hidden bg-blue-600 transition duration-500 dark:bg-blue-500"
+                                        style=format!("width: {percent}%")
+                                    ></div>
+                                </div>
+                                <ImportReport outcomes/>
+                            </div>
+                        }
+                    })
+            }}
+
+            <FormButtonBar>
+                <Button
+                    text="Cancel"
+                    color=Color::Gray
+                    on_click=move |_| {
+                        use_navigate()(&list_url("/manage/directory/accounts"), Default::default());
+                    }
+                />
+
+                <Button
+                    text="Preview"
+                    color=Color::Gray
+                    disabled=is_busy
+                    loading=fetch_preview.pending()
+                    on_click=move |_| {
+                        if data.try_update(|data| data.validate_form()).unwrap_or_default() {
+                            fetch_preview.dispatch(1);
+                        }
+                    }
+                />
+
+                <Button
+                    text="Import"
+                    color=Color::Blue
+                    disabled=Signal::derive(move || {
+                        is_busy.get() || preview.with(|preview| preview.is_none())
+                    })
+                    loading=run_import.pending()
+                    on_click=move |_| {
+                        if data.try_update(|data| data.validate_form()).unwrap_or_default() {
+                            run_import.dispatch(());
+                        }
+                    }
+                />
+
+            </FormButtonBar>
+        </Form>
+    }
+}
+
+#[component]
+fn ImportReport(outcomes: RwSignal<BTreeMap<String, ImportOutcome>>) -> impl IntoView {
+    let issues = create_memo(move |_| {
+        outcomes.with(|outcomes| {
+            outcomes
+                .iter()
+                .filter_map(|(login, outcome)| match outcome {
+                    ImportOutcome::Conflict => {
+                        Some((login.clone(), "Account already exists".to_string()))
+                    }
+                    ImportOutcome::Failed(reason) => Some((login.clone(), reason.clone())),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+
+    view! {
+        <Show when=move || issues.with(|issues| !issues.is_empty())>
+            <div class="mt-5 bg-yellow-50 border border-yellow-200 text-sm text-yellow-800 rounded-lg p-4 dark:bg-yellow-800/10 dark:border-yellow-900 dark:text-yellow-500">
+                <p class="font-semibold">
+                    {move || {
+                        format!(
+                            "{} could not be imported",
+                            maybe_plural(issues.with(|issues| issues.len()), "principal", "principals"),
+                        )
+                    }}
+
+                </p>
+                <p class="mt-1">
+                    Run the import again with "Skip existing accounts" enabled to resume without creating duplicates.
+                </p>
+                <ul class="mt-2 space-y-1 font-mono text-xs">
+                    {move || {
+                        issues
+                            .get()
+                            .into_iter()
+                            .map(|(login, reason)| view! { <li>{format!("{login}: {reason}")}</li> })
+                            .collect_view()
+                    }}
+
+                </ul>
+            </div>
+        </Show>
+    }
+}
+
+fn failure_reason(err: http::Error) -> String {
+    match err {
+        http::Error::Network(details)
+        | http::Error::Server(
+            ManagementApiError::Unsupported { details } | ManagementApiError::Other { details, .. },
+        ) => details,
+        http::Error::Server(ManagementApiError::FieldMissing { field }) => {
+            format!("Missing field {field}")
+        }
+        err => Alert::from(err).message,
+    }
+}
+
+fn advance_progress(progress: RwSignal<Option<ImportProgress>>, total: u64) {
+    progress.update(|progress| {
+        if let Some(progress) = progress {
+            progress.processed += 1;
+            progress.total = total;
+        }
+    });
+}
+
+async fn fetch_candidates(
+    auth: &AccessToken,
+    directory: &str,
+    mapping: &ImportMapping,
+    page: u32,
+    limit: u32,
+) -> http::Result<List<ImportCandidate>> {
+    HttpRequest::post(("/api/principal/import", directory))
+        .with_authorization(auth)
+        .with_parameter("page", page.to_string())
+        .with_parameter("limit", limit.to_string())
+        .with_body(mapping)
+        .unwrap()
+        .send::<List<ImportCandidate>>()
+        .await
+}
+
+impl FormData {
+    fn import_mapping(&self) -> ImportMapping {
+        ImportMapping {
+            name: self.value("mapping.name").unwrap_or_default(),
+            description: self.value("mapping.description").unwrap_or_default(),
+            email: self.value("mapping.email").unwrap_or_default(),
+            quota: self.value("mapping.quota").unwrap_or_default(),
+        }
+    }
+
+    fn set_import_mapping(&mut self, settings: &Settings) {
+        let section = if settings.get("type").is_some_and(|typ| typ == "sql") {
+            "columns"
+        } else {
+            "attributes"
+        };
+
+        for key in ["name", "description", "email", "quota"] {
+            let attribute = format!("{section}.{key}");
+            let value = settings
+                .get(&attribute)
+                .or_else(|| settings.get(&format!("{attribute}.0")))
+                .cloned()
+                .unwrap_or_default();
+            self.set(format!("mapping.{key}"), value);
+        }
+    }
+}
+
+impl Builder<Schemas, ()> {
+    pub fn build_principal_import(self) -> Self {
+        self.new_schema("principal-import")
+            .new_field("directory")
+            .typ(Type::Select {
+                source: Source::Static(&[]),
+                typ: SelectType::Single,
+            })
+            .input_check([], [Validator::Required])
+            .build()
+            .new_field("skip-existing")
+            .typ(Type::Boolean)
+            .default("true")
+            .build()
+            .new_field("mapping.name")
+            .typ(Type::Input)
+            .input_check([Transformer::Trim], [Validator::Required])
+            .build()
+            .new_field("mapping.description")
+            .typ(Type::Input)
+            .input_check([Transformer::Trim], [])
+            .build()
+            .new_field("mapping.email")
+            .typ(Type::Input)
+            .input_check([Transformer::Trim], [])
+            .build()
+            .new_field("mapping.quota")
+            .typ(Type::Input)
+            .input_check([Transformer::Trim], [])
+            .build()
+            .build()
+    }
+}
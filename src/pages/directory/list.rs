@@ -14,7 +14,7 @@ use leptos_router::*;
 use crate::{
     components::{
         badge::Badge,
-        icon::{IconAdd, IconThreeDots, IconTrash},
+        icon::{IconAdd, IconArrowUpTray, IconThreeDots, IconTrash},
         list::{
             header::ColumnList,
             pagination::Pagination,
@@ -33,7 +33,8 @@ use crate::{
     core::{
         http::{self, HttpRequest},
         oauth::use_authorization,
-        url::UrlBuilder,
+        url::{use_list_state, UrlBuilder},
+        Permission,
     },
     pages::{
         directory::{Principal, PrincipalType},
@@ -45,6 +46,7 @@ const PAGE_SIZE: u32 = 10;
 
 #[component]
 pub fn PrincipalList() -> impl IntoView {
+    use_list_state();
     let selected = create_rw_signal::<ItemSelection>(ItemSelection::None);
     provide_context(selected);
 
@@ -92,6 +94,10 @@ pub fn PrincipalList() -> impl IntoView {
     let auth = use_authorization();
     let alert = use_alerts();
     let modal = use_modals();
+    let can_import = auth
+        .get_untracked()
+        .permissions()
+        .has_access_all(&[Permission::IndividualCreate, Permission::SettingsList]);
 
     let principals = create_resource(
         move || (page.get(), filter.get()),
@@ -289,6 +295,24 @@ pub fn PrincipalList() -> impl IntoView {
                         <IconTrash/>
                     </ToolbarButton>
 
+                    <Show when=move || {
+                        can_import && selected_type.get() == PrincipalType::Individual
+                    }>
+                        <ToolbarButton
+                            text="Import"
+                            color=Color::Gray
+                            on_click=move |_| {
+                                use_navigate()(
+                                    "/manage/directory/accounts/import",
+                                    Default::default(),
+                                );
+                            }
+                        >
+
+                            <IconArrowUpTray size=16 attr:class="flex-shrink-0 size-3"/>
+                        </ToolbarButton>
+                    </Show>
+
                     <ToolbarButton
                         text=create_memo(move |_| {
                             format!("Create {}", selected_type.get().item_name(false))
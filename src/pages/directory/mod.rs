@@ -15,7 +15,11 @@ use base64::{engine::general_purpose::STANDARD, Engine};
 
 pub mod dns;
 pub mod edit;
+pub mod import;
 pub mod list;
+pub mod permissions;
+pub mod storage;
+pub mod vacation;
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Principal {
@@ -1154,12 +1158,20 @@ pub static PERMISSIONS: &[(&str, &str)] = &[
     ("purge-blob-store", "Purge the blob storage"),
     ("purge-data-store", "Purge the data storage"),
     ("purge-in-memory-store", "Purge the in-memory storage"),
+    ("quarantine-delete", "Delete quarantined messages"),
+    ("quarantine-list", "View quarantined messages"),
+    (
+        "quarantine-release",
+        "Release quarantined messages for delivery",
+    ),
     ("restart", "Restart the email server"),
     ("role-create", "Create new roles"),
     ("role-delete", "Remove roles"),
     ("role-get", "Retrieve specific role information"),
     ("role-list", "View list of roles"),
     ("role-update", "Modify role information"),
+    ("session-delete", "Disconnect active sessions"),
+    ("session-list", "View active sessions"),
     ("settings-delete", "Remove system settings"),
     ("settings-list", "View system settings"),
     ("settings-reload", "Refresh system settings"),
@@ -0,0 +1,488 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use ahash::{AHashMap, AHashSet};
+use leptos::*;
+use leptos_router::use_navigate;
+use serde::Deserialize;
+
+use crate::{
+    components::{
+        form::button::Button,
+        messages::alert::{use_alerts, Alert},
+        Color,
+    },
+    core::{
+        form::FormData,
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+    },
+    pages::{
+        directory::{Principal, PERMISSIONS},
+        List,
+    },
+};
+
+// Removing any of these from the signed in account may prevent it
+// from editing permissions again
+pub const LOCKOUT_PERMISSIONS: &[&str] = &[
+    "principal-update",
+    "individual-update",
+    "group-update",
+    "role-update",
+];
+
+const AREAS: &[(&str, &[&str])] = &[
+    (
+        "Settings",
+        &[
+            "settings-",
+            "webadmin-",
+            "spam-filter-",
+            "dkim-signature-",
+            "tracing-",
+            "logs-",
+            "metrics-",
+            "restart",
+            "purge-",
+            "troubleshoot",
+        ],
+    ),
+    ("Queue", &["message-queue-", "quarantine-"]),
+    (
+        "Directory",
+        &[
+            "individual-",
+            "group-",
+            "domain-",
+            "tenant-",
+            "role-",
+            "mailing-list-",
+            "principal-",
+            "api-key-",
+            "oauth-client-",
+            "impersonate",
+        ],
+    ),
+    ("Reports", &["incoming-report-", "outgoing-report-"]),
+];
+const OTHER_AREA: &str = "Mail & protocols";
+const ADMIN_ROLE: &str = "admin";
+const BUILT_IN_ROLES: &[&str] = &["admin", "tenant-admin", "user"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Preset {
+    FullAdmin,
+    QueueOperator,
+    ReadOnly,
+    Reset,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PermissionInfo {
+    pub id: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Grants {
+    enabled: Vec<String>,
+    disabled: Vec<String>,
+    roles: Vec<String>,
+}
+
+#[component]
+pub fn PermissionsEditor(data: RwSignal<FormData>) -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let search = create_rw_signal(String::new());
+
+    let catalogue = create_resource(
+        move || (),
+        move |_| {
+            let auth = auth.get_untracked();
+
+            async move {
+                let permissions = match HttpRequest::get("/api/permissions")
+                    .with_authorization(&auth)
+                    .send::<Vec<PermissionInfo>>()
+                    .await
+                {
+                    Ok(permissions) => permissions,
+                    // Older servers do not list their permissions
+                    Err(http::Error::NotFound) => PERMISSIONS
+                        .iter()
+                        .map(|(id, description)| PermissionInfo {
+                            id: id.to_string(),
+                            description: description.to_string(),
+                        })
+                        .collect(),
+                    Err(err) => return Err(err),
+                };
+
+                let grants = HttpRequest::get("/api/principal")
+                    .with_authorization(&auth)
+                    .with_parameter("types", "role,group")
+                    .with_parameter(
+                        "fields",
+                        "name,roles,enabledPermissions,disabledPermissions",
+                    )
+                    .send::<List<Principal>>()
+                    .await?
+                    .items
+                    .into_iter()
+                    .filter_map(|principal| {
+                        let name = principal.name.try_unwrap_string()?;
+                        Some((
+                            name,
+                            Grants {
+                                enabled: principal.enabled_permissions.unwrap_string_list(),
+                                disabled: principal.disabled_permissions.unwrap_string_list(),
+                                roles: principal.roles.unwrap_string_list(),
+                            },
+                        ))
+                    })
+                    .collect::<AHashMap<_, _>>();
+
+                Ok((permissions, grants))
+            }
+        },
+    );
+
+    let permissions = create_memo(move |_| {
+        catalogue
+            .get()
+            .and_then(|result| result.ok())
+            .map(|(permissions, _)| permissions)
+            .unwrap_or_default()
+    });
+    let grants = create_memo(move |_| {
+        catalogue
+            .get()
+            .and_then(|result| result.ok())
+            .map(|(_, grants)| grants)
+            .unwrap_or_default()
+    });
+    create_effect(move |_| match catalogue.get() {
+        Some(Err(http::Error::Unauthorized)) => {
+            use_navigate()("/login", Default::default());
+        }
+        Some(Err(err)) => {
+            alert.set(Alert::from(err));
+        }
+        _ => (),
+    });
+
+    let effective = create_memo(move |_| {
+        let all = permissions.with(|permissions| {
+            permissions
+                .iter()
+                .map(|permission| permission.id.clone())
+                .collect::<Vec<_>>()
+        });
+        grants.with(|grants| data.with(|data| effective_permissions(data, grants, &all)))
+    });
+
+    let apply_preset = move |preset: Preset| {
+        let ids = permissions.with_untracked(|permissions| {
+            permissions
+                .iter()
+                .filter(|permission| preset.includes(&permission.id))
+                .map(|permission| permission.id.clone())
+                .collect::<Vec<_>>()
+        });
+
+        data.update(|data| {
+            if preset == Preset::Reset {
+                data.array_set("enabled-permissions", Vec::<String>::new());
+                data.array_set("disabled-permissions", Vec::<String>::new());
+            } else {
+                for id in ids {
+                    data.array_delete_item("disabled-permissions", &id);
+                    data.array_push("enabled-permissions", id, true);
+                }
+            }
+        });
+    };
+
+    let areas = create_memo(move |_| {
+        let search = search.get().trim().to_lowercase();
+        let mut areas: Vec<(&'static str, Vec<PermissionInfo>)> = AREAS
+            .iter()
+            .map(|(area, _)| *area)
+            .chain([OTHER_AREA])
+            .map(|area| (area, Vec::new()))
+            .collect();
+
+        permissions.with(|permissions| {
+            for permission in permissions {
+                if search.is_empty()
+                    || permission.id.contains(&search)
+                    || permission.description.to_lowercase().contains(&search)
+                {
+                    let area = permission_area(&permission.id);
+                    if let Some((_, items)) = areas.iter_mut().find(|(name, _)| *name == area) {
+                        items.push(permission.clone());
+                    }
+                }
+            }
+        });
+
+        areas.retain(|(_, items)| !items.is_empty());
+        areas
+    });
+
+    view! {
+        <div class="grid space-y-3">
+            <div class="flex flex-wrap gap-2">
+                {[
+                    (Preset::FullAdmin, "Full admin"),
+                    (Preset::QueueOperator, "Queue operator"),
+                    (Preset::ReadOnly, "Read-only"),
+                    (Preset::Reset, "Reset to defaults"),
+                ]
+                    .into_iter()
+                    .map(|(preset, text)| {
+                        view! {
+                            <Button
+                                text=text
+                                color=Color::Gray
+                                on_click=move |_| apply_preset(preset)
+                            />
+                        }
+                    })
+                    .collect_view()}
+            </div>
+
+            <input
+                type="text"
+                class="py-2 px-3 block w-full border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                placeholder="Search permissions"
+                prop:value=move || search.get()
+                on:input=move |ev| search.set(event_target_value(&ev))
+            />
+
+            {move || {
+                areas
+                    .get()
+                    .into_iter()
+                    .map(|(area, items)| {
+                        view! {
+                            <div>
+                                <p class="mb-2 text-xs font-semibold uppercase text-gray-500 dark:text-gray-400">
+                                    {area}
+                                </p>
+                                <div class="grid space-y-2">
+                                    {items
+                                        .into_iter()
+                                        .map(|permission| view! { <PermissionRow data permission/> })
+                                        .collect_view()}
+                                </div>
+                            </div>
+                        }
+                    })
+                    .collect_view()
+            }}
+
+            <div class="p-3 bg-gray-50 border border-gray-200 rounded-lg dark:bg-slate-800 dark:border-gray-700">
+                <p class="text-sm font-semibold text-gray-800 dark:text-gray-200">
+                    "Effective permissions"
+                </p>
+                {move || {
+                    let (effective, builtin) = effective.get();
+                    let mut by_area: Vec<(&'static str, usize)> = Vec::new();
+                    for id in &effective {
+                        let area = permission_area(id);
+                        match by_area.iter_mut().find(|(name, _)| *name == area) {
+                            Some((_, count)) => *count += 1,
+                            None => by_area.push((area, 1)),
+                        }
+                    }
+                    view! {
+                        <p class="mt-1 text-sm text-gray-600 dark:text-gray-400">
+                            {if effective.is_empty() {
+                                "No permissions are granted.".to_string()
+                            } else {
+                                by_area
+                                    .into_iter()
+                                    .map(|(area, count)| format!("{area}: {count}"))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            }}
+                        </p>
+                        <p class="mt-1 text-xs font-mono text-gray-500 dark:text-gray-400">
+                            {effective.join(", ")}
+                        </p>
+                        {(!builtin.is_empty())
+                            .then(|| {
+                                view! {
+                                    <p class="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                                        {format!(
+                                            "Also includes the grants of the built-in {} role(s), applied by the server.",
+                                            builtin.join(", "),
+                                        )}
+                                    </p>
+                                }
+                            })}
+                    }
+                }}
+
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn PermissionRow(data: RwSignal<FormData>, permission: PermissionInfo) -> impl IntoView {
+    let id = store_value(permission.id);
+    let state = create_memo(move |_| {
+        let id = id.get_value();
+        data.with(|data| {
+            if data.array_value("enabled-permissions").any(|v| v == id) {
+                Some(true)
+            } else if data.array_value("disabled-permissions").any(|v| v == id) {
+                Some(false)
+            } else {
+                None
+            }
+        })
+    });
+    let set_state = move |state: Option<bool>| {
+        let id = id.get_value();
+        data.update(|data| {
+            data.array_delete_item("enabled-permissions", &id);
+            data.array_delete_item("disabled-permissions", &id);
+            match state {
+                Some(true) => data.array_push("enabled-permissions", id, true),
+                Some(false) => data.array_push("disabled-permissions", id, true),
+                None => (),
+            }
+        });
+    };
+
+    view! {
+        <div class="flex flex-col space-y-3 p-3 w-full bg-white border border-gray-200 rounded-lg focus:border-blue-500 focus:ring-blue-500 dark:bg-neutral-900 dark:border-neutral-700">
+            <div class="flex justify-between items-center">
+                <span class="block text-sm text-gray-600 dark:text-neutral-500">
+                    {permission.description}
+                </span>
+
+                <div class="flex gap-x-6">
+                    {[(Some(true), "On", "on"), (Some(false), "Off", "off"), (None, "Default", "inherit")]
+                        .into_iter()
+                        .map(|(value, label, suffix)| {
+                            let input_id = format!("{}-{suffix}", id.get_value());
+                            view! {
+                                <div class="flex">
+                                    <input
+                                        type="radio"
+                                        name=id.get_value()
+                                        class="shrink-0 mt-0.5 border-gray-200 rounded-full text-blue-600 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-neutral-800 dark:border-neutral-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-800"
+                                        id=input_id.clone()
+                                        prop:checked=move || state.get() == value
+                                        on:input=move |_| set_state(value)
+                                    />
+
+                                    <label
+                                        for=input_id
+                                        class="text-sm text-gray-500 ms-2 dark:text-neutral-400"
+                                    >
+                                        {label}
+                                    </label>
+                                </div>
+                            }
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+        </div>
+    }
+}
+
+impl Preset {
+    fn includes(&self, id: &str) -> bool {
+        match self {
+            Preset::FullAdmin => true,
+            Preset::QueueOperator => permission_area(id) == "Queue",
+            Preset::ReadOnly => {
+                permission_area(id) != OTHER_AREA && (id.ends_with("-list") || id.ends_with("-get"))
+            }
+            Preset::Reset => false,
+        }
+    }
+}
+
+fn permission_area(id: &str) -> &'static str {
+    AREAS
+        .iter()
+        .find(|(_, prefixes)| prefixes.iter().any(|prefix| id.starts_with(prefix)))
+        .map(|(area, _)| *area)
+        .unwrap_or(OTHER_AREA)
+}
+
+// Resolves the permissions granted through roles and groups, followed by
+// the principal's own grants. Built-in roles other than admin are defined
+// by the server and are returned separately.
+fn effective_permissions(
+    data: &FormData,
+    grants: &AHashMap<String, Grants>,
+    all: &[String],
+) -> (Vec<String>, Vec<String>) {
+    let mut effective = AHashSet::new();
+    let mut builtin = Vec::new();
+    let mut visited = AHashSet::new();
+    let mut pending = data
+        .array_value("roles")
+        .chain(data.array_value("member-of"))
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+
+    while let Some(name) = pending.pop() {
+        if !visited.insert(name.clone()) {
+            continue;
+        }
+
+        if name == ADMIN_ROLE {
+            effective.extend(all.iter().cloned());
+        } else if let Some(grant) = grants.get(&name) {
+            effective.extend(grant.enabled.iter().cloned());
+            for id in &grant.disabled {
+                effective.remove(id);
+            }
+            pending.extend(grant.roles.iter().cloned());
+        } else if BUILT_IN_ROLES.contains(&name.as_str()) {
+            builtin.push(name);
+        }
+    }
+
+    effective.extend(data.array_value("enabled-permissions").map(String::from));
+    for id in data.array_value("disabled-permissions") {
+        effective.remove(id);
+    }
+
+    let mut effective = effective.into_iter().collect::<Vec<_>>();
+    effective.sort_unstable();
+    builtin.sort_unstable();
+    (effective, builtin)
+}
+
+// Returns the lockout permissions a change removes from a principal
+pub fn removed_lockout_permissions(current: &Principal, changes: &Principal) -> Vec<&'static str> {
+    let current_enabled = current.enabled_permissions.as_string_list();
+    let current_disabled = current.disabled_permissions.as_string_list();
+    let enabled = changes.enabled_permissions.as_string_list();
+    let disabled = changes.disabled_permissions.as_string_list();
+
+    LOCKOUT_PERMISSIONS
+        .iter()
+        .copied()
+        .filter(|id| {
+            let is_enabled = |list: &[String]| list.iter().any(|item| item == id);
+            (is_enabled(current_enabled) && !is_enabled(enabled))
+                || (!is_enabled(current_disabled) && is_enabled(disabled))
+        })
+        .collect()
+}
@@ -0,0 +1,367 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use humansize::{format_size, DECIMAL};
+use leptos::*;
+use leptos_router::use_navigate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{
+        form::button::Button,
+        messages::{
+            alert::{use_alerts, Alert},
+            modal::{use_modals, Modal},
+        },
+        skeleton::Skeleton,
+        Color,
+    },
+    core::{
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        Permission,
+    },
+    pages::maybe_plural,
+};
+
+const PURGE_AGES: &[(&str, &str)] = &[
+    ("7d", "Older than 7 days"),
+    ("30d", "Older than 30 days"),
+    ("90d", "Older than 90 days"),
+    ("365d", "Older than a year"),
+];
+const PURGE_ROLES: &[&str] = &["trash", "junk"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MailboxUsage {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub messages: u64,
+    #[serde(default)]
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortBy {
+    Name,
+    Messages,
+    Size,
+}
+
+#[component]
+pub fn AccountStorage(#[prop(into)] account: String) -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let modal = use_modals();
+    let can_purge = auth
+        .get_untracked()
+        .permissions()
+        .has_access(Permission::PurgeAccount);
+    let sort = create_rw_signal((SortBy::Size, true));
+    let purge_mailbox = create_rw_signal(String::new());
+    let purge_age = create_rw_signal(PURGE_AGES[1].0.to_string());
+    let account = store_value(account);
+
+    let usage = create_resource(
+        move || (),
+        move |_| {
+            let auth = auth.get_untracked();
+            let account = account.get_value();
+
+            async move {
+                HttpRequest::get(("/api/store/usage", account.as_str()))
+                    .with_authorization(&auth)
+                    .send::<Vec<MailboxUsage>>()
+                    .await
+            }
+        },
+    );
+
+    let purge = create_action(move |(mailbox, age): &(String, String)| {
+        let mailbox = mailbox.clone();
+        let age = age.clone();
+        let auth = auth.get();
+        let account = account.get_value();
+
+        async move {
+            match HttpRequest::get(("/api/store/purge/account", account.as_str()))
+                .with_authorization(&auth)
+                .with_parameter("mailbox", mailbox.clone())
+                .with_parameter("older-than", age)
+                .send::<u64>()
+                .await
+            {
+                Ok(count) => {
+                    usage.refetch();
+                    alert.set(Alert::success(format!(
+                        "Purged {} from {mailbox}.",
+                        maybe_plural(count as usize, "message", "messages")
+                    )));
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+    let preview_purge = create_action(move |_: &()| {
+        let auth = auth.get();
+        let mailbox = purge_mailbox.get();
+        let age = purge_age.get();
+        let account = account.get_value();
+
+        async move {
+            match HttpRequest::get(("/api/store/purge/account", account.as_str()))
+                .with_authorization(&auth)
+                .with_parameter("mailbox", mailbox.clone())
+                .with_parameter("older-than", age.clone())
+                .with_parameter("dry-run", "true")
+                .send::<u64>()
+                .await
+            {
+                Ok(0) => {
+                    alert.set(Alert::warning(format!(
+                        "There are no messages to purge in {mailbox}."
+                    )));
+                }
+                Ok(count) => {
+                    let text = maybe_plural(count as usize, "message", "messages");
+                    modal.set(
+                        Modal::with_title("Confirm purge")
+                            .with_message(format!(
+                                "Are you sure you want to permanently delete {text} from {mailbox}? This action cannot be undone."
+                            ))
+                            .with_button(format!("Purge {text}"))
+                            .with_dangerous_callback(move || {
+                                purge.dispatch((mailbox.clone(), age.clone()));
+                            }),
+                    );
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+    let recalculate = create_action(move |_: &()| {
+        let auth = auth.get();
+        let account = account.get_value();
+
+        async move {
+            match HttpRequest::get(("/api/store/quota/recalculate", account.as_str()))
+                .with_authorization(&auth)
+                .send::<serde_json::Value>()
+                .await
+            {
+                Ok(_) => {
+                    usage.refetch();
+                    alert.set(Alert::success("Quota usage recalculated."));
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+
+    let header = move |label: &'static str, column: SortBy| {
+        view! {
+            <th scope="col" class="px-6 py-3 text-start">
+                <button
+                    type="button"
+                    class="flex items-center gap-x-1 text-xs font-semibold uppercase tracking-wide text-gray-800 dark:text-gray-200"
+                    on:click=move |_| {
+                        sort.update(|(by, desc)| {
+                            if *by == column {
+                                *desc = !*desc;
+                            } else {
+                                *by = column;
+                                *desc = column != SortBy::Name;
+                            }
+                        });
+                    }
+                >
+
+                    {label}
+                    {move || {
+                        let (by, desc) = sort.get();
+                        (by == column).then_some(if desc { "↓" } else { "↑" })
+                    }}
+
+                </button>
+            </th>
+        }
+    };
+
+    view! {
+        <Transition fallback=Skeleton>
+            {move || match usage.get() {
+                None => None,
+                Some(Err(http::Error::Unauthorized)) => {
+                    use_navigate()("/login", Default::default());
+                    Some(view! { <div></div> }.into_view())
+                }
+                Some(Err(err)) => {
+                    alert.set(Alert::from(err));
+                    Some(view! { <Skeleton/> }.into_view())
+                }
+                Some(Ok(mut mailboxes)) => {
+                    let (by, desc) = sort.get();
+                    mailboxes
+                        .sort_by(|a, b| {
+                            let ord = match by {
+                                SortBy::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                                SortBy::Messages => a.messages.cmp(&b.messages),
+                                SortBy::Size => a.size.cmp(&b.size),
+                            };
+                            if desc { ord.reverse() } else { ord }
+                        });
+                    let total_messages = mailboxes.iter().map(|m| m.messages).sum::<u64>();
+                    let total_size = mailboxes.iter().map(|m| m.size).sum::<u64>();
+                    let purgeable = mailboxes
+                        .iter()
+                        .filter(|m| {
+                            m.role.as_deref().is_some_and(|role| PURGE_ROLES.contains(&role))
+                        })
+                        .map(|m| m.name.clone())
+                        .collect::<Vec<_>>();
+                    if !purgeable.contains(&purge_mailbox.get_untracked()) {
+                        purge_mailbox.set(purgeable.first().cloned().unwrap_or_default());
+                    }
+                    let has_purgeable = !purgeable.is_empty();
+                    let rows = mailboxes
+                        .into_iter()
+                        .map(|mailbox| {
+                            view! {
+                                <tr>
+                                    <td class="px-6 py-3 text-sm font-semibold text-gray-800 dark:text-gray-200">
+                                        {mailbox.name}
+                                    </td>
+                                    <td class="px-6 py-3 text-sm text-gray-500">
+                                        {mailbox.messages}
+                                    </td>
+                                    <td class="px-6 py-3 text-sm text-gray-500">
+                                        {format_size(mailbox.size, DECIMAL)}
+                                    </td>
+                                </tr>
+                            }
+                        })
+                        .collect_view();
+                    Some(
+                        view! {
+                            <div class="border border-gray-200 rounded-xl overflow-hidden dark:border-gray-700">
+                                <table class="min-w-full divide-y divide-gray-200 dark:divide-gray-700">
+                                    <thead class="bg-gray-50 dark:bg-slate-800">
+                                        <tr>
+                                            {header("Mailbox", SortBy::Name)}
+                                            {header("Messages", SortBy::Messages)}
+                                            {header("Size", SortBy::Size)}
+                                        </tr>
+                                    </thead>
+                                    <tbody class="divide-y divide-gray-200 dark:divide-gray-700">
+                                        {rows}
+                                    </tbody>
+                                    <tfoot class="bg-gray-50 dark:bg-slate-800">
+                                        <tr>
+                                            <td class="px-6 py-3 text-sm font-semibold text-gray-800 dark:text-gray-200">
+                                                "Total"
+                                            </td>
+                                            <td class="px-6 py-3 text-sm font-semibold text-gray-800 dark:text-gray-200">
+                                                {total_messages}
+                                            </td>
+                                            <td class="px-6 py-3 text-sm font-semibold text-gray-800 dark:text-gray-200">
+                                                {format_size(total_size, DECIMAL)}
+                                            </td>
+                                        </tr>
+                                    </tfoot>
+                                </table>
+                            </div>
+
+                            <Show when=move || can_purge>
+                                <div class="mt-5 grid gap-3 sm:flex sm:items-center sm:justify-end">
+                                    <select
+                                        class="py-2 px-3 pe-9 block border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400"
+                                        disabled=!has_purgeable
+                                        on:change=move |ev| purge_mailbox.set(event_target_value(&ev))
+                                    >
+                                        {purgeable
+                                            .clone()
+                                            .into_iter()
+                                            .map(|name| {
+                                                let name_ = name.clone();
+                                                view! {
+                                                    <option
+                                                        value=name.clone()
+                                                        selected=move || purge_mailbox.get() == name_
+                                                    >
+                                                        {name}
+                                                    </option>
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </select>
+                                    <select
+                                        class="py-2 px-3 pe-9 block border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400"
+                                        on:change=move |ev| purge_age.set(event_target_value(&ev))
+                                    >
+                                        {PURGE_AGES
+                                            .iter()
+                                            .map(|(age, label)| {
+                                                view! {
+                                                    <option
+                                                        value=*age
+                                                        selected=move || purge_age.get() == *age
+                                                    >
+                                                        {*label}
+                                                    </option>
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </select>
+                                    <Button
+                                        text="Purge"
+                                        color=Color::Red
+                                        disabled=!has_purgeable
+                                        loading=Signal::derive(move || {
+                                            preview_purge.pending().get() || purge.pending().get()
+                                        })
+                                        on_click=move |_| {
+                                            preview_purge.dispatch(());
+                                        }
+                                    />
+
+                                    <Button
+                                        text="Recalculate quota"
+                                        color=Color::Gray
+                                        loading=recalculate.pending()
+                                        on_click=move |_| {
+                                            recalculate.dispatch(());
+                                        }
+                                    />
+
+                                </div>
+                            </Show>
+                        }
+                            .into_view(),
+                    )
+                }
+            }}
+
+        </Transition>
+    }
+}
@@ -0,0 +1,348 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate};
+use leptos::*;
+use leptos_router::use_navigate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{
+        form::{
+            button::Button,
+            input::{InputSwitch, InputText, TextArea},
+            FormElement, FormItem, FormSection,
+        },
+        messages::alert::{use_alerts, Alert},
+        skeleton::Skeleton,
+        Color,
+    },
+    core::{
+        form::FormData,
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        schema::{Builder, Schemas, Transformer, Type, Validator},
+        Permission,
+    },
+};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VacationResponse {
+    #[serde(default)]
+    pub is_enabled: bool,
+    #[serde(default)]
+    pub from_date: Option<String>,
+    #[serde(default)]
+    pub to_date: Option<String>,
+    #[serde(default)]
+    pub subject: Option<String>,
+    #[serde(default)]
+    pub text_body: Option<String>,
+    #[serde(default)]
+    pub restrict_to_domain: bool,
+}
+
+#[component]
+pub fn AccountVacation(#[prop(into)] account: String) -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let can_edit = auth
+        .get_untracked()
+        .permissions()
+        .has_access(Permission::IndividualUpdate);
+    let account = store_value(account);
+    let data = expect_context::<Arc<Schemas>>()
+        .build_form("vacation")
+        .into_signal();
+
+    let vacation = create_resource(
+        move || (),
+        move |_| {
+            let auth = auth.get_untracked();
+            let account = account.get_value();
+
+            async move {
+                HttpRequest::get(("/api/vacation", account.as_str()))
+                    .with_authorization(&auth)
+                    .send::<VacationResponse>()
+                    .await
+            }
+        },
+    );
+
+    let save_changes = create_action(move |changes: &VacationResponse| {
+        let changes = changes.clone();
+        let auth = auth.get();
+        let account = account.get_value();
+
+        async move {
+            match HttpRequest::put(("/api/vacation", account.as_str()))
+                .with_authorization(&auth)
+                .with_body(&changes)
+                .unwrap()
+                .send::<serde_json::Value>()
+                .await
+            {
+                Ok(_) => {
+                    alert.set(Alert::success(if changes.is_enabled {
+                        "Vacation response enabled."
+                    } else {
+                        "Vacation response disabled."
+                    }));
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+    let save = move |_| {
+        if save_changes.pending().get_untracked() {
+            return;
+        }
+
+        data.update(|data| {
+            if let Some(changes) = data.to_vacation() {
+                save_changes.dispatch(changes);
+            }
+        });
+    };
+
+    view! {
+        <Transition fallback=Skeleton>
+            {move || match vacation.get() {
+                None => None,
+                Some(Err(http::Error::Unauthorized)) => {
+                    use_navigate()("/login", Default::default());
+                    Some(view! { <div></div> }.into_view())
+                }
+                Some(Err(err)) => {
+                    alert.set(Alert::from(err));
+                    Some(view! { <Skeleton/> }.into_view())
+                }
+                Some(Ok(vacation)) => {
+                    data.update(|data| data.from_vacation(&vacation));
+                    Some(
+                        view! {
+                            <FormSection stacked=true>
+                                <FormItem stacked=true label="Enabled">
+                                    <InputSwitch
+                                        element=FormElement::new("enabled", data)
+                                        label="Send an automatic reply to incoming messages"
+                                        disabled=!can_edit
+                                    />
+                                </FormItem>
+                                <FormItem stacked=true label="Start date" is_optional=true>
+                                    <VacationDate
+                                        element=FormElement::new("from-date", data)
+                                        disabled=!can_edit
+                                    />
+                                </FormItem>
+                                <FormItem stacked=true label="End date" is_optional=true>
+                                    <VacationDate
+                                        element=FormElement::new("to-date", data)
+                                        disabled=!can_edit
+                                    />
+                                </FormItem>
+                                <FormItem stacked=true label="Subject" is_optional=true>
+                                    <InputText
+                                        placeholder="Out of office"
+                                        element=FormElement::new("subject", data)
+                                        disabled=!can_edit
+                                    />
+                                </FormItem>
+                                <FormItem stacked=true label="Message">
+                                    <TextArea
+                                        placeholder="I am currently out of the office and will reply when I return."
+                                        element=FormElement::new("body", data)
+                                        disabled=!can_edit
+                                    />
+                                </FormItem>
+                                <FormItem stacked=true label="Recipients">
+                                    <InputSwitch
+                                        element=FormElement::new("restrict-domain", data)
+                                        label="Only reply to senders from the account's domain"
+                                        disabled=!can_edit
+                                    />
+                                </FormItem>
+                            </FormSection>
+
+                            <Show when=move || can_edit>
+                                <div class="mt-5 flex justify-end">
+                                    <Button
+                                        text="Save vacation response"
+                                        color=Color::Blue
+                                        loading=save_changes.pending()
+                                        on_click=save
+                                    />
+                                </div>
+                            </Show>
+                        }
+                            .into_view(),
+                    )
+                }
+            }}
+
+        </Transition>
+    }
+}
+
+#[component]
+fn VacationDate(element: FormElement, #[prop(optional)] disabled: bool) -> impl IntoView {
+    let value = create_memo(move |_| {
+        element
+            .data
+            .with(|data| data.value::<String>(element.id).unwrap_or_default())
+    });
+    let error = create_memo(move |_| {
+        element
+            .data
+            .with(|data| data.error_string(element.id).map(|s| s.to_string()))
+    });
+
+    view! {
+        <input
+            type="date"
+            class=move || {
+                if error.get().is_none() {
+                    "py-2 px-3 block w-full border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                } else {
+                    "py-2 px-3 block w-full border-red-500 shadow-sm text-sm rounded-lg focus:border-red-500 focus:ring-red-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600"
+                }
+            }
+
+            prop:value=move || value.get()
+            disabled=disabled
+            on:change=move |ev| {
+                element
+                    .data
+                    .update(|data| {
+                        data.update(element.id, event_target_value(&ev));
+                    });
+            }
+        />
+        {move || {
+            error.get().map(|error| view! { <p class="text-xs text-red-600 mt-2">{error}</p> })
+        }}
+    }
+}
+
+impl FormData {
+    fn from_vacation(&mut self, vacation: &VacationResponse) {
+        let date = |date: &Option<String>| {
+            date.as_deref()
+                .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+                .map(|date| date.date_naive().format(DATE_FORMAT).to_string())
+                .unwrap_or_default()
+        };
+
+        self.set(
+            "enabled",
+            if vacation.is_enabled { "true" } else { "false" },
+        );
+        self.set("from-date", date(&vacation.from_date));
+        self.set("to-date", date(&vacation.to_date));
+        self.set("subject", vacation.subject.clone().unwrap_or_default());
+        self.set("body", vacation.text_body.clone().unwrap_or_default());
+        self.set(
+            "restrict-domain",
+            if vacation.restrict_to_domain {
+                "true"
+            } else {
+                "false"
+            },
+        );
+    }
+
+    // Disabling keeps the subject and message stored so the response
+    // can be enabled again later
+    fn to_vacation(&mut self) -> Option<VacationResponse> {
+        if !self.validate_form() {
+            return None;
+        }
+
+        let is_enabled = self.value::<bool>("enabled").unwrap_or_default();
+        let from_date = self.date_value("from-date")?;
+        let to_date = self.date_value("to-date")?;
+        if let (Some(from_date), Some(to_date)) = (from_date, to_date) {
+            if to_date < from_date {
+                self.new_error("to-date", "The end date must not be before the start date");
+                return None;
+            }
+        }
+        let text_body = self.value::<String>("body").filter(|body| !body.is_empty());
+        if is_enabled && text_body.is_none() {
+            self.new_error(
+                "body",
+                "A message is required to enable the vacation response",
+            );
+            return None;
+        }
+
+        Some(VacationResponse {
+            is_enabled,
+            from_date: from_date.map(|date| format!("{}T00:00:00Z", date.format(DATE_FORMAT))),
+            to_date: to_date.map(|date| format!("{}T23:59:59Z", date.format(DATE_FORMAT))),
+            subject: self
+                .value::<String>("subject")
+                .filter(|subject| !subject.is_empty()),
+            text_body,
+            restrict_to_domain: self.value::<bool>("restrict-domain").unwrap_or_default(),
+        })
+    }
+
+    fn date_value(&mut self, id: &str) -> Option<Option<NaiveDate>> {
+        match self.value::<String>(id).filter(|date| !date.is_empty()) {
+            Some(date) => match NaiveDate::parse_from_str(&date, DATE_FORMAT) {
+                Ok(date) => Some(Some(date)),
+                Err(_) => {
+                    self.new_error(id, "Invalid date");
+                    None
+                }
+            },
+            None => Some(None),
+        }
+    }
+}
+
+impl Builder<Schemas, ()> {
+    pub fn build_vacation(self) -> Self {
+        self.new_schema("vacation")
+            .new_field("enabled")
+            .typ(Type::Boolean)
+            .default("false")
+            .build()
+            .new_field("from-date")
+            .typ(Type::Input)
+            .input_check([Transformer::Trim], [])
+            .build()
+            .new_field("to-date")
+            .typ(Type::Input)
+            .input_check([Transformer::Trim], [])
+            .build()
+            .new_field("subject")
+            .typ(Type::Input)
+            .input_check([Transformer::Trim], [Validator::MaxLength(255)])
+            .build()
+            .new_field("body")
+            .typ(Type::Text)
+            .input_check([Transformer::Trim], [])
+            .build()
+            .new_field("restrict-domain")
+            .typ(Type::Boolean)
+            .default("false")
+            .build()
+            .build()
+    }
+}
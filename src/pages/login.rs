@@ -71,6 +71,8 @@ pub fn Login() -> impl IntoView {
                             auth_token.is_valid = true;
                             auth_token.permissions = permissions;
                             auth_token.is_enterprise = response.is_enterprise;
+                            auth_token.server_version = response.server_version;
+                            auth_token.capabilities = response.capabilities.into();
 
                             if let Err(err) =
                                 SessionStorage::set(STATE_STORAGE_KEY, auth_token.clone())
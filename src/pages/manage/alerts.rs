@@ -0,0 +1,319 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::sync::Arc;
+
+use chrono_humanize::HumanTime;
+use leptos::*;
+use leptos_router::{use_navigate, use_params_map};
+
+use crate::{
+    components::{
+        badge::Badge,
+        form::{
+            button::Button, input::InputText, select::Select, Form, FormButtonBar, FormElement,
+            FormItem, FormSection,
+        },
+        icon::{IconAdd, IconBell},
+        list::{
+            header::ColumnList, toolbar::ToolbarButton, Footer, ListItem, ListSection, ListTable,
+            Toolbar, ZeroResults,
+        },
+        messages::modal::{use_modals, Modal},
+        Color,
+    },
+    core::{
+        alerting::{use_alert_monitor, AlertRule, Comparison, ALERT_COMPARISONS, ALERT_METRICS},
+        schema::{Builder, Schemas, SelectType, Source, Transformer, Type, Validator},
+    },
+};
+
+#[component]
+pub fn AlertRuleList() -> impl IntoView {
+    let monitor = use_alert_monitor();
+    let modal = use_modals();
+    let notify = create_memo(move |_| monitor.config.get().notify);
+
+    view! {
+        <ListSection>
+            <ListTable
+                title="Alerts"
+                subtitle="Rules evaluated periodically by this browser while the admin is open"
+            >
+                <Toolbar slot>
+                    <ToolbarButton
+                        text=Signal::derive(move || {
+                            if notify.get() {
+                                "Disable notifications".to_string()
+                            } else {
+                                "Enable notifications".to_string()
+                            }
+                        })
+
+                        color=Color::Gray
+                        on_click=Callback::new(move |_| {
+                            monitor.set_notify(!notify.get());
+                        })
+                    >
+
+                        <IconBell/>
+                    </ToolbarButton>
+
+                    <ToolbarButton
+                        text="Add rule"
+                        color=Color::Blue
+                        on_click=Callback::new(move |_| {
+                            use_navigate()("/manage/alerts/edit", Default::default());
+                        })
+                    >
+
+                        <IconAdd attr:class="flex-shrink-0 size-3"/>
+                    </ToolbarButton>
+
+                </Toolbar>
+
+                {move || {
+                    let rules = monitor.config.get().rules;
+                    if !rules.is_empty() {
+                        let rows = rules
+                            .into_iter()
+                            .map(|rule| {
+                                let rule_id = rule.id.clone();
+                                let delete = Callback::new(move |_| {
+                                    let rule_id = rule_id.clone();
+                                    modal
+                                        .set(
+                                            Modal::with_title("Confirm deletion")
+                                                .with_message(
+                                                    "Are you sure you want to delete this alert rule?",
+                                                )
+                                                .with_button("Delete rule")
+                                                .with_dangerous_callback(move || {
+                                                    monitor.delete_rule(&rule_id);
+                                                }),
+                                        );
+                                });
+                                view! { <AlertRuleItem rule on_delete=delete/> }
+                            })
+                            .collect_view();
+                        view! {
+                            <ColumnList headers=vec![
+                                "Condition".to_string(),
+                                "Interval".to_string(),
+                                "Status".to_string(),
+                                "".to_string(),
+                            ]>{rows}</ColumnList>
+                        }
+                            .into_view()
+                    } else {
+                        view! {
+                            <ZeroResults
+                                title="No alert rules"
+                                subtitle="Add a rule to be notified when a metric crosses a threshold."
+                            />
+                        }
+                            .into_view()
+                    }
+                }}
+
+                <Footer slot>
+                    <div></div>
+                </Footer>
+            </ListTable>
+        </ListSection>
+    }
+}
+
+#[component]
+fn AlertRuleItem(rule: AlertRule, on_delete: Callback<()>) -> impl IntoView {
+    let monitor = use_alert_monitor();
+    let rule_id = store_value(rule.id.clone());
+    let triggered = monitor.triggered(&rule.id);
+    let status = match (&triggered, rule.muted, rule.acknowledged) {
+        (_, true, _) => view! { <Badge color=Color::Gray>"Muted"</Badge> }.into_view(),
+        (Some(_), _, true) => {
+            view! { <Badge color=Color::Yellow>"Acknowledged"</Badge> }.into_view()
+        }
+        (Some(_), _, false) => view! { <Badge color=Color::Red>"Active"</Badge> }.into_view(),
+        (None, _, _) => view! { <Badge color=Color::Green>"OK"</Badge> }.into_view(),
+    };
+    let since = triggered.map(|alert| {
+        format!(
+            "Value {} since {}",
+            alert.value,
+            HumanTime::from(alert.since)
+        )
+    });
+    let muted = rule.muted;
+    let link_class = "text-sm text-blue-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600";
+
+    view! {
+        <tr>
+            <ListItem>
+                <span class="block text-sm font-semibold text-gray-800 dark:text-gray-200">
+                    {rule.title()}
+                </span>
+            </ListItem>
+            <ListItem>
+                <span class="text-sm text-gray-500">
+                    {format!("Every {} min", rule.interval)}
+                </span>
+            </ListItem>
+            <ListItem>
+                {status}
+                <span class="block text-xs text-gray-500">{since}</span>
+            </ListItem>
+            <ListItem subclass="px-6 py-1.5">
+                <div class="flex gap-x-3">
+                    <a class=link_class href=format!("/manage/alerts/edit/{}", rule.id)>
+                        Edit
+                    </a>
+                    <button
+                        type="button"
+                        class=link_class
+                        on:click=move |_| {
+                            monitor.set_muted(&rule_id.get_value(), !muted);
+                        }
+                    >
+
+                        {if muted { "Unmute" } else { "Mute" }}
+                    </button>
+                    <button
+                        type="button"
+                        class="text-sm text-red-600 decoration-2 hover:underline font-medium"
+                        on:click=move |_| on_delete.call(())
+                    >
+                        Delete
+                    </button>
+                </div>
+            </ListItem>
+        </tr>
+    }
+}
+
+#[component]
+pub fn AlertRuleEdit() -> impl IntoView {
+    let monitor = use_alert_monitor();
+    let params = use_params_map();
+    let rule_id = params.get_untracked().get("id").cloned();
+    let existing = rule_id.as_deref().and_then(|id| monitor.rule(id));
+
+    let mut data = expect_context::<Arc<Schemas>>().build_form("alert-rule");
+    data.apply_defaults(false);
+    if let Some(rule) = &existing {
+        data.set("metric", rule.metric.id());
+        data.set("comparison", rule.comparison.id());
+        data.set("threshold", rule.threshold.to_string());
+        data.set("interval", rule.interval.to_string());
+    }
+    let data = data.into_signal();
+
+    view! {
+        <Form
+            title=if existing.is_some() { "Edit alert rule" } else { "Add alert rule" }
+            subtitle="Trigger an alert when a server metric crosses a threshold"
+        >
+
+            <FormSection>
+                <FormItem label="Metric" tooltip="Server metric or status to monitor">
+                    <Select element=FormElement::new("metric", data)/>
+                </FormItem>
+                <FormItem label="Comparison">
+                    <Select element=FormElement::new("comparison", data)/>
+                </FormItem>
+                <FormItem
+                    label="Threshold"
+                    tooltip="Value the metric is compared against. Use 0 for the server readiness check."
+                >
+                    <InputText element=FormElement::new("threshold", data)/>
+                </FormItem>
+                <FormItem label="Check interval" tooltip="Minutes between evaluations of this rule">
+                    <InputText element=FormElement::new("interval", data)/>
+                </FormItem>
+            </FormSection>
+
+            <FormButtonBar>
+                <Button
+                    text="Cancel"
+                    color=Color::Gray
+                    on_click=move |_| {
+                        use_navigate()("/manage/alerts", Default::default());
+                    }
+                />
+
+                <Button
+                    text="Save changes"
+                    color=Color::Blue
+                    on_click=Callback::new(move |_| {
+                        data.update(|data| {
+                            if data.validate_form() {
+                                let (id, muted) = existing
+                                    .as_ref()
+                                    .map(|rule| (rule.id.clone(), rule.muted))
+                                    .unwrap_or_else(|| (AlertRule::new_id(), false));
+                                monitor
+                                    .save_rule(AlertRule {
+                                        id,
+                                        metric: data.value("metric").unwrap(),
+                                        comparison: data
+                                            .value("comparison")
+                                            .unwrap_or(Comparison::Gt),
+                                        threshold: data.value("threshold").unwrap_or_default(),
+                                        interval: data.value("interval").unwrap_or(5),
+                                        muted,
+                                        acknowledged: false,
+                                    });
+                                use_navigate()("/manage/alerts", Default::default());
+                            }
+                        });
+                    })
+                />
+
+            </FormButtonBar>
+        </Form>
+    }
+}
+
+impl Builder<Schemas, ()> {
+    pub fn build_alert_rules(self) -> Self {
+        self.new_schema("alert-rule")
+            .new_field("metric")
+            .typ(Type::Select {
+                typ: SelectType::Single,
+                source: Source::Static(ALERT_METRICS),
+            })
+            .default("queue-size")
+            .build()
+            .new_field("comparison")
+            .typ(Type::Select {
+                typ: SelectType::Single,
+                source: Source::Static(ALERT_COMPARISONS),
+            })
+            .default("gt")
+            .build()
+            .new_field("threshold")
+            .typ(Type::Input)
+            .input_check(
+                [Transformer::Trim],
+                [Validator::Required, Validator::MinValue(0.into())],
+            )
+            .default("1000")
+            .build()
+            .new_field("interval")
+            .typ(Type::Input)
+            .input_check(
+                [Transformer::Trim],
+                [
+                    Validator::Required,
+                    Validator::MinValue(1.into()),
+                    Validator::MaxValue(1440.into()),
+                ],
+            )
+            .default("5")
+            .build()
+            .build()
+    }
+}
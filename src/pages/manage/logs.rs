@@ -7,16 +7,24 @@
 use chrono::{DateTime, Utc};
 use leptos::*;
 use leptos_router::*;
+use regex::RegexBuilder;
 use serde::{Deserialize, Serialize};
-use std::hash::{DefaultHasher, Hash, Hasher};
+use std::{
+    collections::BTreeSet,
+    hash::{DefaultHasher, Hash, Hasher},
+    time::Duration,
+};
 
 use crate::pages::queue::messages::deserialize_datetime;
 use crate::{
     components::{
         badge::Badge,
+        icon::IconRefresh,
         list::{
-            header::ColumnList, pagination::Pagination, toolbar::SearchBox, Footer, ListItem,
-            ListSection, ListTable, Toolbar, ZeroResults,
+            header::ColumnList,
+            pagination::Pagination,
+            toolbar::{SearchBox, ToolbarButton},
+            Footer, ListItem, ListSection, ListTable, Toolbar, ZeroResults,
         },
         messages::alert::{use_alerts, Alert},
         skeleton::Skeleton,
@@ -27,12 +35,20 @@ use crate::{
         oauth::use_authorization,
         url::UrlBuilder,
     },
-    pages::{FormatDateTime, List},
+    pages::{manage::support::download, maybe_plural, FormatDateTime, List},
 };
 
 const PAGE_SIZE: u32 = 50;
+const LIVE_POLL_LIMIT: u32 = 100;
+const LIVE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const LIVE_MAX_ENTRIES: usize = 1000;
+const LEVELS: &[&str] = &["ERROR", "WARN", "INFO", "DEBUG", "TRACE"];
+
+const INPUT_CLASS: &str = "py-2 px-3 block w-full border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600";
+const INPUT_ERROR_CLASS: &str = "py-2 px-3 block w-full border-red-500 shadow-sm text-sm rounded-lg focus:border-red-500 focus:ring-red-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600";
+const SELECT_CLASS: &str = "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600";
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 struct LogEntry {
     #[serde(deserialize_with = "deserialize_datetime")]
     pub timestamp: DateTime<Utc>,
@@ -101,6 +117,17 @@ pub fn Logs() -> impl IntoView {
                         }
                     />
 
+                    <ToolbarButton
+                        text="Live"
+                        color=Color::Gray
+                        on_click=move |_| {
+                            use_navigate()("/manage/logs/live", Default::default());
+                        }
+                    >
+
+                        <IconRefresh/>
+                    </ToolbarButton>
+
                 </Toolbar>
 
                 <Transition fallback=Skeleton>
@@ -178,6 +205,317 @@ pub fn Logs() -> impl IntoView {
     }
 }
 
+// Tails the log by polling the newest entries, filters are applied to the
+// entries received so far
+#[component]
+pub fn LiveLogs() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let following = create_rw_signal(true);
+    let entries = create_rw_signal(Vec::<LogEntry>::new());
+    let level = create_rw_signal(String::new());
+    let module = create_rw_signal(String::new());
+    let search = create_rw_signal(String::new());
+    let scroll_top = create_rw_signal(0);
+
+    http::poll::<List<LogEntry>>(
+        move || {
+            HttpRequest::get("/api/logs")
+                .with_authorization(&auth.get_untracked())
+                .with_parameter("page", "1")
+                .with_parameter("limit", LIVE_POLL_LIMIT.to_string())
+        },
+        LIVE_POLL_INTERVAL,
+        following.into(),
+        move |response| match response {
+            Ok(logs) => {
+                entries.update(|entries| append_entries(entries, logs.items));
+            }
+            Err(http::Error::Unauthorized) => {
+                use_navigate()("/login", Default::default());
+            }
+            Err(err) => {
+                following.set(false);
+                alert.set(Alert::from(err));
+            }
+        },
+    );
+
+    let search_error = create_memo(move |_| {
+        search.with(|search| {
+            RegexBuilder::new(search.trim())
+                .case_insensitive(true)
+                .build()
+                .err()
+                .map(|err| err.to_string())
+        })
+    });
+    let modules = create_memo(move |_| {
+        entries.with(|entries| {
+            entries
+                .iter()
+                .map(|entry| entry.module().to_string())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>()
+        })
+    });
+    let visible = create_memo(move |_| {
+        let max_severity = severity(&level.get());
+        let module = module.get();
+        let pattern = search.with(|search| {
+            let search = search.trim();
+            (!search.is_empty())
+                .then(|| {
+                    RegexBuilder::new(search)
+                        .case_insensitive(true)
+                        .build()
+                        .ok()
+                })
+                .flatten()
+        });
+
+        entries.with(|entries| {
+            entries
+                .iter()
+                .filter(|entry| {
+                    severity(&entry.level) <= max_severity
+                        && (module.is_empty() || entry.module() == module)
+                        && pattern.as_ref().map_or(true, |pattern| {
+                            pattern.is_match(&entry.event)
+                                || pattern.is_match(&entry.event_id)
+                                || pattern.is_match(&entry.details)
+                        })
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    });
+
+    // Keep the newest entry in view while following
+    create_effect(move |_| {
+        visible.track();
+        if following.get_untracked() {
+            scroll_top.set(i32::MAX);
+        }
+    });
+
+    view! {
+        <ListSection>
+            <LiveLogFilters level module search search_error modules/>
+
+            <ListTable
+                title="Live logs"
+                subtitle=Signal::derive(move || {
+                    let total = visible.with(|visible| visible.len());
+                    if following.get() {
+                        format!("Following, {} shown", maybe_plural(total, "entry", "entries"))
+                    } else {
+                        format!("Paused, {} shown", maybe_plural(total, "entry", "entries"))
+                    }
+                })
+
+                scroll_top=scroll_top
+            >
+                <Toolbar slot>
+                    <ToolbarButton
+                        text=Signal::derive(move || {
+                            if following.get() { "Pause" } else { "Resume" }.to_string()
+                        })
+
+                        color=Color::Gray
+                        on_click=move |_| {
+                            following.update(|following| *following = !*following);
+                        }
+                    />
+
+                    <ToolbarButton
+                        text="Export"
+                        color=Color::Gray
+                        on_click=move |_| {
+                            let contents = visible
+                                .with_untracked(|visible| {
+                                    visible
+                                        .iter()
+                                        .map(|entry| entry.to_line())
+                                        .collect::<Vec<_>>()
+                                        .join("\n")
+                                });
+                            let filename = format!(
+                                "logs-{}.txt",
+                                Utc::now().format("%Y%m%d-%H%M%S"),
+                            );
+                            if let Err(err) = download(&filename, "text/plain", &contents) {
+                                alert
+                                    .set(
+                                        Alert::error("Failed to export logs")
+                                            .with_details(format!("{err:?}")),
+                                    );
+                            }
+                        }
+                    />
+
+                    <ToolbarButton
+                        text="Close"
+                        color=Color::Blue
+                        on_click=move |_| {
+                            use_navigate()("/manage/logs", Default::default());
+                        }
+                    />
+
+                </Toolbar>
+
+                <Show
+                    when=move || visible.with(|visible| !visible.is_empty())
+                    fallback=|| {
+                        view! {
+                            <ZeroResults
+                                title="No results"
+                                subtitle="No log entries have been received with the selected criteria."
+                            />
+                        }
+                    }
+                >
+
+                    <ColumnList headers=vec![
+                        "Date".to_string(),
+                        "Level".to_string(),
+                        "Event".to_string(),
+                        "Details".to_string(),
+                    ]>
+
+                        <For each=move || visible.get() key=|log| log.id() let:log>
+                            <LogItem log/>
+                        </For>
+
+                    </ColumnList>
+                </Show>
+
+                <Footer slot>
+                    <div class="px-6 py-4 border-t border-gray-200 dark:border-gray-700">
+                        <p class="text-sm text-gray-600 dark:text-gray-400">
+                            {format!(
+                                "Only the last {LIVE_MAX_ENTRIES} entries received are kept, filters apply to these entries."
+                            )}
+                        </p>
+                    </div>
+                </Footer>
+            </ListTable>
+        </ListSection>
+    }
+}
+
+#[component]
+fn LiveLogFilters(
+    level: RwSignal<String>,
+    module: RwSignal<String>,
+    search: RwSignal<String>,
+    search_error: Memo<Option<String>>,
+    modules: Memo<Vec<String>>,
+) -> impl IntoView {
+    view! {
+        <div class="mb-6 p-4 bg-white border border-gray-200 rounded-xl shadow-sm dark:bg-slate-900 dark:border-gray-700">
+            <div class="grid sm:grid-cols-12 gap-3 items-end">
+                <label class="sm:col-span-2 block text-sm text-gray-800 dark:text-gray-200">
+                    "Level"
+                    <select
+                        class=SELECT_CLASS
+                        prop:value=move || level.get()
+                        on:change=move |ev| level.set(event_target_value(&ev))
+                    >
+                        <option value="">"Any"</option>
+                        {LEVELS
+                            .iter()
+                            .map(|level| {
+                                view! { <option value=*level>{*level}" and above"</option> }
+                            })
+                            .collect_view()}
+                    </select>
+                </label>
+                <label class="sm:col-span-3 block text-sm text-gray-800 dark:text-gray-200">
+                    "Module"
+                    <select
+                        class=SELECT_CLASS
+                        prop:value=move || module.get()
+                        on:change=move |ev| module.set(event_target_value(&ev))
+                    >
+                        <option value="">"Any"</option>
+                        <For
+                            each=move || modules.get()
+                            key=|module| module.clone()
+                            children=move |name| {
+                                view! {
+                                    <option
+                                        value=name.clone()
+                                        selected=move || module.get() == name
+                                    >
+                                        {name.clone()}
+                                    </option>
+                                }
+                            }
+                        />
+
+                    </select>
+                </label>
+                <label class="sm:col-span-7 block text-sm text-gray-800 dark:text-gray-200">
+                    "Search"
+                    <input
+                        type="text"
+                        class=move || {
+                            if search_error.with(|err| err.is_some()) {
+                                INPUT_ERROR_CLASS
+                            } else {
+                                INPUT_CLASS
+                            }
+                        }
+
+                        placeholder="Regular expression, e.g. auth|tls"
+                        prop:value=move || search.get()
+                        on:input=move |ev| search.set(event_target_value(&ev))
+                    />
+                    <Show when=move || search_error.with(|err| err.is_some())>
+                        <p class="text-xs text-red-600 mt-2">
+                            {move || search_error.get().unwrap_or_default()}
+                        </p>
+                    </Show>
+                </label>
+            </div>
+        </div>
+    }
+}
+
+// The API returns the newest entries first, only entries newer than the
+// last one received are appended
+fn append_entries(entries: &mut Vec<LogEntry>, mut new_entries: Vec<LogEntry>) {
+    new_entries.reverse();
+    let last_timestamp = entries.last().map(|entry| entry.timestamp);
+    for entry in new_entries {
+        let is_new = match last_timestamp {
+            Some(last_timestamp) if entry.timestamp == last_timestamp => !entries
+                .iter()
+                .rev()
+                .take_while(|existing| existing.timestamp == last_timestamp)
+                .any(|existing| existing.id() == entry.id()),
+            Some(last_timestamp) => entry.timestamp > last_timestamp,
+            None => true,
+        };
+        if is_new {
+            entries.push(entry);
+        }
+    }
+    if entries.len() > LIVE_MAX_ENTRIES {
+        entries.drain(..entries.len() - LIVE_MAX_ENTRIES);
+    }
+}
+
+// Unknown levels are only shown when no level is selected
+fn severity(level: &str) -> usize {
+    LEVELS
+        .iter()
+        .position(|l| l.eq_ignore_ascii_case(level))
+        .unwrap_or(LEVELS.len())
+}
+
 #[component]
 fn LogItem(log: LogEntry) -> impl IntoView {
     let timestamp = log.timestamp.format_date_time();
@@ -215,6 +553,23 @@ fn LogItem(log: LogEntry) -> impl IntoView {
 }
 
 impl LogEntry {
+    // Event ids are namespaced by the module that emitted them
+    fn module(&self) -> &str {
+        self.event_id
+            .split_once('.')
+            .map_or(self.event_id.as_str(), |(module, _)| module)
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            self.timestamp.to_rfc3339(),
+            self.level,
+            self.event_id,
+            self.details
+        )
+    }
+
     pub fn id(&self) -> String {
         let mut hasher = DefaultHasher::new();
         self.level.hash(&mut hasher);
@@ -20,7 +20,7 @@ use crate::{
         oauth::use_authorization,
         AccessToken, Permission, Permissions,
     },
-    pages::config::ReloadSettings,
+    pages::{config::ReloadSettings, manage::support::SupportBundleAction},
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -42,6 +42,14 @@ const ACTIONS_GENERAL: &[Action] = &[
         success_message: "Successfully reloaded configuration",
         permission: Permission::SettingsReload,
     },
+    Action {
+        title: "Restart server",
+        description: "Restarts the server process to apply changes that cannot be hot reloaded. Active connections will be dropped.",
+        icon: "power",
+        url: "/api/restart",
+        success_message: "Successfully requested server restart",
+        permission: Permission::Restart,
+    },
     Action {
         title: "Validate configuration",
         description: "Checks the configuration for errors and warnings.",
@@ -130,6 +138,7 @@ pub fn Maintenance() -> impl IntoView {
     let (pending, set_pending) = create_signal(false);
 
     let permissions = auth.get_untracked().permissions().clone();
+    let has_support_bundle = permissions.has_access(Permission::SettingsList);
     let actions_general = action_to_view(
         ACTIONS_GENERAL,
         &permissions,
@@ -158,7 +167,12 @@ pub fn Maintenance() -> impl IntoView {
     view! {
         <div class="max-w-5xl px-4 py-10 sm:px-6 lg:px-8 lg:py-14 mx-auto">
             <Alerts/>
-            <div class="grid sm:grid-cols-2 lg:grid-cols-3 gap-3 sm:gap-6">{actions_general}</div>
+            <div class="grid sm:grid-cols-2 lg:grid-cols-3 gap-3 sm:gap-6">
+                {actions_general}
+                <Show when=move || has_support_bundle>
+                    <SupportBundleAction/>
+                </Show>
+            </div>
         </div>
         <div class="max-w-5xl px-4 py-10 sm:px-6 lg:px-8 lg:py-14 mx-auto">
             <div class="grid sm:grid-cols-2 lg:grid-cols-3 gap-3 sm:gap-6">{actions_spam}</div>
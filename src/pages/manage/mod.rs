@@ -4,7 +4,12 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+pub mod alerts;
 pub mod logs;
 pub mod maintenance;
+pub mod protocols;
+pub mod sessions;
 pub mod spam;
+pub mod support;
+pub mod system;
 pub mod troubleshoot;
@@ -0,0 +1,591 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use ahash::{AHashMap, AHashSet};
+use leptos::*;
+use leptos_router::use_navigate;
+
+use crate::{
+    components::{
+        badge::Badge,
+        icon::IconRefresh,
+        list::{
+            header::ColumnList, toolbar::ToolbarButton, Footer, ListItem, ListSection, ListTable,
+            Toolbar,
+        },
+        messages::{
+            alert::{use_alerts, Alert},
+            modal::{use_modals, Modal},
+        },
+        skeleton::Skeleton,
+        Color,
+    },
+    core::{
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        undo::use_undo_stack,
+        url::UrlBuilder,
+        AccessToken, Permission,
+    },
+    pages::{
+        config::{
+            edit::fetch_active_connections, list::fetch_prefix, Settings, SettingsValues,
+            UpdateSettings,
+        },
+        List,
+    },
+};
+
+const LISTENER_PREFIX: &str = "server.listener";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Protocol {
+    pub name: &'static str,
+    pub description: &'static str,
+    // Listener protocol that serves this protocol
+    pub listener: &'static str,
+    // Setting that must also be enabled, along with its default value
+    pub flag: Option<(&'static str, bool)>,
+    pub default_bind: &'static str,
+    pub implicit_tls: bool,
+}
+
+pub static PROTOCOLS: &[Protocol] = &[
+    Protocol {
+        name: "SMTP",
+        description: "Inbound mail and message submission",
+        listener: "smtp",
+        flag: None,
+        default_bind: "[::]:25",
+        implicit_tls: false,
+    },
+    Protocol {
+        name: "LMTP",
+        description: "Local mail delivery from other MTAs",
+        listener: "lmtp",
+        flag: None,
+        default_bind: "[::]:24",
+        implicit_tls: false,
+    },
+    Protocol {
+        name: "IMAP4",
+        description: "Mailbox access for e-mail clients",
+        listener: "imap",
+        flag: None,
+        default_bind: "[::]:993",
+        implicit_tls: true,
+    },
+    Protocol {
+        name: "POP3",
+        description: "Legacy mailbox download",
+        listener: "pop3",
+        flag: None,
+        default_bind: "[::]:995",
+        implicit_tls: true,
+    },
+    Protocol {
+        name: "ManageSieve",
+        description: "Remote management of Sieve scripts",
+        listener: "managesieve",
+        flag: None,
+        default_bind: "[::]:4190",
+        implicit_tls: false,
+    },
+    Protocol {
+        name: "HTTP",
+        description: "JMAP, WebDAV, CalDAV, CardDAV and the management API",
+        listener: "http",
+        flag: None,
+        default_bind: "[::]:443",
+        implicit_tls: true,
+    },
+    Protocol {
+        name: "CalDAV Scheduling",
+        description: "Calendar invitations sent and received over HTTP",
+        listener: "http",
+        flag: Some(("calendar.scheduling.enable", true)),
+        default_bind: "[::]:443",
+        implicit_tls: true,
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolStatus {
+    pub protocol: &'static Protocol,
+    pub listeners: Vec<String>,
+    pub flag_enabled: Option<bool>,
+    pub connections: Option<u64>,
+}
+
+impl ProtocolStatus {
+    // A protocol is enabled when at least one listener with bind addresses
+    // serves it and its enable flag, if any, is set.
+    pub fn derive(
+        protocol: &'static Protocol,
+        listeners: &[Settings],
+        flags: &AHashMap<String, Option<String>>,
+    ) -> Self {
+        let listeners = listeners
+            .iter()
+            .filter(|listener| {
+                listener
+                    .get("protocol")
+                    .is_some_and(|p| p == protocol.listener)
+                    && listener
+                        .array_values("bind")
+                        .iter()
+                        .any(|(_, addr)| !addr.trim().is_empty())
+            })
+            .filter_map(|listener| listener.get("_id").cloned())
+            .collect::<Vec<_>>();
+        let flag_enabled = protocol.flag.map(|(key, default)| {
+            flags
+                .get(key)
+                .and_then(|value| value.as_deref())
+                .map_or(default, |value| value.trim().eq_ignore_ascii_case("true"))
+        });
+
+        ProtocolStatus {
+            protocol,
+            listeners,
+            flag_enabled,
+            connections: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.listeners.is_empty() && self.flag_enabled.unwrap_or(true)
+    }
+
+    pub fn create_listener_url(&self) -> String {
+        UrlBuilder::new("/settings/listener/edit")
+            .with_parameter("protocol", self.protocol.listener)
+            .with_parameter("bind", self.protocol.default_bind)
+            .with_parameter("tls.implicit", self.protocol.implicit_tls.to_string())
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToggleAction {
+    SetFlag(&'static str, bool),
+    DisableListeners,
+}
+
+#[component]
+pub fn ProtocolList() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let modal = use_modals();
+    let undo = use_undo_stack();
+    let can_update = auth
+        .get_untracked()
+        .permissions()
+        .has_access(Permission::SettingsUpdate);
+    let refresh = create_rw_signal(0u32);
+
+    let statuses = create_resource(
+        move || (refresh.get(), undo.version.get()),
+        move |_| {
+            let auth = auth.get_untracked();
+
+            async move { fetch_statuses(&auth).await }
+        },
+    );
+
+    let toggle = create_action(move |(status, action): &(ProtocolStatus, ToggleAction)| {
+        let status = status.clone();
+        let action = *action;
+        let auth = auth.get();
+
+        async move {
+            let (description, updates, restore) = match action {
+                ToggleAction::SetFlag(key, value) => {
+                    let previous = match HttpRequest::get("/api/settings/keys")
+                        .with_authorization(&auth)
+                        .with_parameter("keys", key)
+                        .send::<AHashMap<String, Option<String>>>()
+                        .await
+                    {
+                        Ok(values) => values.into_values().next().flatten(),
+                        Err(err) => {
+                            alert.set(Alert::from(err));
+                            return;
+                        }
+                    };
+                    (
+                        format!(
+                            "{} {}",
+                            if value { "Enabled" } else { "Disabled" },
+                            status.protocol.name
+                        ),
+                        vec![UpdateSettings::Insert {
+                            prefix: None,
+                            values: vec![(key.to_string(), value.to_string())],
+                            assert_empty: false,
+                        }],
+                        vec![match previous {
+                            Some(previous) => UpdateSettings::Insert {
+                                prefix: None,
+                                values: vec![(key.to_string(), previous)],
+                                assert_empty: false,
+                            },
+                            None => UpdateSettings::Delete {
+                                keys: vec![key.to_string()],
+                            },
+                        }],
+                    )
+                }
+                ToggleAction::DisableListeners => {
+                    let mut updates = Vec::new();
+                    let mut restore = Vec::new();
+                    for id in &status.listeners {
+                        let prefix = format!("{LISTENER_PREFIX}.{id}");
+                        match fetch_prefix(&auth, &prefix).await {
+                            Ok(settings) => {
+                                restore.push(UpdateSettings::Insert {
+                                    prefix: Some(prefix.clone()),
+                                    values: settings.into_iter().collect(),
+                                    assert_empty: false,
+                                });
+                            }
+                            Err(err) => {
+                                alert.set(Alert::from(err));
+                                return;
+                            }
+                        }
+                        updates.push(UpdateSettings::Clear {
+                            prefix: format!("{prefix}."),
+                            filter: None,
+                        });
+                    }
+                    (
+                        format!("Removed the {} listeners", status.protocol.name),
+                        updates,
+                        restore,
+                    )
+                }
+            };
+
+            match HttpRequest::post("/api/settings")
+                .with_authorization(&auth)
+                .with_body(updates)
+                .unwrap()
+                .send::<serde_json::Value>()
+                .await
+            {
+                Ok(_) => {
+                    refresh.update(|version| *version += 1);
+                    alert.set(Alert::success(match action {
+                        ToggleAction::DisableListeners => format!(
+                            "{description}. Restart the server for the change to take effect."
+                        ),
+                        ToggleAction::SetFlag(..) => {
+                            format!("{description}. Reload the configuration to apply it.")
+                        }
+                    }));
+                    undo.push(description, restore);
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+
+    let on_toggle = Callback::new(move |status: ProtocolStatus| {
+        let name = status.protocol.name;
+        if status.is_enabled() {
+            let (message, action) = match status.protocol.flag {
+                Some((key, _)) => (
+                    format!("Are you sure you want to disable {name}? Its listeners will keep serving other protocols."),
+                    ToggleAction::SetFlag(key, false),
+                ),
+                None => (
+                    format!(
+                        "Are you sure you want to disable {name}? This removes the following listeners and drops their active connections after a restart."
+                    ),
+                    ToggleAction::DisableListeners,
+                ),
+            };
+            let details = if matches!(action, ToggleAction::DisableListeners) {
+                status.listeners.clone()
+            } else {
+                vec![]
+            };
+            modal.set(
+                Modal::with_title(format!("Disable {name}"))
+                    .with_message(message)
+                    .with_details(details)
+                    .with_button(format!("Disable {name}"))
+                    .with_dangerous_callback(move || {
+                        toggle.dispatch((status.clone(), action));
+                    }),
+            );
+        } else if status.listeners.is_empty() {
+            use_navigate()(&status.create_listener_url(), Default::default());
+        } else if let Some((key, _)) = status.protocol.flag {
+            toggle.dispatch((status, ToggleAction::SetFlag(key, true)));
+        }
+    });
+
+    view! {
+        <ListSection>
+            <ListTable
+                title="Protocols"
+                subtitle="Enable or disable the protocols served by this server"
+            >
+                <Toolbar slot>
+                    <ToolbarButton
+                        text="Refresh"
+                        color=Color::Gray
+                        on_click=Callback::new(move |_| {
+                            refresh.update(|version| *version += 1);
+                        })
+                    >
+
+                        <IconRefresh/>
+                    </ToolbarButton>
+                </Toolbar>
+
+                <Transition fallback=Skeleton>
+                    {move || match statuses.get() {
+                        None => None,
+                        Some(Err(http::Error::Unauthorized)) => {
+                            use_navigate()("/login", Default::default());
+                            Some(view! { <div></div> }.into_view())
+                        }
+                        Some(Err(err)) => {
+                            alert.set(Alert::from(err));
+                            Some(view! { <Skeleton/> }.into_view())
+                        }
+                        Some(Ok(statuses)) => {
+                            let rows = statuses
+                                .into_iter()
+                                .map(|status| {
+                                    view! { <ProtocolItem status can_update on_toggle/> }
+                                })
+                                .collect_view();
+                            Some(
+                                view! {
+                                    <ColumnList headers=vec![
+                                        "Protocol".to_string(),
+                                        "Listeners".to_string(),
+                                        "Connections".to_string(),
+                                        "Enabled".to_string(),
+                                    ]>{rows}</ColumnList>
+                                }
+                                    .into_view(),
+                            )
+                        }
+                    }}
+
+                </Transition>
+
+                <Footer slot>
+                    <div></div>
+                </Footer>
+            </ListTable>
+        </ListSection>
+    }
+}
+
+#[component]
+fn ProtocolItem(
+    status: ProtocolStatus,
+    can_update: bool,
+    on_toggle: Callback<ProtocolStatus>,
+) -> impl IntoView {
+    let is_enabled = status.is_enabled();
+    let protocol = status.protocol;
+    let listeners = if status.listeners.is_empty() {
+        view! { <span class="text-sm text-gray-500">"None"</span> }.into_view()
+    } else {
+        status
+            .listeners
+            .iter()
+            .map(|id| {
+                view! {
+                    <a
+                        class="me-2 text-sm text-blue-600 decoration-2 hover:underline font-medium"
+                        href=format!("/settings/listener/{id}/edit")
+                    >
+                        {id.clone()}
+                    </a>
+                }
+            })
+            .collect_view()
+    };
+    let flag_note = (status.flag_enabled == Some(false) && !status.listeners.is_empty())
+        .then(|| view! { <Badge color=Color::Gray>"Disabled in settings"</Badge> });
+    let connections = status
+        .connections
+        .map(|count| count.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let status = store_value(status);
+
+    view! {
+        <tr>
+            <ListItem>
+                <span class="block text-sm font-semibold text-gray-800 dark:text-gray-200">
+                    {protocol.name}
+                </span>
+                <span class="block text-sm text-gray-500">{protocol.description}</span>
+            </ListItem>
+            <ListItem>{listeners} {flag_note}</ListItem>
+            <ListItem>
+                <span class="text-sm text-gray-500">{connections}</span>
+            </ListItem>
+            <ListItem>
+                <input
+                    type="checkbox"
+                    role="switch"
+                    aria-label=format!("Enable {}", protocol.name)
+                    aria-checked=is_enabled.to_string()
+                    class="relative w-11 h-6 p-px bg-gray-100 border-transparent text-transparent rounded-full cursor-pointer transition-colors ease-in-out duration-200 focus:ring-blue-600 disabled:opacity-50 disabled:pointer-events-none checked:bg-none checked:text-blue-600 checked:border-blue-600 focus:checked:border-blue-600 dark:bg-gray-800 dark:border-gray-700 dark:checked:bg-blue-500 dark:checked:border-blue-500 dark:focus:ring-offset-gray-600 before:inline-block before:size-5 before:bg-white checked:before:bg-blue-200 before:translate-x-0 checked:before:translate-x-full before:rounded-full before:shadow before:transform before:ring-0 before:transition before:ease-in-out before:duration-200 dark:before:bg-gray-400 dark:checked:before:bg-blue-200"
+                    prop:checked=is_enabled
+                    disabled=!can_update
+                    on:click=move |ev| {
+                        // The state only changes once the settings are updated
+                        ev.prevent_default();
+                        on_toggle.call(status.get_value());
+                    }
+                />
+
+            </ListItem>
+        </tr>
+    }
+}
+
+async fn fetch_statuses(auth: &AccessToken) -> http::Result<Vec<ProtocolStatus>> {
+    let listeners = HttpRequest::get("/api/settings/group")
+        .with_authorization(auth)
+        .with_parameter("prefix", LISTENER_PREFIX)
+        .with_parameter("suffix", "protocol")
+        .send::<List<Settings>>()
+        .await?
+        .items;
+    let flag_keys = PROTOCOLS
+        .iter()
+        .filter_map(|protocol| protocol.flag.map(|(key, _)| key))
+        .collect::<Vec<_>>();
+    let flags = if !flag_keys.is_empty() {
+        HttpRequest::get("/api/settings/keys")
+            .with_authorization(auth)
+            .with_parameter("keys", flag_keys.join(","))
+            .send::<AHashMap<String, Option<String>>>()
+            .await?
+    } else {
+        AHashMap::new()
+    };
+
+    let mut connections = AHashMap::new();
+    for listener in PROTOCOLS
+        .iter()
+        .map(|protocol| protocol.listener)
+        .collect::<AHashSet<_>>()
+    {
+        if let Some(count) =
+            fetch_active_connections(auth, &format!("{listener}.active-connections")).await
+        {
+            connections.insert(listener, count);
+        }
+    }
+
+    Ok(PROTOCOLS
+        .iter()
+        .map(|protocol| {
+            let mut status = ProtocolStatus::derive(protocol, &listeners, &flags);
+            status.connections = connections.get(protocol.listener).copied();
+            status
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listener(id: &str, protocol: &str, binds: &[&str]) -> Settings {
+        let mut settings = Settings::new();
+        settings.insert("_id".to_string(), id.to_string());
+        settings.insert("protocol".to_string(), protocol.to_string());
+        for (idx, bind) in binds.iter().enumerate() {
+            settings.insert(format!("bind.{idx:04}"), bind.to_string());
+        }
+        settings
+    }
+
+    fn protocol(name: &str) -> &'static Protocol {
+        PROTOCOLS
+            .iter()
+            .find(|protocol| protocol.name == name)
+            .unwrap()
+    }
+
+    fn flags(value: Option<&str>) -> AHashMap<String, Option<String>> {
+        AHashMap::from_iter([(
+            "calendar.scheduling.enable".to_string(),
+            value.map(|value| value.to_string()),
+        )])
+    }
+
+    #[test]
+    fn enabled_by_listeners_with_bind_addresses() {
+        let listeners = [
+            listener("smtp", "smtp", &["[::]:25"]),
+            listener("submission", "smtp", &["[::]:587", "0.0.0.0:587"]),
+            listener("imap", "imap", &[]),
+            listener("pop3", "pop3", &[" "]),
+        ];
+        let no_flags = AHashMap::new();
+
+        let smtp = ProtocolStatus::derive(protocol("SMTP"), &listeners, &no_flags);
+        let mut smtp_listeners = smtp.listeners.clone();
+        smtp_listeners.sort();
+        assert_eq!(smtp_listeners, ["smtp", "submission"]);
+        assert!(smtp.is_enabled());
+
+        for name in ["IMAP4", "POP3", "LMTP", "HTTP"] {
+            let status = ProtocolStatus::derive(protocol(name), &listeners, &no_flags);
+            assert!(status.listeners.is_empty(), "{name}");
+            assert!(!status.is_enabled(), "{name}");
+        }
+    }
+
+    #[test]
+    fn enabled_by_listener_and_flag() {
+        let listeners = [listener("https", "http", &["[::]:443"])];
+        let scheduling = protocol("CalDAV Scheduling");
+
+        for (value, expected) in [
+            (None, true),
+            (Some("true"), true),
+            (Some(" TRUE "), true),
+            (Some("false"), false),
+            (Some("invalid"), false),
+        ] {
+            let status = ProtocolStatus::derive(scheduling, &listeners, &flags(value));
+            assert_eq!(status.flag_enabled, Some(expected), "{value:?}");
+            assert_eq!(status.is_enabled(), expected, "{value:?}");
+        }
+
+        let status = ProtocolStatus::derive(scheduling, &[], &flags(Some("true")));
+        assert_eq!(status.flag_enabled, Some(true));
+        assert!(!status.is_enabled());
+    }
+
+    #[test]
+    fn protocols_without_flag_ignore_flags() {
+        let listeners = [listener("https", "http", &["[::]:443"])];
+        let status = ProtocolStatus::derive(protocol("HTTP"), &listeners, &flags(Some("false")));
+        assert_eq!(status.flag_enabled, None);
+        assert!(status.is_enabled());
+    }
+}
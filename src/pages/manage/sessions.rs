@@ -0,0 +1,351 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use chrono::{DateTime, Utc};
+use chrono_humanize::{Accuracy, HumanTime, Tense};
+use leptos::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{
+        badge::Badge,
+        icon::IconRefresh,
+        list::{
+            header::ColumnList,
+            pagination::Pagination,
+            toolbar::{SearchBox, ToolbarButton},
+            Footer, ListItem, ListSection, ListTable, Toolbar, ZeroResults,
+        },
+        messages::{
+            alert::{use_alerts, Alert},
+            modal::{use_modals, Modal},
+        },
+        skeleton::Skeleton,
+        Color,
+    },
+    core::{
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        url::UrlBuilder,
+        Permission,
+    },
+    pages::{queue::messages::deserialize_datetime, FormatDateTime, List},
+};
+
+const PAGE_SIZE: u32 = 25;
+const PROTOCOLS: &[(&str, &str)] = &[
+    ("imap", "IMAP"),
+    ("jmap", "JMAP"),
+    ("pop3", "POP3"),
+    ("managesieve", "ManageSieve"),
+    ("smtp", "SMTP"),
+    ("lmtp", "LMTP"),
+    ("http", "HTTP"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Session {
+    pub id: String,
+    pub protocol: String,
+    #[serde(default)]
+    pub account: Option<String>,
+    pub remote_ip: String,
+    #[serde(deserialize_with = "deserialize_datetime")]
+    pub created: DateTime<Utc>,
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub can_disconnect: bool,
+}
+
+#[component]
+pub fn SessionList() -> impl IntoView {
+    let query = use_query_map();
+    let page = create_memo(move |_| {
+        query
+            .with(|q| q.get("page").and_then(|page| page.parse::<u32>().ok()))
+            .filter(|&page| page > 0)
+            .unwrap_or(1)
+    });
+    let account = create_memo(move |_| {
+        query.with(|q| {
+            q.get("account").and_then(|s| {
+                let s = s.trim();
+                if !s.is_empty() {
+                    Some(s.to_string())
+                } else {
+                    None
+                }
+            })
+        })
+    });
+    let protocol = create_memo(move |_| {
+        query.with(|q| {
+            q.get("protocol")
+                .filter(|s| PROTOCOLS.iter().any(|(id, _)| id == s))
+                .cloned()
+        })
+    });
+
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let modal = use_modals();
+    let can_disconnect = auth
+        .get_untracked()
+        .permissions()
+        .has_access(Permission::SessionDelete);
+
+    let sessions = create_resource(
+        move || (page.get(), account.get(), protocol.get()),
+        move |(page, account, protocol)| {
+            let auth = auth.get_untracked();
+
+            async move {
+                HttpRequest::get("/api/sessions")
+                    .with_authorization(&auth)
+                    .with_parameter("page", page.to_string())
+                    .with_parameter("limit", PAGE_SIZE.to_string())
+                    .with_optional_parameter("account", account)
+                    .with_optional_parameter("protocol", protocol)
+                    .send::<List<Session>>()
+                    .await
+            }
+        },
+    );
+
+    let total_results = create_rw_signal(None::<u32>);
+
+    let disconnect_action = create_action(move |id: &String| {
+        let id = id.clone();
+        let auth = auth.get();
+
+        async move {
+            match HttpRequest::delete(("/api/sessions", id.as_str()))
+                .with_authorization(&auth)
+                .send::<serde_json::Value>()
+                .await
+            {
+                Ok(_) | Err(http::Error::NotFound) => {
+                    sessions.refetch();
+                    alert.set(Alert::success("Session disconnected."));
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+
+    let navigate_to = move |page: u32, account: Option<String>, protocol: Option<String>| {
+        use_navigate()(
+            &UrlBuilder::new("/manage/sessions")
+                .with_parameter("page", page.to_string())
+                .with_optional_parameter("account", account)
+                .with_optional_parameter("protocol", protocol)
+                .finish(),
+            Default::default(),
+        );
+    };
+
+    view! {
+        <ListSection>
+            <ListTable title="Sessions" subtitle="View and disconnect active client sessions">
+                <Toolbar slot>
+                    <SearchBox
+                        value=account
+                        on_search=move |value: String| {
+                            navigate_to(1, Some(value), protocol.get());
+                        }
+                    />
+
+                    <div class="max-w-sm">
+                        <select
+                            class="py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400"
+                            on:change=move |ev| {
+                                let value = event_target_value(&ev);
+                                navigate_to(
+                                    1,
+                                    account.get(),
+                                    (!value.is_empty()).then_some(value),
+                                );
+                            }
+                        >
+
+                            <option value="" selected=move || protocol.get().is_none()>
+                                "All protocols"
+                            </option>
+                            {PROTOCOLS
+                                .iter()
+                                .map(|(id, name)| {
+                                    view! {
+                                        <option
+                                            value=*id
+                                            selected=move || protocol.get().is_some_and(|p| p == *id)
+                                        >
+                                            {*name}
+                                        </option>
+                                    }
+                                })
+                                .collect_view()}
+                        </select>
+                    </div>
+
+                    <ToolbarButton
+                        text="Refresh"
+
+                        color=Color::Gray
+                        on_click=Callback::new(move |_| {
+                            sessions.refetch();
+                        })
+                    >
+
+                        <IconRefresh/>
+                    </ToolbarButton>
+
+                </Toolbar>
+
+                <Transition fallback=Skeleton>
+                    {move || match sessions.get() {
+                        None => None,
+                        Some(Err(http::Error::Unauthorized)) => {
+                            use_navigate()("/login", Default::default());
+                            Some(view! { <div></div> }.into_view())
+                        }
+                        Some(Err(err)) => {
+                            total_results.set(Some(0));
+                            alert.set(Alert::from(err));
+                            Some(view! { <Skeleton/> }.into_view())
+                        }
+                        Some(Ok(sessions)) if !sessions.items.is_empty() => {
+                            total_results.set(Some(sessions.total as u32));
+                            Some(
+                                view! {
+                                    <ColumnList headers=vec![
+                                        "Protocol".to_string(),
+                                        "Account".to_string(),
+                                        "Remote IP".to_string(),
+                                        "Age".to_string(),
+                                        "State".to_string(),
+                                        "".to_string(),
+                                    ]>
+
+                                        <For
+                                            each=move || sessions.items.clone()
+                                            key=|session| session.id.clone()
+                                            let:session
+                                        >
+                                            <SessionItem
+                                                session
+                                                can_disconnect
+                                                on_disconnect=Callback::new(move |id: String| {
+                                                    modal
+                                                        .set(
+                                                            Modal::with_title("Confirm disconnect")
+                                                                .with_message(
+                                                                    "Are you sure you want to terminate this session? The client will have to reconnect.",
+                                                                )
+                                                                .with_button("Disconnect")
+                                                                .with_dangerous_callback(move || {
+                                                                    disconnect_action.dispatch(id.clone());
+                                                                }),
+                                                        )
+                                                })
+                                            />
+
+                                        </For>
+
+                                    </ColumnList>
+                                }
+                                    .into_view(),
+                            )
+                        }
+                        Some(Ok(_)) => {
+                            total_results.set(Some(0));
+                            Some(
+                                view! {
+                                    <ZeroResults
+                                        title="No results"
+                                        subtitle="No active sessions were found with the selected criteria."
+                                    />
+                                }
+                                    .into_view(),
+                            )
+                        }
+                    }}
+
+                </Transition>
+
+                <Footer slot>
+
+                    <Pagination
+                        current_page=page
+                        total_results=total_results.read_only()
+                        page_size=PAGE_SIZE
+                        on_page_change=move |page: u32| {
+                            navigate_to(page, account.get(), protocol.get());
+                        }
+                    />
+
+                </Footer>
+            </ListTable>
+        </ListSection>
+    }
+}
+
+#[component]
+fn SessionItem(
+    session: Session,
+    can_disconnect: bool,
+    on_disconnect: Callback<String, ()>,
+) -> impl IntoView {
+    let protocol = PROTOCOLS
+        .iter()
+        .find(|(id, _)| *id == session.protocol)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| session.protocol.to_uppercase());
+    let account = session
+        .account
+        .unwrap_or_else(|| "Not authenticated".to_string());
+    let age = HumanTime::from(session.created).to_text_en(Accuracy::Rough, Tense::Present);
+    let created = session.created.format_date_time();
+    let id = session.id;
+    let disconnect = (can_disconnect && session.can_disconnect).then(|| {
+        view! {
+            <button
+                class="inline-flex items-center gap-x-1 text-sm text-red-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                on:click=move |_| on_disconnect.call(id.clone())
+            >
+                Disconnect
+            </button>
+        }
+    });
+
+    view! {
+        <tr>
+            <ListItem>
+                <Badge color=Color::Blue>{protocol}</Badge>
+            </ListItem>
+            <ListItem>
+                <span class="text-sm font-semibold text-gray-800 dark:text-gray-200">
+                    {account}
+                </span>
+            </ListItem>
+            <ListItem>
+                <span class="text-sm text-gray-500">{session.remote_ip}</span>
+            </ListItem>
+            <ListItem>
+                <span class="text-sm text-gray-500" title=created>
+                    {age}
+                </span>
+            </ListItem>
+            <ListItem>
+                <span class="text-sm text-gray-500">{session.state}</span>
+            </ListItem>
+            <ListItem subclass="px-6 py-1.5">{disconnect}</ListItem>
+        </tr>
+    }
+}
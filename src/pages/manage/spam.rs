@@ -326,7 +326,7 @@ impl Builder<Schemas, ()> {
             .typ(Type::Text)
             .input_check(
                 [Transformer::Trim],
-                [Validator::Required, Validator::IsIpOrMask],
+                [Validator::Required, Validator::IsIpAddr],
             )
             .build()
             .new_field("helo_domain")
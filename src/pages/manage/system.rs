@@ -0,0 +1,304 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use chrono_humanize::{Accuracy, HumanTime, Tense};
+use leptos::*;
+use leptos_router::use_navigate;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{
+    components::{
+        badge::Badge,
+        form::button::Button,
+        icon::{IconClipboard, IconRefresh},
+        messages::alert::{use_alerts, Alert, Alerts},
+        report::{ReportItem, ReportSection, ReportTextValue, ReportView},
+        skeleton::Skeleton,
+        Color,
+    },
+    core::{
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        Semver, MINIMUM_API_VERSION,
+    },
+};
+
+// Number of minor releases the server may be ahead of the
+// minimum supported API version before the webadmin is considered outdated.
+const MAX_MINOR_DRIFT: u16 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct SystemInfo {
+    pub version: String,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub uptime: u64,
+    #[serde(default)]
+    pub os: Option<String>,
+    #[serde(default)]
+    pub arch: Option<String>,
+    #[serde(default)]
+    pub hostname: Option<String>,
+    #[serde(default)]
+    pub protocols: Vec<String>,
+    #[serde(default)]
+    pub stores: Vec<StoreInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct StoreInfo {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub typ: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VersionMismatch {
+    ServerTooOld,
+    WebadminTooOld,
+}
+
+#[component]
+pub fn SystemInformation() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+
+    let info = create_resource(
+        move || (),
+        move |_| {
+            let auth = auth.get_untracked();
+
+            async move {
+                let info = HttpRequest::get("/api/server/info")
+                    .with_authorization(&auth)
+                    .send::<SystemInfo>()
+                    .await?;
+                let is_ready = HttpRequest::get("/healthz/ready")
+                    .with_base_url(&auth)
+                    .send_raw()
+                    .await
+                    .is_ok();
+
+                Ok::<_, http::Error>((info, is_ready))
+            }
+        },
+    );
+
+    let copy_diagnostics = move |text: String| {
+        let promise = window().navigator().clipboard().write_text(&text);
+        spawn_local(async move {
+            // The browser rejects the write without a secure context or permission
+            match JsFuture::from(promise).await {
+                Ok(_) => alert.set(Alert::success("Diagnostics copied to clipboard")),
+                Err(_) => alert.set(
+                    Alert::error("Failed to copy diagnostics")
+                        .with_details("The browser did not allow access to the clipboard."),
+                ),
+            }
+        });
+    };
+
+    view! {
+        <ReportView>
+            <Alerts/>
+            <Transition fallback=Skeleton>
+                {move || match info.get() {
+                    None => None,
+                    Some(Err(http::Error::Unauthorized)) => {
+                        use_navigate()("/login", Default::default());
+                        Some(view! { <div></div> }.into_view())
+                    }
+                    Some(Err(err)) => {
+                        alert.set(Alert::from(err));
+                        Some(view! { <Skeleton/> }.into_view())
+                    }
+                    Some(Ok((system, is_ready))) => {
+                        let diagnostics = system.diagnostics(is_ready);
+                        match system.version_mismatch() {
+                            Some(VersionMismatch::ServerTooOld) => {
+                                alert
+                                    .set(
+                                        Alert::warning("Server version mismatch")
+                                            .with_details(
+                                                format!(
+                                                    concat!(
+                                                        "This webadmin release requires Stalwart Mail Server version {} ",
+                                                        "or later. Your server is running version {}.",
+                                                    ),
+                                                    MINIMUM_API_VERSION,
+                                                    system.version,
+                                                ),
+                                            )
+                                            .without_timeout(),
+                                    );
+                            }
+                            Some(VersionMismatch::WebadminTooOld) => {
+                                alert
+                                    .set(
+                                        Alert::warning("Webadmin version mismatch")
+                                            .with_details(
+                                                format!(
+                                                    concat!(
+                                                        "Your server is running version {} which is significantly newer ",
+                                                        "than this webadmin release (v{}). Some settings may be missing ",
+                                                        "or outdated, consider updating the webadmin.",
+                                                    ),
+                                                    system.version,
+                                                    env!("CARGO_PKG_VERSION"),
+                                                ),
+                                            )
+                                            .without_timeout(),
+                                    );
+                            }
+                            None => {}
+                        }
+                        let uptime = HumanTime::from(
+                                chrono::Duration::seconds(system.uptime as i64),
+                            )
+                            .to_text_en(Accuracy::Rough, Tense::Present);
+                        let platform = match (system.os, system.arch) {
+                            (Some(os), Some(arch)) => format!("{os} ({arch})"),
+                            (Some(os), None) => os,
+                            (None, Some(arch)) => arch,
+                            (None, None) => "Unknown".to_string(),
+                        };
+                        let features = badges(system.features, Color::Gray);
+                        let protocols = badges(system.protocols, Color::Blue);
+                        let stores = system
+                            .stores
+                            .into_iter()
+                            .map(|store| {
+                                view! {
+                                    <ReportItem label=store.id>
+                                        <ReportTextValue value=store.typ/>
+                                    </ReportItem>
+                                }
+                            })
+                            .collect_view();
+
+                        Some(
+                            view! {
+                                <ReportSection title="Server">
+                                    <ReportItem label="Version">
+                                        <ReportTextValue value=system.version/>
+                                    </ReportItem>
+                                    <ReportItem label="Webadmin version">
+                                        <ReportTextValue value=env!("CARGO_PKG_VERSION")
+                                            .to_string()/>
+                                    </ReportItem>
+                                    <ReportItem label="Status">
+                                        {if is_ready {
+                                            view! { <Badge color=Color::Green>"Ready"</Badge> }
+                                        } else {
+                                            view! { <Badge color=Color::Red>"Not ready"</Badge> }
+                                        }}
+
+                                    </ReportItem>
+                                    <ReportItem label="Uptime">
+                                        <ReportTextValue value=uptime/>
+                                    </ReportItem>
+                                    <ReportItem label="Hostname">
+                                        <ReportTextValue value=system
+                                            .hostname
+                                            .unwrap_or_else(|| "Not configured".to_string())/>
+                                    </ReportItem>
+                                    <ReportItem label="Operating system">
+                                        <ReportTextValue value=platform/>
+                                    </ReportItem>
+                                    <ReportItem label="Build features">{features}</ReportItem>
+                                    <ReportItem label="Enabled protocols">{protocols}</ReportItem>
+                                </ReportSection>
+                                <ReportSection title="Stores">{stores}</ReportSection>
+                                <div class="mt-5 flex justify-end gap-x-2">
+                                    <Button
+                                        text="Refresh"
+                                        color=Color::Gray
+                                        on_click=Callback::new(move |_| {
+                                            info.refetch();
+                                        })
+                                    >
+
+                                        <IconRefresh attr:class="flex-shrink-0 size-4"/>
+                                    </Button>
+                                    <Button
+                                        text="Copy diagnostics"
+                                        color=Color::Blue
+                                        on_click=Callback::new(move |_| {
+                                            copy_diagnostics(diagnostics.clone());
+                                        })
+                                    >
+
+                                        <IconClipboard attr:class="flex-shrink-0 size-4"/>
+                                    </Button>
+                                </div>
+                            }
+                                .into_view(),
+                        )
+                    }
+                }}
+
+            </Transition>
+        </ReportView>
+    }
+}
+
+fn badges(items: Vec<String>, color: Color) -> View {
+    if !items.is_empty() {
+        view! {
+            <div class="flex flex-wrap gap-1 mt-2">
+                {items
+                    .into_iter()
+                    .map(|item| view! { <Badge color=color>{item}</Badge> })
+                    .collect_view()}
+            </div>
+        }
+        .into_view()
+    } else {
+        view! { <ReportTextValue value="None".to_string()/> }.into_view()
+    }
+}
+
+impl SystemInfo {
+    fn version_mismatch(&self) -> Option<VersionMismatch> {
+        let server = Semver::try_from(self.version.as_str()).ok()?;
+        if server < MINIMUM_API_VERSION {
+            Some(VersionMismatch::ServerTooOld)
+        } else if server.major() > MINIMUM_API_VERSION.major()
+            || server.minor() > MINIMUM_API_VERSION.minor() + MAX_MINOR_DRIFT
+        {
+            Some(VersionMismatch::WebadminTooOld)
+        } else {
+            None
+        }
+    }
+
+    fn diagnostics(&self, is_ready: bool) -> String {
+        let mut text = String::new();
+        text.push_str(&format!("Server version: {}\n", self.version));
+        text.push_str(&format!(
+            "Webadmin version: {}\n",
+            env!("CARGO_PKG_VERSION")
+        ));
+        text.push_str(&format!(
+            "Status: {}\n",
+            if is_ready { "ready" } else { "not ready" }
+        ));
+        text.push_str(&format!("Uptime: {}s\n", self.uptime));
+        text.push_str(&format!(
+            "OS: {} {}\n",
+            self.os.as_deref().unwrap_or("unknown"),
+            self.arch.as_deref().unwrap_or_default()
+        ));
+        text.push_str(&format!("Features: {}\n", self.features.join(", ")));
+        text.push_str(&format!("Protocols: {}\n", self.protocols.join(", ")));
+        for store in &self.stores {
+            text.push_str(&format!("Store {}: {}\n", store.id, store.typ));
+        }
+        text
+    }
+}
@@ -8,7 +8,7 @@ use chrono::Duration;
 use chrono_humanize::{Accuracy, HumanTime, Tense};
 use codee::string::JsonSerdeCodec;
 use leptos::*;
-use leptos_router::use_query_map;
+use leptos_router::{use_navigate, use_query_map};
 use leptos_use::{
     use_event_source_with_options, ReconnectLimit, UseEventSourceOptions, UseEventSourceReturn,
 };
@@ -25,6 +25,7 @@ use crate::{
         form::{
             button::Button,
             input::{InputText, TextArea},
+            select::Select,
             Form, FormButtonBar, FormElement, FormItem, FormSection,
         },
         icon::{
@@ -36,10 +37,16 @@ use crate::{
         Color,
     },
     core::{
-        http::HttpRequest,
+        http::{self, HttpRequest},
         oauth::use_authorization,
         schema::{Builder, Schemas, Transformer, Type, Validator},
         url::UrlBuilder,
+        AccessToken,
+    },
+    pages::{
+        directory::Principal,
+        queue::messages::{Message, Status},
+        List,
     },
 };
 
@@ -497,6 +504,321 @@ pub fn TroubleshootDmarc() -> impl IntoView {
     }
 }
 
+#[component]
+pub fn TroubleshootSendTest() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let data = expect_context::<Arc<Schemas>>()
+        .build_form("troubleshoot-send")
+        .into_signal();
+    let queue_id: RwSignal<Option<u64>> = RwSignal::new(None);
+    let tick = RwSignal::new(0u32);
+    let poll_handle: StoredValue<Option<IntervalHandle>> = store_value(None);
+    let stop_polling = move || {
+        if let Some(handle) = poll_handle.get_value() {
+            handle.clear();
+            poll_handle.set_value(None);
+        }
+    };
+    on_cleanup(stop_polling);
+
+    let domains = create_resource(
+        || (),
+        move |_| {
+            let auth = auth.get_untracked();
+
+            async move {
+                HttpRequest::get("/api/principal")
+                    .with_authorization(&auth)
+                    .with_parameter("types", "domain")
+                    .with_parameter("fields", "name")
+                    .send::<List<Principal>>()
+                    .await
+                    .map(|list| {
+                        list.items
+                            .into_iter()
+                            .filter_map(|principal| principal.name.try_unwrap_string())
+                            .map(|name| (name.clone(), name))
+                            .collect::<Vec<_>>()
+                    })
+            }
+        },
+    );
+    let domain_options = create_memo(move |_| {
+        domains
+            .get()
+            .and_then(|result| result.ok())
+            .unwrap_or_default()
+    });
+    create_effect(move |_| {
+        if let Some((domain, _)) = domain_options.get().into_iter().next() {
+            data.update(|data| {
+                if !data.has_value("from_domain") {
+                    data.set("from_domain", domain);
+                }
+            });
+        }
+    });
+
+    let send_message = create_action(move |request: &TestMessageRequest| {
+        let auth = auth.get();
+        let request = request.clone();
+
+        async move {
+            match HttpRequest::post("/api/queue/messages")
+                .with_authorization(&auth)
+                .with_body(request)
+                .unwrap()
+                .send::<u64>()
+                .await
+            {
+                Ok(id) => {
+                    queue_id.set(Some(id));
+                    stop_polling();
+                    poll_handle.set_value(
+                        set_interval_with_handle(
+                            move || tick.update(|tick| *tick += 1),
+                            std::time::Duration::from_secs(3),
+                        )
+                        .ok(),
+                    );
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+
+    let delivery_status = create_resource(
+        move || (queue_id.get(), tick.get()),
+        move |(queue_id, _)| {
+            let auth = auth.get_untracked();
+
+            async move {
+                let Some(queue_id) = queue_id else {
+                    return Ok(None);
+                };
+                let message = match HttpRequest::get(("/api/queue/messages", queue_id.to_string()))
+                    .with_authorization(&auth)
+                    .send::<Message>()
+                    .await
+                {
+                    Ok(message) => Some(message),
+                    Err(http::Error::NotFound) => None,
+                    Err(err) => return Err(err),
+                };
+                let trace = fetch_delivery_trace(&auth, queue_id).await;
+
+                Ok(Some((queue_id, message, trace)))
+            }
+        },
+    );
+
+    view! {
+        <Form title="Send test e-mail" subtitle="Send a test message and follow its delivery">
+
+            <FormSection>
+                <FormItem label="Sender" tooltip="The local part and domain used as sender address.">
+                    <div class="flex gap-x-2">
+                        <InputText placeholder="postmaster" element=FormElement::new("from_local", data)/>
+                        <Select element=FormElement::new("from_domain", data) options=domain_options/>
+                    </div>
+                </FormItem>
+                <FormItem label="Recipient" tooltip="The destination address.">
+                    <InputText placeholder="john@example.org" element=FormElement::new("to", data)/>
+                </FormItem>
+                <FormItem label="Subject" is_optional=true>
+                    <InputText placeholder="Test message" element=FormElement::new("subject", data)/>
+                </FormItem>
+                <FormItem label="Message Body" is_optional=true>
+                    <TextArea element=FormElement::new("body", data)/>
+                </FormItem>
+            </FormSection>
+
+            <FormButtonBar>
+                <Button
+                    text="Send test e-mail"
+                    color=Color::Blue
+                    disabled=send_message.pending()
+                    on_click=Callback::new(move |_| {
+                        data.update(|data| {
+                            if data.validate_form() {
+                                let from_local = data.get("from_local").unwrap_or("postmaster");
+                                let from_domain = data.get("from_domain").unwrap_or_default();
+                                send_message
+                                    .dispatch(TestMessageRequest {
+                                        from: format!("{from_local}@{from_domain}"),
+                                        to: vec![data.get("to").unwrap_or_default().to_string()],
+                                        subject: data
+                                            .get("subject")
+                                            .unwrap_or("Test message")
+                                            .to_string(),
+                                        body: data.get("body").unwrap_or_default().to_string(),
+                                    });
+                            }
+                        });
+                    })
+                />
+
+            </FormButtonBar>
+
+            <Transition>
+                {move || match delivery_status.get() {
+                    Some(Ok(Some((queue_id, message, trace)))) => {
+                        let queue_url = format!("/manage/queue/message/{queue_id}");
+                        let (is_done, items) = match message {
+                            Some(message) => {
+                                let is_done = message
+                                    .recipients
+                                    .iter()
+                                    .all(|rcpt| !matches!(rcpt.status, Status::Scheduled | Status::TemporaryFailure(_)));
+                                let items = message
+                                    .recipients
+                                    .into_iter()
+                                    .map(|rcpt| {
+                                        let (status, is_failure) = match rcpt.status {
+                                            Status::Scheduled => ("Scheduled".to_string(), false),
+                                            Status::Completed(response) => {
+                                                (format!("Delivered: {response}"), false)
+                                            }
+                                            Status::TemporaryFailure(response) => {
+                                                (format!("Temporary failure: {response}"), true)
+                                            }
+                                            Status::PermanentFailure(response) => {
+                                                (format!("Permanent failure: {response}"), true)
+                                            }
+                                        };
+                                        let queue_url = queue_url.clone();
+                                        view! {
+                                            <li class="ps-1 text-sm text-gray-600 dark:text-neutral-400">
+                                                {format!("{}: {status}", rcpt.address)}
+                                                {is_failure
+                                                    .then(|| {
+                                                        view! {
+                                                            <a
+                                                                class="ms-2 text-blue-600 decoration-2 hover:underline"
+                                                                href=queue_url
+                                                            >
+                                                                View queue entry
+                                                            </a>
+                                                        }
+                                                    })}
+
+                                            </li>
+                                        }
+                                    })
+                                    .collect_view();
+                                (is_done, items)
+                            }
+                            None => {
+                                (
+                                    true,
+                                    view! {
+                                        <li class="ps-1 text-sm text-gray-600 dark:text-neutral-400">
+                                            Message left the queue.
+                                        </li>
+                                    }
+                                        .into_view(),
+                                )
+                            }
+                        };
+                        if is_done {
+                            stop_polling();
+                        }
+                        let trace = trace
+                            .into_iter()
+                            .map(|line| {
+                                view! {
+                                    <li class="ps-1 text-sm text-gray-600 dark:text-neutral-400">
+                                        {line}
+                                    </li>
+                                }
+                            })
+                            .collect_view();
+                        Some(
+                            view! {
+                                <div class="mt-8 pt-6 border-t border-gray-200 dark:border-gray-700">
+                                    <h2 class="text-sm font-semibold text-gray-800 dark:text-gray-200">
+                                        {format!("Delivery progress for queue id {queue_id}")}
+                                    </h2>
+                                    <ul class="list-disc ms-6 mt-3 space-y-1.5">{items}</ul>
+                                    <ul class="list-disc ms-6 mt-3 space-y-1.5">{trace}</ul>
+                                </div>
+                            }
+                                .into_view(),
+                        )
+                    }
+                    Some(Err(http::Error::Unauthorized)) => {
+                        use_navigate()("/login", Default::default());
+                        None
+                    }
+                    Some(Err(err)) => {
+                        stop_polling();
+                        alert.set(Alert::from(err));
+                        None
+                    }
+                    _ => None,
+                }}
+
+            </Transition>
+
+        </Form>
+    }
+}
+
+// SPDX-SnippetBegin
+// SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+// SPDX-License-Identifier: LicenseRef-SEL
+#[cfg(feature = "enterprise")]
+async fn fetch_delivery_trace(auth: &AccessToken, queue_id: u64) -> Vec<String> {
+    use crate::pages::enterprise::tracing::event::{Event, Key};
+
+    HttpRequest::get("/api/telemetry/traces")
+        .with_authorization(auth)
+        .with_parameter("queue_id", queue_id.to_string())
+        .with_parameter("values", "1")
+        .send::<List<Event>>()
+        .await
+        .map(|list| {
+            let mut lines = Vec::new();
+            for event in list.items {
+                if let Some(tls) = event.get_as_str(Key::Tls) {
+                    lines.push(format!("TLS: {tls}"));
+                }
+                for (key, label) in [
+                    (Key::DkimPass, "DKIM pass"),
+                    (Key::DkimFail, "DKIM fail"),
+                    (Key::DkimNone, "DKIM none"),
+                ] {
+                    let domains = event.get_as_str_list(key).collect::<Vec<_>>();
+                    if !domains.is_empty() {
+                        lines.push(format!("{label}: {}", domains.join(", ")));
+                    }
+                }
+                if let Some(text) = event.text {
+                    lines.push(text);
+                }
+            }
+            lines
+        })
+        .unwrap_or_default()
+}
+// SPDX-SnippetEnd
+
+#[cfg(not(feature = "enterprise"))]
+async fn fetch_delivery_trace(_: &AccessToken, _: u64) -> Vec<String> {
+    vec![]
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct TestMessageRequest {
+    from: String,
+    to: Vec<String>,
+    subject: String,
+    body: String,
+}
+
 #[component]
 fn StageView(event: DeliveryStage) -> impl IntoView {
     let icon = event.icon();
@@ -1287,7 +1609,7 @@ impl Builder<Schemas, ()> {
             .new_field("remote_ip")
             .input_check(
                 [Transformer::Trim],
-                [Validator::Required, Validator::IsIpOrMask],
+                [Validator::Required, Validator::IsIpAddr],
             )
             .typ(Type::Input)
             .build()
@@ -1299,5 +1621,33 @@ impl Builder<Schemas, ()> {
             .typ(Type::Input)
             .build()
             .build()
+            .new_schema("troubleshoot-send")
+            .new_field("from_local")
+            .input_check(
+                [Transformer::Lowercase, Transformer::Trim],
+                [Validator::Required],
+            )
+            .typ(Type::Input)
+            .default("postmaster")
+            .build()
+            .new_field("from_domain")
+            .input_check([], [Validator::Required])
+            .typ(Type::Input)
+            .build()
+            .new_field("to")
+            .input_check(
+                [Transformer::Lowercase, Transformer::Trim],
+                [Validator::Required, Validator::IsEmail],
+            )
+            .typ(Type::Input)
+            .build()
+            .new_field("subject")
+            .input_check([Transformer::Trim], [])
+            .typ(Type::Input)
+            .build()
+            .new_field("body")
+            .typ(Type::Text)
+            .build()
+            .build()
     }
 }
@@ -4,6 +4,8 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use ahash::AHashSet;
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
 use leptos::*;
 use leptos_router::*;
 use serde::{Deserialize, Serialize};
@@ -12,6 +14,7 @@ use std::sync::Arc;
 use crate::{
     components::{
         badge::Badge,
+        form::button::Button,
         icon::{
             IconAlertTriangle, IconCancel, IconCheckCircle, IconClock, IconLaunch, IconPauseCircle,
             IconPlayCircle, IconRefresh,
@@ -45,6 +48,10 @@ use chrono_humanize::HumanTime;
 
 const PAGE_SIZE: u32 = 10;
 
+const INPUT_CLASS: &str = "py-2 px-3 block w-full border-gray-200 shadow-sm text-sm rounded-lg focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600";
+const SELECT_CLASS: &str = "py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 disabled:opacity-50 disabled:pointer-events-none dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400 dark:focus:ring-gray-600";
+const ROW_ACTION_CLASS: &str = "inline-flex items-center gap-x-1 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600";
+
 #[derive(Clone, Serialize, Deserialize, Default)]
 pub struct List<T> {
     pub items: Vec<T>,
@@ -52,6 +59,16 @@ pub struct List<T> {
     pub status: bool,
 }
 
+// Search terms kept in the query string. The server filters by text,
+// sender and recipient, the delivery status is matched on the loaded page.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct QueueFilter {
+    text: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    status: Option<String>,
+}
+
 #[component]
 pub fn QueueList() -> impl IntoView {
     let query = use_query_map();
@@ -61,24 +78,16 @@ pub fn QueueList() -> impl IntoView {
             .filter(|&page| page > 0)
             .unwrap_or(1)
     });
-    let filter = create_memo(move |_| {
-        query.with(|q| {
-            q.get("filter").and_then(|s| {
-                let s = s.trim();
-                if !s.is_empty() {
-                    Some(s.to_string())
-                } else {
-                    None
-                }
-            })
-        })
-    });
+    let filter = create_memo(move |_| query.with(QueueFilter::from_query));
+    let text = create_memo(move |_| filter.with(|filter| filter.text.clone()));
 
     let auth = use_authorization();
     let alert = use_alerts();
     let modal = use_modals();
     let selected = create_rw_signal::<ItemSelection>(ItemSelection::None);
     provide_context(selected);
+    let reschedule = create_rw_signal(None::<ItemSelection>);
+    let page_ids = store_value(AHashSet::<String>::new());
 
     let messages = create_resource(
         move || (page.get(), filter.get()),
@@ -86,15 +95,21 @@ pub fn QueueList() -> impl IntoView {
             let auth = auth.get_untracked();
 
             async move {
-                HttpRequest::get("/api/queue/messages")
-                    .with_authorization(&auth)
-                    .with_parameter("page", page.to_string())
-                    .with_parameter("limit", PAGE_SIZE.to_string())
-                    .with_parameter("values", "1")
-                    .with_parameter("max-total", "100")
-                    .with_optional_parameter("text", filter)
+                filter
+                    .apply(
+                        HttpRequest::get("/api/queue/messages")
+                            .with_authorization(&auth)
+                            .with_parameter("page", page.to_string())
+                            .with_parameter("limit", PAGE_SIZE.to_string())
+                            .with_parameter("values", "1")
+                            .with_parameter("max-total", "100"),
+                    )
                     .send::<List<Message>>()
                     .await
+                    .map(|mut list| {
+                        list.items.retain(|message| filter.matches(message));
+                        list
+                    })
             }
         },
     );
@@ -102,6 +117,15 @@ pub fn QueueList() -> impl IntoView {
     let total_results = create_rw_signal(None::<u32>);
     let is_active = create_rw_signal(true);
 
+    // With a status filter only the messages shown are acted upon, the server
+    // would otherwise include those with any status
+    let resolve_selection = move |selection: ItemSelection| match selection {
+        ItemSelection::All if filter.with_untracked(|filter| filter.status.is_some()) => {
+            ItemSelection::Some(page_ids.get_value())
+        }
+        selection => selection,
+    };
+
     let cancel_action = create_action(move |items: &Arc<ItemSelection>| {
         let items = items.clone();
         let auth = auth.get();
@@ -109,12 +133,12 @@ pub fn QueueList() -> impl IntoView {
 
         async move {
             let mut total_deleted = 0;
+            let mut failures = Vec::new();
 
             match items.as_ref() {
                 ItemSelection::All => {
-                    match HttpRequest::delete("/api/queue/messages")
-                        .with_authorization(&auth)
-                        .with_optional_parameter("text", filter)
+                    match filter
+                        .apply(HttpRequest::delete("/api/queue/messages").with_authorization(&auth))
                         .send::<serde_json::Value>()
                         .await
                     {
@@ -128,7 +152,7 @@ pub fn QueueList() -> impl IntoView {
                     }
                 }
                 ItemSelection::Some(items) => {
-                    for id in items {
+                    for id in sorted_ids(items) {
                         match HttpRequest::delete(("/api/queue/messages", id.as_str()))
                             .with_authorization(&auth)
                             .send::<bool>()
@@ -137,61 +161,67 @@ pub fn QueueList() -> impl IntoView {
                             Ok(true) => {
                                 total_deleted += 1;
                             }
-                            Ok(false) | Err(http::Error::NotFound) => {}
-                            Err(err) => {
-                                alert.set(Alert::from(err));
+                            Ok(false) | Err(http::Error::NotFound) => {
+                                failures.push(format!("{id}: the message is no longer queued"));
+                            }
+                            Err(http::Error::Unauthorized) => {
+                                use_navigate()("/login", Default::default());
                                 return;
                             }
+                            Err(err) => {
+                                failures.push(format!("{id}: {}", Alert::from(err).message));
+                            }
                         }
                     }
                 }
                 ItemSelection::None => unreachable!(),
             }
 
-            if total_deleted > 0 {
-                messages.refetch();
-                alert.set(Alert::success(format!(
-                    "Removed {} from queue.",
-                    maybe_plural(total_deleted, "message", "messages")
-                )));
+            messages.refetch();
+            let message = format!(
+                "Removed {} from queue",
+                maybe_plural(total_deleted, "message", "messages")
+            );
+            if failures.is_empty() {
+                alert.set(Alert::success(format!("{message}.")));
+            } else {
+                alert.set(
+                    Alert::warning(format!(
+                        "{message}, {} could not be removed.",
+                        maybe_plural(failures.len(), "message", "messages")
+                    ))
+                    .without_timeout()
+                    .with_details_list(failures),
+                );
             }
         }
     });
-    let retry_action = create_action(move |items: &Arc<ItemSelection>| {
-        let items = items.clone();
-        let auth = auth.get();
-        let filter = filter.get();
-
-        async move {
-            let mut total_rescheduled = 0;
+    // Messages are delivered right away, or at the given time when rescheduled
+    let retry_action = create_action(
+        move |(items, at): &(Arc<ItemSelection>, Option<DateTime<Utc>>)| {
+            let items = items.clone();
+            let at = *at;
+            let auth = auth.get();
+            let filter = filter.get();
 
-            match items.as_ref() {
-                ItemSelection::All => {
-                    match HttpRequest::patch("/api/queue/messages")
-                        .with_authorization(&auth)
-                        .with_optional_parameter("filter", filter)
-                        .send::<bool>()
-                        .await
-                    {
-                        Ok(true) => {
-                            total_rescheduled = total_results.get().unwrap_or_default() as usize;
-                        }
-                        Ok(false) | Err(http::Error::NotFound) => {}
-                        Err(err) => {
-                            alert.set(Alert::from(err));
-                            return;
-                        }
-                    }
-                }
-                ItemSelection::Some(items) => {
-                    for id in items {
-                        match HttpRequest::patch(("/api/queue/messages", id.as_str()))
-                            .with_authorization(&auth)
+            async move {
+                let mut total_rescheduled = 0;
+                let mut failures = Vec::new();
+
+                match items.as_ref() {
+                    ItemSelection::All => {
+                        match filter
+                            .apply(
+                                HttpRequest::patch("/api/queue/messages")
+                                    .with_authorization(&auth)
+                                    .with_optional_parameter("at", at.map(|at| at.to_rfc3339())),
+                            )
                             .send::<bool>()
                             .await
                         {
                             Ok(true) => {
-                                total_rescheduled += 1;
+                                total_rescheduled =
+                                    total_results.get().unwrap_or_default() as usize;
                             }
                             Ok(false) | Err(http::Error::NotFound) => {}
                             Err(err) => {
@@ -200,19 +230,57 @@ pub fn QueueList() -> impl IntoView {
                             }
                         }
                     }
+                    ItemSelection::Some(items) => {
+                        for id in sorted_ids(items) {
+                            match HttpRequest::patch(("/api/queue/messages", id.as_str()))
+                                .with_authorization(&auth)
+                                .with_optional_parameter("at", at.map(|at| at.to_rfc3339()))
+                                .send::<bool>()
+                                .await
+                            {
+                                Ok(true) => {
+                                    total_rescheduled += 1;
+                                }
+                                Ok(false) | Err(http::Error::NotFound) => {
+                                    failures.push(format!("{id}: the message is no longer queued"));
+                                }
+                                Err(http::Error::Unauthorized) => {
+                                    use_navigate()("/login", Default::default());
+                                    return;
+                                }
+                                Err(err) => {
+                                    failures.push(format!("{id}: {}", Alert::from(err).message));
+                                }
+                            }
+                        }
+                    }
+                    ItemSelection::None => unreachable!(),
                 }
-                ItemSelection::None => unreachable!(),
-            }
 
-            if total_rescheduled > 0 {
                 messages.refetch();
-                alert.set(Alert::success(format!(
-                    "Successfully requested immediate delivery of {}.",
-                    maybe_plural(total_rescheduled, "message", "messages")
-                )));
+                let total = maybe_plural(total_rescheduled, "message", "messages");
+                let message = match at {
+                    Some(at) => format!(
+                        "Rescheduled {total} for {}",
+                        at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+                    ),
+                    None => format!("Requested immediate delivery of {total}"),
+                };
+                if failures.is_empty() {
+                    alert.set(Alert::success(format!("{message}.")));
+                } else {
+                    alert.set(
+                        Alert::warning(format!(
+                            "{message}, {} could not be rescheduled.",
+                            maybe_plural(failures.len(), "message", "messages")
+                        ))
+                        .without_timeout()
+                        .with_details_list(failures),
+                    );
+                }
             }
-        }
-    });
+        },
+    );
     let set_status = create_action(move |status: &bool| {
         let auth = auth.get();
         let status = *status;
@@ -241,17 +309,67 @@ pub fn QueueList() -> impl IntoView {
         }
     });
 
+    let confirm_cancel = move |items: ItemSelection, total: usize| {
+        let text = maybe_plural(total, "message", "messages");
+        modal.set(
+            Modal::with_title("Confirm deletion")
+                .with_message(format!(
+                    "Are you sure you want to cancel delivery of {text}? This action cannot be undone.",
+                ))
+                .with_button(format!("Delete {text}"))
+                .with_dangerous_callback(move || {
+                    selected.set(ItemSelection::None);
+                    cancel_action.dispatch(Arc::new(items.clone()));
+                }),
+        )
+    };
+    let on_retry = Callback::new(move |id: String| {
+        retry_action.dispatch((
+            Arc::new(ItemSelection::Some([id].into_iter().collect())),
+            None,
+        ));
+    });
+    let on_reschedule = Callback::new(move |id: String| {
+        reschedule.set(Some(ItemSelection::Some([id].into_iter().collect())));
+    });
+    let on_cancel = Callback::new(move |id: String| {
+        confirm_cancel(ItemSelection::Some([id].into_iter().collect()), 1);
+    });
+
     view! {
         <ListSection>
+            <QueueFilters filter/>
+
+            {move || {
+                reschedule
+                    .get()
+                    .map(|items| {
+                        let total = items.total_selected(total_results.get_untracked());
+                        view! {
+                            <Reschedule
+                                total
+                                on_close=move |_| reschedule.set(None)
+                                on_apply=move |at: DateTime<Utc>| {
+                                    reschedule.set(None);
+                                    selected.set(ItemSelection::None);
+                                    retry_action.dispatch((Arc::new(items.clone()), Some(at)));
+                                }
+                            />
+                        }
+                    })
+            }}
+
             <ListTable title="Message Queue" subtitle="View, cancel or reschedule queued messages">
                 <Toolbar slot>
                     <SearchBox
-                        value=filter
+                        value=text
                         on_search=move |value| {
                             use_navigate()(
-                                &UrlBuilder::new("/manage/queue/messages")
-                                    .with_parameter("filter", value)
-                                    .finish(),
+                                &QueueFilter {
+                                    text: Some(value),
+                                    ..filter.get()
+                                }
+                                    .url(None),
                                 Default::default(),
                             );
                         }
@@ -311,14 +429,12 @@ pub fn QueueList() -> impl IntoView {
 
                         color=Color::Gray
                         on_click=Callback::new(move |_| {
-                            let to_delete = selected.get().total_selected(total_results.get());
-                            if to_delete > 0 {
-                                retry_action
-                                    .dispatch(
-                                        Arc::new(
-                                            selected.try_update(std::mem::take).unwrap_or_default(),
-                                        ),
-                                    );
+                            let to_retry = selected.get().total_selected(total_results.get());
+                            if to_retry > 0 {
+                                let items = resolve_selection(
+                                    selected.try_update(std::mem::take).unwrap_or_default(),
+                                );
+                                retry_action.dispatch((Arc::new(items), None));
                             }
                         })
                     >
@@ -326,6 +442,27 @@ pub fn QueueList() -> impl IntoView {
                         <IconLaunch/>
                     </ToolbarButton>
 
+                    <ToolbarButton
+                        text=Signal::derive(move || {
+                            let ns = selected.get().total_selected(total_results.get());
+                            if ns > 0 {
+                                format!("Reschedule ({ns})")
+                            } else {
+                                "Reschedule".to_string()
+                            }
+                        })
+
+                        color=Color::Gray
+                        on_click=Callback::new(move |_| {
+                            if selected.get().total_selected(total_results.get()) > 0 {
+                                reschedule.set(Some(resolve_selection(selected.get())));
+                            }
+                        })
+                    >
+
+                        <IconClock/>
+                    </ToolbarButton>
+
                     <ToolbarButton
                         text=Signal::derive(move || {
                             let ns = selected.get().total_selected(total_results.get());
@@ -334,27 +471,10 @@ pub fn QueueList() -> impl IntoView {
 
                         color=Color::Red
                         on_click=Callback::new(move |_| {
-                            let to_delete = selected.get().total_selected(total_results.get());
+                            let items = resolve_selection(selected.get());
+                            let to_delete = items.total_selected(total_results.get());
                             if to_delete > 0 {
-                                let text = maybe_plural(to_delete, "message", "messages");
-                                modal
-                                    .set(
-                                        Modal::with_title("Confirm deletion")
-                                            .with_message(
-                                                format!(
-                                                    "Are you sure you want to cancel delivery of {text}? This action cannot be undone.",
-                                                ),
-                                            )
-                                            .with_button(format!("Delete {text}"))
-                                            .with_dangerous_callback(move || {
-                                                cancel_action
-                                                    .dispatch(
-                                                        Arc::new(
-                                                            selected.try_update(std::mem::take).unwrap_or_default(),
-                                                        ),
-                                                    );
-                                            }),
-                                    )
+                                confirm_cancel(items, to_delete);
                             }
                         })
                     >
@@ -379,6 +499,10 @@ pub fn QueueList() -> impl IntoView {
                         Some(Ok(messages)) if !messages.items.is_empty() => {
                             total_results.set(Some(messages.total as u32));
                             is_active.set(messages.status);
+                            page_ids
+                                .set_value(
+                                    messages.items.iter().map(|message| message.id.to_string()).collect(),
+                                );
                             Some(
                                 view! {
                                     <ColumnList
@@ -398,7 +522,7 @@ pub fn QueueList() -> impl IntoView {
                                             key=|message| message.id
                                             let:message
                                         >
-                                            <QueueItem message/>
+                                            <QueueItem message on_retry on_reschedule on_cancel/>
                                         </For>
 
                                     </ColumnList>
@@ -409,6 +533,7 @@ pub fn QueueList() -> impl IntoView {
                         Some(Ok(messages)) => {
                             total_results.set(Some(0));
                             is_active.set(messages.status);
+                            page_ids.set_value(AHashSet::new());
                             Some(
                                 view! {
                                     <ZeroResults
@@ -430,13 +555,7 @@ pub fn QueueList() -> impl IntoView {
                         total_results=total_results.read_only()
                         page_size=PAGE_SIZE
                         on_page_change=move |page: u32| {
-                            use_navigate()(
-                                &UrlBuilder::new("/manage/queue/messages")
-                                    .with_parameter("page", page.to_string())
-                                    .with_optional_parameter("filter", filter.get())
-                                    .finish(),
-                                Default::default(),
-                            );
+                            use_navigate()(&filter.get().url(Some(page)), Default::default());
                         }
                     />
 
@@ -447,7 +566,161 @@ pub fn QueueList() -> impl IntoView {
 }
 
 #[component]
-fn QueueItem(message: Message) -> impl IntoView {
+fn QueueFilters(filter: Memo<QueueFilter>) -> impl IntoView {
+    let from = create_rw_signal(String::new());
+    let to = create_rw_signal(String::new());
+    let status = create_rw_signal(String::new());
+    create_effect(move |_| {
+        filter.with(|filter| {
+            from.set(filter.from.clone().unwrap_or_default());
+            to.set(filter.to.clone().unwrap_or_default());
+            status.set(filter.status.clone().unwrap_or_default());
+        });
+    });
+    let apply = move || {
+        let non_empty = |value: String| {
+            let value = value.trim().to_string();
+            (!value.is_empty()).then_some(value)
+        };
+        use_navigate()(
+            &QueueFilter {
+                text: filter.get_untracked().text,
+                from: non_empty(from.get_untracked()),
+                to: non_empty(to.get_untracked()),
+                status: non_empty(status.get_untracked()),
+            }
+            .url(None),
+            Default::default(),
+        );
+    };
+
+    view! {
+        <div class="mb-6 p-4 bg-white border border-gray-200 rounded-xl shadow-sm dark:bg-slate-900 dark:border-gray-700">
+            <form
+                class="grid sm:grid-cols-12 gap-3 items-end"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    apply();
+                }
+            >
+                <label class="sm:col-span-4 block text-sm text-gray-800 dark:text-gray-200">
+                    "Sender"
+                    <input
+                        type="text"
+                        class=INPUT_CLASS
+                        placeholder="user@example.org"
+                        prop:value=move || from.get()
+                        on:input=move |ev| from.set(event_target_value(&ev))
+                    />
+                </label>
+                <label class="sm:col-span-4 block text-sm text-gray-800 dark:text-gray-200">
+                    "Recipient domain"
+                    <input
+                        type="text"
+                        class=INPUT_CLASS
+                        placeholder="example.org"
+                        prop:value=move || to.get()
+                        on:input=move |ev| to.set(event_target_value(&ev))
+                    />
+                </label>
+                <label class="sm:col-span-2 block text-sm text-gray-800 dark:text-gray-200">
+                    "Status"
+                    <select
+                        class=SELECT_CLASS
+                        prop:value=move || status.get()
+                        on:change=move |ev| status.set(event_target_value(&ev))
+                    >
+                        <option value="">"Any"</option>
+                        <option value="pending">"Pending"</option>
+                        <option value="failed">"Failed"</option>
+                        <option value="completed">"Done"</option>
+                    </select>
+                </label>
+                <div class="sm:col-span-2 flex gap-x-2">
+                    <Button
+                        text="Clear"
+                        color=Color::Gray
+                        on_click=move |_| {
+                            from.set(String::new());
+                            to.set(String::new());
+                            status.set(String::new());
+                            apply();
+                        }
+                    />
+
+                    <Button text="Filter" color=Color::Blue on_click=move |_| apply()/>
+                </div>
+            </form>
+        </div>
+    }
+}
+
+#[component]
+fn Reschedule(
+    total: usize,
+    #[prop(into)] on_close: Callback<()>,
+    #[prop(into)] on_apply: Callback<DateTime<Utc>>,
+) -> impl IntoView {
+    let value = create_rw_signal(String::new());
+    let error = create_rw_signal(None::<&'static str>);
+
+    view! {
+        <div class="mb-6 p-4 sm:p-7 bg-white border border-gray-200 rounded-xl shadow-sm dark:bg-slate-900 dark:border-gray-700">
+            <h3 class="text-lg font-semibold text-gray-800 dark:text-gray-200">
+                {format!("Reschedule {}", maybe_plural(total, "message", "messages"))}
+            </h3>
+            <p class="text-sm text-gray-600 dark:text-gray-400">
+                "Pending recipients are retried at the chosen local time."
+            </p>
+            <div class="mt-4 max-w-xs">
+                <input
+                    type="datetime-local"
+                    class=INPUT_CLASS
+                    aria-invalid=move || error.get().is_some().to_string()
+                    prop:value=move || value.get()
+                    on:change=move |ev| {
+                        value.set(event_target_value(&ev));
+                        error.set(None);
+                    }
+                />
+                {move || {
+                    error.get().map(|error| view! { <p class="text-xs text-red-600 mt-2">{error}</p> })
+                }}
+
+            </div>
+            <div class="mt-6 flex justify-end gap-x-2">
+                <Button
+                    text="Cancel"
+                    color=Color::Gray
+                    on_click=move |_| {
+                        on_close.call(());
+                    }
+                />
+
+                <Button
+                    text="Reschedule"
+                    color=Color::Blue
+                    on_click=move |_| {
+                        match parse_local_time(&value.get_untracked()) {
+                            Some(at) if at > Utc::now() => on_apply.call(at),
+                            Some(_) => error.set(Some("Choose a time in the future.")),
+                            None => error.set(Some("Choose a date and time.")),
+                        }
+                    }
+                />
+
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn QueueItem(
+    message: Message,
+    on_retry: Callback<String>,
+    on_reschedule: Callback<String>,
+    on_cancel: Callback<String>,
+) -> impl IntoView {
     let mut total_success = 0;
     let mut total_pending = 0;
     let mut total_failed = 0;
@@ -483,6 +756,7 @@ fn QueueItem(message: Message) -> impl IntoView {
     } else {
         first_recipient.to_string()
     };
+    let id = store_value(message.id.to_string());
 
     view! {
         <tr>
@@ -531,17 +805,107 @@ fn QueueItem(message: Message) -> impl IntoView {
             </ListItem>
 
             <ListItem subclass="px-6 py-1.5">
-                <a
-                    class="inline-flex items-center gap-x-1 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
-                    href=format!("/manage/queue/message/{}", message.id)
-                >
-                    Manage
-                </a>
+                <div class="flex gap-x-3">
+                    <Show when=move || total_pending > 0>
+                        <button
+                            type="button"
+                            class=ROW_ACTION_CLASS
+                            on:click=move |_| on_retry.call(id.get_value())
+                        >
+                            Retry
+                        </button>
+                        <button
+                            type="button"
+                            class=ROW_ACTION_CLASS
+                            on:click=move |_| on_reschedule.call(id.get_value())
+                        >
+                            Reschedule
+                        </button>
+                    </Show>
+                    <button
+                        type="button"
+                        class=ROW_ACTION_CLASS
+                        on:click=move |_| on_cancel.call(id.get_value())
+                    >
+                        Cancel
+                    </button>
+                    <a class=ROW_ACTION_CLASS href=format!("/manage/queue/message/{}", message.id)>
+                        Manage
+                    </a>
+                </div>
             </ListItem>
         </tr>
     }
 }
 
+impl QueueFilter {
+    fn from_query(query: &ParamsMap) -> Self {
+        let param = |name: &str| {
+            query
+                .get(name)
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_string())
+        };
+        QueueFilter {
+            text: param("filter"),
+            from: param("from"),
+            to: param("to"),
+            status: param("status"),
+        }
+    }
+
+    fn apply(&self, request: HttpRequest) -> HttpRequest {
+        request
+            .with_optional_parameter("text", self.text.clone())
+            .with_optional_parameter("from", self.from.clone())
+            .with_optional_parameter("to", self.to.clone())
+    }
+
+    fn url(&self, page: Option<u32>) -> String {
+        UrlBuilder::new("/manage/queue/messages")
+            .with_optional_parameter("page", page.map(|page| page.to_string()))
+            .with_optional_parameter("filter", self.text.clone())
+            .with_optional_parameter("from", self.from.clone())
+            .with_optional_parameter("to", self.to.clone())
+            .with_optional_parameter("status", self.status.clone())
+            .finish()
+    }
+
+    fn matches(&self, message: &Message) -> bool {
+        match self.status.as_deref() {
+            Some("pending") => message
+                .recipients
+                .iter()
+                .any(|rcpt| matches!(rcpt.status, Status::Scheduled | Status::TemporaryFailure(_))),
+            Some("failed") => message
+                .recipients
+                .iter()
+                .any(|rcpt| matches!(rcpt.status, Status::PermanentFailure(_))),
+            Some("completed") => message
+                .recipients
+                .iter()
+                .all(|rcpt| matches!(rcpt.status, Status::Completed(_))),
+            _ => true,
+        }
+    }
+}
+
+fn sorted_ids(ids: &AHashSet<String>) -> Vec<String> {
+    let mut ids = ids.iter().cloned().collect::<Vec<_>>();
+    ids.sort();
+    ids
+}
+
+// Values of a "datetime-local" input are in the browser's time zone
+fn parse_local_time(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+        .map(|time| time.with_timezone(&Utc))
+}
+
 impl IntoView for Status {
     fn into_view(self) -> View {
         match self {
@@ -6,6 +6,7 @@
 
 pub mod list;
 pub mod manage;
+pub mod overview;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Deserializer, Serialize};
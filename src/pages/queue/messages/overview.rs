@@ -0,0 +1,419 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use ahash::AHashMap;
+use chrono::{DateTime, Duration, Utc};
+use chrono_humanize::{Accuracy, HumanTime, Tense};
+use leptos::*;
+use leptos_router::*;
+
+use crate::{
+    components::{
+        badge::Badge,
+        icon::{IconAlertTriangle, IconRefresh},
+        list::{
+            header::ColumnList, toolbar::ToolbarButton, Footer, ListItem, ListSection, ListTable,
+            Toolbar, ZeroResults,
+        },
+        messages::alert::{use_alerts, Alert},
+        skeleton::Skeleton,
+        Color,
+    },
+    core::{
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        url::UrlBuilder,
+        AccessToken,
+    },
+    pages::{
+        maybe_plural,
+        queue::messages::{list::List, Message, Status},
+        FormatDateTime,
+    },
+};
+
+const FETCH_PAGE_SIZE: usize = 100;
+const MAX_MESSAGES: usize = 2000;
+const DEFAULT_THRESHOLD: i64 = 24;
+const THRESHOLDS: &[i64] = &[1, 6, 12, 24, 48, 72];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DomainStats {
+    domain: String,
+    messages: usize,
+    recipients: usize,
+    retries: u64,
+    oldest: DateTime<Utc>,
+    last_error: Option<(DateTime<Utc>, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DeferralStats {
+    code: String,
+    recipients: usize,
+    example: String,
+}
+
+#[derive(Debug, Clone, Default)]
+struct QueueSummary {
+    domains: Vec<DomainStats>,
+    deferrals: Vec<DeferralStats>,
+    total_messages: usize,
+    is_truncated: bool,
+}
+
+#[component]
+pub fn QueueOverview() -> impl IntoView {
+    let query = use_query_map();
+    let threshold = create_memo(move |_| {
+        query
+            .with(|q| q.get("threshold").and_then(|t| t.parse::<i64>().ok()))
+            .filter(|t| THRESHOLDS.contains(t))
+            .unwrap_or(DEFAULT_THRESHOLD)
+    });
+
+    let auth = use_authorization();
+    let alert = use_alerts();
+
+    let summary = create_resource(
+        move || (),
+        move |_| {
+            let auth = auth.get_untracked();
+
+            async move { fetch_messages(&auth).await.map(QueueSummary::from) }
+        },
+    );
+
+    view! {
+        <ListSection>
+            <ListTable
+                title="Queue overview"
+                subtitle="Delivery health of queued messages grouped by destination domain"
+            >
+                <Toolbar slot>
+                    <div class="max-w-sm">
+                        <select
+                            class="py-2 px-3 pe-9 block w-full border-gray-200 rounded-lg text-sm focus:border-blue-500 focus:ring-blue-500 dark:bg-slate-900 dark:border-gray-700 dark:text-gray-400"
+                            on:change=move |ev| {
+                                use_navigate()(
+                                    &UrlBuilder::new("/manage/queue/overview")
+                                        .with_parameter("threshold", event_target_value(&ev))
+                                        .finish(),
+                                    Default::default(),
+                                );
+                            }
+                        >
+
+                            {THRESHOLDS
+                                .iter()
+                                .map(|hours| {
+                                    let hours = *hours;
+                                    view! {
+                                        <option
+                                            value=hours.to_string()
+                                            selected=move || threshold.get() == hours
+                                        >
+                                            {format!(
+                                                "Older than {}",
+                                                maybe_plural(hours as usize, "hour", "hours"),
+                                            )}
+                                        </option>
+                                    }
+                                })
+                                .collect_view()}
+                        </select>
+                    </div>
+
+                    <ToolbarButton
+                        text="Refresh"
+
+                        color=Color::Gray
+                        on_click=Callback::new(move |_| {
+                            summary.refetch();
+                        })
+                    >
+
+                        <IconRefresh/>
+                    </ToolbarButton>
+
+                </Toolbar>
+
+                <Transition fallback=Skeleton>
+                    {move || match summary.get() {
+                        None => None,
+                        Some(Err(http::Error::Unauthorized)) => {
+                            use_navigate()("/login", Default::default());
+                            Some(view! { <div></div> }.into_view())
+                        }
+                        Some(Err(err)) => {
+                            alert.set(Alert::from(err));
+                            Some(view! { <Skeleton/> }.into_view())
+                        }
+                        Some(Ok(summary)) if !summary.domains.is_empty() => {
+                            if summary.is_truncated {
+                                alert
+                                    .set(
+                                        Alert::warning("Partial results")
+                                            .with_details(
+                                                format!(
+                                                    "Only the first {} queued messages were included in this overview.",
+                                                    summary.total_messages,
+                                                ),
+                                            ),
+                                    );
+                            }
+                            let stale_after = Utc::now() - Duration::hours(threshold.get());
+                            let domains = summary
+                                .domains
+                                .into_iter()
+                                .map(|stats| {
+                                    let is_stale = stats.oldest < stale_after;
+                                    view! { <DomainItem stats is_stale/> }
+                                })
+                                .collect_view();
+                            let deferrals = (!summary.deferrals.is_empty())
+                                .then(|| {
+                                    let rows = summary
+                                        .deferrals
+                                        .into_iter()
+                                        .map(|stats| view! { <DeferralItem stats/> })
+                                        .collect_view();
+                                    view! {
+                                        <ColumnList headers=vec![
+                                            "Response code".to_string(),
+                                            "Recipients".to_string(),
+                                            "Example".to_string(),
+                                        ]>{rows}</ColumnList>
+                                    }
+                                });
+                            Some(
+                                view! {
+                                    <ColumnList headers=vec![
+                                        "Domain".to_string(),
+                                        "Messages".to_string(),
+                                        "Recipients".to_string(),
+                                        "Avg. retries".to_string(),
+                                        "Oldest".to_string(),
+                                        "Last error".to_string(),
+                                    ]>{domains}</ColumnList>
+                                    {deferrals}
+                                }
+                                    .into_view(),
+                            )
+                        }
+                        Some(Ok(_)) => {
+                            Some(
+                                view! {
+                                    <ZeroResults
+                                        title="Queue is empty"
+                                        subtitle="There are no messages pending delivery."
+                                    />
+                                }
+                                    .into_view(),
+                            )
+                        }
+                    }}
+
+                </Transition>
+
+                <Footer slot>
+                    <div></div>
+                </Footer>
+            </ListTable>
+        </ListSection>
+    }
+}
+
+#[component]
+fn DomainItem(stats: DomainStats, is_stale: bool) -> impl IntoView {
+    let url = UrlBuilder::new("/manage/queue/messages")
+        .with_parameter("filter", stats.domain.clone())
+        .finish();
+    let avg_retries = format!(
+        "{:.1}",
+        stats.retries as f64 / stats.recipients.max(1) as f64
+    );
+    let oldest = HumanTime::from(stats.oldest).to_text_en(Accuracy::Rough, Tense::Past);
+    let oldest_title = stats.oldest.format_date_time();
+    let (last_error, last_error_title) = match stats.last_error {
+        Some((date, error)) => (error, date.format_date_time()),
+        None => ("None".to_string(), String::new()),
+    };
+
+    view! {
+        <tr class=if is_stale { "bg-yellow-50 dark:bg-yellow-800/10" } else { "" }>
+            <ListItem>
+                <a
+                    class="text-sm font-semibold text-blue-600 decoration-2 hover:underline dark:text-blue-500"
+                    href=url
+                >
+                    {stats.domain}
+                </a>
+            </ListItem>
+            <ListItem>
+                <span class="text-sm text-gray-500">{stats.messages}</span>
+            </ListItem>
+            <ListItem>
+                <span class="text-sm text-gray-500">{stats.recipients}</span>
+            </ListItem>
+            <ListItem>
+                <span class="text-sm text-gray-500">{avg_retries}</span>
+            </ListItem>
+            <ListItem>
+                <span
+                    class="inline-flex items-center gap-x-1 text-sm text-gray-500"
+                    title=oldest_title
+                >
+                    {is_stale
+                        .then(|| {
+                            view! { <IconAlertTriangle attr:class="flex-shrink-0 size-4 text-yellow-600"/> }
+                        })}
+                    {oldest}
+                </span>
+            </ListItem>
+            <ListItem class="h-px w-72 min-w-72">
+                <span
+                    class="block text-sm text-gray-500 truncate max-w-72"
+                    title=last_error_title
+                >
+                    {last_error}
+                </span>
+            </ListItem>
+        </tr>
+    }
+}
+
+#[component]
+fn DeferralItem(stats: DeferralStats) -> impl IntoView {
+    view! {
+        <tr>
+            <ListItem>
+                <Badge color=if stats.code.starts_with('5') {
+                    Color::Red
+                } else {
+                    Color::Yellow
+                }>{stats.code}</Badge>
+            </ListItem>
+            <ListItem>
+                <span class="text-sm text-gray-500">{stats.recipients}</span>
+            </ListItem>
+            <ListItem class="h-px w-96 min-w-96">
+                <span class="block text-sm text-gray-500 truncate max-w-96" title=stats.example.clone()>
+                    {stats.example.clone()}
+                </span>
+            </ListItem>
+        </tr>
+    }
+}
+
+async fn fetch_messages(auth: &AccessToken) -> http::Result<(Vec<Message>, bool)> {
+    let mut messages = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let list = HttpRequest::get("/api/queue/messages")
+            .with_authorization(auth)
+            .with_parameter("page", page.to_string())
+            .with_parameter("limit", FETCH_PAGE_SIZE.to_string())
+            .with_parameter("values", "1")
+            .send::<List<Message>>()
+            .await?;
+        let is_last = list.items.len() < FETCH_PAGE_SIZE;
+        messages.extend(list.items);
+
+        if is_last || messages.len() as u64 >= list.total {
+            return Ok((messages, false));
+        } else if messages.len() >= MAX_MESSAGES {
+            return Ok((messages, true));
+        }
+        page += 1;
+    }
+}
+
+impl From<(Vec<Message>, bool)> for QueueSummary {
+    fn from((messages, is_truncated): (Vec<Message>, bool)) -> Self {
+        let mut domains: AHashMap<String, DomainStats> = AHashMap::new();
+        let mut deferrals: AHashMap<String, DeferralStats> = AHashMap::new();
+        let total_messages = messages.len();
+
+        for message in messages {
+            let mut seen_domains = Vec::new();
+
+            for recipient in &message.recipients {
+                if !matches!(
+                    recipient.status,
+                    Status::Scheduled | Status::TemporaryFailure(_)
+                ) {
+                    continue;
+                }
+
+                let domain = recipient
+                    .address
+                    .rsplit_once('@')
+                    .map(|(_, domain)| domain)
+                    .unwrap_or_default()
+                    .to_lowercase();
+                let stats = domains
+                    .entry(domain.clone())
+                    .or_insert_with(|| DomainStats {
+                        domain: domain.clone(),
+                        messages: 0,
+                        recipients: 0,
+                        retries: 0,
+                        oldest: message.created,
+                        last_error: None,
+                    });
+                if !seen_domains.contains(&domain) {
+                    stats.messages += 1;
+                    seen_domains.push(domain);
+                }
+                stats.recipients += 1;
+                stats.retries += recipient.retry_num as u64;
+                if message.created < stats.oldest {
+                    stats.oldest = message.created;
+                }
+
+                if let Status::TemporaryFailure(error) = &recipient.status {
+                    if stats
+                        .last_error
+                        .as_ref()
+                        .map_or(true, |(date, _)| message.created > *date)
+                    {
+                        stats.last_error = Some((message.created, error.clone()));
+                    }
+
+                    let code = response_code(error).unwrap_or("Other");
+                    deferrals
+                        .entry(code.to_string())
+                        .or_insert_with(|| DeferralStats {
+                            code: code.to_string(),
+                            recipients: 0,
+                            example: error.clone(),
+                        })
+                        .recipients += 1;
+                }
+            }
+        }
+
+        let mut domains = domains.into_values().collect::<Vec<_>>();
+        domains.sort_by(|a, b| a.oldest.cmp(&b.oldest).then(b.messages.cmp(&a.messages)));
+        let mut deferrals = deferrals.into_values().collect::<Vec<_>>();
+        deferrals.sort_by(|a, b| b.recipients.cmp(&a.recipients));
+
+        QueueSummary {
+            domains,
+            deferrals,
+            total_messages,
+            is_truncated,
+        }
+    }
+}
+
+fn response_code(error: &str) -> Option<&str> {
+    error
+        .split(|ch: char| !ch.is_ascii_digit())
+        .find(|code| code.len() == 3 && (code.starts_with('4') || code.starts_with('5')))
+}
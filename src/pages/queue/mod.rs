@@ -5,4 +5,5 @@
  */
 
 pub mod messages;
+pub mod quarantine;
 pub mod reports;
@@ -0,0 +1,368 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use chrono_humanize::HumanTime;
+use leptos::*;
+use leptos_router::*;
+use std::sync::Arc;
+
+use crate::{
+    components::{
+        badge::Badge,
+        icon::{IconCancel, IconLaunch, IconRefresh},
+        list::{
+            header::ColumnList,
+            pagination::Pagination,
+            row::SelectItem,
+            toolbar::{SearchBox, ToolbarButton},
+            Footer, ItemSelection, ListItem, ListSection, ListTable, Toolbar, ZeroResults,
+        },
+        messages::{
+            alert::{use_alerts, Alert},
+            modal::{use_modals, Modal},
+        },
+        skeleton::Skeleton,
+        Color,
+    },
+    core::{
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        url::UrlBuilder,
+        Permission,
+    },
+    pages::{
+        maybe_plural,
+        queue::quarantine::{QuarantineAction, QuarantinedMessage},
+        List,
+    },
+};
+
+const PAGE_SIZE: u32 = 10;
+
+#[component]
+pub fn QuarantineList() -> impl IntoView {
+    let query = use_query_map();
+    let page = create_memo(move |_| {
+        query
+            .with(|q| q.get("page").and_then(|page| page.parse::<u32>().ok()))
+            .filter(|&page| page > 0)
+            .unwrap_or(1)
+    });
+    let filter = create_memo(move |_| {
+        query.with(|q| {
+            q.get("filter").and_then(|s| {
+                let s = s.trim();
+                if !s.is_empty() {
+                    Some(s.to_string())
+                } else {
+                    None
+                }
+            })
+        })
+    });
+
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let modal = use_modals();
+    let selected = create_rw_signal::<ItemSelection>(ItemSelection::None);
+    provide_context(selected);
+    let permissions = auth.get_untracked().permissions().clone();
+    let can_release = permissions.has_access(Permission::QuarantineRelease);
+    let can_delete = permissions.has_access(Permission::QuarantineDelete);
+
+    let messages = create_resource(
+        move || (page.get(), filter.get()),
+        move |(page, filter)| {
+            let auth = auth.get_untracked();
+
+            async move {
+                HttpRequest::get("/api/quarantine")
+                    .with_authorization(&auth)
+                    .with_parameter("page", page.to_string())
+                    .with_parameter("limit", PAGE_SIZE.to_string())
+                    .with_optional_parameter("text", filter)
+                    .send::<List<QuarantinedMessage>>()
+                    .await
+            }
+        },
+    );
+
+    let total_results = create_rw_signal(None::<u32>);
+
+    let bulk_action = create_action(
+        move |(action, items): &(QuarantineAction, Arc<ItemSelection>)| {
+            let action = *action;
+            let items = items.clone();
+            let auth = auth.get();
+            let filter = filter.get();
+
+            async move {
+                let result = match items.as_ref() {
+                    ItemSelection::All => action
+                        .apply_all(&auth, filter)
+                        .await
+                        .map(|_| total_results.get().unwrap_or_default() as usize),
+                    ItemSelection::Some(items) => action.apply(&auth, items.iter().cloned()).await,
+                    ItemSelection::None => unreachable!(),
+                };
+
+                match result {
+                    Ok(total) => {
+                        if total > 0 {
+                            messages.refetch();
+                            alert.set(Alert::success(action.success_message(total)));
+                        }
+                    }
+                    Err(http::Error::Unauthorized) => {
+                        use_navigate()("/login", Default::default());
+                    }
+                    Err(err) => {
+                        alert.set(Alert::from(err));
+                    }
+                }
+            }
+        },
+    );
+    let confirm_action = move |action: QuarantineAction| {
+        let total = selected.get().total_selected(total_results.get());
+        if total > 0 {
+            let text = maybe_plural(total, "message", "messages");
+            let (title, message, button) = if action.is_release() {
+                (
+                    "Confirm release",
+                    format!("Are you sure you want to release {text} for delivery?"),
+                    format!("Release {text}"),
+                )
+            } else {
+                (
+                    "Confirm deletion",
+                    format!(
+                        "Are you sure you want to delete {text}? This action cannot be undone."
+                    ),
+                    format!("Delete {text}"),
+                )
+            };
+            modal.set(
+                Modal::with_title(title)
+                    .with_message(message)
+                    .with_button(button)
+                    .with_dangerous_callback(move || {
+                        bulk_action.dispatch((
+                            action,
+                            Arc::new(selected.try_update(std::mem::take).unwrap_or_default()),
+                        ));
+                    }),
+            )
+        }
+    };
+    let selected_text = move |text: &str| {
+        let ns = selected.get().total_selected(total_results.get());
+        if ns > 0 {
+            format!("{text} ({ns})")
+        } else {
+            text.to_string()
+        }
+    };
+
+    view! {
+        <ListSection>
+            <ListTable
+                title="Quarantine"
+                subtitle="Review, release or delete messages held by the spam filter"
+            >
+                <Toolbar slot>
+                    <SearchBox
+                        value=filter
+                        on_search=move |value| {
+                            use_navigate()(
+                                &UrlBuilder::new("/manage/queue/quarantine")
+                                    .with_parameter("filter", value)
+                                    .finish(),
+                                Default::default(),
+                            );
+                        }
+                    />
+
+                    <ToolbarButton
+                        text="Refresh"
+
+                        color=Color::Gray
+                        on_click=Callback::new(move |_| {
+                            messages.refetch();
+                        })
+                    >
+
+                        <IconRefresh/>
+                    </ToolbarButton>
+
+                    <Show when=move || can_release>
+                        <ToolbarButton
+                            text=Signal::derive(move || selected_text("Release"))
+                            color=Color::Gray
+                            on_click=Callback::new(move |_| {
+                                confirm_action(QuarantineAction::Release);
+                            })
+                        >
+
+                            <IconLaunch/>
+                        </ToolbarButton>
+                    </Show>
+
+                    <Show when=move || can_delete>
+                        <ToolbarButton
+                            text=Signal::derive(move || selected_text("Delete"))
+                            color=Color::Red
+                            on_click=Callback::new(move |_| {
+                                confirm_action(QuarantineAction::Delete);
+                            })
+                        >
+
+                            <IconCancel/>
+                        </ToolbarButton>
+                    </Show>
+
+                </Toolbar>
+
+                <Transition fallback=Skeleton>
+                    {move || match messages.get() {
+                        None => None,
+                        Some(Err(http::Error::Unauthorized)) => {
+                            use_navigate()("/login", Default::default());
+                            Some(view! { <div></div> }.into_view())
+                        }
+                        Some(Err(err)) => {
+                            total_results.set(Some(0));
+                            alert.set(Alert::from(err));
+                            Some(view! { <Skeleton/> }.into_view())
+                        }
+                        Some(Ok(messages)) if !messages.items.is_empty() => {
+                            total_results.set(Some(messages.total as u32));
+                            Some(
+                                view! {
+                                    <ColumnList
+                                        headers=vec![
+                                            "Envelope".to_string(),
+                                            "Subject".to_string(),
+                                            "Score".to_string(),
+                                            "Reason".to_string(),
+                                            "Received".to_string(),
+                                            "".to_string(),
+                                        ]
+
+                                        has_select_all=true
+                                    >
+
+                                        <For
+                                            each=move || messages.items.clone()
+                                            key=|message| message.id.clone()
+                                            let:message
+                                        >
+                                            <QuarantineItem message/>
+                                        </For>
+
+                                    </ColumnList>
+                                }
+                                    .into_view(),
+                            )
+                        }
+                        Some(Ok(_)) => {
+                            total_results.set(Some(0));
+                            Some(
+                                view! {
+                                    <ZeroResults
+                                        title="No results"
+                                        subtitle="No quarantined messages were found with the selected criteria."
+                                    />
+                                }
+                                    .into_view(),
+                            )
+                        }
+                    }}
+
+                </Transition>
+
+                <Footer slot>
+
+                    <Pagination
+                        current_page=page
+                        total_results=total_results.read_only()
+                        page_size=PAGE_SIZE
+                        on_page_change=move |page: u32| {
+                            use_navigate()(
+                                &UrlBuilder::new("/manage/queue/quarantine")
+                                    .with_parameter("page", page.to_string())
+                                    .with_optional_parameter("filter", filter.get())
+                                    .finish(),
+                                Default::default(),
+                            );
+                        }
+                    />
+
+                </Footer>
+            </ListTable>
+        </ListSection>
+    }
+}
+
+#[component]
+fn QuarantineItem(message: QuarantinedMessage) -> impl IntoView {
+    let recipients = match message.recipients.len() {
+        0 => String::new(),
+        1 => message.recipients[0].clone(),
+        n => format!("{} and {} more", message.recipients[0], n - 1),
+    };
+    let from = if !message.from.is_empty() {
+        message.from
+    } else {
+        "Mailer Daemon".to_string()
+    };
+    let score = message.score.map(|score| {
+        view! {
+            <Badge color=if score >= 5.0 { Color::Red } else { Color::Yellow }>
+                {format!("{score:.1}")}
+            </Badge>
+        }
+    });
+    let received = HumanTime::from(message.received).to_string();
+
+    view! {
+        <tr>
+            <ListItem>
+                <label class="flex">
+                    <SelectItem item_id=message.id.clone()/>
+
+                    <span class="sr-only">Checkbox</span>
+                </label>
+            </ListItem>
+            <td class="size-px whitespace-nowrap">
+                <div class="ps-6 lg:ps-3 xl:ps-0 pe-6 py-3">
+                    <span class="block text-sm font-semibold text-gray-800 dark:text-gray-200">
+                        {from}
+                    </span>
+                    <span class="block text-sm text-gray-500">{recipients}</span>
+                </div>
+            </td>
+            <ListItem class="h-px w-72 min-w-72">
+                <span class="block text-sm text-gray-500 truncate max-w-72">{message.subject}</span>
+            </ListItem>
+            <ListItem>{score}</ListItem>
+            <ListItem>
+                <span class="text-sm text-gray-500">{message.reason}</span>
+            </ListItem>
+            <ListItem>
+                <span class="text-sm text-gray-500">{received}</span>
+            </ListItem>
+            <ListItem subclass="px-6 py-1.5">
+                <a
+                    class="inline-flex items-center gap-x-1 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                    href=format!("/manage/queue/quarantine/{}", message.id)
+                >
+                    Review
+                </a>
+            </ListItem>
+        </tr>
+    }
+}
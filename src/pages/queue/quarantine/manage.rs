@@ -0,0 +1,327 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use chrono_humanize::HumanTime;
+use humansize::{format_size, DECIMAL};
+use leptos::*;
+use leptos_router::{use_navigate, use_params_map};
+
+use crate::{
+    components::{
+        card::{Card, CardItem},
+        form::button::Button,
+        icon::{IconClock, IconEnvelope, IconScale, IconShieldCheck},
+        messages::{
+            alert::{use_alerts, Alert, Alerts},
+            modal::{use_modals, Modal},
+        },
+        skeleton::Skeleton,
+        Color,
+    },
+    core::{
+        http::{self, HttpRequest},
+        oauth::use_authorization,
+        Permission,
+    },
+    pages::{
+        maybe_plural,
+        queue::quarantine::{sandboxed_html, MessagePreview, QuarantineAction, QuarantinedMessage},
+        FormatDateTime,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BodyView {
+    Text,
+    HtmlSource,
+    HtmlRendered,
+}
+
+#[component]
+pub fn QuarantineManage() -> impl IntoView {
+    let auth = use_authorization();
+    let alert = use_alerts();
+    let modal = use_modals();
+    let params = use_params_map();
+    let body_view = create_rw_signal(BodyView::Text);
+    let permissions = auth.get_untracked().permissions().clone();
+    let can_release = permissions.has_access(Permission::QuarantineRelease);
+    let can_delete = permissions.has_access(Permission::QuarantineDelete);
+
+    let fetch_message = create_resource(
+        move || params.get().get("id").cloned().unwrap_or_default(),
+        move |id| {
+            let auth = auth.get_untracked();
+
+            async move {
+                let message = HttpRequest::get(("/api/quarantine", id.as_str()))
+                    .with_authorization(&auth)
+                    .send::<QuarantinedMessage>()
+                    .await?;
+                let preview = HttpRequest::get(format!("/api/quarantine/{id}/preview"))
+                    .with_authorization(&auth)
+                    .send::<MessagePreview>()
+                    .await?;
+
+                Ok::<_, http::Error>((message, preview))
+            }
+        },
+    );
+
+    let apply_action = create_action(move |action: &QuarantineAction| {
+        let action = *action;
+        let id = params.get().get("id").cloned().unwrap_or_default();
+        let auth = auth.get();
+
+        async move {
+            match action.apply(&auth, [id]).await {
+                Ok(total) => {
+                    alert.set(Alert::success(action.success_message(total)));
+                    use_navigate()("/manage/queue/quarantine", Default::default());
+                }
+                Err(http::Error::Unauthorized) => {
+                    use_navigate()("/login", Default::default());
+                }
+                Err(err) => {
+                    alert.set(Alert::from(err));
+                }
+            }
+        }
+    });
+    let confirm_delete = move |action: QuarantineAction| {
+        modal.set(
+            Modal::with_title("Confirm deletion")
+                .with_message(
+                    "Are you sure you want to delete this message? This action cannot be undone.",
+                )
+                .with_button("Delete message")
+                .with_dangerous_callback(move || {
+                    apply_action.dispatch(action);
+                }),
+        );
+    };
+
+    view! {
+        <Alerts/>
+        <Transition fallback=Skeleton>
+
+            {move || match fetch_message.get() {
+                None => None,
+                Some(Err(http::Error::Unauthorized)) => {
+                    use_navigate()("/login", Default::default());
+                    Some(view! { <div></div> }.into_view())
+                }
+                Some(Err(http::Error::NotFound)) => {
+                    use_navigate()("/manage/queue/quarantine", Default::default());
+                    Some(view! { <div></div> }.into_view())
+                }
+                Some(Err(err)) => {
+                    alert.set(Alert::from(err));
+                    Some(view! { <div></div> }.into_view())
+                }
+                Some(Ok((message, preview))) => {
+                    let from = if !message.from.is_empty() {
+                        message.from.clone()
+                    } else {
+                        "Mailer Daemon".to_string()
+                    };
+                    let has_text = preview.text_body.is_some();
+                    let has_html = preview.html_body.is_some();
+                    if !has_text && has_html {
+                        body_view.set(BodyView::HtmlSource);
+                    }
+                    let text_body = preview.text_body.unwrap_or_default();
+                    let html_body = preview.html_body.unwrap_or_default();
+                    let html_rendered = sandboxed_html(&html_body);
+                    let headers = preview
+                        .headers
+                        .into_iter()
+                        .map(|(name, value)| {
+                            view! {
+                                <div class="flex gap-x-2 text-sm">
+                                    <span class="font-semibold text-gray-800 dark:text-gray-200 whitespace-nowrap">
+                                        {name}
+                                        ":"
+                                    </span>
+                                    <span class="text-gray-600 break-all dark:text-gray-400">
+                                        {value}
+                                    </span>
+                                </div>
+                            }
+                        })
+                        .collect_view();
+                    let tab_class = move |view: BodyView| {
+                        if body_view.get() == view {
+                            "py-1.5 px-3 text-sm font-medium rounded-lg bg-gray-100 text-gray-800 dark:bg-gray-700 dark:text-gray-200"
+                        } else {
+                            "py-1.5 px-3 text-sm font-medium rounded-lg text-gray-500 hover:text-gray-800 dark:text-gray-400 dark:hover:text-gray-200"
+                        }
+                    };
+                    Some(
+                        view! {
+                            <Card>
+                                <CardItem
+                                    title="Envelope"
+                                    contents=from
+                                    subcontents=maybe_plural(
+                                        message.recipients.len(),
+                                        "recipient",
+                                        "recipients",
+                                    )
+                                >
+
+                                    <IconEnvelope attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
+
+                                </CardItem>
+                                <CardItem
+                                    title="Received"
+                                    contents=HumanTime::from(message.received).to_string()
+                                    subcontents=message.received.format_date_time()
+                                >
+
+                                    <IconClock attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
+
+                                </CardItem>
+                                <CardItem
+                                    title="Spam score"
+                                    contents=message
+                                        .score
+                                        .map(|score| format!("{score:.1}"))
+                                        .unwrap_or_else(|| "N/A".to_string())
+                                    subcontents=message.reason.clone()
+                                >
+
+                                    <IconShieldCheck attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
+
+                                </CardItem>
+                                <CardItem title="Size" contents=format_size(message.size, DECIMAL)>
+
+                                    <IconScale attr:class="flex-shrink-0 size-5 text-gray-400 dark:text-gray-600"/>
+
+                                </CardItem>
+
+                            </Card>
+
+                            <div class="max-w-[85rem] px-4 py-8 sm:px-6 lg:px-8 lg:py-10 mx-auto">
+                                <div class="bg-white border border-gray-200 rounded-xl shadow-sm p-4 sm:p-7 dark:bg-slate-900 dark:border-gray-700">
+                                    <h2 class="text-xl font-semibold text-gray-800 dark:text-gray-200">
+                                        {message.subject.clone()}
+                                    </h2>
+                                    <p class="text-sm text-gray-600 dark:text-gray-400">
+                                        {message.recipients.join(", ")}
+                                    </p>
+
+                                    <div class="mt-5 space-y-1 max-h-64 overflow-y-auto">
+                                        {headers}
+                                    </div>
+
+                                    <div class="mt-5 pt-5 border-t border-gray-200 dark:border-gray-700">
+                                        <nav class="flex gap-x-1">
+                                            <button
+                                                type="button"
+                                                class=move || tab_class(BodyView::Text)
+                                                class:hidden=!has_text
+                                                on:click=move |_| body_view.set(BodyView::Text)
+                                            >
+                                                "Text"
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class=move || tab_class(BodyView::HtmlSource)
+                                                class:hidden=!has_html
+                                                on:click=move |_| body_view.set(BodyView::HtmlSource)
+                                            >
+                                                "HTML source"
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class=move || tab_class(BodyView::HtmlRendered)
+                                                class:hidden=!has_html
+                                                on:click=move |_| {
+                                                    body_view.set(BodyView::HtmlRendered)
+                                                }
+                                            >
+
+                                                "HTML preview"
+                                            </button>
+                                        </nav>
+
+                                        <pre
+                                            class="mt-3 p-4 max-h-[32rem] overflow-auto text-xs whitespace-pre-wrap break-all rounded-lg bg-gray-50 text-gray-800 dark:bg-slate-800 dark:text-gray-200"
+                                            class:hidden=move || body_view.get() != BodyView::Text
+                                        >
+                                            {text_body}
+                                        </pre>
+                                        <pre
+                                            class="mt-3 p-4 max-h-[32rem] overflow-auto text-xs whitespace-pre-wrap break-all rounded-lg bg-gray-50 text-gray-800 dark:bg-slate-800 dark:text-gray-200"
+                                            class:hidden=move || body_view.get() != BodyView::HtmlSource
+                                        >
+                                            {html_body}
+                                        </pre>
+                                        <Show when=move || body_view.get() == BodyView::HtmlRendered>
+                                            <p class="mt-3 text-xs text-gray-500">
+                                                "Scripts and remote content are blocked in this preview."
+                                            </p>
+                                            <iframe
+                                                class="mt-2 w-full h-[32rem] rounded-lg border border-gray-200 bg-white dark:border-gray-700"
+                                                sandbox=""
+                                                referrerpolicy="no-referrer"
+                                                srcdoc=html_rendered.clone()
+                                            ></iframe>
+                                        </Show>
+                                    </div>
+
+                                    <div class="mt-5 flex justify-end gap-x-2">
+                                        <Show when=move || can_delete>
+                                            <Button
+                                                text="Delete"
+                                                color=Color::Red
+                                                loading=apply_action.pending()
+                                                on_click=move |_| {
+                                                    confirm_delete(QuarantineAction::Delete);
+                                                }
+                                            />
+
+                                            <Button
+                                                text="Delete & train as spam"
+                                                color=Color::Red
+                                                loading=apply_action.pending()
+                                                on_click=move |_| {
+                                                    confirm_delete(QuarantineAction::DeleteAsSpam);
+                                                }
+                                            />
+                                        </Show>
+                                        <Show when=move || can_release>
+                                            <Button
+                                                text="Release & train as ham"
+                                                color=Color::Gray
+                                                loading=apply_action.pending()
+                                                on_click=move |_| {
+                                                    apply_action.dispatch(QuarantineAction::ReleaseAsHam);
+                                                }
+                                            />
+
+                                            <Button
+                                                text="Release"
+                                                color=Color::Blue
+                                                loading=apply_action.pending()
+                                                on_click=move |_| {
+                                                    apply_action.dispatch(QuarantineAction::Release);
+                                                }
+                                            />
+                                        </Show>
+                                    </div>
+                                </div>
+                            </div>
+                        }
+                            .into_view(),
+                    )
+                }
+            }}
+
+        </Transition>
+    }
+}
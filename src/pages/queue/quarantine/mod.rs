@@ -0,0 +1,183 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+pub mod list;
+pub mod manage;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{
+        http::{self, HttpRequest},
+        AccessToken,
+    },
+    pages::{maybe_plural, queue::messages::deserialize_datetime},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuarantinedMessage {
+    pub id: String,
+    #[serde(default)]
+    pub from: String,
+    #[serde(default)]
+    pub recipients: Vec<String>,
+    #[serde(default)]
+    pub subject: String,
+    #[serde(default)]
+    pub score: Option<f64>,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(deserialize_with = "deserialize_datetime")]
+    pub received: DateTime<Utc>,
+    #[serde(default)]
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct MessagePreview {
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub text_body: Option<String>,
+    #[serde(default)]
+    pub html_body: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuarantineAction {
+    Release,
+    ReleaseAsHam,
+    Delete,
+    DeleteAsSpam,
+}
+
+impl QuarantineAction {
+    fn request(&self, id: Option<&str>) -> HttpRequest {
+        let url = match id {
+            Some(id) => format!("/api/quarantine/{id}"),
+            None => "/api/quarantine".to_string(),
+        };
+
+        match self {
+            QuarantineAction::Release => HttpRequest::post(format!("{url}/release")),
+            QuarantineAction::ReleaseAsHam => {
+                HttpRequest::post(format!("{url}/release")).with_parameter("train", "ham")
+            }
+            QuarantineAction::Delete => HttpRequest::delete(url),
+            QuarantineAction::DeleteAsSpam => {
+                HttpRequest::delete(url).with_parameter("train", "spam")
+            }
+        }
+    }
+
+    pub fn success_message(&self, total: usize) -> String {
+        let messages = maybe_plural(total, "message", "messages");
+        match self {
+            QuarantineAction::Release => format!("Released {messages} for delivery."),
+            QuarantineAction::ReleaseAsHam => {
+                format!("Released {messages} for delivery and trained as ham.")
+            }
+            QuarantineAction::Delete => format!("Deleted {messages}."),
+            QuarantineAction::DeleteAsSpam => format!("Deleted {messages} and trained as spam."),
+        }
+    }
+
+    pub fn is_release(&self) -> bool {
+        matches!(
+            self,
+            QuarantineAction::Release | QuarantineAction::ReleaseAsHam
+        )
+    }
+
+    pub async fn apply(
+        &self,
+        auth: &AccessToken,
+        ids: impl IntoIterator<Item = String>,
+    ) -> http::Result<usize> {
+        let mut total = 0;
+        for id in ids {
+            match self
+                .request(Some(&id))
+                .with_authorization(auth)
+                .send::<bool>()
+                .await
+            {
+                Ok(true) => {
+                    total += 1;
+                }
+                Ok(false) | Err(http::Error::NotFound) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(total)
+    }
+
+    pub async fn apply_all(&self, auth: &AccessToken, filter: Option<String>) -> http::Result<()> {
+        self.request(None)
+            .with_authorization(auth)
+            .with_optional_parameter("text", filter)
+            .send::<serde_json::Value>()
+            .await
+            .map(|_| ())
+    }
+}
+
+// Wraps untrusted HTML so it can be shown inside a sandboxed iframe
+// without loading remote resources.
+pub fn sandboxed_html(html: &str) -> String {
+    format!(
+        concat!(
+            "<!DOCTYPE html><html><head>",
+            "<meta http-equiv=\"Content-Security-Policy\" content=\"default-src 'none'; ",
+            "style-src 'unsafe-inline'; img-src data:; font-src data:\">",
+            "<base target=\"_blank\">",
+            "</head><body>{}</body></html>"
+        ),
+        strip_tags(
+            html,
+            &["script", "iframe", "object", "embed", "link", "meta", "base"]
+        )
+    )
+}
+
+fn strip_tags(html: &str, tags: &[&str]) -> String {
+    let mut result = String::with_capacity(html.len());
+    let lower = html.to_ascii_lowercase();
+    let mut pos = 0;
+
+    while let Some(start) = lower[pos..].find('<').map(|idx| idx + pos) {
+        result.push_str(&html[pos..start]);
+        let tag = lower[start + 1..]
+            .trim_start_matches('/')
+            .split(|ch: char| !ch.is_ascii_alphanumeric())
+            .next()
+            .unwrap_or_default();
+        let end = lower[start..]
+            .find('>')
+            .map(|idx| idx + start + 1)
+            .unwrap_or(html.len());
+
+        if tags.contains(&tag) {
+            // Drop the contents of script blocks as well
+            pos = if tag == "script" && !lower[start + 1..].starts_with('/') {
+                lower[end..]
+                    .find("</script")
+                    .and_then(|idx| lower[end + idx..].find('>').map(|e| end + idx + e + 1))
+                    .unwrap_or(html.len())
+            } else {
+                end
+            };
+        } else {
+            result.push_str(&html[start..end]);
+            pos = end;
+        }
+    }
+    result.push_str(&html[pos..]);
+
+    result
+}
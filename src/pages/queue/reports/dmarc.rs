@@ -5,6 +5,7 @@
  */
 
 use std::{
+    cmp::Ordering,
     hash::{DefaultHasher, Hash, Hasher},
     vec,
 };
@@ -23,7 +24,7 @@ use crate::{
             IconEnvelope, IconId,
         },
         list::{
-            header::ColumnList,
+            header::{ColumnList, ColumnSort},
             pagination::{ItemPagination, Pagination},
             table::{Table, TableRow},
             toolbar::SearchBox,
@@ -32,13 +33,14 @@ use crate::{
         report::{ReportItem, ReportSection, ReportTextValue, ReportView},
         Color,
     },
+    core::schema::SortOrder,
     pages::{
         queue::reports::{display::PAGE_SIZE, ActionDisposition, Report},
         FormatDateTime,
     },
 };
 
-use super::{DkimResult, DmarcResult, Record, SpfResult};
+use super::{sources::SourceBreakdown, DkimResult, DmarcResult, Record, SpfResult};
 
 #[component]
 #[allow(unused_parens)]
@@ -84,37 +86,63 @@ pub fn DmarcReportDisplay(
         "N/A".to_string()
     };
     let email = report.report_metadata.email.clone();
-    let total_results = report.record.len() as u32;
+    let sources = report.sources();
     let page = create_rw_signal(1u32);
     let filter = create_rw_signal(None::<String>);
+    let sort = create_rw_signal(None::<(String, SortOrder)>);
     let selected_record = create_rw_signal(0u32);
+    let total_results = create_rw_signal(report.record.len() as u32);
     let fetch_records = create_memo(move |_| {
-        let mut records = Vec::with_capacity(PAGE_SIZE as usize);
-        let mut offset = (page.get().saturating_sub(1)) * PAGE_SIZE;
         let filter = filter
             .get()
             .map(|s| s.trim().to_lowercase())
             .unwrap_or_default();
-
-        let mut record_id: u32 = 0;
-        for record in &report.record {
-            if record.contains_string(&filter) {
-                if offset == 0 {
-                    records.push((record_id, record.clone()));
-                    record_id += 1;
-
-                    if records.len() >= PAGE_SIZE as usize {
-                        break;
-                    }
-                } else {
-                    offset -= 1;
+        let mut matches = report
+            .record
+            .iter()
+            .filter(|record| record.contains_string(&filter))
+            .collect::<Vec<_>>();
+        if let Some((key, order)) = sort.get() {
+            matches.sort_by(|a, b| {
+                let ordering = a.compare(b, &key);
+                match order {
+                    SortOrder::Ascending => ordering,
+                    SortOrder::Descending => ordering.reverse(),
                 }
-            }
+            });
         }
+        total_results.set(matches.len() as u32);
+
+        let offset = (page.get().saturating_sub(1)) * PAGE_SIZE;
+        let records = matches
+            .into_iter()
+            .skip(offset as usize)
+            .take(PAGE_SIZE as usize)
+            .enumerate()
+            .map(|(record_id, record)| (record_id as u32, record.clone()))
+            .collect::<Vec<_>>();
 
         selected_record.set(0u32);
         records
     });
+    let column_sort = ColumnSort {
+        keys: vec![
+            Some("from".to_string()),
+            Some("to".to_string()),
+            Some("ip".to_string()),
+            Some("disposition".to_string()),
+            None,
+        ],
+        current: sort.into(),
+        on_sort: Callback::new(move |key: String| {
+            let order = match sort.get_untracked() {
+                Some((current, order)) if current == key => order.reverse(),
+                _ => SortOrder::Ascending,
+            };
+            sort.set(Some((key, order)));
+            page.set(1);
+        }),
+    };
     let display_record = create_rw_signal(false);
     let fetch_selected_record = create_memo(move |_| {
         if display_record.get() {
@@ -238,6 +266,21 @@ pub fn DmarcReportDisplay(
                     <ReportTextValue value="Yes"/>
                 </ReportItem>
             </ReportSection>
+            {(!sources.is_empty())
+                .then(|| {
+                    view! {
+                        <SourceBreakdown
+                            title="Sources"
+                            header="Source IP"
+                            sources=sources
+                            on_select=move |source: String| {
+                                filter.set(Some(source));
+                                page.set(1);
+                            }
+                        />
+                    }
+                })}
+
             <div class="gap-2 sm:gap-4 py-8 first:pt-0 last:pb-0 border-t first:border-transparent border-gray-200 dark:border-gray-700 dark:first:border-transparent">
                 <div class="sm:col-span-12 pb-4">
                     <h2 class="text-lg font-semibold text-gray-800 dark:text-gray-200">Records</h2>
@@ -248,17 +291,22 @@ pub fn DmarcReportDisplay(
                             value=filter
                             on_search=move |value| {
                                 filter.set(Some(value));
+                                page.set(1);
                             }
                         />
 
                     </Toolbar>
-                    <ColumnList headers=vec![
-                        "From".to_string(),
-                        "To".to_string(),
-                        "Source IP".to_string(),
-                        "Disposition".to_string(),
-                        "".to_string(),
-                    ]>
+                    <ColumnList
+                        headers=vec![
+                            "From".to_string(),
+                            "To".to_string(),
+                            "Source IP".to_string(),
+                            "Disposition".to_string(),
+                            "".to_string(),
+                        ]
+
+                        sort=column_sort
+                    >
 
                         <For
                             each=move || fetch_records.get()
@@ -306,7 +354,7 @@ pub fn DmarcReportDisplay(
 
                         <Pagination
                             current_page=page
-                            total_results=Some(total_results)
+                            total_results=Signal::derive(move || Some(total_results.get()))
                             page_size=PAGE_SIZE
                             on_page_change=move |new_page: u32| {
                                 page.set(new_page);
@@ -718,6 +766,26 @@ impl Record {
                 .is_some_and(|ip| ip.to_string().contains(filter))
     }
 
+    fn compare(&self, other: &Self, key: &str) -> Ordering {
+        match key {
+            "to" => self
+                .identifiers
+                .envelope_to
+                .cmp(&other.identifiers.envelope_to),
+            "ip" => self.row.source_ip.cmp(&other.row.source_ip),
+            "disposition" => self
+                .row
+                .policy_evaluated
+                .disposition
+                .to_string()
+                .cmp(&other.row.policy_evaluated.disposition.to_string()),
+            _ => self
+                .identifiers
+                .header_from
+                .cmp(&other.identifiers.header_from),
+        }
+    }
+
     pub fn id(&self) -> u64 {
         let mut hasher = DefaultHasher::new();
         self.hash(&mut hasher);
@@ -8,6 +8,7 @@ pub mod arf;
 pub mod display;
 pub mod dmarc;
 pub mod list;
+pub mod sources;
 pub mod tls;
 
 use std::{fmt::Display, net::IpAddr};
@@ -0,0 +1,230 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use leptos::*;
+
+use crate::{
+    components::list::{
+        header::{ColumnList, ColumnSort},
+        ListItem, ListTextItem,
+    },
+    core::schema::SortOrder,
+};
+
+use super::{DmarcResult, Report, TlsReport};
+
+// Pass and fail counts of a single sending source, an empty source is shown
+// as unknown and cannot be selected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceStats {
+    pub source: String,
+    pub passed: u64,
+    pub failed: u64,
+}
+
+impl SourceStats {
+    pub fn total(&self) -> u64 {
+        self.passed + self.failed
+    }
+
+    pub fn pass_rate(&self) -> u64 {
+        match self.total() {
+            0 => 0,
+            total => self.passed * 100 / total,
+        }
+    }
+
+    fn compare(&self, other: &Self, key: &str) -> Ordering {
+        match key {
+            "messages" => self.total().cmp(&other.total()),
+            "passed" => self.passed.cmp(&other.passed),
+            "failed" => self.failed.cmp(&other.failed),
+            "rate" => self.pass_rate().cmp(&other.pass_rate()),
+            _ => self.source.cmp(&other.source),
+        }
+        .then_with(|| self.source.cmp(&other.source))
+    }
+}
+
+impl Report {
+    // Messages pass DMARC when either DKIM or SPF passes and is aligned
+    pub fn sources(&self) -> Vec<SourceStats> {
+        let mut sources: BTreeMap<String, SourceStats> = BTreeMap::new();
+        for record in &self.record {
+            let source = record
+                .row
+                .source_ip
+                .map(|ip| ip.to_string())
+                .unwrap_or_default();
+            let stats = sources.entry(source.clone()).or_insert(SourceStats {
+                source,
+                passed: 0,
+                failed: 0,
+            });
+            let evaluated = &record.row.policy_evaluated;
+            if evaluated.dkim == DmarcResult::Pass || evaluated.spf == DmarcResult::Pass {
+                stats.passed += record.row.count as u64;
+            } else {
+                stats.failed += record.row.count as u64;
+            }
+        }
+        sources.into_values().collect()
+    }
+}
+
+impl TlsReport {
+    // TLS reports only break down sessions per policy domain, the sending IPs
+    // are listed for failed sessions only
+    pub fn sources(&self) -> Vec<SourceStats> {
+        let mut sources: BTreeMap<String, SourceStats> = BTreeMap::new();
+        for policy in &self.policies {
+            let source = policy.policy.policy_domain.clone();
+            let stats = sources.entry(source.clone()).or_insert(SourceStats {
+                source,
+                passed: 0,
+                failed: 0,
+            });
+            stats.passed += policy.summary.total_success as u64;
+            stats.failed += policy.summary.total_failure as u64;
+        }
+        sources.into_values().collect()
+    }
+}
+
+#[component]
+pub fn SourceBreakdown(
+    #[prop(into)] title: String,
+    #[prop(into)] header: String,
+    sources: Vec<SourceStats>,
+    #[prop(into)] on_select: Callback<String>,
+) -> impl IntoView {
+    let sort = create_rw_signal(Some(("messages".to_string(), SortOrder::Descending)));
+    let sorted_sources = create_memo(move |_| {
+        let mut sources = sources.clone();
+        if let Some((key, order)) = sort.get() {
+            sources.sort_by(|a, b| {
+                let ordering = a.compare(b, &key);
+                match order {
+                    SortOrder::Ascending => ordering,
+                    SortOrder::Descending => ordering.reverse(),
+                }
+            });
+        }
+        sources
+    });
+    let column_sort = ColumnSort {
+        keys: vec![
+            Some("source".to_string()),
+            Some("messages".to_string()),
+            Some("passed".to_string()),
+            Some("failed".to_string()),
+            Some("rate".to_string()),
+            None,
+        ],
+        current: sort.into(),
+        on_sort: Callback::new(move |key: String| {
+            let order = match sort.get_untracked() {
+                Some((current, order)) if current == key => order.reverse(),
+                _ => SortOrder::Ascending,
+            };
+            sort.set(Some((key, order)));
+        }),
+    };
+
+    view! {
+        <div class="gap-2 sm:gap-4 py-8 first:pt-0 last:pb-0 border-t first:border-transparent border-gray-200 dark:border-gray-700 dark:first:border-transparent">
+            <div class="sm:col-span-12 pb-4">
+                <h2 class="text-lg font-semibold text-gray-800 dark:text-gray-200">{title}</h2>
+            </div>
+            <div class="overflow-x-auto border border-gray-200 rounded-xl dark:border-gray-700">
+                <table class="min-w-full divide-y divide-gray-200 dark:divide-gray-700">
+                    <ColumnList
+                        headers=vec![
+                            header,
+                            "Messages".to_string(),
+                            "Passed".to_string(),
+                            "Failed".to_string(),
+                            "Pass rate".to_string(),
+                            "".to_string(),
+                        ]
+
+                        sort=column_sort
+                    >
+
+                        <For
+                            each=move || sorted_sources.get()
+                            key=|stats| stats.source.clone()
+                            children=move |stats| {
+                                let rate = stats.pass_rate();
+                                let passed_width = match stats.total() {
+                                    0 => 0.0,
+                                    total => stats.passed as f64 * 100.0 / total as f64,
+                                };
+                                let source = stats.source.clone();
+                                view! {
+                                    <tr>
+                                        <ListTextItem>
+                                            {if stats.source.is_empty() {
+                                                "Unknown".to_string()
+                                            } else {
+                                                stats.source.clone()
+                                            }}
+
+                                        </ListTextItem>
+                                        <ListTextItem>{stats.total()}</ListTextItem>
+                                        <ListTextItem>{stats.passed}</ListTextItem>
+                                        <ListTextItem>{stats.failed}</ListTextItem>
+                                        <ListItem>
+                                            <div class="flex items-center gap-x-3">
+                                                <div
+                                                    class="flex w-32 h-2 bg-red-500 rounded-full overflow-hidden"
+                                                    title=format!(
+                                                        "{} passed, {} failed",
+                                                        stats.passed,
+                                                        stats.failed,
+                                                    )
+                                                >
+
+                                                    <div
+                                                        class="flex flex-col justify-center overflow-hidden bg-teal-500"
+                                                        style=format!("width: {passed_width:.1}%")
+                                                    ></div>
+                                                </div>
+                                                <span class="text-sm text-gray-800 dark:text-gray-200">
+                                                    {format!("{rate}%")}
+                                                </span>
+                                            </div>
+                                        </ListItem>
+                                        <ListItem subclass="px-6 py-1.5">
+                                            {(!source.is_empty())
+                                                .then(|| {
+                                                    view! {
+                                                        <button
+                                                            class="inline-flex items-center gap-x-1 text-sm text-blue-600 decoration-2 hover:underline font-medium dark:focus:outline-none dark:focus:ring-1 dark:focus:ring-gray-600"
+                                                            on:click=move |_| {
+                                                                on_select.call(source.clone());
+                                                            }
+                                                        >
+
+                                                            Show
+                                                        </button>
+                                                    }
+                                                })}
+
+                                        </ListItem>
+                                    </tr>
+                                }
+                            }
+                        />
+
+                    </ColumnList>
+                </table>
+            </div>
+        </div>
+    }
+}
@@ -5,6 +5,7 @@
  */
 
 use std::{
+    cmp::Ordering,
     hash::{DefaultHasher, Hash, Hasher},
     vec,
 };
@@ -19,19 +20,22 @@ use crate::{
         form::button::Button,
         icon::{IconCancel, IconCheckCircle, IconClock},
         list::{
-            header::ColumnList, pagination::Pagination, toolbar::SearchBox, Footer, ListItem,
-            ListTable, ListTextItem, Toolbar,
+            header::{ColumnList, ColumnSort},
+            pagination::Pagination,
+            toolbar::SearchBox,
+            Footer, ListItem, ListTable, ListTextItem, Toolbar,
         },
         report::{ReportItem, ReportSection, ReportTextValue, ReportView},
         Color,
     },
+    core::schema::SortOrder,
     pages::{
         queue::reports::{display::PAGE_SIZE, Policy},
         FormatDateTime,
     },
 };
 
-use super::{FailureDetails, PolicyType, TlsReport};
+use super::{sources::SourceBreakdown, FailureDetails, PolicyType, TlsReport};
 
 #[derive(Clone, Debug, PartialEq, Copy)]
 enum CurrentView {
@@ -60,35 +64,45 @@ pub fn TlsReportDisplay(
         total_fail += policy.summary.total_failure;
     }
 
+    let sources = report.sources();
     let current_view = create_rw_signal(CurrentView::Main);
     let total_policies = report.policies.len() as u32;
     let policy_page = create_rw_signal(1u32);
     let policy_filter = create_rw_signal(None::<String>);
+    let policy_sort = create_rw_signal(None::<(String, SortOrder)>);
+    let filtered_policies = create_rw_signal(total_policies);
     let failure_page = create_rw_signal(1u32);
     let failure_filter = create_rw_signal(None::<String>);
     let selected_policy = create_rw_signal(None::<Policy>);
     let selected_failure = create_rw_signal(None::<FailureDetails>);
     let fetch_policies = create_memo(move |_| {
-        let mut policies = Vec::with_capacity(PAGE_SIZE as usize);
-        let mut offset = (policy_page.get().saturating_sub(1)) * PAGE_SIZE;
         let policy_filter = policy_filter
             .get()
             .map(|s| s.trim().to_lowercase())
             .unwrap_or_default();
-
-        for policy in &report.policies {
-            if policy.contains_string(&policy_filter) {
-                if offset == 0 {
-                    policies.push(policy.clone());
-
-                    if policies.len() >= PAGE_SIZE as usize {
-                        break;
-                    }
-                } else {
-                    offset -= 1;
+        let mut matches = report
+            .policies
+            .iter()
+            .filter(|policy| policy.contains_string(&policy_filter))
+            .collect::<Vec<_>>();
+        if let Some((key, order)) = policy_sort.get() {
+            matches.sort_by(|a, b| {
+                let ordering = a.compare(b, &key);
+                match order {
+                    SortOrder::Ascending => ordering,
+                    SortOrder::Descending => ordering.reverse(),
                 }
-            }
+            });
         }
+        filtered_policies.set(matches.len() as u32);
+
+        let offset = (policy_page.get().saturating_sub(1)) * PAGE_SIZE;
+        let policies = matches
+            .into_iter()
+            .skip(offset as usize)
+            .take(PAGE_SIZE as usize)
+            .cloned()
+            .collect::<Vec<_>>();
 
         selected_policy.set(None);
         selected_failure.set(None);
@@ -96,6 +110,24 @@ pub fn TlsReportDisplay(
         failure_filter.set(None);
         policies
     });
+    let column_sort = ColumnSort {
+        keys: vec![
+            Some("domain".to_string()),
+            Some("type".to_string()),
+            Some("successes".to_string()),
+            Some("failures".to_string()),
+            None,
+        ],
+        current: policy_sort.into(),
+        on_sort: Callback::new(move |key: String| {
+            let order = match policy_sort.get_untracked() {
+                Some((current, order)) if current == key => order.reverse(),
+                _ => SortOrder::Ascending,
+            };
+            policy_sort.set(Some((key, order)));
+            policy_page.set(1);
+        }),
+    };
 
     let extra = extra
         .into_iter()
@@ -153,6 +185,16 @@ pub fn TlsReportDisplay(
             {if total_policies > 0 {
                 Some(
                     view! {
+                        <SourceBreakdown
+                            title="Policy Domains"
+                            header="Policy Domain"
+                            sources=sources
+                            on_select=move |source: String| {
+                                policy_filter.set(Some(source));
+                                policy_page.set(1);
+                            }
+                        />
+
                         <div class="gap-2 sm:gap-4 py-8 first:pt-0 last:pb-0 border-t first:border-transparent border-gray-200 dark:border-gray-700 dark:first:border-transparent">
                             <div class="sm:col-span-12 pb-4">
                                 <h2 class="text-lg font-semibold text-gray-800 dark:text-gray-200">
@@ -165,17 +207,22 @@ pub fn TlsReportDisplay(
                                         value=policy_filter
                                         on_search=move |value| {
                                             policy_filter.set(Some(value));
+                                            policy_page.set(1);
                                         }
                                     />
 
                                 </Toolbar>
-                                <ColumnList headers=vec![
-                                    "Domain".to_string(),
-                                    "Type".to_string(),
-                                    "Successes".to_string(),
-                                    "Failures".to_string(),
-                                    "".to_string(),
-                                ]>
+                                <ColumnList
+                                    headers=vec![
+                                        "Domain".to_string(),
+                                        "Type".to_string(),
+                                        "Successes".to_string(),
+                                        "Failures".to_string(),
+                                        "".to_string(),
+                                    ]
+
+                                    sort=column_sort
+                                >
 
                                     <For
                                         each=move || fetch_policies.get()
@@ -212,7 +259,9 @@ pub fn TlsReportDisplay(
 
                                     <Pagination
                                         current_page=policy_page
-                                        total_results=Some(total_policies)
+                                        total_results=Signal::derive(move || {
+                                            Some(filtered_policies.get())
+                                        })
                                         page_size=PAGE_SIZE
                                         on_page_change=move |new_page: u32| {
                                             policy_page.set(new_page);
@@ -517,6 +566,19 @@ impl Policy {
                 .any(|f| f.contains_string(filter))
     }
 
+    fn compare(&self, other: &Self, key: &str) -> Ordering {
+        match key {
+            "type" => self
+                .policy
+                .policy_type
+                .to_string()
+                .cmp(&other.policy.policy_type.to_string()),
+            "successes" => self.summary.total_success.cmp(&other.summary.total_success),
+            "failures" => self.summary.total_failure.cmp(&other.summary.total_failure),
+            _ => self.policy.policy_domain.cmp(&other.policy.policy_domain),
+        }
+    }
+
     pub fn id(&self) -> u64 {
         let mut hasher = DefaultHasher::new();
         self.hash(&mut hasher);